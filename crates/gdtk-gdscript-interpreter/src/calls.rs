@@ -0,0 +1,673 @@
+//! Calls, properties and signals.
+
+use std::{future::Future, pin::Pin, rc::Rc, task::Poll};
+
+use gdtk_gdscript_ast::{ASTExprKind, ASTFunction, ASTVariable};
+
+use crate::{
+    class::Class,
+    error::{Error, ErrorKind},
+    interpreter::{coerce, typehint, variable_name, Eval, Frame, Interpreter, MAX_CALL_DEPTH},
+    statements::Flow,
+    stdlib::{self, Args},
+    value::{Array, Callable, CallableKind, Object, Signal, Type, Value},
+};
+
+const RED_ZONE: usize = 256 * 1024;
+const STACK_SEGMENT: usize = 4 * 1024 * 1024;
+
+impl<'a> Interpreter<'a> {
+    /// Call `function`. Unless the call is awaited, a coroutine that
+    /// suspends is left to the scheduler and the call evaluates to `null`.
+    pub(crate) async fn call_function(
+        &self,
+        function: &'a ASTFunction<'a>,
+        this: Option<Object<'a>>,
+        class: Rc<Class<'a>>,
+        args: Vec<Value<'a>>,
+        awaited: bool,
+    ) -> Result<Value<'a>, Error> {
+        let this = if function.kind.is_static() {
+            None
+        } else {
+            this
+        };
+        let mut frame = Frame::new(class, this);
+        frame.function = function_name(function);
+
+        let future = self.run_function(function, frame, args);
+
+        if awaited {
+            future.await
+        } else {
+            self.run_or_spawn(future).await
+        }
+    }
+
+    pub(crate) fn run_function(
+        &self,
+        function: &'a ASTFunction<'a>,
+        frame: Frame<'a>,
+        args: Vec<Value<'a>>,
+    ) -> Eval<'a> {
+        let this = self.clone();
+        let state = self.0.clone();
+        let mut body: Eval<'a> =
+            Box::pin(async move { this.run_function_body(function, &frame, args).await });
+
+        // Calls are nested only while their futures are being polled, so
+        // that's when the depth is tracked.
+        Box::pin(std::future::poll_fn(move |cx| {
+            let depth = state.depth.get();
+
+            if depth >= MAX_CALL_DEPTH {
+                return Poll::Ready(Err(ErrorKind::StackOverflow(depth).into()));
+            }
+
+            state.depth.set(depth + 1);
+            // Futures of nested calls take a lot of stack, especially in
+            // debug builds, so grow it instead of limiting the depth further.
+            let result = stacker::maybe_grow(RED_ZONE, STACK_SEGMENT, || body.as_mut().poll(cx));
+            state.depth.set(depth);
+
+            result
+        }))
+    }
+
+    async fn run_function_body(
+        &self,
+        function: &'a ASTFunction<'a>,
+        frame: &Frame<'a>,
+        args: Vec<Value<'a>>,
+    ) -> Result<Value<'a>, Error> {
+        let params = function.parameters.as_deref().unwrap_or_default();
+        let required = params.iter().filter(|param| param.value.is_none()).count();
+
+        if args.len() < required || args.len() > params.len() {
+            return Err(ErrorKind::ArgumentCount {
+                function: function_name(function).unwrap_or("<lambda>").to_owned(),
+                expected: if required == params.len() {
+                    required.to_string()
+                } else {
+                    format!("{required} to {}", params.len())
+                },
+                received: args.len(),
+            }
+            .into());
+        }
+
+        let mut args = args.into_iter();
+
+        for param in params {
+            let value = match args.next() {
+                Some(value) => coerce(param.typehint.as_ref().and_then(typehint), value),
+                None => self.initial_value(param, frame).await?,
+            };
+
+            frame.declare(variable_name(param), value);
+        }
+
+        match self.exec_block(&function.body, frame).await? {
+            Flow::Return(value) => Ok(value),
+            _ => Ok(Value::Null),
+        }
+    }
+
+    pub(crate) async fn call_callable(
+        &self,
+        callable: &Callable<'a>,
+        mut args: Vec<Value<'a>>,
+        awaited: bool,
+    ) -> Result<Value<'a>, Error> {
+        args.extend(callable.bound.iter().cloned());
+
+        match &callable.kind {
+            CallableKind::Method { receiver, name } => {
+                self.call_method(receiver, name, args, awaited).await
+            }
+            CallableKind::Lambda(lambda) => {
+                let future = self.run_function(lambda.function, lambda.frame.enter(), args);
+
+                if awaited {
+                    future.await
+                } else {
+                    self.run_or_spawn(future).await
+                }
+            }
+            CallableKind::Builtin(name) => stdlib::functions::call(self, name, args)
+                .unwrap_or_else(|| {
+                    Err(ErrorKind::InvalidMethod {
+                        name: name.to_string(),
+                        base: "@GlobalScope".to_owned(),
+                    }
+                    .into())
+                }),
+        }
+    }
+
+    /// Call a function by name, as in `foo()`. Methods of the current class
+    /// take precedence over global functions.
+    pub(crate) async fn call_global(
+        &self,
+        name: &str,
+        args: Vec<Value<'a>>,
+        frame: &Frame<'a>,
+        awaited: bool,
+    ) -> Result<Value<'a>, Error> {
+        // Methods are dispatched on the class of the object, so that
+        // overrides are called from base classes too.
+        let class = frame
+            .this
+            .as_ref()
+            .map_or_else(|| frame.class.clone(), Object::class);
+
+        if let Some((defining, function)) = class.function(name) {
+            return self
+                .call_function(function, frame.this.clone(), defining, args, awaited)
+                .await;
+        }
+
+        match &frame.this {
+            Some(this) if !stdlib::functions::exists(name) && Type::from_name(name).is_none() => {
+                self.call_object_method(this, name, args, awaited).await
+            }
+            _ => stdlib::functions::call(self, name, args).unwrap_or_else(|| {
+                Err(ErrorKind::InvalidMethod {
+                    name: name.to_owned(),
+                    base: class.name.clone(),
+                }
+                .into())
+            }),
+        }
+    }
+
+    /// Call the base class' version of `name`, as in `super.name()`.
+    pub(crate) async fn call_super(
+        &self,
+        name: &str,
+        args: Vec<Value<'a>>,
+        frame: &Frame<'a>,
+        awaited: bool,
+    ) -> Result<Value<'a>, Error> {
+        match frame
+            .class
+            .base_class()
+            .and_then(|base| base.function(name))
+        {
+            Some((defining, function)) => {
+                self.call_function(function, frame.this.clone(), defining, args, awaited)
+                    .await
+            }
+            // Engine classes have an implicit constructor.
+            None if name == "_init" => Ok(Value::Null),
+            None => Err(ErrorKind::InvalidMethod {
+                name: name.to_owned(),
+                base: frame.class.native().to_owned(),
+            }
+            .into()),
+        }
+    }
+
+    /// Call a method of any value. Boxed, as methods may call other methods.
+    pub(crate) fn call_method<'s>(
+        &'s self,
+        receiver: &'s Value<'a>,
+        name: &'s str,
+        args: Vec<Value<'a>>,
+        awaited: bool,
+    ) -> Pin<Box<dyn Future<Output = Result<Value<'a>, Error>> + 's>> {
+        Box::pin(async move {
+            match receiver {
+                Value::Object(object) => {
+                    if let Some((defining, function)) = object.class().function(name) {
+                        return self
+                            .call_function(function, Some(object.clone()), defining, args, awaited)
+                            .await;
+                    }
+
+                    self.call_object_method(object, name, args, awaited).await
+                }
+                Value::Class(class) if name == "new" => self.instantiate_async(class, args).await,
+                Value::Class(class) => match class.function(name) {
+                    Some((defining, function)) => {
+                        self.call_function(function, None, defining, args, awaited)
+                            .await
+                    }
+                    None => Err(ErrorKind::InvalidMethod {
+                        name: name.to_owned(),
+                        base: class.name.clone(),
+                    }
+                    .into()),
+                },
+                Value::Callable(callable) if name == "call" => {
+                    self.call_callable(callable, args, awaited).await
+                }
+                Value::Callable(callable) if name == "callv" => {
+                    let args = Args::new(name, &args);
+                    args.count(1, 1)?;
+                    let args = args.array(0)?.to_vec();
+
+                    self.call_callable(callable, args, awaited).await
+                }
+                Value::Signal(signal) if name == "emit" => {
+                    self.emit(&signal.object, &signal.name, args).await?;
+
+                    Ok(Value::Null)
+                }
+                Value::Array(array) if is_higher_order(name) => {
+                    self.call_higher_order(array, name, args).await
+                }
+                receiver => stdlib::methods::call(receiver, name, args),
+            }
+        })
+    }
+
+    /// Methods every object has.
+    async fn call_object_method(
+        &self,
+        object: &Object<'a>,
+        name: &str,
+        args: Vec<Value<'a>>,
+        awaited: bool,
+    ) -> Result<Value<'a>, Error> {
+        let receiver = Value::Object(object.clone());
+        let class = object.class();
+        let parsed = Args::new(name, &args);
+
+        match name {
+            "get" => {
+                parsed.count(1, 1)?;
+                self.get_property(&receiver, parsed.str(0)?).await
+            }
+            "set" => {
+                parsed.count(2, 2)?;
+                self.set_property(&receiver, parsed.str(0)?, args[1].clone())
+                    .await?;
+
+                Ok(Value::Null)
+            }
+            "has_method" => {
+                parsed.count(1, 1)?;
+                let method = parsed.str(0)?;
+
+                Ok(Value::Bool(
+                    class.function(method).is_some()
+                        || self.native_method(class.native(), method).is_some(),
+                ))
+            }
+            "has_signal" => {
+                parsed.count(1, 1)?;
+                Ok(Value::Bool(class.has_signal(parsed.str(0)?)))
+            }
+            "call" | "emit_signal" | "connect" | "disconnect" | "is_connected" => {
+                parsed.count(1, usize::MAX)?;
+                let target = parsed.str(0)?.to_owned();
+                let rest = args[1..].to_vec();
+
+                match name {
+                    "call" => self.call_method(&receiver, &target, rest, awaited).await,
+                    "emit_signal" => {
+                        self.emit(object, &target, rest).await?;
+
+                        Ok(Value::Null)
+                    }
+                    _ => {
+                        let signal = Value::Signal(Signal {
+                            object: object.clone(),
+                            name: target.into(),
+                        });
+
+                        stdlib::methods::call(&signal, name, rest)
+                    }
+                }
+            }
+            "callv" => {
+                parsed.count(2, 2)?;
+                let args = parsed.array(1)?.to_vec();
+
+                self.call_method(&receiver, parsed.str(0)?, args, awaited)
+                    .await
+            }
+            "get_class" => Ok(Value::string(class.native())),
+            "get_script" => Ok(Value::Class(class)),
+            "get_instance_id" => Ok(Value::Int(object.id() as i64)),
+            "to_string" => Ok(Value::string(receiver.to_string())),
+            // There is no scene tree to remove the object from, and memory is
+            // managed by reference counting.
+            "free" | "queue_free" => Ok(Value::Null),
+            _ => match self.native_method(class.native(), name) {
+                Some(method) => method(self.clone(), object.clone(), args).await,
+                None => Err(ErrorKind::InvalidMethod {
+                    name: name.to_owned(),
+                    base: class.name.clone(),
+                }
+                .into()),
+            },
+        }
+    }
+
+    /// Array methods that take a callable.
+    async fn call_higher_order(
+        &self,
+        array: &Array<'a>,
+        name: &str,
+        args: Vec<Value<'a>>,
+    ) -> Result<Value<'a>, Error> {
+        let parsed = Args::new(name, &args);
+        let (min, max) = if name == "reduce" { (1, 2) } else { (1, 1) };
+        parsed.count(min, max)?;
+
+        let callable = parsed.callable(0)?;
+        let items = array.to_vec();
+
+        match name {
+            "map" => {
+                let mut mapped = Vec::with_capacity(items.len());
+
+                for item in items {
+                    mapped.push(self.call_callable(callable, vec![item], true).await?);
+                }
+
+                Ok(Value::array(mapped))
+            }
+            "filter" => {
+                let mut filtered = vec![];
+
+                for item in items {
+                    if self
+                        .call_callable(callable, vec![item.clone()], true)
+                        .await?
+                        .is_truthy()
+                    {
+                        filtered.push(item);
+                    }
+                }
+
+                Ok(Value::array(filtered))
+            }
+            "reduce" => {
+                let mut items = items.into_iter();
+                let mut accumulator = match args.get(1) {
+                    Some(initial) => initial.clone(),
+                    None => items.next().unwrap_or_default(),
+                };
+
+                for item in items {
+                    accumulator = self
+                        .call_callable(callable, vec![accumulator, item], true)
+                        .await?;
+                }
+
+                Ok(accumulator)
+            }
+            "any" | "all" => {
+                let expected = name == "all";
+
+                for item in items {
+                    if self
+                        .call_callable(callable, vec![item], true)
+                        .await?
+                        .is_truthy()
+                        != expected
+                    {
+                        return Ok(Value::Bool(!expected));
+                    }
+                }
+
+                Ok(Value::Bool(expected))
+            }
+            "sort_custom" => {
+                let sorted = self.merge_sort(items, callable).await?;
+                *array.borrow_mut() = sorted;
+
+                Ok(Value::Null)
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    /// A stable sort, using `before(a, b)` to tell whether `a` goes before `b`.
+    async fn merge_sort(
+        &self,
+        mut items: Vec<Value<'a>>,
+        before: &Callable<'a>,
+    ) -> Result<Vec<Value<'a>>, Error> {
+        let len = items.len();
+        let mut width = 1;
+
+        while width < len {
+            for start in (0..len).step_by(width * 2) {
+                let mid = (start + width).min(len);
+                let end = (start + width * 2).min(len);
+                let (mut left, mut right) = (start, mid);
+                let mut merged = Vec::with_capacity(end - start);
+
+                while left < mid && right < end {
+                    let args = vec![items[right].clone(), items[left].clone()];
+
+                    if self.call_callable(before, args, true).await?.is_truthy() {
+                        merged.push(items[right].clone());
+                        right += 1;
+                    } else {
+                        merged.push(items[left].clone());
+                        left += 1;
+                    }
+                }
+
+                merged.extend_from_slice(&items[left..mid]);
+                merged.extend_from_slice(&items[right..end]);
+                items[start..end].clone_from_slice(&merged);
+            }
+
+            width *= 2;
+        }
+
+        Ok(items)
+    }
+
+    pub(crate) async fn get_property(
+        &self,
+        base: &Value<'a>,
+        name: &str,
+    ) -> Result<Value<'a>, Error> {
+        let property = match base {
+            Value::Object(object) => {
+                let class = object.class();
+
+                if let Some(value) = object.field(name) {
+                    match class.variable(name) {
+                        Some(
+                            variable @ ASTVariable {
+                                getter: Some(getter),
+                                ..
+                            },
+                        ) if !object.is_accessing(name) => {
+                            return self.run_accessor(object, variable, getter, vec![]).await;
+                        }
+                        _ => Some(value),
+                    }
+                } else if class.function(name).is_some() {
+                    Some(Value::Callable(Callable::method(base.clone(), name)))
+                } else if class.has_signal(name) {
+                    Some(Value::Signal(Signal {
+                        object: object.clone(),
+                        name: name.into(),
+                    }))
+                } else {
+                    class.constant(name)
+                }
+            }
+            Value::Class(class) => match class.constant(name) {
+                Some(value) => Some(value),
+                None => class
+                    .function(name)
+                    .map(|_| Value::Callable(Callable::method(base.clone(), name))),
+            },
+            Value::Dictionary(dictionary) => dictionary.get(&Value::string(name)),
+            _ => None,
+        };
+
+        property.ok_or_else(|| {
+            ErrorKind::InvalidProperty {
+                name: name.to_owned(),
+                base: base.type_name(),
+            }
+            .into()
+        })
+    }
+
+    pub(crate) async fn set_property(
+        &self,
+        base: &Value<'a>,
+        name: &str,
+        value: Value<'a>,
+    ) -> Result<(), Error> {
+        let invalid = || -> Error {
+            ErrorKind::InvalidProperty {
+                name: name.to_owned(),
+                base: base.type_name(),
+            }
+            .into()
+        };
+
+        match base {
+            Value::Object(object) => {
+                let class = object.class();
+
+                match class.variable(name) {
+                    Some(
+                        variable @ ASTVariable {
+                            setter: Some(setter),
+                            ..
+                        },
+                    ) if !object.is_accessing(name) => {
+                        self.run_accessor(object, variable, setter, vec![value])
+                            .await?;
+                        Ok(())
+                    }
+                    Some(variable) => {
+                        let ty = variable.typehint.as_ref().and_then(typehint);
+                        object.set_field(name, coerce(ty, value));
+                        Ok(())
+                    }
+                    None => set_static(&class, name, value).ok_or_else(invalid)?,
+                }
+            }
+            Value::Class(class) => set_static(class, name, value).ok_or_else(invalid)?,
+            Value::Dictionary(dictionary) => {
+                dictionary.insert(Value::string(name), value);
+                Ok(())
+            }
+            _ => Err(invalid()),
+        }
+    }
+
+    /// Run the getter or setter of a property. While it runs, the property
+    /// is accessed directly.
+    async fn run_accessor(
+        &self,
+        object: &Object<'a>,
+        variable: &'a ASTVariable<'a>,
+        accessor: &'a ASTFunction<'a>,
+        args: Vec<Value<'a>>,
+    ) -> Result<Value<'a>, Error> {
+        let name = variable_name(variable);
+        object.borrow_mut().accessors.push(name);
+
+        let result = self
+            .call_function(accessor, Some(object.clone()), object.class(), args, true)
+            .await;
+
+        let mut instance = object.borrow_mut();
+        if let Some(idx) = instance
+            .accessors
+            .iter()
+            .rposition(|accessor| *accessor == name)
+        {
+            instance.accessors.remove(idx);
+        }
+
+        result
+    }
+
+    /// Emit a signal, resuming the coroutines awaiting it and calling the
+    /// connected callables.
+    pub(crate) async fn emit(
+        &self,
+        object: &Object<'a>,
+        name: &str,
+        args: Vec<Value<'a>>,
+    ) -> Result<(), Error> {
+        if !object.class().has_signal(name) {
+            return Err(ErrorKind::UndefinedSignal {
+                name: name.to_owned(),
+                base: object.class().name.clone(),
+            }
+            .into());
+        }
+
+        let (awaiters, connections) = {
+            let mut instance = object.borrow_mut();
+            let awaiters = instance.awaiters.remove(name).unwrap_or_default();
+            let connections = instance.connections.get(name).cloned().unwrap_or_default();
+
+            if let Some(connections) = instance.connections.get_mut(name) {
+                connections.retain(|connection| !connection.one_shot);
+            }
+
+            (awaiters, connections)
+        };
+
+        // `await` evaluates to the only argument of the signal, or to all of
+        // them as an array.
+        let value = match args.as_slice() {
+            [] => Value::Null,
+            [value] => value.clone(),
+            _ => Value::array(args.clone()),
+        };
+
+        for slot in awaiters {
+            *slot.borrow_mut() = Some(value.clone());
+        }
+
+        self.bump();
+        self.resume()?;
+
+        for connection in connections {
+            self.call_callable(&connection.callable, args.clone(), false)
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+fn function_name<'a>(function: &ASTFunction<'a>) -> Option<&'a str> {
+    match function.identifier.as_deref()?.kind {
+        ASTExprKind::Identifier(name) => Some(name),
+        _ => None,
+    }
+}
+
+fn is_higher_order(name: &str) -> bool {
+    matches!(
+        name,
+        "map" | "filter" | "reduce" | "any" | "all" | "sort_custom"
+    )
+}
+
+/// Assign to a static variable of `class`. Returns `None` if there's no such
+/// variable or constant.
+fn set_static<'a>(
+    class: &Rc<Class<'a>>,
+    name: &str,
+    value: Value<'a>,
+) -> Option<Result<(), Error>> {
+    if class.set_static(name, value) {
+        Some(Ok(()))
+    } else if class.constant(name).is_some() {
+        Some(Err(ErrorKind::AssignToConstant(name.to_owned()).into()))
+    } else {
+        None
+    }
+}
@@ -0,0 +1,136 @@
+//! Script classes.
+
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    rc::{Rc, Weak},
+};
+
+use gdtk_gdscript_ast::{ASTFunction, ASTVariable};
+
+use crate::value::Value;
+
+/// A class, either a whole script or an inner class.
+pub struct Class<'a> {
+    /// The `class_name` of a script, or the name of an inner class.
+    pub name: String,
+    pub base: Base<'a>,
+    /// Instance variables, in declaration order.
+    pub(crate) variables: Vec<&'a ASTVariable<'a>>,
+    pub(crate) functions: HashMap<&'a str, &'a ASTFunction<'a>>,
+    pub(crate) signals: Vec<&'a str>,
+    /// Constants, enums and inner classes.
+    pub(crate) constants: RefCell<HashMap<&'a str, Value<'a>>>,
+    pub(crate) statics: RefCell<HashMap<&'a str, Value<'a>>>,
+    /// The class this one is nested in.
+    pub(crate) outer: Option<Weak<Class<'a>>>,
+}
+
+/// What a class extends.
+pub enum Base<'a> {
+    Script(Rc<Class<'a>>),
+    /// An engine class, like `Node`. These have no behavior of their own.
+    Native(String),
+}
+
+impl<'a> Class<'a> {
+    pub fn base_class(&self) -> Option<&Rc<Class<'a>>> {
+        match &self.base {
+            Base::Script(class) => Some(class),
+            Base::Native(_) => None,
+        }
+    }
+
+    /// The engine class at the root of the inheritance chain.
+    pub fn native(&self) -> &str {
+        match &self.base {
+            Base::Script(class) => class.native(),
+            Base::Native(name) => name,
+        }
+    }
+
+    /// The class and its bases, starting with the class itself.
+    pub fn ancestors(self: &Rc<Self>) -> Vec<Rc<Class<'a>>> {
+        let mut ancestors = vec![self.clone()];
+
+        while let Some(base) = ancestors.last().unwrap().base_class() {
+            ancestors.push(base.clone());
+        }
+
+        ancestors
+    }
+
+    /// Whether the class is `other` or inherits from it.
+    pub fn inherits(self: &Rc<Self>, other: &Rc<Class<'a>>) -> bool {
+        self.ancestors()
+            .iter()
+            .any(|class| Rc::ptr_eq(class, other))
+    }
+
+    /// Look up a function in the class and its bases. Returns the class that
+    /// defines it, too.
+    pub fn function(self: &Rc<Self>, name: &str) -> Option<(Rc<Class<'a>>, &'a ASTFunction<'a>)> {
+        self.ancestors()
+            .into_iter()
+            .find_map(|class| class.functions.get(name).copied().map(|f| (class, f)))
+    }
+
+    /// Look up an instance variable in the class and its bases.
+    pub fn variable(self: &Rc<Self>, name: &str) -> Option<&'a ASTVariable<'a>> {
+        self.ancestors().into_iter().find_map(|class| {
+            class
+                .variables
+                .iter()
+                .find(|variable| variable.identifier.kind.as_identifier() == Some(&name))
+                .copied()
+        })
+    }
+
+    pub fn has_signal(self: &Rc<Self>, name: &str) -> bool {
+        self.ancestors()
+            .iter()
+            .any(|class| class.signals.contains(&name))
+    }
+
+    /// Look up a constant or a static variable in the class and its bases.
+    pub fn constant(self: &Rc<Self>, name: &str) -> Option<Value<'a>> {
+        self.ancestors().into_iter().find_map(|class| {
+            let constant = class.constants.borrow().get(name).cloned();
+            constant.or_else(|| class.statics.borrow().get(name).cloned())
+        })
+    }
+
+    /// Like [Class::constant], but also looks into outer classes.
+    pub fn resolve(self: &Rc<Self>, name: &str) -> Option<Value<'a>> {
+        let mut class = Some(self.clone());
+
+        while let Some(current) = class {
+            if let Some(value) = current.constant(name) {
+                return Some(value);
+            }
+
+            class = current.outer.as_ref().and_then(Weak::upgrade);
+        }
+
+        None
+    }
+
+    /// Set a static variable of the class, its bases or outer classes.
+    /// Returns whether the variable exists.
+    pub fn set_static(self: &Rc<Self>, name: &str, value: Value<'a>) -> bool {
+        let mut class = Some(self.clone());
+
+        while let Some(current) = class {
+            for ancestor in current.ancestors() {
+                if let Some(old) = ancestor.statics.borrow_mut().get_mut(name) {
+                    *old = value;
+                    return true;
+                }
+            }
+
+            class = current.outer.as_ref().and_then(Weak::upgrade);
+        }
+
+        false
+    }
+}
@@ -0,0 +1,82 @@
+use gdtk_span::Span;
+
+/// A runtime error.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[error("{kind}")]
+pub struct Error {
+    pub kind: ErrorKind,
+    /// The span of the innermost expression or statement that failed.
+    pub span: Option<Span>,
+}
+
+impl Error {
+    /// Attach `span` to the error, unless it already has a more precise one.
+    pub fn at(mut self, span: &Span) -> Self {
+        self.span.get_or_insert_with(|| span.clone());
+        self
+    }
+}
+
+impl From<ErrorKind> for Error {
+    fn from(kind: ErrorKind) -> Self {
+        Self { kind, span: None }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum ErrorKind {
+    #[error("Identifier \"{0}\" not declared in the current scope.")]
+    UndefinedIdentifier(String),
+    #[error("Invalid access to property or key \"{name}\" on a base of type \"{base}\".")]
+    InvalidProperty { name: String, base: String },
+    #[error("Invalid call. Nonexistent function \"{name}\" in base \"{base}\".")]
+    InvalidMethod { name: String, base: String },
+    #[error("Value of type \"{0}\" can't be called directly. Use `.call()` for Callables.")]
+    NotCallable(String),
+    #[error(
+        "Invalid call to \"{function}\". Expected {expected} argument(s), but received {received}."
+    )]
+    ArgumentCount {
+        function: String,
+        expected: String,
+        received: usize,
+    },
+    #[error("Invalid argument for \"{function}\": {message}")]
+    InvalidArgument { function: String, message: String },
+    #[error("Invalid operands \"{left}\" and \"{right}\" in operator \"{op}\".")]
+    InvalidOperands {
+        op: &'static str,
+        left: String,
+        right: String,
+    },
+    #[error("Invalid operand \"{operand}\" in unary operator \"{op}\".")]
+    InvalidOperand { op: &'static str, operand: String },
+    #[error("Out of bounds index {index} (on a base of size {size}).")]
+    IndexOutOfBounds { index: i64, size: usize },
+    #[error("Invalid index {index} on a base of type \"{base}\".")]
+    InvalidIndex { index: String, base: String },
+    #[error("Division by zero error in operator \"{0}\".")]
+    DivisionByZero(&'static str),
+    #[error("Invalid assignment target.")]
+    InvalidAssignment,
+    #[error("Cannot assign a new value to constant \"{0}\".")]
+    AssignToConstant(String),
+    #[error("Signal \"{name}\" doesn't exist in \"{base}\".")]
+    UndefinedSignal { name: String, base: String },
+    #[error("Assertion failed{}", .0.as_ref().map(|message| format!(": {message}")).unwrap_or_else(|| ".".to_owned()))]
+    AssertionFailed(Option<String>),
+    #[error("Stack overflow (stack size: {0}).")]
+    StackOverflow(usize),
+    #[error("Awaited signal was never emitted.")]
+    NeverResumed,
+    #[error("{0} is not supported outside of the engine.")]
+    Unsupported(String),
+    /// The script couldn't be parsed. Only raised by embedders, as the
+    /// interpreter runs already parsed code.
+    #[error("Parse Error: {0}")]
+    ParseError(String),
+    /// The script couldn't be read. Only raised by embedders, like
+    /// [ParseError](ErrorKind::ParseError).
+    #[error("Couldn't read the script: {0}")]
+    ReadError(String),
+}
@@ -0,0 +1,578 @@
+use std::{cell::RefCell, rc::Rc};
+
+use gdtk_gdscript_ast::{
+    ASTBinaryOp, ASTExpr, ASTExprKind, ASTPostfixOp, ASTPostfixOpKind, ASTPrefixOpKind,
+};
+
+use crate::{
+    error::{Error, ErrorKind},
+    interpreter::{Eval, Frame, Interpreter},
+    operators, stdlib,
+    value::{Callable, CallableKind, Dictionary, Lambda, Signal, Type, Value},
+};
+
+impl<'a> Interpreter<'a> {
+    pub(crate) fn eval(&self, expr: &'a ASTExpr<'a>, frame: &Frame<'a>) -> Eval<'a> {
+        let this = self.clone();
+        let frame = frame.clone();
+
+        Box::pin(async move {
+            this.eval_expr(expr, &frame)
+                .await
+                .map_err(|err| err.at(&expr.span))
+        })
+    }
+
+    async fn eval_expr(
+        &self,
+        expr: &'a ASTExpr<'a>,
+        frame: &Frame<'a>,
+    ) -> Result<Value<'a>, Error> {
+        match &expr.kind {
+            ASTExprKind::Group(exprs) => {
+                let mut value = Value::Null;
+
+                for expr in exprs {
+                    value = self.eval(expr, frame).await?;
+                }
+
+                Ok(value)
+            }
+            ASTExprKind::Identifier(name) => self.lookup(name, frame).await,
+            ASTExprKind::Number(value) => Ok(Value::Int(*value as i64)),
+            ASTExprKind::Float(value) => Ok(Value::Float(*value)),
+            ASTExprKind::String(value) => Ok(Value::string(unescape(value))),
+            ASTExprKind::StringName(value) => Ok(Value::StringName(unescape(value).into())),
+            ASTExprKind::Node(_) | ASTExprKind::UniqueNode(_) | ASTExprKind::NodePath(_) => {
+                Err(ErrorKind::Unsupported("Accessing nodes".to_owned()).into())
+            }
+            ASTExprKind::Boolean(value) => Ok(Value::Bool(*value)),
+            ASTExprKind::Null => Ok(Value::Null),
+            ASTExprKind::Array(exprs) => Ok(Value::array(self.eval_all(exprs, frame).await?)),
+            ASTExprKind::Dictionary(entries) => {
+                let dictionary = Dictionary::default();
+
+                for (key, value) in entries {
+                    let key = self.eval_key(key, frame).await?;
+                    let value = self.eval(value, frame).await?;
+
+                    dictionary.insert(key, value);
+                }
+
+                Ok(Value::Dictionary(dictionary))
+            }
+            ASTExprKind::Lambda(function) => Ok(Value::Callable(Callable {
+                kind: CallableKind::Lambda(Rc::new(Lambda {
+                    function,
+                    frame: frame.capture(),
+                })),
+                bound: vec![],
+            })),
+            ASTExprKind::PrefixExpr(op, value) => match op.kind {
+                ASTPrefixOpKind::Await => self.eval_await(value, frame).await,
+                kind => operators::unary(kind, self.eval(value, frame).await?),
+            },
+            ASTExprKind::PostfixExpr(callee, op) => match &op.kind {
+                ASTPostfixOpKind::Call(args) => self.eval_call(callee, args, frame, false).await,
+                ASTPostfixOpKind::Subscript(index) => {
+                    let base = self.eval(callee, frame).await?;
+                    let index = self.eval_subscript(index, frame).await?;
+
+                    operators::index(&base, &index)
+                }
+            },
+            ASTExprKind::BinaryExpr(left, op, right) => {
+                self.eval_binary(left, op, right, frame).await
+            }
+        }
+    }
+
+    pub(crate) async fn eval_all(
+        &self,
+        exprs: &'a [ASTExpr<'a>],
+        frame: &Frame<'a>,
+    ) -> Result<Vec<Value<'a>>, Error> {
+        let mut values = Vec::with_capacity(exprs.len());
+
+        for expr in exprs {
+            values.push(self.eval(expr, frame).await?);
+        }
+
+        Ok(values)
+    }
+
+    /// Evaluate a dictionary key. The parser doesn't tell `{a = 1}` apart from
+    /// `{a: 1}`, so identifiers that don't refer to anything are treated as
+    /// Lua-style string keys.
+    async fn eval_key(&self, key: &'a ASTExpr<'a>, frame: &Frame<'a>) -> Result<Value<'a>, Error> {
+        match key.kind {
+            ASTExprKind::Identifier(name) => match self.lookup(name, frame).await {
+                Err(Error {
+                    kind: ErrorKind::UndefinedIdentifier(_),
+                    ..
+                }) => Ok(Value::string(name)),
+                result => result,
+            },
+            _ => self.eval(key, frame).await,
+        }
+    }
+
+    async fn eval_subscript(
+        &self,
+        index: &'a [ASTExpr<'a>],
+        frame: &Frame<'a>,
+    ) -> Result<Value<'a>, Error> {
+        match index {
+            [index] => self.eval(index, frame).await,
+            _ => Err(ErrorKind::InvalidIndex {
+                index: format!("{} values", index.len()),
+                base: "subscript".to_owned(),
+            }
+            .into()),
+        }
+    }
+
+    async fn eval_binary(
+        &self,
+        left: &'a ASTExpr<'a>,
+        op: &'a ASTBinaryOp<'a>,
+        right: &'a ASTExpr<'a>,
+        frame: &Frame<'a>,
+    ) -> Result<Value<'a>, Error> {
+        match op {
+            ASTBinaryOp::PropertyAccess => {
+                let base = self.eval(left, frame).await?;
+
+                self.eval_member(base, right, frame).await
+            }
+            ASTBinaryOp::And => Ok(Value::Bool(
+                self.eval(left, frame).await?.is_truthy()
+                    && self.eval(right, frame).await?.is_truthy(),
+            )),
+            ASTBinaryOp::Or => Ok(Value::Bool(
+                self.eval(left, frame).await?.is_truthy()
+                    || self.eval(right, frame).await?.is_truthy(),
+            )),
+            ASTBinaryOp::TernaryIfElse(condition) => {
+                if self.eval(condition, frame).await?.is_truthy() {
+                    self.eval(left, frame).await
+                } else {
+                    self.eval(right, frame).await
+                }
+            }
+            ASTBinaryOp::TypeCheck => {
+                let value = self.eval(left, frame).await?;
+
+                Ok(Value::Bool(self.is_instance(&value, right, frame).await?))
+            }
+            ASTBinaryOp::TypeCast => {
+                let value = self.eval(left, frame).await?;
+
+                self.cast(value, right, frame).await
+            }
+            ASTBinaryOp::Range | ASTBinaryOp::TernaryIfElsePlaceholder => {
+                Err(ErrorKind::Unsupported("The `..` operator".to_owned()).into())
+            }
+            op if op.is_any_assignment() => {
+                self.eval_assignment(left, op, right, frame).await?;
+
+                Ok(Value::Null)
+            }
+            op => {
+                let left = self.eval(left, frame).await?;
+                let right = self.eval(right, frame).await?;
+
+                operators::binary(op, left, right)
+            }
+        }
+    }
+
+    /// Evaluate the right side of `base.member`. Besides plain properties,
+    /// the parser puts subscripts of a property there, as in `base.member[0]`.
+    fn eval_member(&self, base: Value<'a>, member: &'a ASTExpr<'a>, frame: &Frame<'a>) -> Eval<'a> {
+        let this = self.clone();
+        let frame = frame.clone();
+
+        Box::pin(async move {
+            match &member.kind {
+                ASTExprKind::Identifier(name) => this.get_property(&base, name).await,
+                ASTExprKind::PostfixExpr(inner, op) => match &op.kind {
+                    ASTPostfixOpKind::Subscript(index) => {
+                        let value = this.eval_member(base, inner, &frame).await?;
+                        let index = this.eval_subscript(index, &frame).await?;
+
+                        operators::index(&value, &index)
+                    }
+                    ASTPostfixOpKind::Call(args) => match inner.kind {
+                        ASTExprKind::Identifier(name) => {
+                            let args = this.eval_all(args, &frame).await?;
+
+                            this.call_method(&base, name, args, false).await
+                        }
+                        _ => Err(ErrorKind::NotCallable(base.type_name()).into()),
+                    },
+                },
+                _ => Err(ErrorKind::InvalidProperty {
+                    name: "<expression>".to_owned(),
+                    base: base.type_name(),
+                }
+                .into()),
+            }
+            .map_err(|err| err.at(&member.span))
+        })
+    }
+
+    /// Resolve an identifier. Locals shadow members of `self`, which shadow
+    /// constants of the class, which shadow globals.
+    async fn lookup(&self, name: &'a str, frame: &Frame<'a>) -> Result<Value<'a>, Error> {
+        if name == "self" {
+            return frame
+                .this
+                .clone()
+                .map(Value::Object)
+                .ok_or_else(|| ErrorKind::UndefinedIdentifier(name.to_owned()).into());
+        }
+
+        if let Some(value) = frame.local(name) {
+            return Ok(value);
+        }
+
+        if let Some(object) = &frame.this {
+            let class = object.class();
+
+            if object.field(name).is_some() {
+                return self
+                    .get_property(&Value::Object(object.clone()), name)
+                    .await;
+            }
+
+            if class.function(name).is_some() {
+                return Ok(Value::Callable(Callable::method(
+                    Value::Object(object.clone()),
+                    name,
+                )));
+            }
+
+            if class.has_signal(name) {
+                return Ok(Value::Signal(Signal {
+                    object: object.clone(),
+                    name: name.into(),
+                }));
+            }
+        } else if frame.class.function(name).is_some() {
+            return Ok(Value::Callable(Callable::method(
+                Value::Class(frame.class.clone()),
+                name,
+            )));
+        }
+
+        if let Some(value) = frame.class.resolve(name).or_else(|| self.global(name)) {
+            return Ok(value);
+        }
+
+        if let Some(value) = stdlib::constant(name) {
+            return Ok(value);
+        }
+
+        if let Some(ty) = Type::from_name(name) {
+            return Ok(Value::Type(ty));
+        }
+
+        if stdlib::functions::exists(name) {
+            return Ok(Value::Callable(Callable::builtin(name)));
+        }
+
+        Err(ErrorKind::UndefinedIdentifier(name.to_owned()).into())
+    }
+
+    async fn eval_assignment(
+        &self,
+        target: &'a ASTExpr<'a>,
+        op: &'a ASTBinaryOp<'a>,
+        value: &'a ASTExpr<'a>,
+        frame: &Frame<'a>,
+    ) -> Result<(), Error> {
+        let value = match operators::compound(op) {
+            Some(op) => {
+                let current = self.eval(target, frame).await?;
+                let value = self.eval(value, frame).await?;
+
+                operators::binary(&op, current, value)?
+            }
+            None => self.eval(value, frame).await?,
+        };
+
+        self.assign(target, value, frame).await
+    }
+
+    async fn assign(
+        &self,
+        target: &'a ASTExpr<'a>,
+        value: Value<'a>,
+        frame: &Frame<'a>,
+    ) -> Result<(), Error> {
+        match &target.kind {
+            ASTExprKind::Identifier(name) => {
+                if frame.set_local(name, value.clone()) {
+                    return Ok(());
+                }
+
+                if let Some(object) = &frame.this {
+                    if object.class().variable(name).is_some() {
+                        return self
+                            .set_property(&Value::Object(object.clone()), name, value)
+                            .await;
+                    }
+                }
+
+                if frame.class.set_static(name, value) {
+                    Ok(())
+                } else if frame.class.resolve(name).is_some() || self.global(name).is_some() {
+                    Err(ErrorKind::AssignToConstant((*name).to_owned()).into())
+                } else {
+                    Err(ErrorKind::UndefinedIdentifier((*name).to_owned()).into())
+                }
+            }
+            ASTExprKind::BinaryExpr(base, ASTBinaryOp::PropertyAccess, member) => {
+                let base = self.eval(base, frame).await?;
+
+                match &member.kind {
+                    ASTExprKind::Identifier(name) => self.set_property(&base, name, value).await,
+                    ASTExprKind::PostfixExpr(
+                        inner,
+                        ASTPostfixOp {
+                            kind: ASTPostfixOpKind::Subscript(index),
+                            ..
+                        },
+                    ) => {
+                        let container = self.eval_member(base, inner, frame).await?;
+                        let index = self.eval_subscript(index, frame).await?;
+
+                        operators::set_index(&container, index, value)
+                    }
+                    _ => Err(ErrorKind::InvalidAssignment.into()),
+                }
+            }
+            ASTExprKind::PostfixExpr(
+                base,
+                ASTPostfixOp {
+                    kind: ASTPostfixOpKind::Subscript(index),
+                    ..
+                },
+            ) => {
+                let container = self.eval(base, frame).await?;
+                let index = self.eval_subscript(index, frame).await?;
+
+                operators::set_index(&container, index, value)
+            }
+            _ => Err(ErrorKind::InvalidAssignment.into()),
+        }
+        .map_err(|err| err.at(&target.span))
+    }
+
+    async fn eval_call(
+        &self,
+        callee: &'a ASTExpr<'a>,
+        args: &'a [ASTExpr<'a>],
+        frame: &Frame<'a>,
+        awaited: bool,
+    ) -> Result<Value<'a>, Error> {
+        match &callee.kind {
+            ASTExprKind::Identifier("super") => {
+                let args = self.eval_all(args, frame).await?;
+
+                self.call_super(frame.function.unwrap_or("_init"), args, frame, awaited)
+                    .await
+            }
+            ASTExprKind::Identifier(name) => {
+                let args = self.eval_all(args, frame).await?;
+
+                self.call_global(name, args, frame, awaited).await
+            }
+            ASTExprKind::BinaryExpr(base, ASTBinaryOp::PropertyAccess, member) => {
+                let ASTExprKind::Identifier(name) = member.kind else {
+                    let callee = self.eval(callee, frame).await?;
+                    return Err(ErrorKind::NotCallable(callee.type_name()).into());
+                };
+
+                if let ASTExprKind::Identifier("super") = base.kind {
+                    let args = self.eval_all(args, frame).await?;
+
+                    return self.call_super(name, args, frame, awaited).await;
+                }
+
+                let receiver = self.eval(base, frame).await?;
+                let args = self.eval_all(args, frame).await?;
+
+                self.call_method(&receiver, name, args, awaited)
+                    .await
+                    .map_err(|err| err.at(&member.span))
+            }
+            _ => {
+                let callee = self.eval(callee, frame).await?;
+
+                Err(ErrorKind::NotCallable(callee.type_name()).into())
+            }
+        }
+    }
+
+    async fn eval_await(
+        &self,
+        expr: &'a ASTExpr<'a>,
+        frame: &Frame<'a>,
+    ) -> Result<Value<'a>, Error> {
+        match &expr.kind {
+            ASTExprKind::PostfixExpr(
+                callee,
+                ASTPostfixOp {
+                    kind: ASTPostfixOpKind::Call(args),
+                    ..
+                },
+            ) => self.eval_call(callee, args, frame, true).await,
+            _ => match self.eval(expr, frame).await? {
+                Value::Signal(signal) => self.wait_for_signal(&signal).await,
+                // Awaiting anything else is a no-op.
+                value => Ok(value),
+            },
+        }
+    }
+
+    pub(crate) async fn wait_for_signal(&self, signal: &Signal<'a>) -> Result<Value<'a>, Error> {
+        if !signal.object.class().has_signal(&signal.name) {
+            return Err(ErrorKind::UndefinedSignal {
+                name: signal.name.to_string(),
+                base: signal.object.class().name.clone(),
+            }
+            .into());
+        }
+
+        let slot = Rc::new(RefCell::new(None));
+
+        signal
+            .object
+            .borrow_mut()
+            .awaiters
+            .entry(signal.name.to_string())
+            .or_default()
+            .push(slot.clone());
+
+        Ok(self.wait(slot).await)
+    }
+
+    /// Whether `value is ty`. Engine classes aren't available, so checks
+    /// against them only look at the engine class a script extends.
+    async fn is_instance(
+        &self,
+        value: &Value<'a>,
+        ty: &'a ASTExpr<'a>,
+        frame: &Frame<'a>,
+    ) -> Result<bool, Error> {
+        if let Some(native) = self.native_class(ty, frame) {
+            return Ok(matches!(value, Value::Object(object) if object.class().native() == native));
+        }
+
+        match self.eval(ty, frame).await? {
+            Value::Type(Type::Object) => Ok(matches!(value, Value::Object(_))),
+            Value::Type(ty) => Ok(value.type_of() == ty),
+            Value::Class(class) => {
+                Ok(matches!(value, Value::Object(object) if object.class().inherits(&class)))
+            }
+            other => Err(ErrorKind::InvalidArgument {
+                function: "is".to_owned(),
+                message: format!("{} is not a type", other.repr()),
+            }
+            .into()),
+        }
+    }
+
+    async fn cast(
+        &self,
+        value: Value<'a>,
+        ty: &'a ASTExpr<'a>,
+        frame: &Frame<'a>,
+    ) -> Result<Value<'a>, Error> {
+        if self.native_class(ty, frame).is_some() {
+            return Ok(if self.is_instance(&value, ty, frame).await? {
+                value
+            } else {
+                Value::Null
+            });
+        }
+
+        match self.eval(ty, frame).await? {
+            Value::Type(Type::Object) | Value::Class(_) => {
+                Ok(if self.is_instance(&value, ty, frame).await? {
+                    value
+                } else {
+                    Value::Null
+                })
+            }
+            Value::Type(ty) if value.type_of() == ty => Ok(value),
+            Value::Type(
+                ty @ (Type::Bool | Type::Int | Type::Float | Type::String | Type::StringName),
+            ) => stdlib::functions::construct(ty, vec![value]),
+            Value::Type(ty) => Err(ErrorKind::InvalidArgument {
+                function: "as".to_owned(),
+                message: format!("cannot convert {} to {}", value.type_name(), ty.name()),
+            }
+            .into()),
+            other => Err(ErrorKind::InvalidArgument {
+                function: "as".to_owned(),
+                message: format!("{} is not a type", other.repr()),
+            }
+            .into()),
+        }
+    }
+
+    /// The name of the engine class `ty` refers to, if it doesn't refer to
+    /// anything else.
+    fn native_class(&self, ty: &'a ASTExpr<'a>, frame: &Frame<'a>) -> Option<&'a str> {
+        let ASTExprKind::Identifier(name) = ty.kind else {
+            return None;
+        };
+
+        let defined = frame.local(name).is_some()
+            || frame.class.resolve(name).is_some()
+            || self.global(name).is_some()
+            || Type::from_name(name).is_some();
+
+        (!defined).then_some(name)
+    }
+}
+
+/// Process escape sequences of a string literal.
+pub(crate) fn unescape(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut chars = input.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            output.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n') => output.push('\n'),
+            Some('t') => output.push('\t'),
+            Some('r') => output.push('\r'),
+            Some('0') => output.push('\0'),
+            Some('u') => {
+                let code = chars.by_ref().take(4).collect::<String>();
+
+                match u32::from_str_radix(&code, 16).ok().and_then(char::from_u32) {
+                    Some(c) => output.push(c),
+                    None => {
+                        output.push_str("\\u");
+                        output.push_str(&code);
+                    }
+                }
+            }
+            // Line continuations.
+            Some('\n') => (),
+            Some(other) => output.push(other),
+            None => output.push('\\'),
+        }
+    }
+
+    output
+}
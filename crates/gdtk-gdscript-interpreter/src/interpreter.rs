@@ -0,0 +1,612 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    future::Future,
+    io::Write,
+    pin::Pin,
+    rc::Rc,
+};
+
+use gdtk_gdscript_ast::{ASTEnumStmt, ASTExpr, ASTExprKind, ASTFile, ASTStatement, ASTVariable};
+
+use crate::{
+    class::{Base, Class},
+    error::{Error, ErrorKind},
+    value::{Callable, Dictionary, Object, Type, Value},
+};
+
+/// A boxed future of the interpreter. Evaluation is asynchronous so that
+/// `await` can suspend a coroutine until the scheduler resumes it.
+pub type Eval<'a, T = Value<'a>> = Pin<Box<dyn Future<Output = Result<T, Error>> + 'a>>;
+
+/// A method of an engine class, implemented by the host. It's called with the
+/// object and the arguments.
+pub type NativeMethod<'a> =
+    Rc<dyn Fn(Interpreter<'a>, Object<'a>, Vec<Value<'a>>) -> Eval<'a> + 'a>;
+
+/// The maximum depth of nested function calls.
+pub const MAX_CALL_DEPTH: usize = 256;
+
+/// A GDScript interpreter.
+///
+/// Cloning an interpreter is cheap, and all clones share the same state.
+#[derive(Clone)]
+pub struct Interpreter<'a>(pub(crate) Rc<State<'a>>);
+
+pub(crate) struct State<'a> {
+    /// Classes with a `class_name`, by name.
+    pub globals: RefCell<HashMap<String, Value<'a>>>,
+    pub output: RefCell<Box<dyn Write + 'a>>,
+    /// Where `printerr`, `push_error` and `push_warning` write to.
+    pub errors: RefCell<Box<dyn Write + 'a>>,
+    /// Coroutines that were suspended and are left to the scheduler.
+    pub tasks: RefCell<Vec<Eval<'a>>>,
+    /// Bumped every time something happens that may let a suspended
+    /// coroutine continue, like a signal being emitted.
+    pub progress: Cell<u64>,
+    pub depth: Cell<usize>,
+    /// The state of the random number generator.
+    pub rng: Cell<u64>,
+    /// Methods given to engine classes, by class and method name.
+    pub natives: RefCell<HashMap<(String, String), NativeMethod<'a>>>,
+}
+
+/// An output that keeps everything written to it, for hosts that show what
+/// scripts print themselves. Clones share the same buffer, so one can be given
+/// to [Interpreter::with_output] and the other read from.
+#[derive(Clone, Default)]
+pub struct Capture(Rc<RefCell<Vec<u8>>>);
+
+impl Capture {
+    /// Take everything written so far, leaving the buffer empty.
+    pub fn take(&self) -> String {
+        String::from_utf8_lossy(&self.0.take()).into_owned()
+    }
+}
+
+impl Write for Capture {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Default for Interpreter<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> Interpreter<'a> {
+    /// Create an interpreter that prints to stdout, and prints errors to
+    /// stderr.
+    pub fn new() -> Self {
+        Self::with_output(std::io::stdout())
+    }
+
+    /// Create an interpreter that prints to `output`, and prints errors to
+    /// stderr.
+    pub fn with_output(output: impl Write + 'a) -> Self {
+        Self::with_outputs(output, std::io::stderr())
+    }
+
+    /// Create an interpreter that prints to `output`, and prints errors to
+    /// `errors`.
+    pub fn with_outputs(output: impl Write + 'a, errors: impl Write + 'a) -> Self {
+        let interpreter = Self(Rc::new(State {
+            globals: RefCell::new(HashMap::new()),
+            output: RefCell::new(Box::new(output)),
+            errors: RefCell::new(Box::new(errors)),
+            tasks: RefCell::new(vec![]),
+            progress: Cell::new(0),
+            depth: Cell::new(0),
+            rng: Cell::new(0),
+            natives: RefCell::new(HashMap::new()),
+        }));
+
+        interpreter.seed(None);
+        interpreter
+    }
+
+    /// Load a script. If it has a `class_name`, it becomes available to
+    /// scripts loaded afterwards. `name` is used if it doesn't.
+    pub fn load(&self, file: &'a ASTFile<'a>, name: &str) -> Result<Rc<Class<'a>>, Error> {
+        let class = self.load_class(name.to_owned(), None, file.body.iter(), None)?;
+
+        let has_class_name = file
+            .body
+            .iter()
+            .any(|stmt| matches!(stmt, ASTStatement::ClassName(_)));
+
+        if has_class_name {
+            self.set_global(&class.name.clone(), Value::Class(class.clone()));
+        }
+
+        Ok(class)
+    }
+
+    pub fn global(&self, name: &str) -> Option<Value<'a>> {
+        self.0.globals.borrow().get(name).cloned()
+    }
+
+    pub fn set_global(&self, name: &str, value: Value<'a>) {
+        self.0.globals.borrow_mut().insert(name.to_owned(), value);
+    }
+
+    /// Give objects extending the engine class `class` a method. This is
+    /// how hosts give engine classes behavior.
+    pub fn define_native_method(
+        &self,
+        class: &str,
+        name: &str,
+        method: impl Fn(Interpreter<'a>, Object<'a>, Vec<Value<'a>>) -> Eval<'a> + 'a,
+    ) {
+        self.0
+            .natives
+            .borrow_mut()
+            .insert((class.to_owned(), name.to_owned()), Rc::new(method));
+    }
+
+    pub(crate) fn native_method(&self, class: &str, name: &str) -> Option<NativeMethod<'a>> {
+        self.0
+            .natives
+            .borrow()
+            .get(&(class.to_owned(), name.to_owned()))
+            .cloned()
+    }
+
+    /// Call `callable` from a native method. Unlike [Interpreter::call], this
+    /// doesn't start the scheduler, which is already running.
+    pub fn invoke(&self, callable: Callable<'a>, args: Vec<Value<'a>>) -> Eval<'a> {
+        let this = self.clone();
+
+        Box::pin(async move { this.call_callable(&callable, args, true).await })
+    }
+
+    /// Create an instance of `class`, as `class.new(args)` would.
+    pub fn instantiate(
+        &self,
+        class: &Rc<Class<'a>>,
+        args: Vec<Value<'a>>,
+    ) -> Result<Object<'a>, Error> {
+        let this = self.clone();
+        let class = class.clone();
+
+        let value = self.block_on(Box::pin(async move {
+            this.instantiate_async(&class, args).await
+        }))?;
+
+        match value {
+            Value::Object(object) => Ok(object),
+            _ => unreachable!("instantiation always returns an object"),
+        }
+    }
+
+    /// Call a method of `receiver`, running every coroutine it starts.
+    pub fn call(
+        &self,
+        receiver: &Value<'a>,
+        method: &str,
+        args: Vec<Value<'a>>,
+    ) -> Result<Value<'a>, Error> {
+        let this = self.clone();
+        let receiver = receiver.clone();
+        let method = method.to_owned();
+
+        self.block_on(Box::pin(async move {
+            this.call_method(&receiver, &method, args, true).await
+        }))
+    }
+
+    /// Write `text` to the output.
+    pub(crate) fn print(&self, text: &str) {
+        // Printing is best-effort, like it is in the engine.
+        let _ = writeln!(self.0.output.borrow_mut(), "{text}");
+    }
+
+    /// Write `text` to the error output.
+    pub(crate) fn print_error(&self, text: &str) {
+        let _ = writeln!(self.0.errors.borrow_mut(), "{text}");
+    }
+
+    /// The next number of the random number generator (xorshift64*).
+    pub(crate) fn random(&self) -> u64 {
+        let mut x = self.0.rng.get();
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        self.0.rng.set(x);
+
+        x.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// Seed the random number generator, using the current time if `seed` is
+    /// `None`.
+    pub(crate) fn seed(&self, seed: Option<u64>) {
+        let seed = seed.unwrap_or_else(|| {
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_or(0, |duration| duration.as_nanos() as u64)
+        });
+
+        // Xorshift gets stuck on zero.
+        self.0.rng.set((seed ^ 0x9E37_79B9_7F4A_7C15).max(1));
+    }
+
+    pub(crate) fn load_class(
+        &self,
+        name: String,
+        extends: Option<&'a ASTExpr<'a>>,
+        body: impl Iterator<Item = &'a ASTStatement<'a>> + Clone,
+        outer: Option<&Rc<Class<'a>>>,
+    ) -> Result<Rc<Class<'a>>, Error> {
+        let mut name = name;
+        let mut extends = extends;
+        let mut variables = vec![];
+        let mut functions = HashMap::new();
+        let mut signals = vec![];
+
+        for stmt in body.clone() {
+            match stmt {
+                ASTStatement::ClassName(stmt) if outer.is_none() => {
+                    if let ASTExprKind::Identifier(class_name) = stmt.identifier.kind {
+                        name = class_name.to_owned();
+                    }
+                }
+                ASTStatement::Extends(stmt) => extends = Some(&stmt.identifier),
+                ASTStatement::Variable(variable) if variable.kind.is_regular() => {
+                    variables.push(variable);
+                }
+                ASTStatement::Func(function) => {
+                    if let Some(ASTExprKind::Identifier(function_name)) =
+                        function.identifier.as_deref().map(|expr| &expr.kind)
+                    {
+                        functions.insert(*function_name, function);
+                    }
+                }
+                ASTStatement::Signal(signal) => {
+                    if let ASTExprKind::Identifier(signal_name) = signal.identifier.kind {
+                        signals.push(signal_name);
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        let base = match extends {
+            Some(expr) => self.resolve_base(expr, outer)?,
+            None => Base::Native("RefCounted".to_owned()),
+        };
+
+        let class = Rc::new(Class {
+            name,
+            base,
+            variables,
+            functions,
+            signals,
+            constants: RefCell::new(HashMap::new()),
+            statics: RefCell::new(HashMap::new()),
+            outer: outer.map(Rc::downgrade),
+        });
+
+        let frame = Frame::new(class.clone(), None);
+
+        for stmt in body {
+            match stmt {
+                ASTStatement::Variable(variable) if variable.kind.is_constant() => {
+                    let value = self.now(self.initial_value(variable, &frame))?;
+                    class
+                        .constants
+                        .borrow_mut()
+                        .insert(variable_name(variable), value);
+                }
+                ASTStatement::Variable(variable) if variable.kind.is_static() => {
+                    let value = self.now(self.initial_value(variable, &frame))?;
+                    class
+                        .statics
+                        .borrow_mut()
+                        .insert(variable_name(variable), value);
+                }
+                ASTStatement::Enum(enum_) => {
+                    let constants = self.now(self.eval_enum(enum_, &frame))?;
+                    class.constants.borrow_mut().extend(constants);
+                }
+                ASTStatement::Class(inner) => {
+                    let ASTExprKind::Identifier(inner_name) = inner.identifier.kind else {
+                        continue;
+                    };
+
+                    let inner = self.load_class(
+                        inner_name.to_owned(),
+                        inner.extends.as_ref(),
+                        inner.body.iter(),
+                        Some(&class),
+                    )?;
+
+                    class
+                        .constants
+                        .borrow_mut()
+                        .insert(inner_name, Value::Class(inner));
+                }
+                _ => (),
+            }
+        }
+
+        Ok(class)
+    }
+
+    fn resolve_base(
+        &self,
+        expr: &'a ASTExpr<'a>,
+        outer: Option<&Rc<Class<'a>>>,
+    ) -> Result<Base<'a>, Error> {
+        let value = match &expr.kind {
+            ASTExprKind::Identifier(name) => {
+                let value = outer
+                    .and_then(|outer| outer.resolve(name))
+                    .or_else(|| self.global(name));
+
+                match value {
+                    Some(value) => value,
+                    None => return Ok(Base::Native((*name).to_owned())),
+                }
+            }
+            ASTExprKind::BinaryExpr(base, gdtk_gdscript_ast::ASTBinaryOp::PropertyAccess, name) => {
+                let Base::Script(base) = self.resolve_base(base, outer)? else {
+                    return Err(Error::from(ErrorKind::Unsupported(
+                        "Extending inner classes of engine classes".to_owned(),
+                    ))
+                    .at(&expr.span));
+                };
+
+                let name = name.kind.as_identifier().copied().unwrap_or_default();
+
+                base.constant(name).ok_or_else(|| {
+                    Error::from(ErrorKind::UndefinedIdentifier(name.to_owned())).at(&expr.span)
+                })?
+            }
+            _ => {
+                return Err(Error::from(ErrorKind::Unsupported(
+                    "Extending scripts by path".to_owned(),
+                ))
+                .at(&expr.span));
+            }
+        };
+
+        match value {
+            Value::Class(class) => Ok(Base::Script(class)),
+            other => Err(Error::from(ErrorKind::InvalidArgument {
+                function: "extends".to_owned(),
+                message: format!("expected a class, found {}", other.type_name()),
+            })
+            .at(&expr.span)),
+        }
+    }
+
+    /// Evaluate the variants of an enum. Named enums become a dictionary, and
+    /// variants of unnamed ones become constants of their own.
+    pub(crate) async fn eval_enum(
+        &self,
+        enum_: &'a ASTEnumStmt<'a>,
+        frame: &Frame<'a>,
+    ) -> Result<Vec<(&'a str, Value<'a>)>, Error> {
+        let mut variants = vec![];
+        let mut next = 0;
+
+        for variant in &enum_.variants {
+            let value = match &variant.value {
+                Some(expr) => match self.eval(expr, frame).await? {
+                    Value::Int(value) => value,
+                    other => {
+                        return Err(Error::from(ErrorKind::InvalidArgument {
+                            function: "enum".to_owned(),
+                            message: format!("expected an int, found {}", other.type_name()),
+                        })
+                        .at(&expr.span))
+                    }
+                },
+                None => next,
+            };
+
+            next = value.wrapping_add(1);
+
+            let name = variant
+                .identifier
+                .kind
+                .as_identifier()
+                .copied()
+                .unwrap_or_default();
+            variants.push((name, Value::Int(value)));
+        }
+
+        match enum_.identifier.as_ref().map(|expr| &expr.kind) {
+            Some(ASTExprKind::Identifier(name)) => {
+                let entries = variants
+                    .into_iter()
+                    .map(|(name, value)| (Value::string(name), value))
+                    .collect();
+
+                Ok(vec![(*name, Value::Dictionary(Dictionary::new(entries)))])
+            }
+            _ => Ok(variants),
+        }
+    }
+
+    pub(crate) async fn instantiate_async(
+        &self,
+        class: &Rc<Class<'a>>,
+        args: Vec<Value<'a>>,
+    ) -> Result<Value<'a>, Error> {
+        let object = Object::new(class.clone());
+
+        for ancestor in class.ancestors().into_iter().rev() {
+            let frame = Frame::new(ancestor.clone(), Some(object.clone()));
+
+            for variable in &ancestor.variables {
+                let value = self.initial_value(variable, &frame).await?;
+                object
+                    .borrow_mut()
+                    .fields
+                    .insert(variable_name(variable), value);
+            }
+        }
+
+        match class.function("_init") {
+            Some((defining, function)) => {
+                self.call_function(function, Some(object.clone()), defining, args, true)
+                    .await?;
+            }
+            None if !args.is_empty() => {
+                return Err(ErrorKind::ArgumentCount {
+                    function: "new".to_owned(),
+                    expected: "0".to_owned(),
+                    received: args.len(),
+                }
+                .into());
+            }
+            None => (),
+        }
+
+        Ok(Value::Object(object))
+    }
+
+    /// The value of a variable when it's declared.
+    pub(crate) async fn initial_value(
+        &self,
+        variable: &'a ASTVariable<'a>,
+        frame: &Frame<'a>,
+    ) -> Result<Value<'a>, Error> {
+        let ty = variable.typehint.as_ref().and_then(typehint);
+
+        match &variable.value {
+            Some(expr) => Ok(coerce(ty, self.eval(expr, frame).await?)),
+            None => Ok(ty.map(Type::default_value).unwrap_or_default()),
+        }
+    }
+}
+
+/// The built-in type a typehint refers to, if any.
+pub(crate) fn typehint(expr: &ASTExpr<'_>) -> Option<Type> {
+    match &expr.kind {
+        ASTExprKind::Identifier(name) => Type::from_name(name),
+        // Typed collections, like `Array[int]`.
+        ASTExprKind::PostfixExpr(base, _) => typehint(base),
+        _ => None,
+    }
+}
+
+/// Convert numbers assigned to variables of the other numeric type.
+pub(crate) fn coerce(ty: Option<Type>, value: Value<'_>) -> Value<'_> {
+    match (ty, value) {
+        (Some(Type::Float), Value::Int(value)) => Value::Float(value as f64),
+        (Some(Type::Int), Value::Float(value)) => Value::Int(value as i64),
+        (_, value) => value,
+    }
+}
+
+pub(crate) fn variable_name<'a>(variable: &ASTVariable<'a>) -> &'a str {
+    variable
+        .identifier
+        .kind
+        .as_identifier()
+        .copied()
+        .unwrap_or_default()
+}
+
+/// The state of a function call.
+#[derive(Clone)]
+pub(crate) struct Frame<'a> {
+    /// The object the function was called on, if it isn't static.
+    pub this: Option<Object<'a>>,
+    /// The class that defines the function.
+    pub class: Rc<Class<'a>>,
+    /// The name of the function, for `super()`.
+    pub function: Option<&'a str>,
+    /// Local variables, by block.
+    locals: Rc<RefCell<Vec<HashMap<&'a str, Value<'a>>>>>,
+}
+
+impl<'a> Frame<'a> {
+    pub fn new(class: Rc<Class<'a>>, this: Option<Object<'a>>) -> Self {
+        Self {
+            this,
+            class,
+            function: None,
+            locals: Rc::new(RefCell::new(vec![HashMap::new()])),
+        }
+    }
+
+    pub fn push_scope(&self) {
+        self.locals.borrow_mut().push(HashMap::new());
+    }
+
+    pub fn pop_scope(&self) {
+        self.locals.borrow_mut().pop();
+    }
+
+    pub fn declare(&self, name: &'a str, value: Value<'a>) {
+        if let Some(scope) = self.locals.borrow_mut().last_mut() {
+            scope.insert(name, value);
+        }
+    }
+
+    pub fn local(&self, name: &str) -> Option<Value<'a>> {
+        self.locals
+            .borrow()
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name).cloned())
+    }
+
+    /// Set an existing local variable. Returns whether it exists.
+    pub fn set_local(&self, name: &str, value: Value<'a>) -> bool {
+        let mut locals = self.locals.borrow_mut();
+
+        match locals
+            .iter_mut()
+            .rev()
+            .find_map(|scope| scope.get_mut(name))
+        {
+            Some(local) => {
+                *local = value;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// A copy of the frame with the current values of its locals. Lambdas
+    /// capture their environment this way.
+    pub fn capture(&self) -> Self {
+        let captured = self
+            .locals
+            .borrow()
+            .iter()
+            .flatten()
+            .map(|(name, value)| (*name, value.clone()))
+            .collect();
+
+        Self {
+            this: self.this.clone(),
+            class: self.class.clone(),
+            function: None,
+            locals: Rc::new(RefCell::new(vec![captured])),
+        }
+    }
+
+    /// A fresh frame for a call to a captured lambda.
+    pub fn enter(&self) -> Self {
+        let mut locals = self.locals.borrow().clone();
+        locals.push(HashMap::new());
+
+        Self {
+            this: self.this.clone(),
+            class: self.class.clone(),
+            function: None,
+            locals: Rc::new(RefCell::new(locals)),
+        }
+    }
+}
@@ -0,0 +1,29 @@
+//! A tree-walking GDScript interpreter.
+//!
+//! Scripts run outside of the engine: classes may extend engine classes, but
+//! those have no behavior beyond what [Object](value::Object) itself offers.
+//! Coroutines are supported, and `await`ing a signal suspends the caller until
+//! the signal is emitted.
+
+pub mod class;
+pub mod error;
+pub mod interpreter;
+pub mod operators;
+pub mod session;
+pub mod stdlib;
+pub mod value;
+
+mod calls;
+mod expressions;
+mod scheduler;
+mod statements;
+#[cfg(test)]
+mod tests;
+
+pub use crate::{
+    class::Class,
+    error::{Error, ErrorKind},
+    interpreter::{Capture, Eval, Interpreter, NativeMethod},
+    session::Session,
+    value::{Callable, Object, Type, Value},
+};
@@ -0,0 +1,284 @@
+//! Operators on values.
+
+use std::cmp::Ordering;
+
+use gdtk_gdscript_ast::{ASTBinaryOp, ASTPrefixOpKind};
+
+use crate::{
+    error::{Error, ErrorKind},
+    stdlib::format::format,
+    value::Value,
+};
+
+/// Apply an unary operator, except `await`.
+pub fn unary<'a>(op: ASTPrefixOpKind, value: Value<'a>) -> Result<Value<'a>, Error> {
+    Ok(match (op, value) {
+        (ASTPrefixOpKind::Not, value) => Value::Bool(!value.is_truthy()),
+        (ASTPrefixOpKind::Identity, value @ (Value::Int(_) | Value::Float(_))) => value,
+        (ASTPrefixOpKind::Negation, Value::Int(value)) => Value::Int(value.wrapping_neg()),
+        (ASTPrefixOpKind::Negation, Value::Float(value)) => Value::Float(-value),
+        (ASTPrefixOpKind::BitwiseNot, Value::Int(value)) => Value::Int(!value),
+        (op, value) => {
+            return Err(ErrorKind::InvalidOperand {
+                op: match op {
+                    ASTPrefixOpKind::Identity => "+",
+                    ASTPrefixOpKind::Negation => "-",
+                    ASTPrefixOpKind::BitwiseNot => "~",
+                    ASTPrefixOpKind::Not | ASTPrefixOpKind::Await => "not",
+                },
+                operand: value.type_name(),
+            }
+            .into())
+        }
+    })
+}
+
+/// Apply a binary operator that evaluates both of its operands.
+pub fn binary<'a>(
+    op: &ASTBinaryOp<'a>,
+    left: Value<'a>,
+    right: Value<'a>,
+) -> Result<Value<'a>, Error> {
+    let invalid = |left: &Value<'a>, right: &Value<'a>| -> Error {
+        ErrorKind::InvalidOperands {
+            op: symbol(op),
+            left: left.type_name(),
+            right: right.type_name(),
+        }
+        .into()
+    };
+
+    Ok(match op {
+        ASTBinaryOp::Equals => Value::Bool(left.equals(&right)),
+        ASTBinaryOp::NotEqual => Value::Bool(!left.equals(&right)),
+        ASTBinaryOp::LessThan
+        | ASTBinaryOp::LessOrEqual
+        | ASTBinaryOp::Greater
+        | ASTBinaryOp::GreaterOrEqual => {
+            let ordering = compare(&left, &right).ok_or_else(|| invalid(&left, &right))?;
+
+            Value::Bool(match op {
+                ASTBinaryOp::LessThan => ordering.is_lt(),
+                ASTBinaryOp::LessOrEqual => ordering.is_le(),
+                ASTBinaryOp::Greater => ordering.is_gt(),
+                _ => ordering.is_ge(),
+            })
+        }
+        ASTBinaryOp::Contains => Value::Bool(contains(&left, &right)?),
+        ASTBinaryOp::NotContains => Value::Bool(!contains(&left, &right)?),
+        ASTBinaryOp::Add => match (&left, &right) {
+            (Value::Int(a), Value::Int(b)) => Value::Int(a.wrapping_add(*b)),
+            (Value::Array(a), Value::Array(b)) => {
+                let mut values = a.to_vec();
+                values.extend(b.to_vec());
+
+                Value::array(values)
+            }
+            _ => match (left.as_str(), right.as_str()) {
+                (Some(a), Some(b)) => Value::string(format!("{a}{b}")),
+                _ => float_op(&left, &right, |a, b| a + b).ok_or_else(|| invalid(&left, &right))?,
+            },
+        },
+        ASTBinaryOp::Subtract => match (&left, &right) {
+            (Value::Int(a), Value::Int(b)) => Value::Int(a.wrapping_sub(*b)),
+            _ => float_op(&left, &right, |a, b| a - b).ok_or_else(|| invalid(&left, &right))?,
+        },
+        ASTBinaryOp::Multiply => match (&left, &right) {
+            (Value::Int(a), Value::Int(b)) => Value::Int(a.wrapping_mul(*b)),
+            _ => float_op(&left, &right, |a, b| a * b).ok_or_else(|| invalid(&left, &right))?,
+        },
+        ASTBinaryOp::Divide => match (&left, &right) {
+            (Value::Int(_), Value::Int(0)) => return Err(ErrorKind::DivisionByZero("/").into()),
+            (Value::Int(a), Value::Int(b)) => Value::Int(a.wrapping_div(*b)),
+            _ => float_op(&left, &right, |a, b| a / b).ok_or_else(|| invalid(&left, &right))?,
+        },
+        ASTBinaryOp::Remainder => match (&left, &right) {
+            (Value::Int(_), Value::Int(0)) => return Err(ErrorKind::DivisionByZero("%").into()),
+            (Value::Int(a), Value::Int(b)) => Value::Int(a.wrapping_rem(*b)),
+            (template, args) if template.as_str().is_some() => {
+                let args = match args {
+                    Value::Array(array) => array.to_vec(),
+                    other => vec![other.clone()],
+                };
+
+                Value::string(format(template.as_str().unwrap(), &args)?)
+            }
+            _ => return Err(invalid(&left, &right)),
+        },
+        ASTBinaryOp::Power => match (&left, &right) {
+            (Value::Int(a), Value::Int(b)) if *b >= 0 => {
+                Value::Int(a.wrapping_pow(u32::try_from(*b).unwrap_or(u32::MAX)))
+            }
+            _ => float_op(&left, &right, f64::powf).ok_or_else(|| invalid(&left, &right))?,
+        },
+        ASTBinaryOp::BitwiseAnd
+        | ASTBinaryOp::BitwiseOr
+        | ASTBinaryOp::BitwiseXor
+        | ASTBinaryOp::BitwiseShiftLeft
+        | ASTBinaryOp::BitwiseShiftRight => {
+            let (Value::Int(a), Value::Int(b)) = (&left, &right) else {
+                return Err(invalid(&left, &right));
+            };
+
+            Value::Int(match op {
+                ASTBinaryOp::BitwiseAnd => a & b,
+                ASTBinaryOp::BitwiseOr => a | b,
+                ASTBinaryOp::BitwiseXor => a ^ b,
+                ASTBinaryOp::BitwiseShiftLeft => a.wrapping_shl(*b as u32),
+                _ => a.wrapping_shr(*b as u32),
+            })
+        }
+        _ => return Err(invalid(&left, &right)),
+    })
+}
+
+/// The operator a compound assignment applies, e.g. `+` for `+=`.
+pub fn compound<'a>(op: &ASTBinaryOp<'a>) -> Option<ASTBinaryOp<'a>> {
+    Some(match op {
+        ASTBinaryOp::PlusAssignment => ASTBinaryOp::Add,
+        ASTBinaryOp::MinusAssignment => ASTBinaryOp::Subtract,
+        ASTBinaryOp::MultiplyAssignment => ASTBinaryOp::Multiply,
+        ASTBinaryOp::PowerAssignment => ASTBinaryOp::Power,
+        ASTBinaryOp::DivideAssignment => ASTBinaryOp::Divide,
+        ASTBinaryOp::RemainderAssignment => ASTBinaryOp::Remainder,
+        ASTBinaryOp::BitwiseAndAssignment => ASTBinaryOp::BitwiseAnd,
+        ASTBinaryOp::BitwiseOrAssignment => ASTBinaryOp::BitwiseOr,
+        ASTBinaryOp::BitwiseXorAssignment => ASTBinaryOp::BitwiseXor,
+        ASTBinaryOp::BitwiseShiftLeftAssignment => ASTBinaryOp::BitwiseShiftLeft,
+        ASTBinaryOp::BitwiseShiftRightAssignment => ASTBinaryOp::BitwiseShiftRight,
+        _ => return None,
+    })
+}
+
+/// The ordering of two numbers or two strings.
+pub fn compare(left: &Value<'_>, right: &Value<'_>) -> Option<Ordering> {
+    match (left, right) {
+        (Value::Int(a), Value::Int(b)) => Some(a.cmp(b)),
+        (Value::Bool(a), Value::Bool(b)) => Some(a.cmp(b)),
+        _ => match (left.as_str(), right.as_str()) {
+            (Some(a), Some(b)) => Some(a.cmp(b)),
+            _ => left.as_float()?.partial_cmp(&right.as_float()?),
+        },
+    }
+}
+
+/// Whether `value in container`.
+pub fn contains<'a>(value: &Value<'a>, container: &Value<'a>) -> Result<bool, Error> {
+    match container {
+        Value::Array(array) => Ok(array.borrow().iter().any(|item| item.equals(value))),
+        Value::Dictionary(dictionary) => Ok(dictionary.contains_key(value)),
+        Value::Object(object) => Ok(value
+            .as_str()
+            .is_some_and(|name| object.field(name).is_some())),
+        _ => match (value.as_str(), container.as_str()) {
+            (Some(needle), Some(haystack)) => Ok(haystack.contains(needle)),
+            _ => Err(ErrorKind::InvalidOperands {
+                op: "in",
+                left: value.type_name(),
+                right: container.type_name(),
+            }
+            .into()),
+        },
+    }
+}
+
+/// `base[index]`.
+pub fn index<'a>(base: &Value<'a>, index: &Value<'a>) -> Result<Value<'a>, Error> {
+    match (base, index) {
+        (Value::Array(array), Value::Int(idx)) => {
+            let array = array.borrow();
+            let idx = resolve_index(*idx, array.len())?;
+
+            Ok(array[idx].clone())
+        }
+        (Value::Dictionary(dictionary), key) => dictionary.get(key).ok_or_else(|| {
+            ErrorKind::InvalidIndex {
+                index: key.repr(),
+                base: "Dictionary".to_owned(),
+            }
+            .into()
+        }),
+        (Value::String(string) | Value::StringName(string), Value::Int(idx)) => {
+            let chars = string.chars().collect::<Vec<_>>();
+            let idx = resolve_index(*idx, chars.len())?;
+
+            Ok(Value::string(chars[idx].to_string()))
+        }
+        (base, index) => Err(ErrorKind::InvalidIndex {
+            index: index.repr(),
+            base: base.type_name(),
+        }
+        .into()),
+    }
+}
+
+/// `base[index] = value`.
+pub fn set_index<'a>(base: &Value<'a>, index: Value<'a>, value: Value<'a>) -> Result<(), Error> {
+    match (base, index) {
+        (Value::Array(array), Value::Int(idx)) => {
+            let mut array = array.borrow_mut();
+            let idx = resolve_index(idx, array.len())?;
+            array[idx] = value;
+
+            Ok(())
+        }
+        (Value::Dictionary(dictionary), key) => {
+            dictionary.insert(key, value);
+
+            Ok(())
+        }
+        (base, index) => Err(ErrorKind::InvalidIndex {
+            index: index.repr(),
+            base: base.type_name(),
+        }
+        .into()),
+    }
+}
+
+/// Resolve a possibly negative index into a container of size `len`.
+pub fn resolve_index(idx: i64, len: usize) -> Result<usize, Error> {
+    let resolved = if idx < 0 { idx + len as i64 } else { idx };
+
+    if (0..len as i64).contains(&resolved) {
+        Ok(resolved as usize)
+    } else {
+        Err(ErrorKind::IndexOutOfBounds {
+            index: idx,
+            size: len,
+        }
+        .into())
+    }
+}
+
+fn float_op<'a>(
+    left: &Value<'a>,
+    right: &Value<'a>,
+    op: impl Fn(f64, f64) -> f64,
+) -> Option<Value<'a>> {
+    Some(Value::Float(op(left.as_float()?, right.as_float()?)))
+}
+
+fn symbol(op: &ASTBinaryOp<'_>) -> &'static str {
+    match op {
+        ASTBinaryOp::LessThan => "<",
+        ASTBinaryOp::LessOrEqual => "<=",
+        ASTBinaryOp::Greater => ">",
+        ASTBinaryOp::GreaterOrEqual => ">=",
+        ASTBinaryOp::Equals => "==",
+        ASTBinaryOp::NotEqual => "!=",
+        ASTBinaryOp::BitwiseAnd => "&",
+        ASTBinaryOp::BitwiseOr => "|",
+        ASTBinaryOp::BitwiseXor => "^",
+        ASTBinaryOp::BitwiseShiftLeft => "<<",
+        ASTBinaryOp::BitwiseShiftRight => ">>",
+        ASTBinaryOp::Add => "+",
+        ASTBinaryOp::Subtract => "-",
+        ASTBinaryOp::Multiply => "*",
+        ASTBinaryOp::Power => "**",
+        ASTBinaryOp::Divide => "/",
+        ASTBinaryOp::Remainder => "%",
+        ASTBinaryOp::Contains => "in",
+        ASTBinaryOp::NotContains => "not in",
+        _ => "?",
+    }
+}
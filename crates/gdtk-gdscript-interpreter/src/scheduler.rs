@@ -0,0 +1,132 @@
+//! A minimal scheduler for coroutines.
+//!
+//! Coroutines are futures that only ever wait for signals, so there is no
+//! need for wakers: the scheduler simply polls every suspended coroutine again
+//! whenever something happened that may let one of them continue.
+
+use std::{
+    cell::RefCell,
+    future::Future,
+    pin::pin,
+    rc::Rc,
+    task::{Context, Poll, Waker},
+};
+
+use crate::{
+    error::{Error, ErrorKind},
+    interpreter::{Eval, Interpreter},
+    value::Value,
+};
+
+impl<'a> Interpreter<'a> {
+    /// Run `future` and every coroutine it starts, until they all finish or
+    /// wait for a signal that is never emitted. Coroutines left waiting keep
+    /// their place, and continue in a later call if the signal is emitted.
+    pub(crate) fn block_on(&self, future: Eval<'a>) -> Result<Value<'a>, Error> {
+        let mut cx = Context::from_waker(Waker::noop());
+        let mut main = Some(future);
+        let mut result = None;
+
+        loop {
+            let progress = self.0.progress.get();
+
+            if let Some(future) = main.as_mut() {
+                if let Poll::Ready(value) = future.as_mut().poll(&mut cx) {
+                    result = Some(value?);
+                    main = None;
+                    self.bump();
+                }
+            }
+
+            self.resume()?;
+
+            if self.0.progress.get() == progress {
+                break;
+            }
+        }
+
+        result.ok_or_else(|| ErrorKind::NeverResumed.into())
+    }
+
+    /// Poll every suspended coroutine once. Called when a signal is emitted,
+    /// so that coroutines waiting for it continue right away, like in the
+    /// engine.
+    pub(crate) fn resume(&self) -> Result<(), Error> {
+        let mut cx = Context::from_waker(Waker::noop());
+        let count = self.0.tasks.borrow().len();
+        let mut pending = vec![];
+
+        // Coroutines are taken out of the queue while they run, so that
+        // signals they emit don't poll them again.
+        for _ in 0..count {
+            let Some(mut task) = self.next_task() else {
+                break;
+            };
+
+            match task.as_mut().poll(&mut cx) {
+                Poll::Ready(value) => {
+                    value?;
+                    self.bump();
+                }
+                Poll::Pending => pending.push(task),
+            }
+        }
+
+        // Keep the coroutines started meanwhile after the older ones.
+        self.0.tasks.borrow_mut().splice(0..0, pending);
+
+        Ok(())
+    }
+
+    fn next_task(&self) -> Option<Eval<'a>> {
+        let mut tasks = self.0.tasks.borrow_mut();
+
+        if tasks.is_empty() {
+            None
+        } else {
+            Some(tasks.remove(0))
+        }
+    }
+
+    /// Poll `future` once, for evaluation that can't be suspended.
+    pub(crate) fn now<T>(
+        &self,
+        future: impl Future<Output = Result<T, Error>>,
+    ) -> Result<T, Error> {
+        let mut cx = Context::from_waker(Waker::noop());
+
+        match pin!(future).poll(&mut cx) {
+            Poll::Ready(result) => result,
+            Poll::Pending => {
+                Err(ErrorKind::Unsupported("Awaiting outside of a function".to_owned()).into())
+            }
+        }
+    }
+
+    /// Run a coroutine until it finishes or suspends. Suspended coroutines are
+    /// left to the scheduler, and evaluate to `null`.
+    pub(crate) async fn run_or_spawn(&self, mut future: Eval<'a>) -> Result<Value<'a>, Error> {
+        match std::future::poll_fn(|cx| Poll::Ready(future.as_mut().poll(cx))).await {
+            Poll::Ready(result) => result,
+            Poll::Pending => {
+                self.0.tasks.borrow_mut().push(future);
+                self.bump();
+
+                Ok(Value::Null)
+            }
+        }
+    }
+
+    /// Suspend until `slot` is filled.
+    pub(crate) async fn wait(&self, slot: Rc<RefCell<Option<Value<'a>>>>) -> Value<'a> {
+        std::future::poll_fn(|_| match slot.borrow_mut().take() {
+            Some(value) => Poll::Ready(value),
+            None => Poll::Pending,
+        })
+        .await
+    }
+
+    pub(crate) fn bump(&self) {
+        self.0.progress.set(self.0.progress.get() + 1);
+    }
+}
@@ -0,0 +1,186 @@
+//! Running code piece by piece, as a REPL does.
+
+use gdtk_gdscript_ast::{ASTBinaryOp, ASTExprKind, ASTFile, ASTStatement, ASTVariable};
+
+use crate::{
+    error::Error,
+    interpreter::{variable_name, Frame, Interpreter},
+    operators::compound,
+    statements::Flow,
+    value::Value,
+};
+
+/// A sequence of inputs that share state.
+///
+/// Declarations (functions, signals, constants, enums, inner classes and
+/// variables) are added to an implicit script that grows with every input.
+/// Variables become static variables of that script, so that its functions
+/// can use them too. Everything else runs as if in a static function of the
+/// script.
+pub struct Session<'a> {
+    interpreter: Interpreter<'a>,
+    name: String,
+    declarations: Vec<&'a ASTStatement<'a>>,
+    frame: Frame<'a>,
+}
+
+impl<'a> Session<'a> {
+    /// Start a session. `name` is the name of the implicit script.
+    pub fn new(interpreter: Interpreter<'a>, name: &str) -> Self {
+        let class = interpreter
+            .load_class(name.to_owned(), None, std::iter::empty(), None)
+            .expect("an empty script can't fail to load");
+
+        Self {
+            interpreter,
+            name: name.to_owned(),
+            declarations: vec![],
+            frame: Frame::new(class, None),
+        }
+    }
+
+    pub fn interpreter(&self) -> &Interpreter<'a> {
+        &self.interpreter
+    }
+
+    /// Run an input. Returns the value of its last statement if that's an
+    /// expression, and `null` otherwise.
+    pub fn run(&mut self, file: &'a ASTFile<'a>) -> Result<Value<'a>, Error> {
+        let mut body = file.body.as_slice();
+        let mut result = Value::Null;
+
+        while let Some(stmt) = body.first() {
+            if let ASTStatement::Variable(variable) = stmt {
+                if variable.kind.is_regular() {
+                    self.define(variable)?;
+                    result = Value::Null;
+                    body = &body[1..];
+                    continue;
+                }
+            }
+
+            if is_declaration(stmt) {
+                self.declare(stmt)?;
+                result = Value::Null;
+                body = &body[1..];
+                continue;
+            }
+
+            let len = body
+                .iter()
+                .position(|stmt| is_declaration(stmt) || matches!(stmt, ASTStatement::Variable(_)))
+                .unwrap_or(body.len());
+
+            result = self.exec(&body[..len])?;
+            body = &body[len..];
+        }
+
+        Ok(result)
+    }
+
+    /// Add a declaration to the implicit script. The script is loaded again,
+    /// so later declarations replace earlier ones with the same name.
+    fn declare(&mut self, stmt: &'a ASTStatement<'a>) -> Result<(), Error> {
+        self.declarations.push(stmt);
+
+        let class = self.interpreter.load_class(
+            self.name.clone(),
+            None,
+            self.declarations.iter().copied(),
+            None,
+        );
+
+        let class = match class {
+            Ok(class) => class,
+            Err(error) => {
+                self.declarations.pop();
+                return Err(error);
+            }
+        };
+
+        // Loading evaluated the variables again, so restore their values,
+        // except for the one just declared.
+        let declared = match stmt {
+            ASTStatement::Variable(variable) => Some(variable_name(variable)),
+            _ => None,
+        };
+
+        for (name, value) in self.frame.class.statics.borrow().iter() {
+            if Some(*name) != declared {
+                class.statics.borrow_mut().insert(name, value.clone());
+            }
+        }
+
+        self.frame.class = class;
+
+        Ok(())
+    }
+
+    /// Declare a variable. Unlike other declarations, its value is only
+    /// evaluated once.
+    fn define(&mut self, variable: &'a ASTVariable<'a>) -> Result<(), Error> {
+        let this = self.interpreter.clone();
+        let frame = self.frame.clone();
+
+        let value = self.interpreter.block_on(Box::pin(async move {
+            this.initial_value(variable, &frame).await
+        }))?;
+
+        self.frame
+            .class
+            .statics
+            .borrow_mut()
+            .insert(variable_name(variable), value);
+
+        Ok(())
+    }
+
+    /// Execute statements. If the last one is an expression, its value is
+    /// returned.
+    fn exec(&self, statements: &'a [ASTStatement<'a>]) -> Result<Value<'a>, Error> {
+        let this = self.interpreter.clone();
+        let frame = self.frame.clone();
+
+        self.interpreter.block_on(Box::pin(async move {
+            let (body, last) = match statements.split_last() {
+                Some((ASTStatement::Expr(expr), body)) if !is_assignment(&expr.kind) => {
+                    (body, Some(expr))
+                }
+                _ => (statements, None),
+            };
+
+            match this.exec_statements(body, &frame).await? {
+                // A top-level `return` ends the input early.
+                Flow::Return(value) => return Ok(value),
+                Flow::Normal | Flow::Break | Flow::Continue => (),
+            }
+
+            match last {
+                Some(expr) => this.eval(expr, &frame).await,
+                None => Ok(Value::Null),
+            }
+        }))
+    }
+}
+
+fn is_declaration(stmt: &ASTStatement<'_>) -> bool {
+    match stmt {
+        ASTStatement::Variable(variable) => !variable.kind.is_regular(),
+        ASTStatement::ClassName(_)
+        | ASTStatement::Extends(_)
+        | ASTStatement::Func(_)
+        | ASTStatement::Signal(_)
+        | ASTStatement::Enum(_)
+        | ASTStatement::Class(_) => true,
+        _ => false,
+    }
+}
+
+fn is_assignment(kind: &ASTExprKind<'_>) -> bool {
+    match kind {
+        ASTExprKind::BinaryExpr(_, op, _) => {
+            matches!(op, ASTBinaryOp::Assignment) || compound(op).is_some()
+        }
+        _ => false,
+    }
+}
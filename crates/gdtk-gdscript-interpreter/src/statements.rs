@@ -0,0 +1,374 @@
+use gdtk_gdscript_ast::{
+    ASTAssertStmt, ASTExprKind, ASTForStmt, ASTMatchArm, ASTMatchPattern, ASTMatchStmt,
+    ASTStatement,
+};
+
+use crate::{
+    error::{Error, ErrorKind},
+    interpreter::{variable_name, Eval, Frame, Interpreter},
+    value::Value,
+};
+
+/// How a block of statements finished.
+pub(crate) enum Flow<'a> {
+    Normal,
+    Break,
+    Continue,
+    Return(Value<'a>),
+}
+
+impl<'a> Interpreter<'a> {
+    /// Execute a block in a new scope.
+    pub(crate) fn exec_block(
+        &self,
+        block: &'a [ASTStatement<'a>],
+        frame: &Frame<'a>,
+    ) -> Eval<'a, Flow<'a>> {
+        let this = self.clone();
+        let frame = frame.clone();
+
+        Box::pin(async move {
+            frame.push_scope();
+            let result = this.exec_statements(block, &frame).await;
+            frame.pop_scope();
+
+            result
+        })
+    }
+
+    /// Execute statements in the current scope.
+    pub(crate) async fn exec_statements(
+        &self,
+        block: &'a [ASTStatement<'a>],
+        frame: &Frame<'a>,
+    ) -> Result<Flow<'a>, Error> {
+        // Whether a branch of the current `if` chain was already taken.
+        let mut branch_taken = true;
+
+        for stmt in block {
+            let flow = match stmt {
+                ASTStatement::If(stmt) => {
+                    branch_taken = self.eval(&stmt.expr, frame).await?.is_truthy();
+
+                    if branch_taken {
+                        self.exec_block(&stmt.block, frame).await?
+                    } else {
+                        Flow::Normal
+                    }
+                }
+                ASTStatement::Elif(stmt) if !branch_taken => {
+                    branch_taken = self.eval(&stmt.expr, frame).await?.is_truthy();
+
+                    if branch_taken {
+                        self.exec_block(&stmt.block, frame).await?
+                    } else {
+                        Flow::Normal
+                    }
+                }
+                ASTStatement::Else(stmt) if !branch_taken => {
+                    branch_taken = true;
+
+                    self.exec_block(&stmt.block, frame).await?
+                }
+                ASTStatement::Elif(_) | ASTStatement::Else(_) => Flow::Normal,
+                stmt => self.exec_statement(stmt, frame).await?,
+            };
+
+            if !matches!(flow, Flow::Normal) {
+                return Ok(flow);
+            }
+        }
+
+        Ok(Flow::Normal)
+    }
+
+    async fn exec_statement(
+        &self,
+        stmt: &'a ASTStatement<'a>,
+        frame: &Frame<'a>,
+    ) -> Result<Flow<'a>, Error> {
+        match stmt {
+            ASTStatement::Expr(expr) => {
+                self.eval(expr, frame).await?;
+            }
+            ASTStatement::Variable(variable) => {
+                let value = self.initial_value(variable, frame).await?;
+                frame.declare(variable_name(variable), value);
+            }
+            ASTStatement::Return(stmt) => {
+                let value = match &stmt.expr {
+                    Some(expr) => self.eval(expr, frame).await?,
+                    None => Value::Null,
+                };
+
+                return Ok(Flow::Return(value));
+            }
+            ASTStatement::Break(_) => return Ok(Flow::Break),
+            ASTStatement::Continue(_) => return Ok(Flow::Continue),
+            ASTStatement::Assert(stmt) => self.exec_assert(stmt, frame).await?,
+            ASTStatement::While(stmt) => {
+                while self.eval(&stmt.expr, frame).await?.is_truthy() {
+                    match self.exec_block(&stmt.block, frame).await? {
+                        Flow::Break => break,
+                        Flow::Return(value) => return Ok(Flow::Return(value)),
+                        Flow::Normal | Flow::Continue => (),
+                    }
+                }
+            }
+            ASTStatement::For(stmt) => return self.exec_for(stmt, frame).await,
+            ASTStatement::Match(stmt) => return self.exec_match(stmt, frame).await,
+            ASTStatement::Enum(enum_) => {
+                for (name, value) in self.eval_enum(enum_, frame).await? {
+                    frame.declare(name, value);
+                }
+            }
+            ASTStatement::Class(class) => {
+                if let ASTExprKind::Identifier(name) = class.identifier.kind {
+                    let class = self.load_class(
+                        name.to_owned(),
+                        class.extends.as_ref(),
+                        class.body.iter(),
+                        Some(&frame.class),
+                    )?;
+                    frame.declare(name, Value::Class(class));
+                }
+            }
+            // Declarations are collected when the class is loaded.
+            ASTStatement::Annotation(_)
+            | ASTStatement::ClassName(_)
+            | ASTStatement::Extends(_)
+            | ASTStatement::Func(_)
+            | ASTStatement::Signal(_)
+            | ASTStatement::Breakpoint(_)
+            | ASTStatement::Pass(_) => (),
+            ASTStatement::If(_) | ASTStatement::Elif(_) | ASTStatement::Else(_) => {
+                unreachable!("conditionals are handled by exec_statements")
+            }
+        }
+
+        Ok(Flow::Normal)
+    }
+
+    async fn exec_assert(
+        &self,
+        stmt: &'a ASTAssertStmt<'a>,
+        frame: &Frame<'a>,
+    ) -> Result<(), Error> {
+        // `assert(condition, message)` parses as a group.
+        let (condition, message) = match &stmt.expr.kind {
+            ASTExprKind::Group(exprs) => (exprs.first(), exprs.get(1)),
+            _ => (Some(&stmt.expr), None),
+        };
+
+        let Some(condition) = condition else {
+            return Ok(());
+        };
+
+        if self.eval(condition, frame).await?.is_truthy() {
+            return Ok(());
+        }
+
+        let message = match message {
+            Some(message) => Some(self.eval(message, frame).await?.to_string()),
+            None => None,
+        };
+
+        Err(Error::from(ErrorKind::AssertionFailed(message)).at(&stmt.span))
+    }
+
+    async fn exec_for(
+        &self,
+        stmt: &'a ASTForStmt<'a>,
+        frame: &Frame<'a>,
+    ) -> Result<Flow<'a>, Error> {
+        let container = self.eval(&stmt.container, frame).await?;
+        let name = variable_name(&stmt.binding);
+
+        for item in Items::new(container).map_err(|err| err.at(&stmt.container.span))? {
+            frame.push_scope();
+            frame.declare(name, item);
+            let flow = self.exec_block(&stmt.block, frame).await;
+            frame.pop_scope();
+
+            match flow? {
+                Flow::Break => break,
+                Flow::Return(value) => return Ok(Flow::Return(value)),
+                Flow::Normal | Flow::Continue => (),
+            }
+        }
+
+        Ok(Flow::Normal)
+    }
+
+    async fn exec_match(
+        &self,
+        stmt: &'a ASTMatchStmt<'a>,
+        frame: &Frame<'a>,
+    ) -> Result<Flow<'a>, Error> {
+        let value = self.eval(&stmt.expr, frame).await?;
+
+        for arm in &stmt.arms {
+            // Bindings of an arm are only visible inside of it.
+            frame.push_scope();
+            let flow = self.exec_arm(arm, &value, frame).await;
+            frame.pop_scope();
+
+            if let Some(flow) = flow? {
+                return Ok(flow);
+            }
+        }
+
+        Ok(Flow::Normal)
+    }
+
+    /// Execute an arm if it matches, returning `None` if it doesn't.
+    async fn exec_arm(
+        &self,
+        arm: &'a ASTMatchArm<'a>,
+        value: &Value<'a>,
+        frame: &Frame<'a>,
+    ) -> Result<Option<Flow<'a>>, Error> {
+        if !self.matches(&arm.pattern, value.clone(), frame).await? {
+            return Ok(None);
+        }
+
+        if let Some(guard) = &arm.guard {
+            if !self.eval(guard, frame).await?.is_truthy() {
+                return Ok(None);
+            }
+        }
+
+        Ok(Some(self.exec_block(&arm.block, frame).await?))
+    }
+
+    /// Whether `value` matches `pattern`, declaring the pattern's bindings.
+    fn matches(
+        &self,
+        pattern: &'a ASTMatchPattern<'a>,
+        value: Value<'a>,
+        frame: &Frame<'a>,
+    ) -> Eval<'a, bool> {
+        let this = self.clone();
+        let frame = frame.clone();
+
+        Box::pin(async move {
+            match pattern {
+                ASTMatchPattern::Value(expr) if expr.kind == ASTExprKind::Identifier("_") => {
+                    Ok(true)
+                }
+                ASTMatchPattern::Value(expr) => {
+                    let expected = this.eval(expr, &frame).await?;
+
+                    Ok(value.strictly_equals(&expected))
+                }
+                ASTMatchPattern::Binding(variable) => {
+                    frame.declare(variable_name(variable), value);
+
+                    Ok(true)
+                }
+                ASTMatchPattern::Array(patterns) => {
+                    let Value::Array(array) = value else {
+                        return Ok(false);
+                    };
+
+                    let items = array.to_vec();
+
+                    // A trailing `..` matches the rest of the array.
+                    let (patterns, open) = match patterns.split_last() {
+                        Some((ASTMatchPattern::Ignore, patterns)) => (patterns, true),
+                        _ => (patterns.as_slice(), false),
+                    };
+
+                    if items.len() < patterns.len() || (!open && items.len() != patterns.len()) {
+                        return Ok(false);
+                    }
+
+                    for (pattern, item) in patterns.iter().zip(items) {
+                        if !this.matches(pattern, item, &frame).await? {
+                            return Ok(false);
+                        }
+                    }
+
+                    Ok(true)
+                }
+                ASTMatchPattern::Dictionary(entries) => {
+                    let Value::Dictionary(dictionary) = value else {
+                        return Ok(false);
+                    };
+
+                    if dictionary.len() != entries.len() {
+                        return Ok(false);
+                    }
+
+                    for (key, pattern) in entries {
+                        let key = this.eval(key, &frame).await?;
+
+                        let Some(item) = dictionary.get(&key) else {
+                            return Ok(false);
+                        };
+
+                        if let Some(pattern) = pattern {
+                            if !this.matches(pattern, item, &frame).await? {
+                                return Ok(false);
+                            }
+                        }
+                    }
+
+                    Ok(true)
+                }
+                ASTMatchPattern::Alternative(patterns) => {
+                    for pattern in patterns {
+                        if this.matches(pattern, value.clone(), &frame).await? {
+                            return Ok(true);
+                        }
+                    }
+
+                    Ok(false)
+                }
+                ASTMatchPattern::Ignore => Ok(true),
+            }
+        })
+    }
+}
+
+/// The items a `for` loop iterates over.
+enum Items<'a> {
+    Range(std::ops::Range<i64>),
+    Values(std::vec::IntoIter<Value<'a>>),
+}
+
+impl<'a> Items<'a> {
+    fn new(container: Value<'a>) -> Result<Self, Error> {
+        Ok(match container {
+            Value::Int(end) => Items::Range(0..end),
+            Value::Float(end) => Items::Range(0..end.ceil() as i64),
+            Value::Array(array) => Items::Values(array.to_vec().into_iter()),
+            Value::Dictionary(dictionary) => Items::Values(dictionary.keys().into_iter()),
+            Value::String(string) | Value::StringName(string) => Items::Values(
+                string
+                    .chars()
+                    .map(|c| Value::string(c.to_string()))
+                    .collect::<Vec<_>>()
+                    .into_iter(),
+            ),
+            other => {
+                return Err(ErrorKind::InvalidArgument {
+                    function: "for".to_owned(),
+                    message: format!("can't iterate over a value of type {}", other.type_name()),
+                }
+                .into())
+            }
+        })
+    }
+}
+
+impl<'a> Iterator for Items<'a> {
+    type Item = Value<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Items::Range(range) => range.next().map(Value::Int),
+            Items::Values(values) => values.next(),
+        }
+    }
+}
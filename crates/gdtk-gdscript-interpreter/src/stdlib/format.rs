@@ -0,0 +1,158 @@
+//! The `%` operator on strings.
+
+use crate::{
+    error::{Error, ErrorKind},
+    value::Value,
+};
+
+/// Format `template` with `args`, as `template % args` does.
+///
+/// Supports the `s`, `c`, `d`, `i`, `o`, `x`, `X` and `f` conversions, the
+/// `-`, `+` and `0` flags, widths and precisions.
+pub fn format(template: &str, args: &[Value<'_>]) -> Result<String, Error> {
+    let mut output = String::with_capacity(template.len());
+    let mut args = args.iter();
+    let mut chars = template.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            output.push(c);
+            continue;
+        }
+
+        if chars.next_if_eq(&'%').is_some() {
+            output.push('%');
+            continue;
+        }
+
+        let (mut left, mut plus, mut zero) = (false, false, false);
+
+        while let Some(flag) = chars.next_if(|c| matches!(c, '-' | '+' | '0')) {
+            match flag {
+                '-' => left = true,
+                '+' => plus = true,
+                _ => zero = true,
+            }
+        }
+
+        let width = number(&mut chars);
+        let precision = chars
+            .next_if_eq(&'.')
+            .map(|_| number(&mut chars).unwrap_or(0));
+
+        let conversion = chars.next().ok_or_else(|| invalid("incomplete format"))?;
+        let arg = args
+            .next()
+            .ok_or_else(|| invalid("not enough arguments for format string"))?;
+
+        let numeric = matches!(conversion, 'd' | 'i' | 'o' | 'x' | 'X' | 'f');
+
+        let body = match conversion {
+            's' => arg.to_string(),
+            'c' => match arg {
+                Value::Int(code) => u32::try_from(*code)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .unwrap_or_default()
+                    .to_string(),
+                other => other
+                    .to_string()
+                    .chars()
+                    .next()
+                    .map(String::from)
+                    .unwrap_or_default(),
+            },
+            'd' | 'i' | 'o' | 'x' | 'X' => {
+                let value = match arg {
+                    Value::Int(value) => *value,
+                    Value::Float(value) => *value as i64,
+                    _ => return Err(invalid("a number is required")),
+                };
+
+                let digits = match conversion {
+                    'o' => format!("{:o}", value.unsigned_abs()),
+                    'x' => format!("{:x}", value.unsigned_abs()),
+                    'X' => format!("{:X}", value.unsigned_abs()),
+                    _ => value.unsigned_abs().to_string(),
+                };
+
+                sign(value < 0, plus, digits)
+            }
+            'f' => {
+                let value = arg
+                    .as_float()
+                    .ok_or_else(|| invalid("a number is required"))?;
+                let digits = format!("{:.*}", precision.unwrap_or(6), value.abs());
+
+                sign(value.is_sign_negative() && value != 0.0, plus, digits)
+            }
+            other => return Err(invalid(&format!("unsupported format character '{other}'"))),
+        };
+
+        output.push_str(&pad(
+            body,
+            width.unwrap_or(0),
+            left,
+            zero && numeric && !left,
+        ));
+    }
+
+    if args.next().is_some() {
+        return Err(invalid(
+            "not all arguments converted during string formatting",
+        ));
+    }
+
+    Ok(output)
+}
+
+fn number(chars: &mut std::iter::Peekable<std::str::Chars<'_>>) -> Option<usize> {
+    let mut digits = String::new();
+
+    while let Some(digit) = chars.next_if(char::is_ascii_digit) {
+        digits.push(digit);
+    }
+
+    digits.parse().ok()
+}
+
+fn sign(negative: bool, plus: bool, digits: String) -> String {
+    match (negative, plus) {
+        (true, _) => format!("-{digits}"),
+        (false, true) => format!("+{digits}"),
+        (false, false) => digits,
+    }
+}
+
+/// Pad `body` to `width`. Zeros go after the sign.
+fn pad(body: String, width: usize, left: bool, zero: bool) -> String {
+    let len = body.chars().count();
+
+    if len >= width {
+        return body;
+    }
+
+    let padding = width - len;
+
+    if left {
+        format!("{body}{}", " ".repeat(padding))
+    } else if zero {
+        let split = usize::from(body.starts_with(['-', '+']));
+        format!(
+            "{}{}{}",
+            &body[..split],
+            "0".repeat(padding),
+            &body[split..]
+        )
+    } else {
+        format!("{}{body}", " ".repeat(padding))
+    }
+}
+
+fn invalid(message: &str) -> Error {
+    ErrorKind::InvalidArgument {
+        function: "%".to_owned(),
+        message: message.to_owned(),
+    }
+    .into()
+}
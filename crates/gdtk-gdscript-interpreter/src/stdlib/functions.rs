@@ -0,0 +1,529 @@
+//! Global functions, like `print` and `range`, and constructors of built-in
+//! types.
+
+use crate::{
+    error::{Error, ErrorKind},
+    interpreter::Interpreter,
+    stdlib::{methods, Args},
+    value::{Callable, Dictionary, Signal, Type, Value},
+};
+
+/// Names of all global functions.
+const FUNCTIONS: &[&str] = &[
+    "print",
+    "print_rich",
+    "prints",
+    "printt",
+    "printerr",
+    "push_error",
+    "push_warning",
+    "str",
+    "len",
+    "range",
+    "typeof",
+    "type_string",
+    "is_instance_valid",
+    "is_same",
+    "char",
+    "abs",
+    "absi",
+    "absf",
+    "sign",
+    "signi",
+    "signf",
+    "floor",
+    "floori",
+    "floorf",
+    "ceil",
+    "ceili",
+    "ceilf",
+    "round",
+    "roundi",
+    "roundf",
+    "sqrt",
+    "sin",
+    "cos",
+    "tan",
+    "asin",
+    "acos",
+    "atan",
+    "exp",
+    "log",
+    "deg_to_rad",
+    "rad_to_deg",
+    "is_nan",
+    "is_inf",
+    "is_zero_approx",
+    "pow",
+    "atan2",
+    "fmod",
+    "fposmod",
+    "posmod",
+    "min",
+    "max",
+    "mini",
+    "maxi",
+    "minf",
+    "maxf",
+    "clamp",
+    "clampi",
+    "clampf",
+    "lerp",
+    "lerpf",
+    "inverse_lerp",
+    "remap",
+    "snapped",
+    "wrapi",
+    "wrapf",
+    "is_equal_approx",
+    "randi",
+    "randf",
+    "randi_range",
+    "randf_range",
+    "randomize",
+    "seed",
+    "load",
+    "preload",
+];
+
+pub fn exists(name: &str) -> bool {
+    FUNCTIONS.contains(&name)
+}
+
+/// Call a global function or a constructor. Returns `None` if there's no
+/// function named `name`.
+pub fn call<'a>(
+    interpreter: &Interpreter<'a>,
+    name: &str,
+    args: Vec<Value<'a>>,
+) -> Option<Result<Value<'a>, Error>> {
+    if let Some(ty) = Type::from_name(name) {
+        return Some(construct(ty, args));
+    }
+
+    exists(name).then(|| call_function(interpreter, name, &args))
+}
+
+fn call_function<'a>(
+    interpreter: &Interpreter<'a>,
+    name: &str,
+    values: &[Value<'a>],
+) -> Result<Value<'a>, Error> {
+    let args = Args::new(name, values);
+
+    let value = match name {
+        "print" | "print_rich" => {
+            interpreter.print(&join(values, ""));
+            Value::Null
+        }
+        "prints" => {
+            interpreter.print(&join(values, " "));
+            Value::Null
+        }
+        "printt" => {
+            interpreter.print(&join(values, "\t"));
+            Value::Null
+        }
+        "printerr" => {
+            interpreter.print_error(&join(values, ""));
+            Value::Null
+        }
+        "push_error" => {
+            interpreter.print_error(&format!("ERROR: {}", join(values, "")));
+            Value::Null
+        }
+        "push_warning" => {
+            interpreter.print_error(&format!("WARNING: {}", join(values, "")));
+            Value::Null
+        }
+        "str" => Value::string(join(values, "")),
+        "len" => {
+            args.count(1, 1)?;
+
+            Value::Int(match &values[0] {
+                Value::String(string) | Value::StringName(string) => string.chars().count(),
+                Value::Array(array) => array.len(),
+                Value::Dictionary(dictionary) => dictionary.len(),
+                other => {
+                    return Err(ErrorKind::InvalidArgument {
+                        function: name.to_owned(),
+                        message: format!("a value of type {} has no length", other.type_name()),
+                    }
+                    .into())
+                }
+            } as i64)
+        }
+        "range" => {
+            args.count(1, 3)?;
+
+            let (start, end) = match values.len() {
+                1 => (0, args.int(0)?),
+                _ => (args.int(0)?, args.int(1)?),
+            };
+            let step = args.int_or(2, 1)?;
+
+            if step == 0 {
+                return Err(ErrorKind::InvalidArgument {
+                    function: name.to_owned(),
+                    message: "step can't be zero".to_owned(),
+                }
+                .into());
+            }
+
+            let mut items = vec![];
+            let mut current = start;
+
+            while (step > 0 && current < end) || (step < 0 && current > end) {
+                items.push(Value::Int(current));
+
+                // the next value would be past `i64::MAX` or `i64::MIN`, and so past `end`
+                match current.checked_add(step) {
+                    Some(next) => current = next,
+                    None => break,
+                }
+            }
+
+            Value::array(items)
+        }
+        "typeof" => {
+            args.count(1, 1)?;
+            Value::Int(values[0].type_of().id())
+        }
+        "type_string" => {
+            args.count(1, 1)?;
+            Value::string(Type::from_id(args.int(0)?).map_or("<unknown type>", Type::name))
+        }
+        "is_instance_valid" => {
+            args.count(1, 1)?;
+            Value::Bool(matches!(values[0], Value::Object(_)))
+        }
+        "is_same" => {
+            args.count(2, 2)?;
+
+            Value::Bool(match (&values[0], &values[1]) {
+                (Value::Array(a), Value::Array(b)) => a.ptr_eq(b),
+                (Value::Dictionary(a), Value::Dictionary(b)) => a.ptr_eq(b),
+                (a, b) => a.strictly_equals(b),
+            })
+        }
+        "char" => {
+            args.count(1, 1)?;
+
+            let c = u32::try_from(args.int(0)?)
+                .ok()
+                .and_then(char::from_u32)
+                .unwrap_or_default();
+
+            Value::string(c.to_string())
+        }
+        "abs" | "sign" | "floor" | "ceil" | "round" => {
+            args.count(1, 1)?;
+
+            match &values[0] {
+                Value::Int(value) => Value::Int(match name {
+                    "abs" => value.wrapping_abs(),
+                    "sign" => value.signum(),
+                    _ => *value,
+                }),
+                _ => Value::Float(float_function(name, args.float(0)?)),
+            }
+        }
+        "absi" | "signi" | "floori" | "ceili" | "roundi" => {
+            args.count(1, 1)?;
+
+            match &values[0] {
+                Value::Int(value) if name == "absi" => Value::Int(value.wrapping_abs()),
+                Value::Int(value) if name == "signi" => Value::Int(value.signum()),
+                Value::Int(value) => Value::Int(*value),
+                _ => Value::Int(float_function(name.trim_end_matches('i'), args.float(0)?) as i64),
+            }
+        }
+        "absf" | "signf" | "floorf" | "ceilf" | "roundf" | "sqrt" | "sin" | "cos" | "tan"
+        | "asin" | "acos" | "atan" | "exp" | "log" | "deg_to_rad" | "rad_to_deg" => {
+            args.count(1, 1)?;
+            Value::Float(float_function(name.trim_end_matches('f'), args.float(0)?))
+        }
+        "is_nan" | "is_inf" | "is_zero_approx" => {
+            args.count(1, 1)?;
+            let value = args.float(0)?;
+
+            Value::Bool(match name {
+                "is_nan" => value.is_nan(),
+                "is_inf" => value.is_infinite(),
+                _ => value.abs() < EPSILON,
+            })
+        }
+        "pow" | "atan2" | "fmod" | "fposmod" => {
+            args.count(2, 2)?;
+            let (a, b) = (args.float(0)?, args.float(1)?);
+
+            Value::Float(match name {
+                "pow" => a.powf(b),
+                "atan2" => a.atan2(b),
+                "fmod" => a % b,
+                _ => a.rem_euclid(b),
+            })
+        }
+        "posmod" => {
+            args.count(2, 2)?;
+
+            match args.int(1)? {
+                0 => return Err(ErrorKind::DivisionByZero("posmod").into()),
+                b => Value::Int(args.int(0)?.rem_euclid(b)),
+            }
+        }
+        "min" | "max" | "mini" | "maxi" | "minf" | "maxf" => {
+            args.count(2, usize::MAX)?;
+
+            let pick_max = name.starts_with("max");
+            let mut best = values[0].clone();
+
+            for value in &values[1..] {
+                let ordering = crate::operators::compare(value, &best).ok_or_else(|| {
+                    ErrorKind::InvalidArgument {
+                        function: name.to_owned(),
+                        message: format!(
+                            "can't compare {} and {}",
+                            value.type_name(),
+                            best.type_name()
+                        ),
+                    }
+                })?;
+
+                if (pick_max && ordering.is_gt()) || (!pick_max && ordering.is_lt()) {
+                    best = value.clone();
+                }
+            }
+
+            match name {
+                "mini" | "maxi" => Value::Int(Args::new(name, &[best]).int(0)?),
+                "minf" | "maxf" => Value::Float(Args::new(name, &[best]).float(0)?),
+                _ => best,
+            }
+        }
+        "clamp" | "clampi" | "clampf" => {
+            args.count(3, 3)?;
+
+            match (&values[0], &values[1], &values[2], name) {
+                (Value::Int(value), Value::Int(min), Value::Int(max), "clamp" | "clampi") => {
+                    Value::Int((*value).max(*min).min(*max))
+                }
+                _ if name == "clampi" => {
+                    Value::Int(args.int(0)?.max(args.int(1)?).min(args.int(2)?))
+                }
+                _ => Value::Float(args.float(0)?.max(args.float(1)?).min(args.float(2)?)),
+            }
+        }
+        "lerp" | "lerpf" | "inverse_lerp" => {
+            args.count(3, 3)?;
+            let (from, to, weight) = (args.float(0)?, args.float(1)?, args.float(2)?);
+
+            Value::Float(match name {
+                "inverse_lerp" => (weight - from) / (to - from),
+                _ => from + (to - from) * weight,
+            })
+        }
+        "remap" => {
+            args.count(5, 5)?;
+            let value = args.float(0)?;
+            let (istart, istop) = (args.float(1)?, args.float(2)?);
+            let (ostart, ostop) = (args.float(3)?, args.float(4)?);
+
+            Value::Float(ostart + (ostop - ostart) * ((value - istart) / (istop - istart)))
+        }
+        "snapped" => {
+            args.count(2, 2)?;
+
+            match (&values[0], &values[1]) {
+                (Value::Int(value), Value::Int(step)) if *step != 0 => {
+                    Value::Int(((*value as f64 / *step as f64).round() as i64) * step)
+                }
+                _ => {
+                    let (value, step) = (args.float(0)?, args.float(1)?);
+
+                    Value::Float(if step == 0.0 {
+                        value
+                    } else {
+                        (value / step).round() * step
+                    })
+                }
+            }
+        }
+        "wrapi" => {
+            args.count(3, 3)?;
+            let (value, min, max) = (args.int(0)?, args.int(1)?, args.int(2)?);
+            let range = max - min;
+
+            Value::Int(if range == 0 {
+                min
+            } else {
+                min + (value - min).rem_euclid(range)
+            })
+        }
+        "wrapf" => {
+            args.count(3, 3)?;
+            let (value, min, max) = (args.float(0)?, args.float(1)?, args.float(2)?);
+            let range = max - min;
+
+            Value::Float(if range == 0.0 {
+                min
+            } else {
+                min + (value - min).rem_euclid(range)
+            })
+        }
+        "is_equal_approx" => {
+            args.count(2, 2)?;
+            let (a, b) = (args.float(0)?, args.float(1)?);
+
+            Value::Bool(a == b || (a - b).abs() < (EPSILON * a.abs()).max(EPSILON))
+        }
+        "randi" => {
+            args.count(0, 0)?;
+            Value::Int((interpreter.random() >> 32) as i64)
+        }
+        "randf" => {
+            args.count(0, 0)?;
+            Value::Float(random_float(interpreter))
+        }
+        "randi_range" => {
+            args.count(2, 2)?;
+            let (from, to) = (args.int(0)?, args.int(1)?);
+            let (low, high) = (from.min(to), from.max(to));
+            let span = (high - low) as u64 + 1;
+
+            Value::Int(low + (interpreter.random() % span) as i64)
+        }
+        "randf_range" => {
+            args.count(2, 2)?;
+            let (from, to) = (args.float(0)?, args.float(1)?);
+
+            Value::Float(from + (to - from) * random_float(interpreter))
+        }
+        "randomize" => {
+            args.count(0, 0)?;
+            interpreter.seed(None);
+            Value::Null
+        }
+        "seed" => {
+            args.count(1, 1)?;
+            interpreter.seed(Some(args.int(0)? as u64));
+            Value::Null
+        }
+        "load" | "preload" => {
+            return Err(ErrorKind::Unsupported("Loading resources".to_owned()).into())
+        }
+        _ => unreachable!("{name} is listed in FUNCTIONS"),
+    };
+
+    Ok(value)
+}
+
+/// Call the constructor of a built-in type, as in `int("5")`.
+pub fn construct<'a>(ty: Type, values: Vec<Value<'a>>) -> Result<Value<'a>, Error> {
+    let args = Args::new(ty.name(), &values);
+
+    if values.is_empty() {
+        return Ok(ty.default_value());
+    }
+
+    let invalid = |value: &Value<'a>| -> Error {
+        ErrorKind::InvalidArgument {
+            function: ty.name().to_owned(),
+            message: format!("can't construct from a value of type {}", value.type_name()),
+        }
+        .into()
+    };
+
+    match ty {
+        Type::Callable => {
+            args.count(2, 2)?;
+            return Ok(Value::Callable(Callable::method(
+                values[0].clone(),
+                args.str(1)?,
+            )));
+        }
+        Type::Signal => {
+            args.count(2, 2)?;
+
+            let Value::Object(object) = &values[0] else {
+                return Err(invalid(&values[0]));
+            };
+
+            return Ok(Value::Signal(Signal {
+                object: object.clone(),
+                name: args.str(1)?.into(),
+            }));
+        }
+        _ => args.count(1, 1)?,
+    }
+
+    let value = &values[0];
+
+    Ok(match (ty, value) {
+        (Type::Bool, Value::Bool(_) | Value::Int(_) | Value::Float(_)) => {
+            Value::Bool(value.is_truthy())
+        }
+        (Type::Int, Value::Int(_)) => value.clone(),
+        (Type::Int, Value::Float(float)) => Value::Int(*float as i64),
+        (Type::Int, Value::Bool(boolean)) => Value::Int(*boolean as i64),
+        (Type::Int, Value::String(string) | Value::StringName(string)) => {
+            Value::Int(methods::to_int(string))
+        }
+        (Type::Float, Value::Int(int)) => Value::Float(*int as f64),
+        (Type::Float, Value::Float(_)) => value.clone(),
+        (Type::Float, Value::Bool(boolean)) => Value::Float(*boolean as i64 as f64),
+        (Type::Float, Value::String(string) | Value::StringName(string)) => {
+            Value::Float(methods::to_float(string))
+        }
+        (Type::String, value) => Value::string(value.to_string()),
+        (Type::StringName, value) => Value::StringName(value.to_string().into()),
+        (Type::Array, Value::Array(array)) => Value::array(array.to_vec()),
+        (Type::Dictionary, Value::Dictionary(dictionary)) => {
+            Value::Dictionary(dictionary.duplicate())
+        }
+        (Type::Dictionary, Value::Array(_)) => Value::Dictionary(Dictionary::default()),
+        _ => return Err(invalid(value)),
+    })
+}
+
+const EPSILON: f64 = 0.00001;
+
+fn join(values: &[Value<'_>], separator: &str) -> String {
+    values
+        .iter()
+        .map(Value::to_string)
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+fn float_function(name: &str, value: f64) -> f64 {
+    match name {
+        "abs" => value.abs(),
+        "sign" => {
+            if value == 0.0 || value.is_nan() {
+                0.0
+            } else {
+                value.signum()
+            }
+        }
+        "floor" => value.floor(),
+        "ceil" => value.ceil(),
+        "round" => value.round(),
+        "sqrt" => value.sqrt(),
+        "sin" => value.sin(),
+        "cos" => value.cos(),
+        "tan" => value.tan(),
+        "asin" => value.asin(),
+        "acos" => value.acos(),
+        "atan" => value.atan(),
+        "exp" => value.exp(),
+        "log" => value.ln(),
+        "deg_to_rad" => value.to_radians(),
+        "rad_to_deg" => value.to_degrees(),
+        _ => unreachable!("{name} is not a float function"),
+    }
+}
+
+fn random_float(interpreter: &Interpreter<'_>) -> f64 {
+    (interpreter.random() >> 11) as f64 / (1u64 << 53) as f64
+}
@@ -0,0 +1,864 @@
+//! Methods of built-in types. Array methods that take a callable are
+//! implemented by the interpreter itself.
+
+use crate::{
+    error::{Error, ErrorKind},
+    operators::{compare, resolve_index},
+    stdlib::Args,
+    value::{Array, Callable, CallableKind, Connection, Dictionary, Signal, Value},
+};
+
+/// Call a method of a built-in type.
+pub fn call<'a>(
+    receiver: &Value<'a>,
+    name: &str,
+    values: Vec<Value<'a>>,
+) -> Result<Value<'a>, Error> {
+    let args = Args::new(name, &values);
+
+    let value = match receiver {
+        Value::String(string) | Value::StringName(string) => string_method(string, name, &args)?,
+        Value::Array(array) => array_method(array, name, &args)?,
+        Value::Dictionary(dictionary) => dictionary_method(dictionary, name, &args)?,
+        Value::Callable(callable) => callable_method(callable, name, &args)?,
+        Value::Signal(signal) => signal_method(signal, name, &args)?,
+        _ => None,
+    };
+
+    value.ok_or_else(|| {
+        ErrorKind::InvalidMethod {
+            name: name.to_owned(),
+            base: receiver.type_name(),
+        }
+        .into()
+    })
+}
+
+fn string_method<'a>(
+    string: &str,
+    name: &str,
+    args: &Args<'_, 'a>,
+) -> Result<Option<Value<'a>>, Error> {
+    let chars = || string.chars().collect::<Vec<_>>();
+    let from_chars = |chars: &[char]| Value::string(chars.iter().collect::<String>());
+
+    Ok(Some(match name {
+        "length" => {
+            args.count(0, 0)?;
+            Value::Int(string.chars().count() as i64)
+        }
+        "is_empty" => {
+            args.count(0, 0)?;
+            Value::Bool(string.is_empty())
+        }
+        "to_upper" => {
+            args.count(0, 0)?;
+            Value::string(string.to_uppercase())
+        }
+        "to_lower" => {
+            args.count(0, 0)?;
+            Value::string(string.to_lowercase())
+        }
+        "capitalize" => {
+            args.count(0, 0)?;
+            Value::string(capitalize(string))
+        }
+        "substr" => {
+            args.count(1, 2)?;
+            let chars = chars();
+            let from = (args.int(0)?.max(0) as usize).min(chars.len());
+            let len = args.int_or(1, -1)?;
+            let to = if len < 0 {
+                chars.len()
+            } else {
+                from.saturating_add(len as usize).min(chars.len())
+            };
+
+            from_chars(&chars[from..to])
+        }
+        "left" | "right" => {
+            args.count(1, 1)?;
+            let chars = chars();
+            let len = chars.len() as i64;
+            let n = args.int(0)?;
+            // Negative counts are relative to the other end.
+            let count = if n < 0 { (len + n).max(0) } else { n.min(len) } as usize;
+
+            if name == "left" {
+                from_chars(&chars[..count])
+            } else {
+                from_chars(&chars[chars.len() - count..])
+            }
+        }
+        "begins_with" => {
+            args.count(1, 1)?;
+            Value::Bool(string.starts_with(args.str(0)?))
+        }
+        "ends_with" => {
+            args.count(1, 1)?;
+            Value::Bool(string.ends_with(args.str(0)?))
+        }
+        "contains" => {
+            args.count(1, 1)?;
+            Value::Bool(string.contains(args.str(0)?))
+        }
+        "find" => {
+            args.count(1, 2)?;
+            let from = args.int_or(1, 0)?.max(0) as usize;
+            let offset = string
+                .char_indices()
+                .nth(from)
+                .map_or(string.len(), |(idx, _)| idx);
+
+            Value::Int(match string[offset..].find(args.str(0)?) {
+                Some(idx) => string[..offset + idx].chars().count() as i64,
+                None => -1,
+            })
+        }
+        "rfind" => {
+            args.count(1, 1)?;
+
+            Value::Int(match string.rfind(args.str(0)?) {
+                Some(idx) => string[..idx].chars().count() as i64,
+                None => -1,
+            })
+        }
+        "count" => {
+            args.count(1, 1)?;
+            let what = args.str(0)?;
+
+            Value::Int(if what.is_empty() {
+                0
+            } else {
+                string.matches(what).count() as i64
+            })
+        }
+        "replace" => {
+            args.count(2, 2)?;
+            Value::string(string.replace(args.str(0)?, args.str(1)?))
+        }
+        "trim_prefix" => {
+            args.count(1, 1)?;
+            Value::string(string.strip_prefix(args.str(0)?).unwrap_or(string))
+        }
+        "trim_suffix" => {
+            args.count(1, 1)?;
+            Value::string(string.strip_suffix(args.str(0)?).unwrap_or(string))
+        }
+        "split" => {
+            args.count(0, 3)?;
+            let delimiter = match args.get(0) {
+                Some(_) => args.str(0)?,
+                None => "",
+            };
+            let allow_empty = args.bool_or(1, true)?;
+            let max_splits = args.int_or(2, 0)?;
+
+            let parts: Vec<String> = if delimiter.is_empty() {
+                string.chars().map(String::from).collect()
+            } else if max_splits > 0 {
+                string
+                    .splitn((max_splits as usize).saturating_add(1), delimiter)
+                    .map(String::from)
+                    .collect()
+            } else {
+                string.split(delimiter).map(String::from).collect()
+            };
+
+            Value::array(
+                parts
+                    .into_iter()
+                    .filter(|part| allow_empty || !part.is_empty())
+                    .map(Value::string)
+                    .collect(),
+            )
+        }
+        "join" => {
+            args.count(1, 1)?;
+
+            let parts = args
+                .array(0)?
+                .borrow()
+                .iter()
+                .map(Value::to_string)
+                .collect::<Vec<_>>();
+
+            Value::string(parts.join(string))
+        }
+        "strip_edges" => {
+            args.count(0, 2)?;
+
+            let string = if args.bool_or(0, true)? {
+                string.trim_start()
+            } else {
+                string
+            };
+            let string = if args.bool_or(1, true)? {
+                string.trim_end()
+            } else {
+                string
+            };
+
+            Value::string(string)
+        }
+        "lstrip" | "rstrip" => {
+            args.count(1, 1)?;
+            let chars = args.str(0)?.chars().collect::<Vec<_>>();
+
+            Value::string(if name == "lstrip" {
+                string.trim_start_matches(chars.as_slice())
+            } else {
+                string.trim_end_matches(chars.as_slice())
+            })
+        }
+        "to_int" => {
+            args.count(0, 0)?;
+            Value::Int(to_int(string))
+        }
+        "to_float" => {
+            args.count(0, 0)?;
+            Value::Float(to_float(string))
+        }
+        "is_valid_int" => {
+            args.count(0, 0)?;
+            Value::Bool(string.parse::<i64>().is_ok())
+        }
+        "is_valid_float" => {
+            args.count(0, 0)?;
+            Value::Bool(string.parse::<f64>().is_ok())
+        }
+        "repeat" => {
+            args.count(1, 1)?;
+            let count = args.int(0)?.max(0) as usize;
+
+            // `str::repeat` panics when the result can't fit in memory
+            if string.len().saturating_mul(count) > isize::MAX as usize {
+                return Err(ErrorKind::InvalidArgument {
+                    function: name.to_owned(),
+                    message: "the repeated string would be too long".to_owned(),
+                }
+                .into());
+            }
+
+            Value::string(string.repeat(count))
+        }
+        "reverse" => {
+            args.count(0, 0)?;
+            Value::string(string.chars().rev().collect::<String>())
+        }
+        "pad_zeros" => {
+            args.count(1, 1)?;
+            let digits = args.int(0)?.max(0) as usize;
+            let (sign, unsigned) = match string.strip_prefix('-') {
+                Some(unsigned) => ("-", unsigned),
+                None => ("", string),
+            };
+            let integer = unsigned.find('.').unwrap_or(unsigned.len());
+            let padding = "0".repeat(digits.saturating_sub(integer));
+
+            Value::string(format!("{sign}{padding}{unsigned}"))
+        }
+        "unicode_at" => {
+            args.count(1, 1)?;
+            let chars = chars();
+            let idx = resolve_index(args.int(0)?, chars.len())?;
+
+            Value::Int(chars[idx] as i64)
+        }
+        "format" => {
+            args.count(1, 1)?;
+            let mut formatted = string.to_owned();
+
+            let entries = match args.get(0) {
+                Some(Value::Dictionary(dictionary)) => dictionary.borrow().clone(),
+                Some(Value::Array(array)) => array
+                    .to_vec()
+                    .into_iter()
+                    .enumerate()
+                    .map(|(idx, value)| (Value::Int(idx as i64), value))
+                    .collect(),
+                _ => {
+                    args.dictionary(0)?;
+                    unreachable!()
+                }
+            };
+
+            for (key, value) in entries {
+                formatted = formatted.replace(&format!("{{{key}}}"), &value.to_string());
+            }
+
+            Value::string(formatted)
+        }
+        _ => return Ok(None),
+    }))
+}
+
+fn array_method<'a>(
+    array: &Array<'a>,
+    name: &str,
+    args: &Args<'_, 'a>,
+) -> Result<Option<Value<'a>>, Error> {
+    let arg = |idx: usize| args.get(idx).cloned().unwrap_or_default();
+
+    Ok(Some(match name {
+        "size" => {
+            args.count(0, 0)?;
+            Value::Int(array.len() as i64)
+        }
+        "is_empty" => {
+            args.count(0, 0)?;
+            Value::Bool(array.is_empty())
+        }
+        "append" | "push_back" => {
+            args.count(1, 1)?;
+            array.borrow_mut().push(arg(0));
+            Value::Null
+        }
+        "append_array" => {
+            args.count(1, 1)?;
+            let other = args.array(0)?.to_vec();
+            array.borrow_mut().extend(other);
+            Value::Null
+        }
+        "push_front" => {
+            args.count(1, 1)?;
+            array.borrow_mut().insert(0, arg(0));
+            Value::Null
+        }
+        "pop_back" => {
+            args.count(0, 0)?;
+            array.borrow_mut().pop().unwrap_or_default()
+        }
+        "pop_front" => {
+            args.count(0, 0)?;
+            let mut array = array.borrow_mut();
+
+            if array.is_empty() {
+                Value::Null
+            } else {
+                array.remove(0)
+            }
+        }
+        "pop_at" | "remove_at" => {
+            args.count(1, 1)?;
+            let mut array = array.borrow_mut();
+            let idx = resolve_index(args.int(0)?, array.len())?;
+            let removed = array.remove(idx);
+
+            if name == "pop_at" {
+                removed
+            } else {
+                Value::Null
+            }
+        }
+        "insert" => {
+            args.count(2, 2)?;
+            let mut array = array.borrow_mut();
+            // Inserting right after the last element is allowed.
+            let idx = resolve_index(args.int(0)?, array.len() + 1)?;
+            array.insert(idx, arg(1));
+
+            Value::Null
+        }
+        "erase" => {
+            args.count(1, 1)?;
+            let mut array = array.borrow_mut();
+
+            if let Some(idx) = array.iter().position(|item| item.equals(&arg(0))) {
+                array.remove(idx);
+            }
+
+            Value::Null
+        }
+        "clear" => {
+            args.count(0, 0)?;
+            array.borrow_mut().clear();
+            Value::Null
+        }
+        "has" => {
+            args.count(1, 1)?;
+            Value::Bool(array.borrow().iter().any(|item| item.equals(&arg(0))))
+        }
+        "find" => {
+            args.count(1, 2)?;
+            let from = args.int_or(1, 0)?.max(0) as usize;
+            let array = array.borrow();
+            let position = array
+                .iter()
+                .skip(from)
+                .position(|item| item.equals(&arg(0)));
+
+            Value::Int(position.map_or(-1, |idx| (idx + from) as i64))
+        }
+        "rfind" => {
+            args.count(1, 1)?;
+            let position = array.borrow().iter().rposition(|item| item.equals(&arg(0)));
+
+            Value::Int(position.map_or(-1, |idx| idx as i64))
+        }
+        "count" => {
+            args.count(1, 1)?;
+            Value::Int(
+                array
+                    .borrow()
+                    .iter()
+                    .filter(|item| item.equals(&arg(0)))
+                    .count() as i64,
+            )
+        }
+        "reverse" => {
+            args.count(0, 0)?;
+            array.borrow_mut().reverse();
+            Value::Null
+        }
+        "sort" => {
+            args.count(0, 0)?;
+            array.borrow_mut().sort_by(|a, b| {
+                compare(a, b).unwrap_or_else(|| a.type_of().id().cmp(&b.type_of().id()))
+            });
+
+            Value::Null
+        }
+        "duplicate" => {
+            args.count(0, 1)?;
+            duplicate(&Value::Array(array.clone()), args.bool_or(0, false)?)
+        }
+        "slice" => {
+            args.count(1, 4)?;
+            let items = array.to_vec();
+            let len = items.len() as i64;
+            let clamp = |idx: i64| {
+                if idx < 0 {
+                    (len + idx).max(0)
+                } else {
+                    idx.min(len)
+                }
+            };
+            let begin = clamp(args.int(0)?);
+            let end = clamp(args.int_or(1, i64::from(i32::MAX))?);
+            let step = args.int_or(2, 1)?;
+            let deep = args.bool_or(3, false)?;
+
+            if step == 0 {
+                return Err(ErrorKind::InvalidArgument {
+                    function: name.to_owned(),
+                    message: "step can't be zero".to_owned(),
+                }
+                .into());
+            }
+
+            let mut sliced = vec![];
+            let mut idx = begin;
+
+            while (step > 0 && idx < end) || (step < 0 && idx > end) {
+                if (0..len).contains(&idx) {
+                    sliced.push(duplicate(&items[idx as usize], deep));
+                }
+
+                idx += step;
+            }
+
+            Value::array(sliced)
+        }
+        "front" | "back" => {
+            args.count(0, 0)?;
+            let array = array.borrow();
+            let item = if name == "front" {
+                array.first()
+            } else {
+                array.last()
+            };
+
+            item.cloned().unwrap_or_default()
+        }
+        "get" => {
+            args.count(1, 1)?;
+            let array = array.borrow();
+
+            array[resolve_index(args.int(0)?, array.len())?].clone()
+        }
+        "set" => {
+            args.count(2, 2)?;
+            let mut array = array.borrow_mut();
+            let idx = resolve_index(args.int(0)?, array.len())?;
+            array[idx] = arg(1);
+
+            Value::Null
+        }
+        "min" | "max" => {
+            args.count(0, 0)?;
+            let array = array.borrow();
+            let mut items = array.iter();
+            let Some(mut best) = items.next() else {
+                return Ok(Some(Value::Null));
+            };
+
+            for item in items {
+                match compare(item, best) {
+                    Some(ordering) if (name == "max") == ordering.is_gt() && ordering.is_ne() => {
+                        best = item
+                    }
+                    Some(_) => (),
+                    // Like in the engine, incomparable elements give `null`.
+                    None => return Ok(Some(Value::Null)),
+                }
+            }
+
+            best.clone()
+        }
+        "resize" => {
+            args.count(1, 1)?;
+            array
+                .borrow_mut()
+                .resize(args.int(0)?.max(0) as usize, Value::Null);
+            Value::Null
+        }
+        "fill" => {
+            args.count(1, 1)?;
+            array.borrow_mut().fill(arg(0));
+            Value::Null
+        }
+        _ => return Ok(None),
+    }))
+}
+
+fn dictionary_method<'a>(
+    dictionary: &Dictionary<'a>,
+    name: &str,
+    args: &Args<'_, 'a>,
+) -> Result<Option<Value<'a>>, Error> {
+    let arg = |idx: usize| args.get(idx).cloned().unwrap_or_default();
+
+    Ok(Some(match name {
+        "size" => {
+            args.count(0, 0)?;
+            Value::Int(dictionary.len() as i64)
+        }
+        "is_empty" => {
+            args.count(0, 0)?;
+            Value::Bool(dictionary.is_empty())
+        }
+        "has" => {
+            args.count(1, 1)?;
+            Value::Bool(dictionary.contains_key(&arg(0)))
+        }
+        "has_all" => {
+            args.count(1, 1)?;
+            let keys = args.array(0)?.to_vec();
+
+            Value::Bool(keys.iter().all(|key| dictionary.contains_key(key)))
+        }
+        "get" => {
+            args.count(1, 2)?;
+            dictionary.get(&arg(0)).unwrap_or_else(|| arg(1))
+        }
+        "keys" => {
+            args.count(0, 0)?;
+            Value::array(dictionary.keys())
+        }
+        "values" => {
+            args.count(0, 0)?;
+            Value::array(dictionary.values())
+        }
+        "erase" => {
+            args.count(1, 1)?;
+            Value::Bool(dictionary.remove(&arg(0)).is_some())
+        }
+        "clear" => {
+            args.count(0, 0)?;
+            dictionary.clear();
+            Value::Null
+        }
+        "merge" => {
+            args.count(1, 2)?;
+            let other = args.dictionary(0)?.borrow().clone();
+            let overwrite = args.bool_or(1, false)?;
+
+            for (key, value) in other {
+                if overwrite || !dictionary.contains_key(&key) {
+                    dictionary.insert(key, value);
+                }
+            }
+
+            Value::Null
+        }
+        "duplicate" => {
+            args.count(0, 1)?;
+            duplicate(
+                &Value::Dictionary(dictionary.clone()),
+                args.bool_or(0, false)?,
+            )
+        }
+        "find_key" => {
+            args.count(1, 1)?;
+
+            dictionary
+                .borrow()
+                .iter()
+                .find(|(_, value)| value.equals(&arg(0)))
+                .map(|(key, _)| key.clone())
+                .unwrap_or_default()
+        }
+        _ => return Ok(None),
+    }))
+}
+
+fn callable_method<'a>(
+    callable: &Callable<'a>,
+    name: &str,
+    args: &Args<'_, 'a>,
+) -> Result<Option<Value<'a>>, Error> {
+    Ok(Some(match name {
+        "bind" | "bindv" => {
+            let bound = if name == "bind" {
+                args.values.to_vec()
+            } else {
+                args.count(1, 1)?;
+                args.array(0)?.to_vec()
+            };
+
+            let mut callable = callable.clone();
+            // Later bindings go before earlier ones, as they are closer to the call.
+            callable.bound.splice(0..0, bound);
+
+            Value::Callable(callable)
+        }
+        "is_valid" => {
+            args.count(0, 0)?;
+
+            Value::Bool(match &callable.kind {
+                CallableKind::Method { receiver, name } => match receiver.as_ref() {
+                    Value::Object(object) => object.class().function(name).is_some(),
+                    Value::Class(class) => class.function(name).is_some(),
+                    _ => true,
+                },
+                CallableKind::Lambda(_) | CallableKind::Builtin(_) => true,
+            })
+        }
+        "is_null" => {
+            args.count(0, 0)?;
+            Value::Bool(false)
+        }
+        "get_method" => {
+            args.count(0, 0)?;
+            Value::StringName(callable.name().unwrap_or_default().into())
+        }
+        "get_object" => {
+            args.count(0, 0)?;
+
+            match &callable.kind {
+                CallableKind::Method { receiver, .. } => receiver.as_ref().clone(),
+                CallableKind::Lambda(lambda) => lambda
+                    .frame
+                    .this
+                    .clone()
+                    .map(Value::Object)
+                    .unwrap_or_default(),
+                CallableKind::Builtin(_) => Value::Null,
+            }
+        }
+        "get_bound_arguments" => {
+            args.count(0, 0)?;
+            Value::array(callable.bound.clone())
+        }
+        "get_bound_arguments_count" => {
+            args.count(0, 0)?;
+            Value::Int(callable.bound.len() as i64)
+        }
+        _ => return Ok(None),
+    }))
+}
+
+fn signal_method<'a>(
+    signal: &Signal<'a>,
+    name: &str,
+    args: &Args<'_, 'a>,
+) -> Result<Option<Value<'a>>, Error> {
+    if !signal.object.class().has_signal(&signal.name) {
+        return Err(ErrorKind::UndefinedSignal {
+            name: signal.name.to_string(),
+            base: signal.object.class().name.clone(),
+        }
+        .into());
+    }
+
+    let is_connected = |callable: &Callable<'a>| {
+        let target = Value::Callable(callable.clone());
+
+        signal
+            .object
+            .borrow()
+            .connections
+            .get(signal.name.as_ref())
+            .is_some_and(|connections| {
+                connections
+                    .iter()
+                    .any(|connection| Value::Callable(connection.callable.clone()).equals(&target))
+            })
+    };
+
+    Ok(Some(match name {
+        "get_name" => {
+            args.count(0, 0)?;
+            Value::StringName(signal.name.clone())
+        }
+        "get_object" => {
+            args.count(0, 0)?;
+            Value::Object(signal.object.clone())
+        }
+        "connect" => {
+            args.count(1, 2)?;
+            let callable = args.callable(0)?;
+
+            // Connecting twice is an error in the engine, ERR_INVALID_PARAMETER.
+            if is_connected(callable) {
+                return Ok(Some(Value::Int(31)));
+            }
+
+            let flags = args.int_or(1, 0)?;
+
+            signal
+                .object
+                .borrow_mut()
+                .connections
+                .entry(signal.name.to_string())
+                .or_default()
+                .push(Connection {
+                    callable: callable.clone(),
+                    one_shot: flags & 4 != 0,
+                });
+
+            Value::Int(0)
+        }
+        "disconnect" => {
+            args.count(1, 1)?;
+            let target = Value::Callable(args.callable(0)?.clone());
+
+            if let Some(connections) = signal
+                .object
+                .borrow_mut()
+                .connections
+                .get_mut(signal.name.as_ref())
+            {
+                connections.retain(|connection| {
+                    !Value::Callable(connection.callable.clone()).equals(&target)
+                });
+            }
+
+            Value::Null
+        }
+        "is_connected" => {
+            args.count(1, 1)?;
+            Value::Bool(is_connected(args.callable(0)?))
+        }
+        "has_connections" => {
+            args.count(0, 0)?;
+
+            Value::Bool(
+                signal
+                    .object
+                    .borrow()
+                    .connections
+                    .get(signal.name.as_ref())
+                    .is_some_and(|connections| !connections.is_empty()),
+            )
+        }
+        _ => return Ok(None),
+    }))
+}
+
+/// A copy of a container. Deep copies also copy nested containers.
+pub(crate) fn duplicate<'a>(value: &Value<'a>, deep: bool) -> Value<'a> {
+    let copy = |value: &Value<'a>| {
+        if deep {
+            duplicate(value, true)
+        } else {
+            value.clone()
+        }
+    };
+
+    match value {
+        Value::Array(array) => Value::array(array.borrow().iter().map(copy).collect()),
+        Value::Dictionary(dictionary) => Value::Dictionary(Dictionary::new(
+            dictionary
+                .borrow()
+                .iter()
+                .map(|(key, value)| (key.clone(), copy(value)))
+                .collect(),
+        )),
+        other => other.clone(),
+    }
+}
+
+/// Parse the leading integer of a string, like `String.to_int()`.
+pub(crate) fn to_int(string: &str) -> i64 {
+    let string = string.trim();
+    let (negative, digits) = match string.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, string.strip_prefix('+').unwrap_or(string)),
+    };
+
+    let value = digits
+        .chars()
+        .take_while(char::is_ascii_digit)
+        .fold(0i64, |value, digit| {
+            value
+                .wrapping_mul(10)
+                .wrapping_add(digit.to_digit(10).unwrap() as i64)
+        });
+
+    if negative {
+        value.wrapping_neg()
+    } else {
+        value
+    }
+}
+
+/// Parse the leading float of a string, like `String.to_float()`.
+pub(crate) fn to_float(string: &str) -> f64 {
+    let string = string.trim();
+
+    (1..=string.len())
+        .rev()
+        .filter(|end| string.is_char_boundary(*end))
+        .find_map(|end| string[..end].parse().ok())
+        .unwrap_or(0.0)
+}
+
+/// Turn `snake_case` and `camelCase` into `Title Case`.
+fn capitalize(string: &str) -> String {
+    let mut words = vec![];
+    let mut current = String::new();
+    let mut previous_lowercase = false;
+
+    for c in string.chars() {
+        if c == '_' || c == ' ' {
+            words.push(std::mem::take(&mut current));
+            previous_lowercase = false;
+            continue;
+        }
+
+        if c.is_uppercase() && previous_lowercase {
+            words.push(std::mem::take(&mut current));
+        }
+
+        previous_lowercase = c.is_lowercase() || c.is_ascii_digit();
+        current.push(c);
+    }
+
+    words.push(current);
+
+    words
+        .into_iter()
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            let first = chars.next().unwrap().to_uppercase();
+
+            first
+                .chain(chars.flat_map(char::to_lowercase))
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
@@ -0,0 +1,150 @@
+//! A small subset of Godot's built-in functions and methods.
+
+pub mod format;
+pub mod functions;
+pub mod methods;
+
+use crate::{
+    error::{Error, ErrorKind},
+    value::{Array, Callable, Dictionary, Type, Value},
+};
+
+/// Look up a global constant, like `PI` or `TYPE_INT`.
+pub fn constant<'a>(name: &str) -> Option<Value<'a>> {
+    Some(match name {
+        "PI" => Value::Float(std::f64::consts::PI),
+        "TAU" => Value::Float(std::f64::consts::TAU),
+        "INF" => Value::Float(f64::INFINITY),
+        "NAN" => Value::Float(f64::NAN),
+        "CONNECT_ONE_SHOT" => Value::Int(4),
+        _ => {
+            let ty = name.strip_prefix("TYPE_")?;
+            let ty = match ty {
+                "NIL" => Type::Nil,
+                "BOOL" => Type::Bool,
+                "INT" => Type::Int,
+                "FLOAT" => Type::Float,
+                "STRING" => Type::String,
+                "STRING_NAME" => Type::StringName,
+                "ARRAY" => Type::Array,
+                "DICTIONARY" => Type::Dictionary,
+                "CALLABLE" => Type::Callable,
+                "SIGNAL" => Type::Signal,
+                "OBJECT" => Type::Object,
+                _ => return None,
+            };
+
+            Value::Int(ty.id())
+        }
+    })
+}
+
+/// Arguments of a built-in function or method, with checked accessors.
+pub(crate) struct Args<'v, 'a> {
+    function: &'v str,
+    values: &'v [Value<'a>],
+}
+
+impl<'v, 'a> Args<'v, 'a> {
+    pub fn new(function: &'v str, values: &'v [Value<'a>]) -> Self {
+        Self { function, values }
+    }
+
+    /// Check that there are between `min` and `max` arguments.
+    pub fn count(&self, min: usize, max: usize) -> Result<(), Error> {
+        if (min..=max).contains(&self.values.len()) {
+            return Ok(());
+        }
+
+        let expected = if min == max {
+            min.to_string()
+        } else if max == usize::MAX {
+            format!("at least {min}")
+        } else {
+            format!("{min} to {max}")
+        };
+
+        Err(ErrorKind::ArgumentCount {
+            function: self.function.to_owned(),
+            expected,
+            received: self.values.len(),
+        }
+        .into())
+    }
+
+    pub fn get(&self, idx: usize) -> Option<&'v Value<'a>> {
+        self.values.get(idx)
+    }
+
+    pub fn int(&self, idx: usize) -> Result<i64, Error> {
+        match self.values.get(idx) {
+            Some(Value::Int(value)) => Ok(*value),
+            Some(Value::Float(value)) => Ok(*value as i64),
+            other => Err(self.invalid(idx, "int", other)),
+        }
+    }
+
+    pub fn int_or(&self, idx: usize, default: i64) -> Result<i64, Error> {
+        match self.values.get(idx) {
+            Some(_) => self.int(idx),
+            None => Ok(default),
+        }
+    }
+
+    pub fn float(&self, idx: usize) -> Result<f64, Error> {
+        let value = self.values.get(idx);
+
+        value
+            .and_then(Value::as_float)
+            .ok_or_else(|| self.invalid(idx, "float", value))
+    }
+
+    pub fn bool_or(&self, idx: usize, default: bool) -> Result<bool, Error> {
+        match self.values.get(idx) {
+            Some(Value::Bool(value)) => Ok(*value),
+            None => Ok(default),
+            other => Err(self.invalid(idx, "bool", other)),
+        }
+    }
+
+    pub fn str(&self, idx: usize) -> Result<&'v str, Error> {
+        let value = self.values.get(idx);
+
+        value
+            .and_then(Value::as_str)
+            .ok_or_else(|| self.invalid(idx, "String", value))
+    }
+
+    pub fn array(&self, idx: usize) -> Result<&'v Array<'a>, Error> {
+        match self.values.get(idx) {
+            Some(Value::Array(array)) => Ok(array),
+            other => Err(self.invalid(idx, "Array", other)),
+        }
+    }
+
+    pub fn dictionary(&self, idx: usize) -> Result<&'v Dictionary<'a>, Error> {
+        match self.values.get(idx) {
+            Some(Value::Dictionary(dictionary)) => Ok(dictionary),
+            other => Err(self.invalid(idx, "Dictionary", other)),
+        }
+    }
+
+    pub fn callable(&self, idx: usize) -> Result<&'v Callable<'a>, Error> {
+        match self.values.get(idx) {
+            Some(Value::Callable(callable)) => Ok(callable),
+            other => Err(self.invalid(idx, "Callable", other)),
+        }
+    }
+
+    fn invalid(&self, idx: usize, expected: &str, found: Option<&Value<'a>>) -> Error {
+        ErrorKind::InvalidArgument {
+            function: self.function.to_owned(),
+            message: format!(
+                "expected argument {} to be {expected}, found {}",
+                idx + 1,
+                found.map_or_else(|| "nothing".to_owned(), Value::type_name)
+            ),
+        }
+        .into()
+    }
+}
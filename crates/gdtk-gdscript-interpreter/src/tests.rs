@@ -0,0 +1,425 @@
+use gdtk_gdscript_ast::ASTFile;
+use gdtk_gdscript_parser::{lexer::lex, parse_file};
+
+use crate::{Capture, Error, ErrorKind, Interpreter, Session, Value};
+
+/// Parse a script, leaking it so that it lives as long as the interpreter.
+fn parse(source: &str) -> &'static ASTFile<'static> {
+    let source = Box::leak(source.to_owned().into_boxed_str());
+    Box::leak(Box::new(parse_file(lex(source))))
+}
+
+/// Run `main` of a script and return what it printed.
+fn run(source: &str) -> Result<String, Error> {
+    let file = parse(source);
+
+    let output = Capture::default();
+    let interpreter = Interpreter::with_output(output.clone());
+    let class = interpreter.load(file, "test.gd")?;
+    let object = interpreter.instantiate(&class, vec![])?;
+    interpreter.call(&Value::Object(object), "main", vec![])?;
+
+    Ok(output.take())
+}
+
+macro_rules! test_output {
+    ($source: expr, $expected: expr) => {
+        assert_eq!(run($source).unwrap(), $expected);
+    };
+}
+
+macro_rules! test_error {
+    ($source: expr, $pattern: pat) => {
+        let error = run($source).unwrap_err();
+        assert!(
+            matches!(error.kind, $pattern),
+            "unexpected error: {error:?}"
+        );
+    };
+}
+
+#[test]
+fn test_arithmetic() {
+    test_output!(
+        "func main():\n\tprint(1 + 2 * 3, \" \", 7 / 2, \" \", 7.0 / 2, \" \", 7 % 3, \" \", 2 ** 10)\n",
+        "7 3 3.5 1 1024\n"
+    );
+    test_output!(
+        "func main():\n\tprint(1 + 1.0, \" \", -3 / 2, \" \", 5 & 3, \" \", 1 << 4, \" \", 0x10)\n",
+        "2.0 -1 1 16 16\n"
+    );
+    test_output!(
+        "func main():\n\tprint(1 < 2 and 2 <= 2, \" \", not true or false, \" \", 3 in [1, 2, 3], \" \", 4 not in [4])\n",
+        "true false true false\n"
+    );
+    test_error!(
+        "func main():\n\tprint(1 / 0)\n",
+        ErrorKind::DivisionByZero(_)
+    );
+    test_error!(
+        "func main():\n\tprint(1 + \"a\")\n",
+        ErrorKind::InvalidOperands { .. }
+    );
+}
+
+#[test]
+fn test_strings() {
+    test_output!(
+        "func main():\n\tprint(\"a\\tb\".length(), \" \", \"abc\".to_upper(), \" \", \"hello_world\".capitalize())\n",
+        "3 ABC Hello World\n"
+    );
+    test_output!(
+        "func main():\n\tprint(\"%s is %03d, %.2f\" % [\"x\", 7, 1.5])\n",
+        "x is 007, 1.50\n"
+    );
+    test_output!(
+        "func main():\n\tprint(\"a,b,,c\".split(\",\"), \" \", \",\".join([\"x\", \"y\"]), \" \", \"  x \".strip_edges())\n",
+        "[\"a\", \"b\", \"\", \"c\"] x,y x\n"
+    );
+    test_output!(
+        "func main():\n\tprint(\"{a}-{b}\".format({\"a\": 1, \"b\": 2}), \" \", \"12abc\".to_int(), \" \", \"hello\".find(\"l\"))\n",
+        "1-2 12 2\n"
+    );
+    // no overflows on extreme arguments
+    test_output!(
+        "func main():\n\tprint(\"ab\".substr(1, 9223372036854775807), \" \", \"a,b\".split(\",\", true, 9223372036854775807), \" \", \"-9223372036854775808\".to_int())\n",
+        "b [\"a\", \"b\"] -9223372036854775808\n"
+    );
+    test_error!(
+        "func main():\n\tprint(\"ab\".repeat(9223372036854775807))\n",
+        ErrorKind::InvalidArgument { .. }
+    );
+}
+
+#[test]
+fn test_containers() {
+    test_output!(
+        "func main():\n\tvar a = [3, 1, 2]\n\ta.append(0)\n\ta.sort()\n\tprint(a, \" \", a.size(), \" \", a[-1], \" \", a.slice(1, 3))\n",
+        "[0, 1, 2, 3] 4 3 [1, 2]\n"
+    );
+    test_output!(
+        "func main():\n\tvar d = {\"a\": 1, \"b\": 2}\n\td[\"c\"] = 3\n\td.b += 1\n\tprint(d, \" \", d.keys(), \" \", d.get(\"z\", 0))\n",
+        "{ \"a\": 1, \"b\": 3, \"c\": 3 } [\"a\", \"b\", \"c\"] 0\n"
+    );
+    test_output!(
+        "func main():\n\tvar d = {x = 1, y = 2}\n\tprint(d.x + d[\"y\"])\n",
+        "3\n"
+    );
+    test_output!(
+        "func main():\n\tvar a = [1]\n\tvar b = a\n\tvar c = a.duplicate()\n\tb.append(2)\n\tprint(a, c, a == [1, 2])\n",
+        "[1, 2][1]true\n"
+    );
+    test_error!(
+        "func main():\n\tvar a = []\n\tprint(a[0])\n",
+        ErrorKind::IndexOutOfBounds { .. }
+    );
+}
+
+#[test]
+fn test_control_flow() {
+    test_output!(
+        concat!(
+            "func main():\n",
+            "\tvar total = 0\n",
+            "\tfor i in range(10):\n",
+            "\t\tif i == 8:\n",
+            "\t\t\tbreak\n",
+            "\t\telif i % 2:\n",
+            "\t\t\tcontinue\n",
+            "\t\telse:\n",
+            "\t\t\ttotal += i\n",
+            "\twhile total > 5:\n",
+            "\t\ttotal -= 5\n",
+            "\tprint(total)\n",
+        ),
+        "2\n"
+    );
+    test_output!(
+        "func main():\n\tfor c in \"ab\":\n\t\tprint(c)\n\tfor k in {\"x\": 1}:\n\t\tprint(k)\n",
+        "a\nb\nx\n"
+    );
+}
+
+#[test]
+fn test_match() {
+    let source = concat!(
+        "func describe(x):\n",
+        "\tmatch x:\n",
+        "\t\t1, 2:\n",
+        "\t\t\treturn \"small\"\n",
+        "\t\t[var first, ..]:\n",
+        "\t\t\treturn \"array starting with %s\" % first\n",
+        "\t\t{\"kind\": \"point\", \"x\": var px}:\n",
+        "\t\t\treturn \"point at %s\" % px\n",
+        "\t\tvar n when n is int and n > 100:\n",
+        "\t\t\treturn \"big\"\n",
+        "\t\t_:\n",
+        "\t\t\treturn \"other\"\n",
+        "func main():\n",
+        "\tfor x in [2, [5, 6], {\"kind\": \"point\", \"x\": 3}, 500, 50]:\n",
+        "\t\tprint(describe(x))\n",
+    );
+
+    test_output!(
+        source,
+        "small\narray starting with 5\npoint at 3\nbig\nother\n"
+    );
+}
+
+#[test]
+fn test_classes() {
+    let source = concat!(
+        "class_name Test\n",
+        "extends Node\n",
+        "const GREETING = \"hi\"\n",
+        "enum State { IDLE, RUNNING = 5 }\n",
+        "static var created = 0\n",
+        "var name: String\n",
+        "var health: int:\n",
+        "\tset(value):\n",
+        "\t\thealth = clamp(value, 0, 100)\n",
+        "class Base:\n",
+        "\tvar level = 1\n",
+        "\tfunc _init(start):\n",
+        "\t\tlevel = start\n",
+        "\tfunc describe():\n",
+        "\t\treturn \"level %d\" % level\n",
+        "class Derived extends Base:\n",
+        "\tfunc _init():\n",
+        "\t\tsuper(3)\n",
+        "\tfunc describe():\n",
+        "\t\treturn \"derived \" + super()\n",
+        "func _init():\n",
+        "\tcreated += 1\n",
+        "\tname = \"test\"\n",
+        "static func twice(x):\n",
+        "\treturn x * 2\n",
+        "func main():\n",
+        "\thealth = 500\n",
+        "\tprint(GREETING, \" \", State.RUNNING, \" \", State.keys(), \" \", created, \" \", name, \" \", health)\n",
+        "\tvar d = Derived.new()\n",
+        "\tprint(d.describe(), \" \", d is Base, \" \", d.level, \" \", twice(4), \" \", Test.twice(5))\n",
+        "\tprint(get_class(), \" \", self is Node, \" \", typeof(d) == TYPE_OBJECT)\n",
+    );
+
+    test_output!(
+        source,
+        "hi 5 [\"IDLE\", \"RUNNING\"] 1 test 100\nderived level 3 true 3 8 10\nNode true true\n"
+    );
+}
+
+#[test]
+fn test_lambdas() {
+    let source = concat!(
+        "func apply(f, x):\n",
+        "\treturn f.call(x)\n",
+        "func main():\n",
+        "\tvar offset = 10\n",
+        "\tvar add = func(x): return x + offset\n",
+        "\tprint(apply(add, 1))\n",
+        "\tprint([1, 2, 3, 4].filter(func(x): return x % 2 == 0).map(func(x): return x * x))\n",
+        "\tprint([1, 2, 3].reduce(func(acc, x): return acc + x, 10))\n",
+        "\tvar sorted = [3, 1, 2]\n",
+        "\tsorted.sort_custom(func(a, b): return a > b)\n",
+        "\tprint(sorted)\n",
+        "\tvar greet = func(a, b): return a + b\n",
+        "\tprint(greet.bind(\"!\").call(\"hi\"))\n",
+        "\tvar m = apply\n",
+        "\tprint(m.call(func(x): return -x, 3))\n",
+    );
+
+    test_output!(source, "11\n[4, 16]\n16\n[3, 2, 1]\nhi!\n-3\n");
+}
+
+#[test]
+fn test_signals() {
+    let source = concat!(
+        "signal hit(damage)\n",
+        "signal done\n",
+        "var total = 0\n",
+        "func _on_hit(damage):\n",
+        "\ttotal += damage\n",
+        "func wait_for_done():\n",
+        "\tprint(\"waiting\")\n",
+        "\tawait done\n",
+        "\tprint(\"resumed\")\n",
+        "\treturn 42\n",
+        "func main():\n",
+        "\thit.connect(_on_hit)\n",
+        "\thit.connect(func(d): print(\"hit for \", d), CONNECT_ONE_SHOT)\n",
+        "\thit.emit(3)\n",
+        "\temit_signal(\"hit\", 4)\n",
+        "\tprint(total, \" \", hit.is_connected(_on_hit))\n",
+        "\twait_for_done()\n",
+        "\tprint(\"emitting\")\n",
+        "\tdone.emit()\n",
+        "\tprint(\"emitted\")\n",
+    );
+
+    test_output!(
+        source,
+        "hit for 3\n7 true\nwaiting\nemitting\nresumed\nemitted\n"
+    );
+}
+
+#[test]
+fn test_await() {
+    let source = concat!(
+        "signal ready_to_go(value)\n",
+        "func produce():\n",
+        "\tvar value = await ready_to_go\n",
+        "\treturn value * 2\n",
+        "func consume():\n",
+        "\tvar result = await produce()\n",
+        "\tprint(\"got \", result)\n",
+        "func main():\n",
+        "\tconsume()\n",
+        "\tprint(\"started\")\n",
+        "\tready_to_go.emit(21)\n",
+        "\tprint(await identity(1))\n",
+        "func identity(x):\n",
+        "\treturn x\n",
+    );
+
+    test_output!(source, "started\ngot 42\n1\n");
+    test_error!(
+        "signal never\nfunc main():\n\tawait never\n",
+        ErrorKind::NeverResumed
+    );
+}
+
+#[test]
+fn test_builtins() {
+    test_output!(
+        "func main():\n\tprint(str(1, \"a\"), \" \", len([1, 2]), \" \", abs(-2), \" \", max(1, 5, 3), \" \", clamp(1.5, 0, 1))\n",
+        "1a 2 2 5 1.0\n"
+    );
+    test_output!(
+        "func main():\n\tprint(int(\"42\") + 1, \" \", float(1), \" \", Array([1]), \" \", typeof(\"\") == TYPE_STRING, \" \", type_string(TYPE_INT))\n",
+        "43 1.0 [1] true int\n"
+    );
+    test_output!(
+        "func main():\n\tseed(1)\n\tvar a = randi_range(1, 6)\n\tseed(1)\n\tprint(a == randi_range(1, 6), \" \", range(0, 10, 3))\n",
+        "true [0, 3, 6, 9]\n"
+    );
+    // no overflows when stepping past the largest or smallest int
+    test_output!(
+        "func main():\n\tprint(range(9223372036854775806, 9223372036854775807, 2), \" \", range(-9223372036854775807, -9223372036854775808, -2))\n",
+        "[9223372036854775806] [-9223372036854775807]\n"
+    );
+    test_output!(
+        "func main():\n\tprints(1, 2)\n\tprintt(1, 2)\n",
+        "1 2\n1\t2\n"
+    );
+}
+
+#[test]
+fn test_error_output() {
+    let source = "func main():\n\tprint(1)\n\tprinterr(2)\n\tpush_error(3)\n\tpush_warning(4)\n";
+    let file = parse(source);
+
+    let (output, errors) = (Capture::default(), Capture::default());
+    let interpreter = Interpreter::with_outputs(output.clone(), errors.clone());
+    let class = interpreter.load(file, "test.gd").unwrap();
+    let object = interpreter.instantiate(&class, vec![]).unwrap();
+    interpreter
+        .call(&Value::Object(object), "main", vec![])
+        .unwrap();
+
+    assert_eq!(output.take(), "1\n");
+    assert_eq!(errors.take(), "2\nERROR: 3\nWARNING: 4\n");
+}
+
+#[test]
+fn test_errors() {
+    test_error!(
+        "func main():\n\tprint(x)\n",
+        ErrorKind::UndefinedIdentifier(_)
+    );
+    test_error!(
+        "func main():\n\tassert(1 == 2, \"math\")\n",
+        ErrorKind::AssertionFailed(Some(_))
+    );
+    test_error!(
+        "func main():\n\t[].nope()\n",
+        ErrorKind::InvalidMethod { .. }
+    );
+    test_error!(
+        "const X = 1\nfunc main():\n\tX = 2\n",
+        ErrorKind::AssignToConstant(_)
+    );
+    test_error!(
+        "func f(a):\n\tpass\nfunc main():\n\tf()\n",
+        ErrorKind::ArgumentCount { .. }
+    );
+    test_error!(
+        "func f():\n\tf()\nfunc main():\n\tf()\n",
+        ErrorKind::StackOverflow(_)
+    );
+
+    let error = run("func main():\n\tpass\n\tprint(undefined)\n").unwrap_err();
+    assert!(error.span.is_some());
+}
+
+#[test]
+fn test_session() {
+    let output = Capture::default();
+    let mut session = Session::new(Interpreter::with_output(output.clone()), "<test>");
+    let mut run = |source: &str| session.run(parse(source));
+
+    assert_eq!(run("var x = 2\n").unwrap(), Value::Null);
+    assert_eq!(run("x * 3\n").unwrap(), Value::Int(6));
+    assert_eq!(run("x += 1\n").unwrap(), Value::Null);
+    assert_eq!(
+        run("const Y = 10\nfunc f(a):\n\treturn a + Y\n").unwrap(),
+        Value::Null
+    );
+    assert_eq!(run("f(x)\n").unwrap(), Value::Int(13));
+    assert_eq!(run("func f(a):\n\treturn -a\n").unwrap(), Value::Null);
+    assert_eq!(run("f(x)\n").unwrap(), Value::Int(-3));
+    assert_eq!(
+        run("func g():\n\tx *= 2\n\treturn x\n").unwrap(),
+        Value::Null
+    );
+    assert_eq!(run("g()\n").unwrap(), Value::Int(6));
+    assert_eq!(run("x\n").unwrap(), Value::Int(6));
+
+    assert!(run("undefined\n").is_err());
+    assert!(run("const Z = undefined\n").is_err());
+    assert_eq!(
+        run("if x > 5:\n\tprint(\"big\")\nelse:\n\tprint(\"small\")\n").unwrap(),
+        Value::Null
+    );
+    assert_eq!(output.take(), "big\n");
+}
+
+#[test]
+fn test_native_methods() {
+    let source = "extends Greeter\nfunc main():\n\tprint(greet(\"world\"), \" \", has_method(\"greet\"), \" \", twice(func(): return 21))\n";
+    let file = parse(source);
+
+    let output = Capture::default();
+    let interpreter = Interpreter::with_output(output.clone());
+
+    interpreter.define_native_method("Greeter", "greet", |_, _, args| {
+        Box::pin(async move { Ok(Value::string(format!("hello, {}", args[0]))) })
+    });
+    interpreter.define_native_method("Greeter", "twice", |interpreter, _, args| {
+        Box::pin(async move {
+            let Value::Callable(callable) = &args[0] else {
+                unreachable!();
+            };
+
+            let value = interpreter.invoke(callable.clone(), vec![]).await?;
+            Ok(Value::Int(value.as_float().unwrap() as i64 * 2))
+        })
+    });
+
+    let class = interpreter.load(file, "test.gd").unwrap();
+    let object = interpreter.instantiate(&class, vec![]).unwrap();
+    interpreter
+        .call(&Value::Object(object), "main", vec![])
+        .unwrap();
+
+    assert_eq!(output.take(), "hello, world true 42\n");
+}
@@ -0,0 +1,627 @@
+//! The value model of the interpreter, mirroring Godot's `Variant`.
+
+use std::{
+    cell::{Ref, RefCell, RefMut},
+    collections::HashMap,
+    fmt,
+    rc::Rc,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use crate::{class::Class, interpreter::Frame};
+
+/// A GDScript value.
+///
+/// Arrays, dictionaries and objects are shared by reference, like they are
+/// in Godot; cloning a [Value] never copies them.
+#[derive(Clone, Default)]
+pub enum Value<'a> {
+    #[default]
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(Rc<str>),
+    StringName(Rc<str>),
+    Array(Array<'a>),
+    Dictionary(Dictionary<'a>),
+    Callable(Callable<'a>),
+    Signal(Signal<'a>),
+    Object(Object<'a>),
+    /// A script class, e.g. `MyClass` in `MyClass.new()`.
+    Class(Rc<Class<'a>>),
+    /// A built-in type, e.g. `int` in `x is int`.
+    Type(Type),
+}
+
+impl<'a> Value<'a> {
+    pub fn string(value: impl Into<Rc<str>>) -> Self {
+        Self::String(value.into())
+    }
+
+    pub fn array(values: Vec<Value<'a>>) -> Self {
+        Self::Array(Array::new(values))
+    }
+
+    /// The built-in type of the value.
+    pub fn type_of(&self) -> Type {
+        match self {
+            Value::Null => Type::Nil,
+            Value::Bool(_) => Type::Bool,
+            Value::Int(_) => Type::Int,
+            Value::Float(_) => Type::Float,
+            Value::String(_) => Type::String,
+            Value::StringName(_) => Type::StringName,
+            Value::Array(_) => Type::Array,
+            Value::Dictionary(_) => Type::Dictionary,
+            Value::Callable(_) => Type::Callable,
+            Value::Signal(_) => Type::Signal,
+            Value::Object(_) | Value::Class(_) | Value::Type(_) => Type::Object,
+        }
+    }
+
+    /// The name of the value's type, as used in error messages. For objects,
+    /// this is the name of their class.
+    pub fn type_name(&self) -> String {
+        match self {
+            Value::Object(object) => object.class().name.clone(),
+            Value::Class(_) | Value::Type(_) => "GDScript".to_owned(),
+            other => other.type_of().name().to_owned(),
+        }
+    }
+
+    /// Whether the value is considered `true` in a condition.
+    pub fn is_truthy(&self) -> bool {
+        match self {
+            Value::Null => false,
+            Value::Bool(value) => *value,
+            Value::Int(value) => *value != 0,
+            Value::Float(value) => *value != 0.0,
+            Value::String(value) | Value::StringName(value) => !value.is_empty(),
+            Value::Array(array) => !array.is_empty(),
+            Value::Dictionary(dictionary) => !dictionary.is_empty(),
+            Value::Callable(_)
+            | Value::Signal(_)
+            | Value::Object(_)
+            | Value::Class(_)
+            | Value::Type(_) => true,
+        }
+    }
+
+    /// The contents of a `String` or a `StringName`.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(value) | Value::StringName(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// The value as a float, if it is a number.
+    pub fn as_float(&self) -> Option<f64> {
+        match self {
+            Value::Int(value) => Some(*value as f64),
+            Value::Float(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Equality as defined by the `==` operator. Numbers of different types
+    /// compare by value, and containers compare by contents.
+    pub fn equals(&self, other: &Value<'a>) -> bool {
+        match (self, other) {
+            (Value::Null, Value::Null) => true,
+            (Value::Bool(a), Value::Bool(b)) => a == b,
+            (Value::Int(a), Value::Int(b)) => a == b,
+            (Value::Float(a), Value::Float(b)) => a == b,
+            (Value::Int(a), Value::Float(b)) | (Value::Float(b), Value::Int(a)) => *a as f64 == *b,
+            (Value::Array(a), Value::Array(b)) => {
+                a.ptr_eq(b) || {
+                    let (a, b) = (a.borrow(), b.borrow());
+                    a.len() == b.len() && a.iter().zip(b.iter()).all(|(a, b)| a.equals(b))
+                }
+            }
+            (Value::Dictionary(a), Value::Dictionary(b)) => {
+                a.ptr_eq(b)
+                    || (a.len() == b.len()
+                        && a.borrow().iter().all(|(key, value)| {
+                            b.get(key).is_some_and(|other| value.equals(&other))
+                        }))
+            }
+            (Value::Callable(a), Value::Callable(b)) => a.same_as(b),
+            (Value::Signal(a), Value::Signal(b)) => a.object.ptr_eq(&b.object) && a.name == b.name,
+            (Value::Object(a), Value::Object(b)) => a.ptr_eq(b),
+            (Value::Class(a), Value::Class(b)) => Rc::ptr_eq(a, b),
+            (Value::Type(a), Value::Type(b)) => a == b,
+            (a, b) => match (a.as_str(), b.as_str()) {
+                (Some(a), Some(b)) => a == b,
+                _ => false,
+            },
+        }
+    }
+
+    /// Equality of dictionary keys and `match` patterns, which additionally
+    /// requires both values to be of the same type. `String`s and
+    /// `StringName`s are interchangeable.
+    pub fn strictly_equals(&self, other: &Value<'a>) -> bool {
+        let same_type = self.type_of() == other.type_of()
+            || (self.as_str().is_some() && other.as_str().is_some());
+
+        same_type && self.equals(other)
+    }
+
+    /// The value as it is shown when nested in a container, with strings
+    /// quoted.
+    pub fn repr(&self) -> String {
+        match self {
+            Value::String(value) => format!("{value:?}"),
+            Value::StringName(value) => format!("&{value:?}"),
+            other => other.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Value<'_> {
+    /// The value as it's converted by `str()`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Null => f.write_str("<null>"),
+            Value::Bool(value) => write!(f, "{value}"),
+            Value::Int(value) => write!(f, "{value}"),
+            Value::Float(value) => f.write_str(&format_float(*value)),
+            Value::String(value) | Value::StringName(value) => f.write_str(value),
+            Value::Array(array) => {
+                let items = array.borrow().iter().map(Value::repr).collect::<Vec<_>>();
+
+                write!(f, "[{}]", items.join(", "))
+            }
+            Value::Dictionary(dictionary) => {
+                let items = dictionary
+                    .borrow()
+                    .iter()
+                    .map(|(key, value)| format!("{}: {}", key.repr(), value.repr()))
+                    .collect::<Vec<_>>();
+
+                write!(f, "{{ {} }}", items.join(", "))
+            }
+            Value::Callable(callable) => match &callable.kind {
+                CallableKind::Method { receiver, name } => {
+                    write!(f, "{}::{name}", receiver.type_name())
+                }
+                CallableKind::Lambda(lambda) => match lambda.name() {
+                    Some(name) => write!(f, "{name}(lambda)"),
+                    None => f.write_str("<anonymous lambda>"),
+                },
+                CallableKind::Builtin(name) => write!(f, "@GlobalScope::{name}"),
+            },
+            Value::Signal(signal) => {
+                write!(f, "{}::{}", signal.object.class().name, signal.name)
+            }
+            Value::Object(object) => write!(f, "<{}#{}>", object.class().name, object.id()),
+            Value::Class(class) => write!(f, "<GDScript:{}>", class.name),
+            Value::Type(ty) => f.write_str(ty.name()),
+        }
+    }
+}
+
+impl fmt::Debug for Value<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.repr())
+    }
+}
+
+impl PartialEq for Value<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.equals(other)
+    }
+}
+
+/// Format a float like Godot does, always keeping the decimal point for
+/// whole numbers.
+fn format_float(value: f64) -> String {
+    if value.is_nan() {
+        "nan".to_owned()
+    } else if value.is_infinite() {
+        if value > 0.0 { "inf" } else { "-inf" }.to_owned()
+    } else if value.fract() == 0.0 && value.abs() < 1e15 {
+        format!("{value:.1}")
+    } else {
+        format!("{value}")
+    }
+}
+
+/// Built-in types, as returned by `typeof()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Type {
+    Nil,
+    Bool,
+    Int,
+    Float,
+    String,
+    StringName,
+    Array,
+    Dictionary,
+    Callable,
+    Signal,
+    Object,
+}
+
+impl Type {
+    /// Look up a type by its name in GDScript.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "bool" => Type::Bool,
+            "int" => Type::Int,
+            "float" => Type::Float,
+            "String" => Type::String,
+            "StringName" => Type::StringName,
+            "Array" => Type::Array,
+            "Dictionary" => Type::Dictionary,
+            "Callable" => Type::Callable,
+            "Signal" => Type::Signal,
+            "Object" => Type::Object,
+            _ => return None,
+        })
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Type::Nil => "Nil",
+            Type::Bool => "bool",
+            Type::Int => "int",
+            Type::Float => "float",
+            Type::String => "String",
+            Type::StringName => "StringName",
+            Type::Array => "Array",
+            Type::Dictionary => "Dictionary",
+            Type::Callable => "Callable",
+            Type::Signal => "Signal",
+            Type::Object => "Object",
+        }
+    }
+
+    /// The value of the type's `TYPE_*` constant.
+    pub fn id(self) -> i64 {
+        match self {
+            Type::Nil => 0,
+            Type::Bool => 1,
+            Type::Int => 2,
+            Type::Float => 3,
+            Type::String => 4,
+            Type::StringName => 21,
+            Type::Object => 24,
+            Type::Callable => 25,
+            Type::Signal => 26,
+            Type::Dictionary => 27,
+            Type::Array => 28,
+        }
+    }
+
+    pub fn from_id(id: i64) -> Option<Self> {
+        [
+            Type::Nil,
+            Type::Bool,
+            Type::Int,
+            Type::Float,
+            Type::String,
+            Type::StringName,
+            Type::Object,
+            Type::Callable,
+            Type::Signal,
+            Type::Dictionary,
+            Type::Array,
+        ]
+        .into_iter()
+        .find(|ty| ty.id() == id)
+    }
+
+    /// The value variables of this type hold when they aren't initialized.
+    pub fn default_value<'a>(self) -> Value<'a> {
+        match self {
+            Type::Bool => Value::Bool(false),
+            Type::Int => Value::Int(0),
+            Type::Float => Value::Float(0.0),
+            Type::String => Value::string(""),
+            Type::StringName => Value::StringName("".into()),
+            Type::Array => Value::array(vec![]),
+            Type::Dictionary => Value::Dictionary(Dictionary::default()),
+            Type::Nil | Type::Callable | Type::Signal | Type::Object => Value::Null,
+        }
+    }
+}
+
+/// A shared array.
+#[derive(Clone, Default)]
+pub struct Array<'a>(Rc<RefCell<Vec<Value<'a>>>>);
+
+impl<'a> Array<'a> {
+    pub fn new(values: Vec<Value<'a>>) -> Self {
+        Self(Rc::new(RefCell::new(values)))
+    }
+
+    pub fn borrow(&self) -> Ref<'_, Vec<Value<'a>>> {
+        self.0.borrow()
+    }
+
+    pub fn borrow_mut(&self) -> RefMut<'_, Vec<Value<'a>>> {
+        self.0.borrow_mut()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.borrow().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.borrow().is_empty()
+    }
+
+    /// A copy of the array's elements.
+    pub fn to_vec(&self) -> Vec<Value<'a>> {
+        self.0.borrow().clone()
+    }
+
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+/// A shared dictionary. Keys keep their insertion order.
+#[derive(Clone, Default)]
+pub struct Dictionary<'a>(Rc<RefCell<Vec<(Value<'a>, Value<'a>)>>>);
+
+impl<'a> Dictionary<'a> {
+    pub fn new(entries: Vec<(Value<'a>, Value<'a>)>) -> Self {
+        let dictionary = Self::default();
+
+        for (key, value) in entries {
+            dictionary.insert(key, value);
+        }
+
+        dictionary
+    }
+
+    pub fn borrow(&self) -> Ref<'_, Vec<(Value<'a>, Value<'a>)>> {
+        self.0.borrow()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.borrow().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.borrow().is_empty()
+    }
+
+    pub fn get(&self, key: &Value<'a>) -> Option<Value<'a>> {
+        self.0
+            .borrow()
+            .iter()
+            .find(|(other, _)| other.strictly_equals(key))
+            .map(|(_, value)| value.clone())
+    }
+
+    pub fn contains_key(&self, key: &Value<'a>) -> bool {
+        self.0
+            .borrow()
+            .iter()
+            .any(|(other, _)| other.strictly_equals(key))
+    }
+
+    pub fn insert(&self, key: Value<'a>, value: Value<'a>) {
+        let mut entries = self.0.borrow_mut();
+
+        match entries
+            .iter_mut()
+            .find(|(other, _)| other.strictly_equals(&key))
+        {
+            Some((_, old)) => *old = value,
+            None => entries.push((key, value)),
+        }
+    }
+
+    pub fn remove(&self, key: &Value<'a>) -> Option<Value<'a>> {
+        let mut entries = self.0.borrow_mut();
+        let idx = entries
+            .iter()
+            .position(|(other, _)| other.strictly_equals(key))?;
+
+        Some(entries.remove(idx).1)
+    }
+
+    pub fn clear(&self) {
+        self.0.borrow_mut().clear();
+    }
+
+    pub fn keys(&self) -> Vec<Value<'a>> {
+        self.0.borrow().iter().map(|(key, _)| key.clone()).collect()
+    }
+
+    pub fn values(&self) -> Vec<Value<'a>> {
+        self.0
+            .borrow()
+            .iter()
+            .map(|(_, value)| value.clone())
+            .collect()
+    }
+
+    /// A shallow copy of the dictionary.
+    pub fn duplicate(&self) -> Self {
+        Self(Rc::new(RefCell::new(self.0.borrow().clone())))
+    }
+
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+static NEXT_OBJECT_ID: AtomicU64 = AtomicU64::new(1);
+
+/// An instance of a script class.
+#[derive(Clone)]
+pub struct Object<'a>(Rc<RefCell<Instance<'a>>>);
+
+pub struct Instance<'a> {
+    pub class: Rc<Class<'a>>,
+    pub fields: HashMap<&'a str, Value<'a>>,
+    pub(crate) connections: HashMap<String, Vec<Connection<'a>>>,
+    /// Coroutines waiting for a signal, by signal name. Emitting the signal
+    /// fills their slots with its arguments.
+    pub(crate) awaiters: HashMap<String, Vec<Rc<RefCell<Option<Value<'a>>>>>>,
+    /// Properties whose getter or setter is currently running. These are
+    /// accessed directly, to not recurse into the accessor.
+    pub(crate) accessors: Vec<&'a str>,
+    id: u64,
+}
+
+#[derive(Clone)]
+pub(crate) struct Connection<'a> {
+    pub callable: Callable<'a>,
+    pub one_shot: bool,
+}
+
+impl<'a> Object<'a> {
+    pub(crate) fn new(class: Rc<Class<'a>>) -> Self {
+        Self(Rc::new(RefCell::new(Instance {
+            class,
+            fields: HashMap::new(),
+            connections: HashMap::new(),
+            awaiters: HashMap::new(),
+            accessors: vec![],
+            id: NEXT_OBJECT_ID.fetch_add(1, Ordering::Relaxed),
+        })))
+    }
+
+    pub fn borrow(&self) -> Ref<'_, Instance<'a>> {
+        self.0.borrow()
+    }
+
+    pub(crate) fn borrow_mut(&self) -> RefMut<'_, Instance<'a>> {
+        self.0.borrow_mut()
+    }
+
+    pub fn class(&self) -> Rc<Class<'a>> {
+        self.0.borrow().class.clone()
+    }
+
+    /// The value of a field, bypassing its getter.
+    pub fn field(&self, name: &str) -> Option<Value<'a>> {
+        self.0.borrow().fields.get(name).cloned()
+    }
+
+    /// Set an existing field, bypassing its setter. Returns whether the field
+    /// exists.
+    pub(crate) fn set_field(&self, name: &str, value: Value<'a>) -> bool {
+        match self.0.borrow_mut().fields.get_mut(name) {
+            Some(field) => {
+                *field = value;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Whether the getter or setter of `name` is currently running.
+    pub(crate) fn is_accessing(&self, name: &str) -> bool {
+        self.0.borrow().accessors.contains(&name)
+    }
+
+    pub fn id(&self) -> u64 {
+        self.0.borrow().id
+    }
+
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+/// A reference to a function, along with its bound arguments.
+#[derive(Clone)]
+pub struct Callable<'a> {
+    pub kind: CallableKind<'a>,
+    /// Arguments added by `bind()`, passed after the call's own arguments.
+    pub bound: Vec<Value<'a>>,
+}
+
+#[derive(Clone)]
+pub enum CallableKind<'a> {
+    /// A method of an object, a static function of a class, or a method of a
+    /// built-in type.
+    Method {
+        receiver: Box<Value<'a>>,
+        name: Rc<str>,
+    },
+    Lambda(Rc<Lambda<'a>>),
+    /// A global function like `print`.
+    Builtin(Rc<str>),
+}
+
+impl<'a> Callable<'a> {
+    pub fn method(receiver: Value<'a>, name: impl Into<Rc<str>>) -> Self {
+        Self {
+            kind: CallableKind::Method {
+                receiver: Box::new(receiver),
+                name: name.into(),
+            },
+            bound: vec![],
+        }
+    }
+
+    pub fn builtin(name: impl Into<Rc<str>>) -> Self {
+        Self {
+            kind: CallableKind::Builtin(name.into()),
+            bound: vec![],
+        }
+    }
+
+    /// The name of the function, if it has one.
+    pub fn name(&self) -> Option<&str> {
+        match &self.kind {
+            CallableKind::Method { name, .. } | CallableKind::Builtin(name) => Some(name),
+            CallableKind::Lambda(lambda) => lambda.name(),
+        }
+    }
+
+    fn same_as(&self, other: &Self) -> bool {
+        let same_function = match (&self.kind, &other.kind) {
+            (
+                CallableKind::Method { receiver, name },
+                CallableKind::Method {
+                    receiver: other_receiver,
+                    name: other_name,
+                },
+            ) => name == other_name && receiver.equals(other_receiver),
+            (CallableKind::Lambda(a), CallableKind::Lambda(b)) => Rc::ptr_eq(a, b),
+            (CallableKind::Builtin(a), CallableKind::Builtin(b)) => a == b,
+            _ => false,
+        };
+
+        same_function
+            && self.bound.len() == other.bound.len()
+            && self
+                .bound
+                .iter()
+                .zip(&other.bound)
+                .all(|(a, b)| a.equals(b))
+    }
+}
+
+/// A lambda, along with the variables it captured.
+pub struct Lambda<'a> {
+    pub(crate) function: &'a gdtk_gdscript_ast::ASTFunction<'a>,
+    pub(crate) frame: Frame<'a>,
+}
+
+impl Lambda<'_> {
+    pub fn name(&self) -> Option<&str> {
+        match self.function.identifier.as_deref()?.kind {
+            gdtk_gdscript_ast::ASTExprKind::Identifier(name) => Some(name),
+            _ => None,
+        }
+    }
+}
+
+/// A signal of an object.
+#[derive(Clone)]
+pub struct Signal<'a> {
+    pub object: Object<'a>,
+    pub name: Rc<str>,
+}
@@ -115,6 +115,56 @@ pub fn noqas(input: &str) -> ahash::AHashMap<usize, Vec<&str>> {
     map
 }
 
+/// A `##` documentation comment.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DocComment<'a> {
+    /// The (zero-indexed) line the comment is on.
+    pub line: usize,
+    /// The comment's text, without the leading `##` and the space after it.
+    pub text: &'a str,
+    /// Whether the comment follows code on the same line.
+    pub trailing: bool,
+}
+
+/// Collect all `##` documentation comments in the input. Comments are taken
+/// from the real tokens, so `##` inside a string is not mistaken for one.
+pub fn doc_comments(input: &str) -> Vec<DocComment<'_>> {
+    let mut comments = vec![];
+    let mut line = 0usize;
+    let mut line_start = 0usize;
+    // how far newlines have been counted
+    let mut counted = 0usize;
+
+    for (token, span) in TokenKind::lexer(input).spanned() {
+        let Ok(TokenKind::Comment(comm)) = token else {
+            continue;
+        };
+
+        if !comm.starts_with("##") {
+            continue;
+        }
+
+        let skipped = &input[counted..span.start];
+        line += skipped.matches('\n').count();
+
+        if let Some(newline) = skipped.rfind('\n') {
+            line_start = counted + newline + 1;
+        }
+
+        counted = span.start;
+
+        let text = &comm[2..];
+
+        comments.push(DocComment {
+            line,
+            text: text.strip_prefix(' ').unwrap_or(text).trim_end(),
+            trailing: !input[line_start..span.start].trim().is_empty(),
+        });
+    }
+
+    comments
+}
+
 fn find_noqas(input: &str) -> Vec<&str> {
     Regex::new(r"noqa:[ \t]*([a-zA-Z-]+)")
         .unwrap()
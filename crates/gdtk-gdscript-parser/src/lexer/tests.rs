@@ -1,4 +1,4 @@
-use crate::lexer::token::TokenKind;
+use crate::lexer::{token::TokenKind, DocComment};
 
 macro_rules! test_eq {
     ($input: expr, $($expected: expr),*) => {
@@ -368,3 +368,42 @@ fn test_indents() {
 fn test_edge_cases() {
     test_eq!("not info", TokenKind::Not, TokenKind::Identifier("info"));
 }
+
+#[test]
+fn test_doc_comments() {
+    let comments =
+        crate::lexer::doc_comments("## Brief.\n# not docs\nvar x = 1 ## Trailing.\n\t##\n");
+
+    assert_eq!(
+        comments,
+        vec![
+            DocComment {
+                line: 0,
+                text: "Brief.",
+                trailing: false,
+            },
+            DocComment {
+                line: 2,
+                text: "Trailing.",
+                trailing: true,
+            },
+            DocComment {
+                line: 3,
+                text: "",
+                trailing: false,
+            },
+        ]
+    );
+
+    // `##` and `#` in strings don't start comments
+    let comments = crate::lexer::doc_comments("var a = \"## no\"\nvar b = '# no' ## Yes.\n");
+
+    assert_eq!(
+        comments,
+        vec![DocComment {
+            line: 1,
+            text: "Yes.",
+            trailing: true,
+        }]
+    );
+}
@@ -4,7 +4,7 @@ pub type File<'a> = Vec<Line<'a>>;
 type Array<'a, T = Value<'a>> = Vec<T>;
 type Map<'a, K = Value<'a>, V = Value<'a>> = Vec<(K, V)>;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq, enum_as_inner::EnumAsInner)]
 pub enum Line<'a> {
     /// A ``// comment``.
     Comment(&'a str),
@@ -14,22 +14,38 @@ pub enum Line<'a> {
     Parameter(&'a str, Value<'a>),
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq, enum_as_inner::EnumAsInner)]
 pub enum Value<'a> {
     /// A ``null``.
     Null,
     /// A ``true`` or a ``false``.
     Boolean(bool),
     /// An integer literal.
-    Integer(i32),
-    /// A float literal.
+    Integer(i64),
+    /// A float literal, including ``inf``, ``inf_neg`` and ``nan``.
     Float(f64),
-    /// A string literal.
+    /// A string literal. Escape sequences are kept as-is.
     String(&'a str),
+    /// A ``&"string_name"``.
+    StringName(&'a str),
+    /// A ``NodePath("path")`` or a ``^"path"``.
+    NodePath(&'a str),
     /// An array expression.
     Array(Array<'a>),
+    /// A typed array expression, like ``Array[int]([1, 2, 3])``.
+    TypedArray(ElementType<'a>, Array<'a>),
+    /// A packed array expression, like ``PackedVector2Array(0, 0, 1, 1)``.
+    /// Contains the values exactly as written, i.e. vector components
+    /// are not grouped.
+    PackedArray(PackedArrayKind, Array<'a>),
     /// A map expression.
     Map(Map<'a>),
+    /// A typed map expression, like ``Dictionary[String, int]({"a": 1})``.
+    TypedMap(ElementType<'a>, ElementType<'a>, Map<'a>),
+    /// An ``ExtResource("id")`` expression.
+    ExtResource(ResourceId<'a>),
+    /// A ``SubResource("id")`` expression.
+    SubResource(ResourceId<'a>),
     /// An object expression. ``.0`` is object's identifier, ``.1`` is object's properties.
     Object(&'a str, Map<'a, &'a str>),
     /// An object instance expression. The difference between this and [Value::Object] is
@@ -38,3 +54,103 @@ pub enum Value<'a> {
     /// instance in GDScript, by passing arguments to a class' `_init()`.
     ObjectInstance(&'a str, Array<'a>),
 }
+
+/// An identifier of a resource declared in the same file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ResourceId<'a> {
+    /// A numeric identifier, as used by Godot 3.
+    Integer(i64),
+    /// A string identifier, as used by Godot 4.
+    String(&'a str),
+}
+
+impl<'a> ResourceId<'a> {
+    /// Try to interpret a value as a resource identifier.
+    pub fn from_value(value: &Value<'a>) -> Option<Self> {
+        match value {
+            Value::Integer(int) => Some(Self::Integer(*int)),
+            Value::String(string) => Some(Self::String(string)),
+            _ => None,
+        }
+    }
+
+    /// Convert this identifier into a value.
+    pub fn to_value(self) -> Value<'a> {
+        match self {
+            Self::Integer(int) => Value::Integer(int),
+            Self::String(string) => Value::String(string),
+        }
+    }
+}
+
+/// The element type of a typed array or map.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ElementType<'a> {
+    /// A builtin type or a native class, like ``int`` or ``Node``.
+    Named(&'a str),
+    /// A script class, like ``ExtResource("1_abc")``.
+    ExtResource(ResourceId<'a>),
+    /// A built-in script class, like ``SubResource("GDScript_abc")``.
+    SubResource(ResourceId<'a>),
+}
+
+/// The kind of a [Value::PackedArray].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PackedArrayKind {
+    /// A ``PackedByteArray``.
+    Byte,
+    /// A ``PackedInt32Array``.
+    Int32,
+    /// A ``PackedInt64Array``.
+    Int64,
+    /// A ``PackedFloat32Array``.
+    Float32,
+    /// A ``PackedFloat64Array``.
+    Float64,
+    /// A ``PackedStringArray``.
+    String,
+    /// A ``PackedVector2Array``.
+    Vector2,
+    /// A ``PackedVector3Array``.
+    Vector3,
+    /// A ``PackedVector4Array``.
+    Vector4,
+    /// A ``PackedColorArray``.
+    Color,
+}
+
+impl PackedArrayKind {
+    /// Find the kind of a packed array by it's constructor name. Godot 3
+    /// names (``PoolByteArray``, etc.) are accepted too.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "PackedByteArray" | "PoolByteArray" | "ByteArray" => Self::Byte,
+            "PackedInt32Array" | "PoolIntArray" | "IntArray" => Self::Int32,
+            "PackedInt64Array" => Self::Int64,
+            "PackedFloat32Array" | "PoolRealArray" | "FloatArray" => Self::Float32,
+            "PackedFloat64Array" => Self::Float64,
+            "PackedStringArray" | "PoolStringArray" | "StringArray" => Self::String,
+            "PackedVector2Array" | "PoolVector2Array" | "Vector2Array" => Self::Vector2,
+            "PackedVector3Array" | "PoolVector3Array" | "Vector3Array" => Self::Vector3,
+            "PackedVector4Array" => Self::Vector4,
+            "PackedColorArray" | "PoolColorArray" | "ColorArray" => Self::Color,
+            _ => return None,
+        })
+    }
+
+    /// The constructor name of this kind.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Byte => "PackedByteArray",
+            Self::Int32 => "PackedInt32Array",
+            Self::Int64 => "PackedInt64Array",
+            Self::Float32 => "PackedFloat32Array",
+            Self::Float64 => "PackedFloat64Array",
+            Self::String => "PackedStringArray",
+            Self::Vector2 => "PackedVector2Array",
+            Self::Vector3 => "PackedVector3Array",
+            Self::Vector4 => "PackedVector4Array",
+            Self::Color => "PackedColorArray",
+        }
+    }
+}
@@ -0,0 +1,582 @@
+//! A [serde] deserializer for GodotCfg.
+//!
+//! A file is deserialized as a map. Parameters that appear before any
+//! section are entries of that map, and every section is an entry whose
+//! value is a map of the section's header parameters and properties.
+//! Sections that appear more than once (like `[ext_resource]`) can be
+//! deserialized into a sequence.
+//!
+//! Values are mapped onto the serde data model as follows:
+//!
+//! - strings, `StringName`s and `NodePath`s are strings;
+//! - arrays, typed arrays, packed arrays and the arguments of object
+//!   instances (like `Vector2(1, 2)`) are sequences;
+//! - maps, typed maps and the properties of `Object(...)`s are maps;
+//! - `ExtResource(...)` and `SubResource(...)` are their ids.
+//!
+//! Enums can be deserialized from strings (unit variants), object instances
+//! (e.g. `Vector2(1, 2)` is variant `Vector2` with content `(1, 2)`) and
+//! objects (struct variants).
+
+use serde::de::{
+    self,
+    value::{BorrowedStrDeserializer, MapDeserializer, SeqDeserializer},
+    Deserialize, IntoDeserializer, Visitor,
+};
+
+use crate::ast::{Line, ResourceId, Value};
+
+/// An error that occured during deserialization.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum Error {
+    /// The source is not valid GodotCfg.
+    #[error("{0}")]
+    Syntax(String),
+
+    /// An error reported by a [Deserialize] implementation.
+    #[error("{0}")]
+    Custom(String),
+}
+
+impl de::Error for Error {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        Self::Custom(msg.to_string())
+    }
+}
+
+/// Deserialize an instance of `T` from GodotCfg source code.
+pub fn from_str<'de, T: Deserialize<'de>>(source: &'de str) -> Result<T, Error> {
+    let lines = crate::parser(source)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| Error::Syntax(e.to_string()))?;
+
+    from_lines(&lines)
+}
+
+/// Deserialize an instance of `T` from already parsed lines.
+pub fn from_lines<'de, T: Deserialize<'de>>(lines: &[Line<'de>]) -> Result<T, Error> {
+    let mut document: Vec<(&'de str, Entry<'_, 'de>)> = vec![];
+    let mut section = None;
+
+    for line in lines {
+        match line {
+            Line::Comment(_) => (),
+            Line::Section(name, parameters) => {
+                let table = parameters.iter().map(|(k, v)| (*k, v)).collect();
+
+                let existing = document
+                    .iter()
+                    .position(|(key, entry)| key == name && matches!(entry, Entry::Tables(_)));
+
+                section = Some(match existing {
+                    Some(idx) => {
+                        if let Entry::Tables(tables) = &mut document[idx].1 {
+                            tables.push(table);
+                        }
+
+                        idx
+                    }
+                    None => {
+                        document.push((name, Entry::Tables(vec![table])));
+                        document.len() - 1
+                    }
+                });
+            }
+            Line::Parameter(key, value) => match section {
+                Some(idx) => {
+                    if let Entry::Tables(tables) = &mut document[idx].1 {
+                        // a table is pushed along with every section
+                        tables.last_mut().unwrap().push((key, value));
+                    }
+                }
+                None => document.push((key, Entry::Value(value))),
+            },
+        }
+    }
+
+    let iter = document
+        .iter()
+        .map(|(key, entry)| (BorrowedStrDeserializer::new(key), entry));
+
+    T::deserialize(MapDeserializer::new(iter))
+}
+
+/// Deserialize an instance of `T` from a single value.
+pub fn from_value<'de, T: Deserialize<'de>>(value: &Value<'de>) -> Result<T, Error> {
+    T::deserialize(ValueDeserializer { value })
+}
+
+/// A section's header parameters and properties.
+type Table<'v, 'de> = Vec<(&'de str, &'v Value<'de>)>;
+
+/// A top-level entry of a file.
+enum Entry<'v, 'de> {
+    /// A parameter that appears before any section.
+    Value(&'v Value<'de>),
+    /// All sections with a given name.
+    Tables(Vec<Table<'v, 'de>>),
+}
+
+impl<'v, 'de> IntoDeserializer<'de, Error> for &'v Entry<'v, 'de> {
+    type Deserializer = EntryDeserializer<'v, 'de>;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        EntryDeserializer { entry: self }
+    }
+}
+
+struct EntryDeserializer<'v, 'de> {
+    entry: &'v Entry<'v, 'de>,
+}
+
+impl<'v, 'de> EntryDeserializer<'v, 'de> {
+    fn table(
+        table: &'v Table<'v, 'de>,
+    ) -> MapDeserializer<
+        'de,
+        impl Iterator<Item = (BorrowedStrDeserializer<'de, Error>, &'v Value<'de>)> + 'v,
+        Error,
+    > {
+        MapDeserializer::new(
+            table
+                .iter()
+                .map(|(key, value)| (BorrowedStrDeserializer::new(key), *value)),
+        )
+    }
+}
+
+impl<'v, 'de> de::Deserializer<'de> for EntryDeserializer<'v, 'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.entry {
+            Entry::Value(value) => ValueDeserializer { value }.deserialize_any(visitor),
+            Entry::Tables(tables) => match tables.as_slice() {
+                [table] => visitor.visit_map(Self::table(table)),
+                tables => visitor.visit_seq(SeqDeserializer::new(tables.iter().map(Self::table))),
+            },
+        }
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.entry {
+            Entry::Value(value) => ValueDeserializer { value }.deserialize_seq(visitor),
+            Entry::Tables(tables) => {
+                visitor.visit_seq(SeqDeserializer::new(tables.iter().map(Self::table)))
+            }
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.entry {
+            Entry::Value(value) => ValueDeserializer { value }.deserialize_option(visitor),
+            Entry::Tables(_) => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct tuple tuple_struct map struct enum
+        identifier ignored_any
+    }
+}
+
+impl<'v, 'de> IntoDeserializer<'de, Error> for &'v Value<'de> {
+    type Deserializer = ValueDeserializer<'v, 'de>;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        ValueDeserializer { value: self }
+    }
+}
+
+/// A deserializer for a single [Value].
+pub struct ValueDeserializer<'v, 'de> {
+    value: &'v Value<'de>,
+}
+
+impl<'v, 'de> ValueDeserializer<'v, 'de> {
+    /// Create a deserializer for `value`.
+    pub fn new(value: &'v Value<'de>) -> Self {
+        Self { value }
+    }
+}
+
+impl<'v, 'de> de::Deserializer<'de> for ValueDeserializer<'v, 'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.value {
+            Value::Null => visitor.visit_unit(),
+            Value::Boolean(boolean) => visitor.visit_bool(*boolean),
+            Value::Integer(int) => visitor.visit_i64(*int),
+            Value::Float(float) => visitor.visit_f64(*float),
+            Value::String(string) | Value::StringName(string) | Value::NodePath(string) => {
+                visit_str(string, visitor)
+            }
+            Value::Array(values)
+            | Value::TypedArray(_, values)
+            | Value::PackedArray(_, values)
+            | Value::ObjectInstance(_, values) => {
+                visitor.visit_seq(SeqDeserializer::new(values.iter()))
+            }
+            Value::Map(pairs) | Value::TypedMap(_, _, pairs) => {
+                visitor.visit_map(MapDeserializer::new(pairs.iter().map(|(k, v)| (k, v))))
+            }
+            Value::Object(_, properties) => visitor.visit_map(MapDeserializer::new(
+                properties
+                    .iter()
+                    .map(|(key, value)| (BorrowedStrDeserializer::new(key), value)),
+            )),
+            Value::ExtResource(id) | Value::SubResource(id) => match id {
+                ResourceId::Integer(int) => visitor.visit_i64(*int),
+                ResourceId::String(string) => visit_str(string, visitor),
+            },
+        }
+    }
+
+    fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_f64(visitor)
+    }
+
+    fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        // Godot may write integers in place of floats
+        match self.value {
+            Value::Integer(int) => visitor.visit_f64(*int as f64),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.value {
+            Value::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        match self.value {
+            Value::String(string) | Value::StringName(string) => {
+                visitor.visit_enum(BorrowedStrDeserializer::new(string))
+            }
+            Value::ObjectInstance(name, arguments) => visitor.visit_enum(VariantDeserializer {
+                name,
+                content: VariantContent::Arguments(arguments),
+            }),
+            Value::Object(name, properties) => visitor.visit_enum(VariantDeserializer {
+                name,
+                content: VariantContent::Properties(properties),
+            }),
+            other => Err(de::Error::invalid_type(unexpected(other), &"an enum")),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+/// The content of an enum variant.
+enum VariantContent<'v, 'de> {
+    /// Arguments of an object instance.
+    Arguments(&'v [Value<'de>]),
+    /// Properties of an object.
+    Properties(&'v [(&'de str, Value<'de>)]),
+}
+
+struct VariantDeserializer<'v, 'de> {
+    name: &'de str,
+    content: VariantContent<'v, 'de>,
+}
+
+impl<'v, 'de> de::EnumAccess<'de> for VariantDeserializer<'v, 'de> {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V: de::DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self::Variant), Error> {
+        let variant = seed.deserialize(BorrowedStrDeserializer::<Error>::new(self.name))?;
+
+        Ok((variant, self))
+    }
+}
+
+impl<'v, 'de> de::VariantAccess<'de> for VariantDeserializer<'v, 'de> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        match self.content {
+            VariantContent::Arguments([]) | VariantContent::Properties([]) => Ok(()),
+            _ => Err(de::Error::invalid_type(
+                de::Unexpected::NewtypeVariant,
+                &"a unit variant",
+            )),
+        }
+    }
+
+    fn newtype_variant_seed<T: de::DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Error> {
+        match self.content {
+            VariantContent::Arguments([value]) => seed.deserialize(ValueDeserializer { value }),
+            _ => seed.deserialize(self),
+        }
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Error> {
+        de::Deserializer::deserialize_any(self, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        de::Deserializer::deserialize_any(self, visitor)
+    }
+}
+
+impl<'v, 'de> de::Deserializer<'de> for VariantDeserializer<'v, 'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.content {
+            VariantContent::Arguments(arguments) => {
+                visitor.visit_seq(SeqDeserializer::new(arguments.iter()))
+            }
+            VariantContent::Properties(properties) => visitor.visit_map(MapDeserializer::new(
+                properties
+                    .iter()
+                    .map(|(key, value)| (BorrowedStrDeserializer::new(key), value)),
+            )),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+/// Visit a string literal, borrowing it if it contains no escape sequences.
+fn visit_str<'de, V: Visitor<'de>>(string: &'de str, visitor: V) -> Result<V::Value, Error> {
+    if string.contains('\\') {
+        visitor.visit_string(unescape(string))
+    } else {
+        visitor.visit_borrowed_str(string)
+    }
+}
+
+/// Resolve escape sequences in a string literal.
+fn unescape(string: &str) -> String {
+    let mut output = String::with_capacity(string.len());
+    let mut chars = string.chars();
+
+    while let Some(char) = chars.next() {
+        if char != '\\' {
+            output.push(char);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n') => output.push('\n'),
+            Some('t') => output.push('\t'),
+            Some('r') => output.push('\r'),
+            Some('b') => output.push('\u{8}'),
+            Some('f') => output.push('\u{c}'),
+            Some('u') => {
+                let code = chars.by_ref().take(4).collect::<String>();
+
+                match u32::from_str_radix(&code, 16).ok().and_then(char::from_u32) {
+                    Some(char) => output.push(char),
+                    None => {
+                        output.push_str("\\u");
+                        output.push_str(&code);
+                    }
+                }
+            }
+            Some(other) => output.push(other),
+            None => output.push('\\'),
+        }
+    }
+
+    output
+}
+
+/// Describe a value for an "invalid type" error.
+fn unexpected<'v>(value: &'v Value<'_>) -> de::Unexpected<'v> {
+    match value {
+        Value::Null => de::Unexpected::Unit,
+        Value::Boolean(boolean) => de::Unexpected::Bool(*boolean),
+        Value::Integer(int) => de::Unexpected::Signed(*int),
+        Value::Float(float) => de::Unexpected::Float(*float),
+        Value::String(string) | Value::StringName(string) | Value::NodePath(string) => {
+            de::Unexpected::Str(string)
+        }
+        Value::Array(_) | Value::TypedArray(..) | Value::PackedArray(..) => de::Unexpected::Seq,
+        Value::Map(_) | Value::TypedMap(..) | Value::Object(..) => de::Unexpected::Map,
+        Value::ExtResource(_) | Value::SubResource(_) | Value::ObjectInstance(..) => {
+            de::Unexpected::Other("an object instance")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use crate::de::*;
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Preset<'a> {
+        name: &'a str,
+        runnable: bool,
+        custom_features: String,
+        #[serde(default)]
+        missing: Option<i32>,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Options {
+        #[serde(rename = "binary_format/architecture")]
+        architecture: String,
+        #[serde(rename = "texture_format/bptc")]
+        bptc: bool,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct ExportPresets<'a> {
+        #[serde(rename = "preset.0", borrow)]
+        preset: Preset<'a>,
+        #[serde(rename = "preset.0.options")]
+        options: Options,
+    }
+
+    #[test]
+    fn test_export_presets() {
+        let source = r#"[preset.0]
+
+name="Linux"
+runnable=true
+custom_features="a\"b"
+
+[preset.0.options]
+
+binary_format/architecture="x86_64"
+texture_format/bptc=true
+"#;
+
+        assert_eq!(
+            from_str::<ExportPresets<'_>>(source),
+            Ok(ExportPresets {
+                preset: Preset {
+                    name: "Linux",
+                    runnable: true,
+                    custom_features: "a\"b".to_owned(),
+                    missing: None,
+                },
+                options: Options {
+                    architecture: "x86_64".to_owned(),
+                    bptc: true,
+                },
+            })
+        );
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Vector2(f32, f32);
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    enum Rarity {
+        Common,
+        Rare,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct ItemData {
+        name: String,
+        rarity: Rarity,
+        offset: Vector2,
+        tags: Vec<String>,
+        icon: String,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct ExtResource {
+        id: String,
+        path: String,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Item {
+        ext_resource: Vec<ExtResource>,
+        resource: ItemData,
+    }
+
+    #[test]
+    fn test_resource() {
+        let source = r#"[gd_resource type="Resource" format=3]
+
+[ext_resource type="Script" path="res://item_data.gd" id="1_a"]
+[ext_resource type="Texture2D" path="res://sword.png" id="2_b"]
+
+[resource]
+script = ExtResource("1_a")
+name = "Sword"
+rarity = &"Rare"
+offset = Vector2(1, 2.5)
+tags = PackedStringArray("melee", "metal")
+icon = ExtResource("2_b")
+"#;
+
+        let item = from_str::<Item>(source).unwrap();
+
+        assert_eq!(item.ext_resource.len(), 2);
+        assert_eq!(item.ext_resource[1].path, "res://sword.png");
+        assert_eq!(
+            item.resource,
+            ItemData {
+                name: "Sword".to_owned(),
+                rarity: Rarity::Rare,
+                offset: Vector2(1.0, 2.5),
+                tags: vec!["melee".to_owned(), "metal".to_owned()],
+                icon: "2_b".to_owned(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(matches!(
+            from_str::<Item>("[resource"),
+            Err(Error::Syntax(_))
+        ));
+        assert!(matches!(
+            from_str::<Item>("[resource]"),
+            Err(Error::Custom(_))
+        ));
+    }
+}
@@ -1,4 +1,9 @@
-use crate::token::Token;
+use diagnosis::{Diagnostic, Highlight, Severity};
+
+use crate::{
+    ast::ResourceId,
+    token::{Span, Token},
+};
 
 #[derive(Default, Debug, Clone, PartialEq, thiserror::Error)]
 pub enum Error<'a> {
@@ -8,10 +13,109 @@ pub enum Error<'a> {
     UnrecognisedToken,
 
     /// An unexpected token.
-    #[error("Unexpected {0:?}, expected {1}.")]
+    #[error("Unexpected {}, expected {}.", .0.kind, .1)]
     Unexpected(Token<'a>, &'a str),
 
     /// Unexpected end-of-file.
     #[error("Unexpected EOF.")]
     UnexpectedEof,
+
+    /// A file does not start with the expected header section.
+    #[error("Missing the `[{0}]` header.")]
+    MissingHeader(&'a str),
+
+    /// A section that is not allowed at this point. `.1` is the section's span.
+    #[error("Unexpected `[{0}]` section.")]
+    UnexpectedSection(&'a str, Span),
+
+    /// A property that does not belong to any section that can have properties.
+    /// `.1` is the property's span.
+    #[error("Unexpected `{0}` property.")]
+    UnexpectedProperty(&'a str, Span),
+
+    /// A required section parameter is missing. `.0` is the section, `.1` is the parameter,
+    /// `.2` is the section's span.
+    #[error("Missing `{1}` parameter in `[{0}]`.")]
+    MissingParameter(&'a str, &'a str, Span),
+
+    /// A section parameter has a value of the wrong type. `.0` is the section, `.1` is the
+    /// parameter, `.2` is the section's span.
+    #[error("Invalid value of `{1}` parameter in `[{0}]`.")]
+    InvalidParameter(&'a str, &'a str, Span),
+
+    /// A reference to a resource that was not declared. `.1` is the span of the
+    /// section with the reference.
+    #[error("Unknown resource `{0}`.")]
+    UnknownResource(ResourceId<'a>, Span),
+
+    /// A node whose parent was not declared. `.0` is the node, `.1` is the parent's path,
+    /// `.2` is the node's span.
+    #[error("Parent `{1}` of node `{0}` does not exist.")]
+    UnknownParent(&'a str, &'a str, Span),
+
+    /// More than one node without a parent. `.1` is the node's span.
+    #[error("Node `{0}` has no parent, but the scene already has a root node.")]
+    MultipleRoots(&'a str, Span),
+}
+
+impl<'a> Error<'a> {
+    /// The span this error points to, if any.
+    pub fn span(&self) -> Option<&Span> {
+        match self {
+            Self::Unexpected(token, _) => Some(&token.span),
+            Self::UnexpectedSection(_, span)
+            | Self::UnexpectedProperty(_, span)
+            | Self::MissingParameter(_, _, span)
+            | Self::InvalidParameter(_, _, span)
+            | Self::UnknownResource(_, span)
+            | Self::UnknownParent(_, _, span)
+            | Self::MultipleRoots(_, span) => Some(span),
+            Self::UnrecognisedToken | Self::UnexpectedEof | Self::MissingHeader(_) => None,
+        }
+    }
+
+    /// A short, kebab-case code identifying the kind of this error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::UnrecognisedToken => "unrecognised-token",
+            Self::Unexpected(token, _) if token.is_unrecognised() => "unrecognised-token",
+            Self::Unexpected(..) => "unexpected-token",
+            Self::UnexpectedEof => "unexpected-eof",
+            Self::MissingHeader(_) => "missing-header",
+            Self::UnexpectedSection(..) => "unexpected-section",
+            Self::UnexpectedProperty(..) => "unexpected-property",
+            Self::MissingParameter(..) => "missing-parameter",
+            Self::InvalidParameter(..) => "invalid-parameter",
+            Self::UnknownResource(..) => "unknown-resource",
+            Self::UnknownParent(..) => "unknown-parent",
+            Self::MultipleRoots(..) => "multiple-roots",
+        }
+    }
+
+    /// Convert this error into a [Diagnostic]. Since diagnostics borrow
+    /// their message, it must be provided by the caller, usually as
+    /// `error.to_string()`.
+    pub fn as_diagnostic<'d>(&'d self, message: &'d str) -> Diagnostic<'d> {
+        let diagnostic = Diagnostic::new(message, Severity::Error).with_code(self.code());
+
+        match self {
+            Self::Unexpected(token, _) => {
+                let highlight = if token.is_unrecognised() {
+                    "unrecognised token"
+                } else {
+                    "unexpected token"
+                };
+
+                diagnostic
+                    .with_span(&token.span)
+                    .add_highlight(Highlight::new(&token.span).with_message(highlight))
+            }
+            _ => match self.span() {
+                Some(span) => diagnostic
+                    .with_span(span)
+                    .add_highlight(Highlight::new(span)),
+                None => diagnostic,
+            },
+        }
+    }
 }
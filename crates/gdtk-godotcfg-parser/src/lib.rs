@@ -9,22 +9,59 @@ use crate::{
 };
 
 pub mod ast;
+#[cfg(feature = "serde")]
+pub mod de;
 pub mod error;
 pub mod parser;
+pub mod project;
+pub mod scene;
+#[cfg(feature = "serde")]
+pub mod ser;
+pub mod serializer;
 pub mod token;
 pub mod utils;
 
+/// Create a lexer for GodotCfg source code. Character sequences that can not
+/// be lexed are reported as [TokenKind::Unrecognised] tokens, so that the
+/// parser can report them with a span.
 pub fn lexer(source: &str) -> impl PeekableResultIterator<Item = Token<'_>> + Debug {
     TokenKind::lexer(source)
         .spanned()
-        .filter_map(|(result, span)| result.ok().zip(Some(span)))
-        .map(|(token, span)| Token::new(token, span))
+        .map(|(result, span)| {
+            let kind = result.unwrap_or(TokenKind::Unrecognised(&source[span.clone()]));
+
+            Token::new(kind, span)
+        })
         .peekable()
 }
 
-pub fn parser(source: &str) -> Parser<impl PeekableResultIterator<Item = Token<'_>> + Debug> {
+/// Create a parser for GodotCfg source code.
+pub fn parser(source: &str) -> Parser<'_, impl PeekableResultIterator<Item = Token<'_>> + Debug> {
     Parser {
         tokens: lexer(source),
-        had_error: false,
+        source,
     }
 }
+
+/// Parse GodotCfg source code, collecting all errors instead of stopping
+/// at the first one.
+pub fn parse(source: &str) -> (ast::File<'_>, Vec<error::Error<'_>>) {
+    let (lines, errors) = parse_spanned(source);
+
+    (lines.into_iter().map(|(line, _)| line).collect(), errors)
+}
+
+/// Like [parse], but every line comes with its span.
+pub fn parse_spanned(source: &str) -> (Vec<(ast::Line<'_>, token::Span)>, Vec<error::Error<'_>>) {
+    let mut lines = vec![];
+    let mut errors = vec![];
+
+    for (result, span) in parser(source).spanned() {
+        match result {
+            Ok(line) => lines.push((line, span)),
+            Err(error) => errors.push(error),
+        }
+    }
+
+    (lines, errors)
+}
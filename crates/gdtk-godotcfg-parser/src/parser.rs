@@ -3,9 +3,9 @@ use std::fmt::Debug;
 use tracing::trace;
 
 use crate::{
-    ast::{Line, Value},
+    ast::{ElementType, Line, PackedArrayKind, ResourceId, Value},
     error::Error,
-    token::{Token, TokenKind},
+    token::{Span, Token, TokenKind},
     utils::PeekableResultIterator,
 };
 
@@ -23,13 +23,18 @@ use crate::{
 ///
 /// The primary (and only) way to construct a [Parser] is
 /// through [crate::parser].
+///
+/// When a line fails to parse, the error is yielded and the
+/// parser skips ahead to the next line that looks like the start
+/// of a comment, a section or a parameter, so that all problems
+/// in a file can be reported at once.
 #[derive(Debug)]
-pub struct Parser<I> {
+pub struct Parser<'a, I> {
     pub(crate) tokens: I,
-    pub(crate) had_error: bool,
+    pub(crate) source: &'a str,
 }
 
-impl<'a, I> Iterator for Parser<I>
+impl<'a, I> Iterator for Parser<'a, I>
 where
     I: PeekableResultIterator<Item = Token<'a>> + Debug,
 {
@@ -39,14 +44,12 @@ where
     fn next(&mut self) -> Option<Self::Item> {
         trace!("Parser::next");
 
-        if self.had_error {
-            return None;
-        }
-
         let result = match self.tokens.peek()?.kind {
             TokenKind::Comment(_) => self.parse_comment(),
             TokenKind::OpeningBracket => self.parse_section(),
-            TokenKind::Identifier(_) | TokenKind::Path(_) => self.parse_parameter(),
+            TokenKind::Identifier(_) | TokenKind::Path(_) | TokenKind::String(_) => {
+                self.parse_parameter()
+            }
             _ => Err(Error::Unexpected(
                 self.tokens.next()?,
                 "a comment, a section, or a parameter",
@@ -54,17 +57,57 @@ where
         };
 
         if result.is_err() {
-            self.had_error = true;
+            self.recover();
         }
 
         Some(result)
     }
 }
 
-impl<'a, I> Parser<I>
+impl<'a, I> Parser<'a, I>
 where
     I: PeekableResultIterator<Item = Token<'a>> + Debug,
 {
+    /// Like iterating over the parser, but every line comes with its span.
+    pub fn spanned(mut self) -> impl Iterator<Item = (Result<Line<'a>, Error<'a>>, Span)> {
+        std::iter::from_fn(move || {
+            let start = self.tokens.peek()?.span.start;
+            let result = self.next()?;
+            let end = self
+                .tokens
+                .peek()
+                .map_or(self.source.len(), |token| token.span.start);
+
+            Some((
+                result,
+                start..start + self.source[start..end].trim_end().len(),
+            ))
+        })
+    }
+
+    /// Skip tokens until the start of something that looks like a new line.
+    #[tracing::instrument(skip(self), level = tracing::Level::TRACE)]
+    fn recover(&mut self) {
+        trace!("Parser::recover");
+
+        while let Some(token) = self.tokens.peek() {
+            let starts_line = matches!(
+                token.kind,
+                TokenKind::Comment(_)
+                    | TokenKind::OpeningBracket
+                    | TokenKind::Identifier(_)
+                    | TokenKind::Path(_)
+                    | TokenKind::String(_)
+            );
+
+            if starts_line && is_at_line_start(self.source, token) {
+                break;
+            }
+
+            self.tokens.next();
+        }
+    }
+
     #[tracing::instrument(skip(self), level = tracing::Level::TRACE)]
     fn parse_comment(&mut self) -> Result<Line<'a>, Error<'a>> {
         trace!("Parser::parse_comment");
@@ -107,9 +150,9 @@ where
     fn parse_parameter(&mut self) -> Result<Line<'a>, Error<'a>> {
         trace!("Parser::parse_parameter");
 
-        trace!("Parser::parse_parameter - expect_path_like");
+        trace!("Parser::parse_parameter - expect_key");
 
-        let path = self.tokens.next_ok()?.expect_path_like()?;
+        let path = self.tokens.next_ok()?.expect_key()?;
 
         trace!("Parser::parse_parameter - expect_assignment");
 
@@ -117,6 +160,13 @@ where
 
         trace!("Parser::parse_parameter - expect_value");
 
+        // a value must start on the same line, otherwise we'd swallow the next line
+        let next = self.tokens.peek_ok()?;
+
+        if is_at_line_start(self.source, next) {
+            return Err(Error::Unexpected(next.clone(), "a value"));
+        }
+
         let value = self.parse_value()?;
 
         Ok(Line::Parameter(path, value))
@@ -128,6 +178,8 @@ where
 
         match self.tokens.peek_ok()?.kind {
             TokenKind::String(_) => self.parse_string(),
+            TokenKind::StringName(_) => self.parse_string_name(),
+            TokenKind::NodePath(_) => self.parse_node_path(),
             TokenKind::Integer(_) => self.parse_integer(),
             TokenKind::Float(_) => self.parse_float(),
             TokenKind::Boolean(_) => self.parse_boolean(),
@@ -146,6 +198,22 @@ where
         Ok(Value::String(self.tokens.next_ok()?.expect_string()?))
     }
 
+    #[tracing::instrument(skip(self), level = tracing::Level::TRACE)]
+    fn parse_string_name(&mut self) -> Result<Value<'a>, Error<'a>> {
+        trace!("Parser::parse_string_name");
+
+        Ok(Value::StringName(
+            self.tokens.next_ok()?.expect_string_name()?,
+        ))
+    }
+
+    #[tracing::instrument(skip(self), level = tracing::Level::TRACE)]
+    fn parse_node_path(&mut self) -> Result<Value<'a>, Error<'a>> {
+        trace!("Parser::parse_node_path");
+
+        Ok(Value::NodePath(self.tokens.next_ok()?.expect_node_path()?))
+    }
+
     #[tracing::instrument(skip(self), level = tracing::Level::TRACE)]
     fn parse_integer(&mut self) -> Result<Value<'a>, Error<'a>> {
         trace!("Parser::parse_integer");
@@ -243,16 +311,135 @@ where
         let identifier = self.tokens.next_ok()?.expect_identifier()?;
 
         match identifier {
+            "inf" => Ok(Value::Float(f64::INFINITY)),
+            "inf_neg" => Ok(Value::Float(f64::NEG_INFINITY)),
+            "nan" => Ok(Value::Float(f64::NAN)),
+            "nil" => Ok(Value::Null),
             "Object" => self.parse_object(),
-            other => self.parse_object_instance(other),
+            "StringName" => Ok(Value::StringName(self.parse_wrapped_string()?)),
+            "NodePath" => Ok(Value::NodePath(self.parse_wrapped_string()?)),
+            "ExtResource" => Ok(Value::ExtResource(self.parse_resource_id()?)),
+            "SubResource" => Ok(Value::SubResource(self.parse_resource_id()?)),
+            "Array" if self.tokens.peek_ok()?.is_opening_bracket() => self.parse_typed_array(),
+            "Dictionary" if self.tokens.peek_ok()?.is_opening_bracket() => self.parse_typed_map(),
+            other => match PackedArrayKind::from_name(other) {
+                Some(kind) => self.parse_packed_array(kind),
+                None => self.parse_object_instance(other),
+            },
         }
     }
 
+    /// Parse the ``("string")`` part of ``StringName("string")`` or ``NodePath("string")``.
+    #[tracing::instrument(skip(self), level = tracing::Level::TRACE)]
+    fn parse_wrapped_string(&mut self) -> Result<&'a str, Error<'a>> {
+        trace!("Parser::parse_wrapped_string");
+
+        self.tokens.next_ok()?.expect_opening_parenthesis()?;
+
+        let string = self.tokens.next_ok()?.expect_string()?;
+
+        self.tokens.next_ok()?.expect_closing_parenthesis()?;
+
+        Ok(string)
+    }
+
+    /// Parse the ``("id")`` part of ``ExtResource("id")`` or ``SubResource("id")``.
+    #[tracing::instrument(skip(self), level = tracing::Level::TRACE)]
+    fn parse_resource_id(&mut self) -> Result<ResourceId<'a>, Error<'a>> {
+        trace!("Parser::parse_resource_id");
+
+        self.tokens.next_ok()?.expect_opening_parenthesis()?;
+
+        let token = self.tokens.next_ok()?;
+
+        let id = match token.kind {
+            TokenKind::String(string) => ResourceId::String(string),
+            TokenKind::Integer(int) => ResourceId::Integer(int),
+            _ => return Err(Error::Unexpected(token, "a resource id")),
+        };
+
+        self.tokens.next_ok()?.expect_closing_parenthesis()?;
+
+        Ok(id)
+    }
+
+    #[tracing::instrument(skip(self), level = tracing::Level::TRACE)]
+    fn parse_element_type(&mut self) -> Result<ElementType<'a>, Error<'a>> {
+        trace!("Parser::parse_element_type");
+
+        let identifier = self.tokens.next_ok()?.expect_identifier()?;
+
+        Ok(match identifier {
+            "ExtResource" => ElementType::ExtResource(self.parse_resource_id()?),
+            "SubResource" => ElementType::SubResource(self.parse_resource_id()?),
+            other => ElementType::Named(other),
+        })
+    }
+
+    #[tracing::instrument(skip(self), level = tracing::Level::TRACE)]
+    fn parse_typed_array(&mut self) -> Result<Value<'a>, Error<'a>> {
+        trace!("Parser::parse_typed_array");
+
+        self.tokens.next_ok()?.expect_opening_bracket()?;
+
+        let element = self.parse_element_type()?;
+
+        self.tokens.next_ok()?.expect_closing_bracket()?;
+        self.tokens.next_ok()?.expect_opening_parenthesis()?;
+
+        let Value::Array(values) = self.parse_array()? else {
+            unreachable!()
+        };
+
+        self.tokens.next_ok()?.expect_closing_parenthesis()?;
+
+        Ok(Value::TypedArray(element, values))
+    }
+
+    #[tracing::instrument(skip(self), level = tracing::Level::TRACE)]
+    fn parse_typed_map(&mut self) -> Result<Value<'a>, Error<'a>> {
+        trace!("Parser::parse_typed_map");
+
+        self.tokens.next_ok()?.expect_opening_bracket()?;
+
+        let key = self.parse_element_type()?;
+
+        self.tokens.next_ok()?.expect_comma()?;
+
+        let value = self.parse_element_type()?;
+
+        self.tokens.next_ok()?.expect_closing_bracket()?;
+        self.tokens.next_ok()?.expect_opening_parenthesis()?;
+
+        let Value::Map(pairs) = self.parse_map()? else {
+            unreachable!()
+        };
+
+        self.tokens.next_ok()?.expect_closing_parenthesis()?;
+
+        Ok(Value::TypedMap(key, value, pairs))
+    }
+
+    #[tracing::instrument(skip(self), level = tracing::Level::TRACE)]
+    fn parse_packed_array(&mut self, kind: PackedArrayKind) -> Result<Value<'a>, Error<'a>> {
+        trace!("Parser::parse_packed_array");
+
+        Ok(Value::PackedArray(kind, self.parse_arguments()?))
+    }
+
     #[tracing::instrument(skip(self), level = tracing::Level::TRACE)]
     fn parse_object_instance(&mut self, identifier: &'a str) -> Result<Value<'a>, Error<'a>> {
         trace!("Parser::parse_object_instance");
 
-        trace!("Parser::parse_object_instance - expect_opening_parenthesis");
+        Ok(Value::ObjectInstance(identifier, self.parse_arguments()?))
+    }
+
+    /// Parse a parenthesized, comma-separated list of values.
+    #[tracing::instrument(skip(self), level = tracing::Level::TRACE)]
+    fn parse_arguments(&mut self) -> Result<Vec<Value<'a>>, Error<'a>> {
+        trace!("Parser::parse_arguments");
+
+        trace!("Parser::parse_arguments - expect_opening_parenthesis");
 
         self.tokens.next_ok()?.expect_opening_parenthesis()?;
 
@@ -266,11 +453,11 @@ where
             }
         }
 
-        trace!("Parser::parse_object_instance - expect_closing_parenthesis");
+        trace!("Parser::parse_arguments - expect_closing_parenthesis");
 
         self.tokens.next_ok()?.expect_closing_parenthesis()?;
 
-        Ok(Value::ObjectInstance(identifier, values))
+        Ok(values)
     }
 
     #[tracing::instrument(skip(self), level = tracing::Level::TRACE)]
@@ -317,6 +504,13 @@ where
     }
 }
 
+/// Whether ``token`` is the first token on it's line.
+fn is_at_line_start(source: &str, token: &Token<'_>) -> bool {
+    let before = source[..token.span.start].trim_end_matches([' ', '\t', '\r']);
+
+    before.is_empty() || before.ends_with('\n')
+}
+
 #[cfg(test)]
 mod tests {
     use crate::ast::*;
@@ -368,6 +562,10 @@ mod tests {
             parse!("path/to/param=0"),
             Line::Parameter("path/to/param", Value::Integer(0))
         );
+        assert_eq!(
+            parse!("\"quoted param\"=0"),
+            Line::Parameter("quoted param", Value::Integer(0))
+        );
 
         Ok(())
     }
@@ -426,13 +624,120 @@ mod tests {
     #[test]
     fn test_value_object_instance() -> Test {
         assert_eq!(
-            parse_val!(r#"PackedByteArray(0,0,0)"#),
+            parse_val!(r#"Vector3(0,0,0)"#),
             Value::ObjectInstance(
-                "PackedByteArray",
+                "Vector3",
                 vec![Value::Integer(0), Value::Integer(0), Value::Integer(0)],
             )
         );
 
         Ok(())
     }
+
+    #[test]
+    fn test_value_special_floats() -> Test {
+        assert_eq!(parse_val!("inf"), Value::Float(f64::INFINITY));
+        assert_eq!(parse_val!("inf_neg"), Value::Float(f64::NEG_INFINITY));
+        assert_eq!(parse_val!("-inf"), Value::Float(f64::NEG_INFINITY));
+        assert!(parse_val!("nan").as_float().is_some_and(|f| f.is_nan()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_value_names_and_paths() -> Test {
+        assert_eq!(parse_val!("&\"name\""), Value::StringName("name"));
+        assert_eq!(
+            parse_val!("StringName(\"name\")"),
+            Value::StringName("name")
+        );
+        assert_eq!(parse_val!("^\"../a\""), Value::NodePath("../a"));
+        assert_eq!(parse_val!("NodePath(\"../a\")"), Value::NodePath("../a"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_value_resources() -> Test {
+        assert_eq!(
+            parse_val!("ExtResource(\"1_abc\")"),
+            Value::ExtResource(ResourceId::String("1_abc"))
+        );
+        assert_eq!(
+            parse_val!("SubResource( 2 )"),
+            Value::SubResource(ResourceId::Integer(2))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_value_typed_collections() -> Test {
+        assert_eq!(
+            parse_val!("Array[int]([1, 2])"),
+            Value::TypedArray(
+                ElementType::Named("int"),
+                vec![Value::Integer(1), Value::Integer(2)]
+            )
+        );
+        assert_eq!(
+            parse_val!("Array[ExtResource(\"1\")]([])"),
+            Value::TypedArray(ElementType::ExtResource(ResourceId::String("1")), vec![])
+        );
+        assert_eq!(
+            parse_val!("Dictionary[String, int]({\"a\": 1})"),
+            Value::TypedMap(
+                ElementType::Named("String"),
+                ElementType::Named("int"),
+                vec![(Value::String("a"), Value::Integer(1))]
+            )
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_value_packed_array() -> Test {
+        assert_eq!(
+            parse_val!("PackedByteArray(0,0,0)"),
+            Value::PackedArray(
+                PackedArrayKind::Byte,
+                vec![Value::Integer(0), Value::Integer(0), Value::Integer(0)],
+            )
+        );
+        assert_eq!(
+            parse_val!("PoolVector2Array(0, 1.5)"),
+            Value::PackedArray(
+                PackedArrayKind::Vector2,
+                vec![Value::Integer(0), Value::Float(1.5)],
+            )
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_recovery() {
+        let source = "a=1\n<<<<<<< HEAD\nb=\nc=3\n[section]\nd={\n\"x\": ?\n}\ne=5\n";
+        let (lines, errors) = crate::parse(source);
+
+        assert_eq!(
+            lines,
+            vec![
+                Line::Parameter("a", Value::Integer(1)),
+                Line::Parameter("c", Value::Integer(3)),
+                Line::Section("section", vec![]),
+                Line::Parameter("e", Value::Integer(5)),
+            ]
+        );
+        assert_eq!(
+            errors.iter().map(|e| e.code()).collect::<Vec<_>>(),
+            vec![
+                "unrecognised-token",
+                "unexpected-token",
+                "unrecognised-token"
+            ]
+        );
+        assert_eq!(errors[1].span(), Some(&(20..21)));
+    }
 }
@@ -0,0 +1,398 @@
+//! A typed model of `project.godot` files.
+//!
+//! Settings are addressed using the same `section/key` paths Godot uses
+//! in `ProjectSettings.get_setting()`, e.g. `application/config/name`.
+//! Parameters that appear before any section (like `config_version`) are
+//! addressed by their key alone.
+
+use crate::{
+    ast::{Line, Value},
+    error::Error,
+    scene::Properties,
+};
+
+/// A `project.godot` file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProjectSettings<'a> {
+    /// All settings, in declaration order.
+    pub settings: Vec<Setting<'a>>,
+}
+
+/// A single project setting.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Setting<'a> {
+    /// The section the setting belongs to.
+    pub section: Option<&'a str>,
+    /// The setting's key inside it's section, possibly with feature tags
+    /// (e.g. `renderer/rendering_method.mobile`).
+    pub key: &'a str,
+    /// The setting's value.
+    pub value: Value<'a>,
+}
+
+impl<'a> Setting<'a> {
+    /// The full `section/key` path of this setting.
+    pub fn path(&self) -> String {
+        match self.section {
+            Some(section) => format!("{}/{}", section, self.key),
+            None => self.key.to_owned(),
+        }
+    }
+
+    /// The key without feature tags.
+    pub fn base_key(&self) -> &'a str {
+        self.key.split('.').next().unwrap_or(self.key)
+    }
+
+    /// The feature tags this setting is an override for. Empty if this
+    /// is not an override.
+    pub fn features(&self) -> impl Iterator<Item = &'a str> {
+        self.key.split('.').skip(1)
+    }
+
+    /// Whether this setting is located at the given path, ignoring feature tags.
+    fn is_at(&self, path: &str) -> bool {
+        match (self.section, path.split_once('/')) {
+            (Some(section), Some((path_section, key))) => {
+                section == path_section && self.base_key() == key
+            }
+            (None, _) => self.base_key() == path,
+            (Some(_), None) => false,
+        }
+    }
+}
+
+/// An entry of the `[autoload]` section.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Autoload<'a> {
+    /// The autoload's name.
+    pub name: &'a str,
+    /// The path to the autoloaded script or scene.
+    pub path: &'a str,
+    /// Whether the autoload is registered as a global variable (the path
+    /// is prefixed with a `*`).
+    pub singleton: bool,
+}
+
+/// An entry of the `[input]` section.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InputAction<'a> {
+    /// The action's name.
+    pub name: &'a str,
+    /// The action's deadzone.
+    pub deadzone: Option<f64>,
+    /// Events that trigger the action.
+    pub events: Vec<InputEvent<'a>>,
+}
+
+/// An `InputEvent` object bound to an [InputAction].
+#[derive(Debug, Clone, PartialEq)]
+pub struct InputEvent<'a> {
+    /// The event's class, e.g. `InputEventKey`.
+    pub kind: &'a str,
+    /// The event's properties.
+    pub properties: Properties<'a>,
+}
+
+/// An entry of the `[layer_names]` section.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayerName<'a> {
+    /// The kind of layers, e.g. `2d_physics` or `3d_render`.
+    pub category: &'a str,
+    /// The layer's number, starting from 1.
+    pub layer: u32,
+    /// The layer's name.
+    pub name: &'a str,
+}
+
+impl<'a> ProjectSettings<'a> {
+    /// Parse a project from source code.
+    pub fn parse(source: &'a str) -> Result<Self, Error<'a>> {
+        let lines = crate::parser(source).collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self::from_lines(lines))
+    }
+
+    /// Build a project from the result of parsing a `project.godot` file.
+    pub fn from_lines(lines: impl IntoIterator<Item = Line<'a>>) -> Self {
+        let mut settings = vec![];
+        let mut section = None;
+
+        for line in lines {
+            match line {
+                Line::Comment(_) => (),
+                Line::Section(identifier, _) => section = Some(identifier),
+                Line::Parameter(key, value) => settings.push(Setting {
+                    section,
+                    key,
+                    value,
+                }),
+            }
+        }
+
+        Self { settings }
+    }
+
+    /// Get a setting by it's path, ignoring feature overrides.
+    pub fn get(&self, path: &str) -> Option<&Value<'a>> {
+        self.settings
+            .iter()
+            .find(|s| s.is_at(path) && s.features().next().is_none())
+            .map(|s| &s.value)
+    }
+
+    /// Get a setting by it's path, taking feature overrides (e.g. `key.windows`
+    /// or `key.release`) into account. An override applies if all of it's
+    /// feature tags are present in `features`. Like in Godot, the first
+    /// applicable override wins.
+    pub fn get_with_features(&self, path: &str, features: &[&str]) -> Option<&Value<'a>> {
+        self.settings
+            .iter()
+            .filter(|s| s.is_at(path) && s.features().next().is_some())
+            .find(|s| s.features().all(|f| features.contains(&f)))
+            .map(|s| &s.value)
+            .or_else(|| self.get(path))
+    }
+
+    /// Iterate over all settings in a section.
+    pub fn section<'s>(&'s self, name: &'s str) -> impl Iterator<Item = &'s Setting<'a>> + 's {
+        self.settings
+            .iter()
+            .filter(move |s| s.section == Some(name))
+    }
+
+    /// The `config_version`. `5` for Godot 4, `4` for Godot 3.
+    pub fn config_version(&self) -> Option<i64> {
+        self.get("config_version")?.as_integer().copied()
+    }
+
+    /// The project's name.
+    pub fn name(&self) -> Option<&'a str> {
+        self.get("application/config/name")?.as_string().copied()
+    }
+
+    /// The project's main scene.
+    pub fn main_scene(&self) -> Option<&'a str> {
+        self.get("application/run/main_scene")?.as_string().copied()
+    }
+
+    /// The project's `config/features`, e.g. `["4.2", "Forward Plus"]`.
+    pub fn features(&self) -> Vec<&'a str> {
+        let Some(Value::PackedArray(_, features)) = self.get("application/config/features") else {
+            return vec![];
+        };
+
+        features
+            .iter()
+            .filter_map(|f| f.as_string().copied())
+            .collect()
+    }
+
+    /// The Godot version the project targets, e.g. `4.2`. Only available
+    /// for Godot 4 projects.
+    pub fn godot_version(&self) -> Option<&'a str> {
+        self.features().into_iter().find(|f| {
+            f.split('.')
+                .all(|p| !p.is_empty() && p.bytes().all(|b| b.is_ascii_digit()))
+        })
+    }
+
+    /// The project's autoloads.
+    pub fn autoloads(&self) -> Vec<Autoload<'a>> {
+        self.section("autoload")
+            .filter_map(|s| {
+                let path = s.value.as_string()?;
+
+                let (path, singleton) = match path.strip_prefix('*') {
+                    Some(path) => (path, true),
+                    None => (*path, false),
+                };
+
+                Some(Autoload {
+                    name: s.key,
+                    path,
+                    singleton,
+                })
+            })
+            .collect()
+    }
+
+    /// The project's input actions.
+    pub fn input_actions(&self) -> Vec<InputAction<'a>> {
+        self.section("input")
+            .filter_map(|s| {
+                let action = s.value.as_map()?;
+                let mut deadzone = None;
+                let mut events = vec![];
+
+                for (key, value) in action {
+                    match (key, value) {
+                        (Value::String("deadzone"), Value::Float(float)) => deadzone = Some(*float),
+                        (Value::String("deadzone"), Value::Integer(int)) => {
+                            deadzone = Some(*int as f64)
+                        }
+                        (Value::String("events"), Value::Array(values)) => {
+                            events.extend(values.iter().filter_map(|value| match value {
+                                Value::Object(kind, properties) => Some(InputEvent {
+                                    kind,
+                                    properties: properties.clone(),
+                                }),
+                                _ => None,
+                            }))
+                        }
+                        _ => (),
+                    }
+                }
+
+                Some(InputAction {
+                    name: s.key,
+                    deadzone,
+                    events,
+                })
+            })
+            .collect()
+    }
+
+    /// The project's named layers.
+    pub fn layer_names(&self) -> Vec<LayerName<'a>> {
+        self.section("layer_names")
+            .filter_map(|s| {
+                let (category, layer) = s.key.split_once('/')?;
+
+                Some(LayerName {
+                    category,
+                    layer: layer.strip_prefix("layer_")?.parse().ok()?,
+                    name: s.value.as_string()?,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::Value;
+    use crate::project::*;
+
+    type Test = Result<(), crate::error::Error<'static>>;
+
+    const PROJECT: &str = r#"; Engine configuration file.
+
+config_version=5
+
+[application]
+
+config/name="Test"
+run/main_scene="res://main.tscn"
+config/features=PackedStringArray("4.2", "Forward Plus")
+
+[autoload]
+
+Global="*res://global.gd"
+Helpers="res://helpers.gd"
+
+[input]
+
+jump={
+"deadzone": 0.5,
+"events": [Object(InputEventKey,"keycode":0,"physical_keycode":32)
+]
+}
+
+[layer_names]
+
+2d_physics/layer_1="World"
+2d_physics/layer_3="Enemies"
+
+[rendering]
+
+renderer/rendering_method="forward_plus"
+renderer/rendering_method.mobile="gl_compatibility"
+renderer/rendering_method.web.release="mobile"
+"#;
+
+    #[test]
+    fn test_project_application() -> Test {
+        let project = ProjectSettings::parse(PROJECT)?;
+
+        assert_eq!(project.config_version(), Some(5));
+        assert_eq!(project.name(), Some("Test"));
+        assert_eq!(project.main_scene(), Some("res://main.tscn"));
+        assert_eq!(project.features(), vec!["4.2", "Forward Plus"]);
+        assert_eq!(project.godot_version(), Some("4.2"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_project_sections() -> Test {
+        let project = ProjectSettings::parse(PROJECT)?;
+
+        assert_eq!(
+            project.autoloads(),
+            vec![
+                Autoload {
+                    name: "Global",
+                    path: "res://global.gd",
+                    singleton: true
+                },
+                Autoload {
+                    name: "Helpers",
+                    path: "res://helpers.gd",
+                    singleton: false
+                },
+            ]
+        );
+
+        let actions = project.input_actions();
+
+        assert_eq!(actions.len(), 1);
+        assert_eq!(actions[0].name, "jump");
+        assert_eq!(actions[0].deadzone, Some(0.5));
+        assert_eq!(actions[0].events[0].kind, "InputEventKey");
+
+        assert_eq!(
+            project.layer_names(),
+            vec![
+                LayerName {
+                    category: "2d_physics",
+                    layer: 1,
+                    name: "World"
+                },
+                LayerName {
+                    category: "2d_physics",
+                    layer: 3,
+                    name: "Enemies"
+                },
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_project_feature_overrides() -> Test {
+        let project = ProjectSettings::parse(PROJECT)?;
+        let path = "rendering/renderer/rendering_method";
+
+        assert_eq!(project.get(path), Some(&Value::String("forward_plus")));
+        assert_eq!(
+            project.get_with_features(path, &["windows"]),
+            Some(&Value::String("forward_plus"))
+        );
+        assert_eq!(
+            project.get_with_features(path, &["mobile"]),
+            Some(&Value::String("gl_compatibility"))
+        );
+        assert_eq!(
+            project.get_with_features(path, &["web"]),
+            Some(&Value::String("forward_plus"))
+        );
+        assert_eq!(
+            project.get_with_features(path, &["web", "release"]),
+            Some(&Value::String("mobile"))
+        );
+
+        Ok(())
+    }
+}
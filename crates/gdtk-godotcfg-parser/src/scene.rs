@@ -0,0 +1,883 @@
+//! A typed model of `.tscn` (packed scene) files.
+//!
+//! A [Scene] is built from the flat list of [Line]s produced by the parser.
+//! Parameters of sections are split into typed fields where Godot assigns
+//! them a meaning, and everything else is preserved as-is so that a scene
+//! can be written back without losing information.
+
+use std::fmt::{Display, Formatter};
+
+use crate::{
+    ast::{ElementType, Line, ResourceId, Value},
+    error::Error,
+    token::Span,
+};
+
+pub mod diff;
+pub mod merge;
+
+/// An ordered list of `key = value` pairs.
+pub type Properties<'a> = Vec<(&'a str, Value<'a>)>;
+
+/// A `.tscn` file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Scene<'a> {
+    /// The `[gd_scene]` header.
+    pub header: SceneHeader<'a>,
+    /// All `[ext_resource]`s, in declaration order.
+    pub ext_resources: Vec<ExtResource<'a>>,
+    /// All `[sub_resource]`s, in declaration order.
+    pub sub_resources: Vec<SubResource<'a>>,
+    /// All `[node]`s, in declaration order. Parents always come before
+    /// their children.
+    pub nodes: Vec<Node<'a>>,
+    /// All `[connection]`s, in declaration order.
+    pub connections: Vec<Connection<'a>>,
+    /// Paths of instanced nodes whose children are editable (`[editable]`).
+    pub editables: Vec<&'a str>,
+}
+
+/// The `[gd_scene]` header of a scene.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SceneHeader<'a> {
+    /// The total amount of resources (plus one for the scene itself).
+    /// Godot stopped writing this in 4.4.
+    pub load_steps: Option<i64>,
+    /// The version of the file format. `2` for Godot 3, `3` for Godot 4.
+    pub format: Option<i64>,
+    /// The scene's `uid://`.
+    pub uid: Option<&'a str>,
+    /// Any other header parameters.
+    pub parameters: Properties<'a>,
+}
+
+/// A reference to a resource, i.e. an `ExtResource("id")` or a `SubResource("id")`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ResourceRef<'a> {
+    /// An `ExtResource("id")`.
+    External(ResourceId<'a>),
+    /// A `SubResource("id")`.
+    Internal(ResourceId<'a>),
+}
+
+impl<'a> ResourceRef<'a> {
+    /// Try to interpret a value as a resource reference.
+    pub fn from_value(value: &Value<'a>) -> Option<Self> {
+        match value {
+            Value::ExtResource(id) => Some(Self::External(*id)),
+            Value::SubResource(id) => Some(Self::Internal(*id)),
+            _ => None,
+        }
+    }
+
+    /// Convert this reference into a value.
+    pub fn to_value(self) -> Value<'a> {
+        match self {
+            Self::External(id) => Value::ExtResource(id),
+            Self::Internal(id) => Value::SubResource(id),
+        }
+    }
+}
+
+/// A resource resolved by [Scene::resolve].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Resource<'s, 'a> {
+    /// An external resource.
+    External(&'s ExtResource<'a>),
+    /// An internal resource.
+    Internal(&'s SubResource<'a>),
+}
+
+/// An `[ext_resource]`, i.e. a resource loaded from another file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExtResource<'a> {
+    /// The resource's type.
+    pub kind: &'a str,
+    /// The resource's `uid://`, if any.
+    pub uid: Option<&'a str>,
+    /// The resource's `res://` path.
+    pub path: &'a str,
+    /// The resource's scene-local identifier.
+    pub id: ResourceId<'a>,
+    /// Any other section parameters.
+    pub parameters: Properties<'a>,
+}
+
+/// A `[sub_resource]`, i.e. a resource embedded into the scene.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubResource<'a> {
+    /// The resource's type.
+    pub kind: &'a str,
+    /// The resource's scene-local identifier.
+    pub id: ResourceId<'a>,
+    /// Any other section parameters.
+    pub parameters: Properties<'a>,
+    /// The resource's properties.
+    pub properties: Properties<'a>,
+}
+
+/// A `[node]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Node<'a> {
+    /// The node's name.
+    pub name: &'a str,
+    /// The node's type. `None` for instanced scenes and for nodes that only
+    /// override properties of nodes from an instanced scene.
+    pub kind: Option<&'a str>,
+    /// The path to the node's parent, relative to the root node. `None` for
+    /// the root node.
+    pub parent: Option<&'a str>,
+    /// The scene this node is an instance of.
+    pub instance: Option<ResourceId<'a>>,
+    /// The groups this node belongs to.
+    pub groups: Vec<&'a str>,
+    /// Any other section parameters (`owner`, `index`, etc.).
+    pub parameters: Properties<'a>,
+    /// The node's properties.
+    pub properties: Properties<'a>,
+}
+
+impl Node<'_> {
+    /// Whether this node is the root node of the scene.
+    pub fn is_root(&self) -> bool {
+        self.parent.is_none()
+    }
+
+    /// The path to this node, relative to the root node. The root node's
+    /// path is `.`.
+    pub fn path(&self) -> String {
+        match self.parent {
+            None => String::from("."),
+            Some(".") => self.name.to_owned(),
+            Some(parent) => format!("{}/{}", parent, self.name),
+        }
+    }
+}
+
+/// A `[connection]` between a signal and a method.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Connection<'a> {
+    /// The signal's name.
+    pub signal: &'a str,
+    /// The path to the node that emits the signal.
+    pub from: &'a str,
+    /// The path to the node that receives the signal.
+    pub to: &'a str,
+    /// The method to call on the receiving node.
+    pub method: &'a str,
+    /// The connection's `ConnectFlags`.
+    pub flags: Option<i64>,
+    /// Any other section parameters (`binds`, `unbinds`, etc.).
+    pub parameters: Properties<'a>,
+}
+
+/// Find a property by it's key.
+pub fn get_property<'p, 'a>(properties: &'p Properties<'a>, key: &str) -> Option<&'p Value<'a>> {
+    properties.iter().find(|(k, _)| *k == key).map(|(_, v)| v)
+}
+
+/// Normalize a node path so that it can be compared with [Node::path].
+fn normalize_path(path: &str) -> &str {
+    match path.trim_start_matches("./").trim_end_matches('/') {
+        "" => ".",
+        other => other,
+    }
+}
+
+/// Whether a resource reference points to an external or an internal resource.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RefKind {
+    External,
+    Internal,
+}
+
+/// Call `f` on every resource reference in a scene.
+fn for_each_reference<'a>(scene: &mut Scene<'a>, f: &mut impl FnMut(RefKind, &mut ResourceId<'a>)) {
+    let properties = scene
+        .header
+        .parameters
+        .iter_mut()
+        .chain(
+            scene
+                .ext_resources
+                .iter_mut()
+                .flat_map(|r| &mut r.parameters),
+        )
+        .chain(
+            scene
+                .sub_resources
+                .iter_mut()
+                .flat_map(|r| r.parameters.iter_mut().chain(&mut r.properties)),
+        )
+        .chain(
+            scene
+                .nodes
+                .iter_mut()
+                .flat_map(|n| n.parameters.iter_mut().chain(&mut n.properties)),
+        )
+        .chain(scene.connections.iter_mut().flat_map(|c| &mut c.parameters));
+
+    for (_, value) in properties {
+        for_each_value_reference(value, f);
+    }
+
+    for node in &mut scene.nodes {
+        if let Some(id) = &mut node.instance {
+            f(RefKind::External, id);
+        }
+    }
+}
+
+/// Call `f` on every resource reference in a value.
+fn for_each_value_reference<'a>(
+    value: &mut Value<'a>,
+    f: &mut impl FnMut(RefKind, &mut ResourceId<'a>),
+) {
+    match value {
+        Value::ExtResource(id) => f(RefKind::External, id),
+        Value::SubResource(id) => f(RefKind::Internal, id),
+        Value::Array(values) | Value::PackedArray(_, values) | Value::ObjectInstance(_, values) => {
+            for value in values {
+                for_each_value_reference(value, f);
+            }
+        }
+        Value::TypedArray(kind, values) => {
+            for_each_element_reference(kind, f);
+
+            for value in values {
+                for_each_value_reference(value, f);
+            }
+        }
+        Value::Map(pairs) => {
+            for (key, value) in pairs {
+                for_each_value_reference(key, f);
+                for_each_value_reference(value, f);
+            }
+        }
+        Value::TypedMap(key_kind, value_kind, pairs) => {
+            for_each_element_reference(key_kind, f);
+            for_each_element_reference(value_kind, f);
+
+            for (key, value) in pairs {
+                for_each_value_reference(key, f);
+                for_each_value_reference(value, f);
+            }
+        }
+        Value::Object(_, properties) => {
+            for (_, value) in properties {
+                for_each_value_reference(value, f);
+            }
+        }
+        _ => (),
+    }
+}
+
+/// Call `f` on the resource reference in a typed collection's element type.
+fn for_each_element_reference<'a>(
+    element: &mut ElementType<'a>,
+    f: &mut impl FnMut(RefKind, &mut ResourceId<'a>),
+) {
+    match element {
+        ElementType::ExtResource(id) => f(RefKind::External, id),
+        ElementType::SubResource(id) => f(RefKind::Internal, id),
+        ElementType::Named(_) => (),
+    }
+}
+
+impl<'a> Scene<'a> {
+    /// Parse a scene from source code.
+    pub fn parse(source: &'a str) -> Result<Self, Error<'a>> {
+        let lines = crate::parser(source)
+            .spanned()
+            .map(|(result, span)| result.map(|line| (line, span)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Self::from_lines(lines)
+    }
+
+    /// Build a scene from the result of parsing a `.tscn` file, with the span
+    /// of every line (see [crate::parse_spanned]).
+    pub fn from_lines(
+        lines: impl IntoIterator<Item = (Line<'a>, Span)>,
+    ) -> Result<Self, Error<'a>> {
+        let mut scene = Scene::default();
+        let mut has_header = false;
+        let mut current = Current::None;
+        let mut node_spans = vec![];
+
+        for (line, span) in lines {
+            match line {
+                Line::Comment(_) => (),
+                Line::Section("gd_scene", parameters) => {
+                    if has_header {
+                        return Err(Error::UnexpectedSection("gd_scene", span));
+                    }
+
+                    let mut parameters = Parameters::new("gd_scene", span.clone(), parameters);
+
+                    scene.header = SceneHeader {
+                        load_steps: parameters.integer("load_steps")?,
+                        format: parameters.integer("format")?,
+                        uid: parameters.string("uid")?,
+                        parameters: parameters.finish(),
+                    };
+
+                    has_header = true;
+                    current = Current::Other;
+                }
+                Line::Section(identifier, _) if !has_header => {
+                    return Err(Error::UnexpectedSection(identifier, span))
+                }
+                Line::Section("ext_resource", parameters) => {
+                    let mut parameters = Parameters::new("ext_resource", span.clone(), parameters);
+
+                    scene.ext_resources.push(ExtResource {
+                        kind: parameters.required_string("type")?,
+                        uid: parameters.string("uid")?,
+                        path: parameters.required_string("path")?,
+                        id: parameters.required_id("id")?,
+                        parameters: parameters.finish(),
+                    });
+
+                    current = Current::Other;
+                }
+                Line::Section("sub_resource", parameters) => {
+                    let mut parameters = Parameters::new("sub_resource", span.clone(), parameters);
+
+                    scene.sub_resources.push(SubResource {
+                        kind: parameters.required_string("type")?,
+                        id: parameters.required_id("id")?,
+                        parameters: parameters.finish(),
+                        properties: vec![],
+                    });
+
+                    current = Current::SubResource;
+                }
+                Line::Section("node", parameters) => {
+                    let mut parameters = Parameters::new("node", span.clone(), parameters);
+
+                    let name = parameters.required_string("name")?;
+                    let kind = parameters.string("type")?;
+                    let parent = parameters.string("parent")?;
+                    let instance = parameters.reference("instance")?;
+                    let groups = parameters.strings("groups")?;
+
+                    let instance = match instance {
+                        Some(ResourceRef::External(id)) => Some(id),
+                        Some(ResourceRef::Internal(_)) => {
+                            return Err(parameters.invalid("instance"))
+                        }
+                        None => None,
+                    };
+
+                    node_spans.push(span);
+                    scene.nodes.push(Node {
+                        name,
+                        kind,
+                        parent,
+                        instance,
+                        groups,
+                        parameters: parameters.finish(),
+                        properties: vec![],
+                    });
+
+                    current = Current::Node;
+                }
+                Line::Section("connection", parameters) => {
+                    let mut parameters = Parameters::new("connection", span.clone(), parameters);
+
+                    scene.connections.push(Connection {
+                        signal: parameters.required_string("signal")?,
+                        from: parameters.required_string("from")?,
+                        to: parameters.required_string("to")?,
+                        method: parameters.required_string("method")?,
+                        flags: parameters.integer("flags")?,
+                        parameters: parameters.finish(),
+                    });
+
+                    current = Current::Other;
+                }
+                Line::Section("editable", parameters) => {
+                    let mut parameters = Parameters::new("editable", span.clone(), parameters);
+
+                    scene.editables.push(parameters.required_string("path")?);
+
+                    current = Current::Other;
+                }
+                Line::Section(identifier, _) => {
+                    return Err(Error::UnexpectedSection(identifier, span))
+                }
+                Line::Parameter(key, value) => {
+                    let properties = match current {
+                        Current::SubResource => {
+                            scene.sub_resources.last_mut().map(|r| &mut r.properties)
+                        }
+                        Current::Node => scene.nodes.last_mut().map(|n| &mut n.properties),
+                        Current::None | Current::Other => None,
+                    };
+
+                    match properties {
+                        Some(properties) => properties.push((key, value)),
+                        None => return Err(Error::UnexpectedProperty(key, span)),
+                    }
+                }
+            }
+        }
+
+        if !has_header {
+            return Err(Error::MissingHeader("gd_scene"));
+        }
+
+        scene.validate(&node_spans)?;
+
+        Ok(scene)
+    }
+
+    /// Ensure that all node parents and instanced scenes exist. `spans` are
+    /// the spans of the nodes' sections, which errors of nodes without one
+    /// point to the start of the file instead.
+    fn validate(&self, spans: &[Span]) -> Result<(), Error<'a>> {
+        for (idx, node) in self.nodes.iter().enumerate() {
+            let span = spans.get(idx).cloned().unwrap_or_default();
+
+            if let Some(id) = node.instance {
+                if self.ext_resource(id).is_none() {
+                    return Err(Error::UnknownResource(id, span));
+                }
+            }
+
+            let Some(parent) = node.parent else {
+                if idx != 0 {
+                    return Err(Error::MultipleRoots(node.name, span));
+                }
+
+                continue;
+            };
+
+            let declared = |path: &str| self.nodes[..idx].iter().find(|n| n.path() == path);
+            let mut ancestor = normalize_path(parent);
+
+            if declared(ancestor).is_some() {
+                continue;
+            }
+
+            // nodes can also be children of nodes that come from an instanced scene,
+            // in which case only the instanced node itself is declared in this file
+            loop {
+                match ancestor.rsplit_once('/') {
+                    Some((rest, _)) => ancestor = rest,
+                    None => return Err(Error::UnknownParent(node.name, parent, span)),
+                }
+
+                if declared(ancestor).is_some_and(|n| n.instance.is_some()) {
+                    break;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get the root node of the scene.
+    pub fn root(&self) -> Option<&Node<'a>> {
+        self.nodes.first().filter(|n| n.is_root())
+    }
+
+    /// Find a node by it's path relative to the root node (e.g. `.`, `Player`
+    /// or `Player/Sprite2D`).
+    pub fn get_node(&self, path: &str) -> Option<&Node<'a>> {
+        let path = normalize_path(path);
+
+        self.nodes.iter().find(|n| n.path() == path)
+    }
+
+    /// Mutable version of [Scene::get_node].
+    pub fn get_node_mut(&mut self, path: &str) -> Option<&mut Node<'a>> {
+        let path = normalize_path(path);
+
+        self.nodes.iter_mut().find(|n| n.path() == path)
+    }
+
+    /// Iterate over direct children of the node at the given path.
+    pub fn children<'s>(&'s self, path: &'s str) -> impl Iterator<Item = &'s Node<'a>> + 's {
+        let path = normalize_path(path);
+
+        self.nodes
+            .iter()
+            .filter(move |n| n.parent.is_some_and(|p| normalize_path(p) == path))
+    }
+
+    /// Iterate over all descendants of the node at the given path.
+    pub fn descendants<'s>(&'s self, path: &'s str) -> impl Iterator<Item = &'s Node<'a>> + 's {
+        let path = normalize_path(path);
+
+        self.nodes.iter().filter(move |n| {
+            let Some(parent) = n.parent.map(normalize_path) else {
+                return false;
+            };
+
+            path == "."
+                || parent == path
+                || parent
+                    .strip_prefix(path)
+                    .is_some_and(|rest| rest.starts_with('/'))
+        })
+    }
+
+    /// Find an external resource by it's identifier.
+    pub fn ext_resource(&self, id: ResourceId<'_>) -> Option<&ExtResource<'a>> {
+        self.ext_resources.iter().find(|r| r.id == id)
+    }
+
+    /// Find an internal resource by it's identifier.
+    pub fn sub_resource(&self, id: ResourceId<'_>) -> Option<&SubResource<'a>> {
+        self.sub_resources.iter().find(|r| r.id == id)
+    }
+
+    /// Resolve a resource reference (e.g. a property value like `ExtResource("1_abc")`)
+    /// into the resource it refers to.
+    pub fn resolve(&self, value: &Value<'_>) -> Option<Resource<'_, 'a>> {
+        match ResourceRef::from_value(value)? {
+            ResourceRef::External(id) => self.ext_resource(id).map(Resource::External),
+            ResourceRef::Internal(id) => self.sub_resource(id).map(Resource::Internal),
+        }
+    }
+}
+
+/// Which section the subsequent properties belong to.
+enum Current {
+    None,
+    SubResource,
+    Node,
+    Other,
+}
+
+/// A helper for extracting typed section parameters.
+struct Parameters<'a> {
+    section: &'a str,
+    /// The section's span, for errors.
+    span: Span,
+    inner: Properties<'a>,
+}
+
+impl<'a> Parameters<'a> {
+    fn new(section: &'a str, span: Span, inner: Properties<'a>) -> Self {
+        Self {
+            section,
+            span,
+            inner,
+        }
+    }
+
+    fn invalid(&self, key: &'a str) -> Error<'a> {
+        Error::InvalidParameter(self.section, key, self.span.clone())
+    }
+
+    fn missing(&self, key: &'a str) -> Error<'a> {
+        Error::MissingParameter(self.section, key, self.span.clone())
+    }
+
+    fn take(&mut self, key: &str) -> Option<Value<'a>> {
+        let idx = self.inner.iter().position(|(k, _)| *k == key)?;
+
+        Some(self.inner.remove(idx).1)
+    }
+
+    fn string(&mut self, key: &'a str) -> Result<Option<&'a str>, Error<'a>> {
+        match self.take(key) {
+            Some(Value::String(string)) => Ok(Some(string)),
+            Some(_) => Err(self.invalid(key)),
+            None => Ok(None),
+        }
+    }
+
+    fn required_string(&mut self, key: &'a str) -> Result<&'a str, Error<'a>> {
+        self.string(key)?.ok_or_else(|| self.missing(key))
+    }
+
+    fn strings(&mut self, key: &'a str) -> Result<Vec<&'a str>, Error<'a>> {
+        match self.take(key) {
+            Some(Value::Array(values)) => values
+                .into_iter()
+                .map(|value| match value {
+                    Value::String(string) | Value::StringName(string) => Ok(string),
+                    _ => Err(self.invalid(key)),
+                })
+                .collect(),
+            Some(_) => Err(self.invalid(key)),
+            None => Ok(vec![]),
+        }
+    }
+
+    fn integer(&mut self, key: &'a str) -> Result<Option<i64>, Error<'a>> {
+        match self.take(key) {
+            Some(Value::Integer(int)) => Ok(Some(int)),
+            Some(_) => Err(self.invalid(key)),
+            None => Ok(None),
+        }
+    }
+
+    fn required_id(&mut self, key: &'a str) -> Result<ResourceId<'a>, Error<'a>> {
+        match self.take(key) {
+            Some(value) => ResourceId::from_value(&value).ok_or_else(|| self.invalid(key)),
+            None => Err(self.missing(key)),
+        }
+    }
+
+    fn reference(&mut self, key: &'a str) -> Result<Option<ResourceRef<'a>>, Error<'a>> {
+        match self.take(key) {
+            Some(value) => ResourceRef::from_value(&value)
+                .map(Some)
+                .ok_or_else(|| self.invalid(key)),
+            None => Ok(None),
+        }
+    }
+
+    fn finish(self) -> Properties<'a> {
+        self.inner
+    }
+}
+
+impl Display for Scene<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut header = vec![];
+
+        if let Some(load_steps) = self.header.load_steps {
+            header.push(("load_steps", Value::Integer(load_steps)));
+        }
+
+        if let Some(format) = self.header.format {
+            header.push(("format", Value::Integer(format)));
+        }
+
+        if let Some(uid) = self.header.uid {
+            header.push(("uid", Value::String(uid)));
+        }
+
+        header.extend(self.header.parameters.iter().cloned());
+
+        writeln!(f, "{}", Line::Section("gd_scene", header))?;
+
+        if !self.ext_resources.is_empty() {
+            writeln!(f)?;
+        }
+
+        for resource in &self.ext_resources {
+            let mut parameters = vec![("type", Value::String(resource.kind))];
+
+            if let Some(uid) = resource.uid {
+                parameters.push(("uid", Value::String(uid)));
+            }
+
+            parameters.push(("path", Value::String(resource.path)));
+            parameters.push(("id", resource.id.to_value()));
+            parameters.extend(resource.parameters.iter().cloned());
+
+            writeln!(f, "{}", Line::Section("ext_resource", parameters))?;
+        }
+
+        for resource in &self.sub_resources {
+            let mut parameters = vec![
+                ("type", Value::String(resource.kind)),
+                ("id", resource.id.to_value()),
+            ];
+
+            parameters.extend(resource.parameters.iter().cloned());
+
+            writeln!(f, "\n{}", Line::Section("sub_resource", parameters))?;
+            write_properties(f, &resource.properties)?;
+        }
+
+        for node in &self.nodes {
+            let mut parameters = vec![("name", Value::String(node.name))];
+
+            if let Some(kind) = node.kind {
+                parameters.push(("type", Value::String(kind)));
+            }
+
+            if let Some(parent) = node.parent {
+                parameters.push(("parent", Value::String(parent)));
+            }
+
+            parameters.extend(node.parameters.iter().cloned());
+
+            if !node.groups.is_empty() {
+                let groups = node.groups.iter().copied().map(Value::String).collect();
+
+                parameters.push(("groups", Value::Array(groups)));
+            }
+
+            if let Some(id) = node.instance {
+                parameters.push(("instance", ResourceRef::External(id).to_value()));
+            }
+
+            writeln!(f, "\n{}", Line::Section("node", parameters))?;
+            write_properties(f, &node.properties)?;
+        }
+
+        if !self.connections.is_empty() {
+            writeln!(f)?;
+        }
+
+        for connection in &self.connections {
+            let mut parameters = vec![
+                ("signal", Value::String(connection.signal)),
+                ("from", Value::String(connection.from)),
+                ("to", Value::String(connection.to)),
+                ("method", Value::String(connection.method)),
+            ];
+
+            if let Some(flags) = connection.flags {
+                parameters.push(("flags", Value::Integer(flags)));
+            }
+
+            parameters.extend(connection.parameters.iter().cloned());
+
+            writeln!(f, "{}", Line::Section("connection", parameters))?;
+        }
+
+        if !self.editables.is_empty() {
+            writeln!(f)?;
+        }
+
+        for path in &self.editables {
+            let parameters = vec![("path", Value::String(path))];
+
+            writeln!(f, "{}", Line::Section("editable", parameters))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Write properties in the `key = value` form used by resource files.
+fn write_properties(f: &mut Formatter<'_>, properties: &Properties<'_>) -> std::fmt::Result {
+    for (key, value) in properties {
+        writeln!(f, "{} = {}", key, value)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::Value;
+    use crate::scene::*;
+
+    type Test = Result<(), crate::error::Error<'static>>;
+
+    const SCENE: &str = r#"[gd_scene load_steps=4 format=3 uid="uid://c8q2s1kdv6n0f"]
+
+[ext_resource type="Script" path="res://player.gd" id="1_abcde"]
+[ext_resource type="PackedScene" uid="uid://b1x7k3mw4hq2t" path="res://gun.tscn" id="2_fghij"]
+
+[sub_resource type="RectangleShape2D" id="RectangleShape2D_klmno"]
+size = Vector2(16, 32)
+
+[node name="Player" type="CharacterBody2D" groups=["players"]]
+script = ExtResource("1_abcde")
+
+[node name="Shape" type="CollisionShape2D" parent="."]
+shape = SubResource("RectangleShape2D_klmno")
+
+[node name="Gun" parent="." instance=ExtResource("2_fghij")]
+
+[node name="Muzzle" parent="Gun/Barrel"]
+position = Vector2(4, 0)
+
+[connection signal="fired" from="Gun" to="." method="_on_gun_fired"]
+
+[editable path="Gun"]
+"#;
+
+    #[test]
+    fn test_scene_structure() -> Test {
+        let scene = Scene::parse(SCENE)?;
+
+        assert_eq!(scene.header.load_steps, Some(4));
+        assert_eq!(scene.header.format, Some(3));
+        assert_eq!(scene.header.uid, Some("uid://c8q2s1kdv6n0f"));
+        assert_eq!(scene.ext_resources.len(), 2);
+        assert_eq!(scene.sub_resources.len(), 1);
+        assert_eq!(scene.nodes.len(), 4);
+        assert_eq!(scene.connections[0].method, "_on_gun_fired");
+        assert_eq!(scene.editables, vec!["Gun"]);
+
+        let root = scene.root().unwrap();
+
+        assert_eq!(root.name, "Player");
+        assert_eq!(root.groups, vec!["players"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_scene_queries() -> Test {
+        let scene = Scene::parse(SCENE)?;
+
+        assert_eq!(scene.get_node("Gun/Barrel/Muzzle").unwrap().name, "Muzzle");
+        assert_eq!(
+            scene.children(".").map(|n| n.name).collect::<Vec<_>>(),
+            vec!["Shape", "Gun"]
+        );
+        assert_eq!(scene.descendants("Gun").count(), 1);
+
+        let gun = scene.get_node("Gun").unwrap();
+        let instance = scene.ext_resource(gun.instance.unwrap()).unwrap();
+
+        assert_eq!(instance.path, "res://gun.tscn");
+
+        let shape = scene.get_node("Shape").unwrap();
+        let Some(Resource::Internal(resource)) =
+            scene.resolve(get_property(&shape.properties, "shape").unwrap())
+        else {
+            panic!("expected a sub-resource");
+        };
+
+        assert_eq!(resource.kind, "RectangleShape2D");
+        assert_eq!(
+            get_property(&resource.properties, "size"),
+            Some(&Value::ObjectInstance(
+                "Vector2",
+                vec![Value::Integer(16), Value::Integer(32)]
+            ))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_scene_roundtrip() -> Test {
+        let scene = Scene::parse(SCENE)?;
+
+        assert_eq!(scene.to_string(), SCENE);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_scene_errors() {
+        assert_eq!(
+            Scene::parse("[node name=\"A\"]"),
+            Err(crate::error::Error::UnexpectedSection("node", 0..15))
+        );
+        assert_eq!(
+            Scene::parse("[gd_scene format=3]\n[node name=\"A\"]\n[node name=\"B\" parent=\"C\"]"),
+            Err(crate::error::Error::UnknownParent("B", "C", 36..62))
+        );
+        assert_eq!(
+            Scene::parse("[gd_scene format=3]\n[ext_resource type=\"Script\" id=\"1\"]"),
+            Err(crate::error::Error::MissingParameter(
+                "ext_resource",
+                "path",
+                20..55
+            ))
+        );
+        assert_eq!(
+            Scene::parse("[gd_scene format=3]\n\n[ext_resource type=\"Script\" path=\"a.gd\" id=\"1\"]\nkey = 1\n")
+                .unwrap_err()
+                .span(),
+            Some(&(69..76))
+        );
+    }
+}
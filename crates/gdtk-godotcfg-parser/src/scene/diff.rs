@@ -0,0 +1,771 @@
+//! Structural diffing of scenes.
+//!
+//! Unlike a textual diff, [diff] ignores the order in which nodes,
+//! resources and properties are declared, and resource ids: external
+//! resources are compared by their paths and internal resources by their
+//! contents. Nodes are matched by their paths, and a node that is not found
+//! at the same path is looked for elsewhere to detect moves and renames.
+
+use std::fmt::{Display, Formatter};
+
+use crate::{
+    ast::{ResourceId, Value},
+    scene::{
+        for_each_reference, for_each_value_reference, get_property, normalize_path, ExtResource,
+        Properties, RefKind, Scene,
+    },
+};
+
+/// Something that was added, removed or changed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Item<'a> {
+    /// The `[gd_scene]` header.
+    Header,
+    /// An external resource, identified by it's path.
+    ExtResource(&'a str),
+    /// An internal resource, identified by it's id in the newer scene (or
+    /// in the older one, if it was removed).
+    SubResource(ResourceId<'a>),
+    /// A node, identified by it's path.
+    Node(String),
+    /// A connection.
+    Connection {
+        signal: &'a str,
+        from: &'a str,
+        to: &'a str,
+        method: &'a str,
+    },
+}
+
+/// A single difference between two scenes.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change<'a> {
+    /// An item was added.
+    Added(Item<'a>),
+    /// An item was removed.
+    Removed(Item<'a>),
+    /// A node was moved to another parent.
+    Moved { from: String, to: String },
+    /// A node was renamed.
+    Renamed { from: String, to: String },
+    /// A property or a parameter of an item was added, removed or changed.
+    Modified {
+        item: Item<'a>,
+        key: &'a str,
+        old: Option<Value<'a>>,
+        new: Option<Value<'a>>,
+    },
+}
+
+impl Display for Item<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Item::Header => write!(f, "gd_scene"),
+            Item::ExtResource(path) => write!(f, "ext_resource `{}`", path),
+            Item::SubResource(id) => write!(f, "sub_resource `{}`", id),
+            Item::Node(path) => write!(f, "node `{}`", path),
+            Item::Connection {
+                signal,
+                from,
+                to,
+                method,
+            } => write!(
+                f,
+                "connection `{}` from `{}` to `{}::{}`",
+                signal, from, to, method
+            ),
+        }
+    }
+}
+
+impl Display for Change<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Change::Added(item) => write!(f, "+ {}", item),
+            Change::Removed(item) => write!(f, "- {}", item),
+            Change::Moved { from, to } => write!(f, "> node `{}` moved to `{}`", from, to),
+            Change::Renamed { from, to } => write!(f, "> node `{}` renamed to `{}`", from, to),
+            Change::Modified {
+                item,
+                key,
+                old,
+                new,
+            } => {
+                write!(f, "~ {}: {}: ", item, key)?;
+
+                match old {
+                    Some(old) => write!(f, "{}", old)?,
+                    None => write!(f, "(none)")?,
+                }
+
+                match new {
+                    Some(new) => write!(f, " -> {}", new),
+                    None => write!(f, " -> (none)"),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Item<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+
+        let mut map = serializer.serialize_map(None)?;
+
+        match self {
+            Item::Header => map.serialize_entry("type", "header")?,
+            Item::ExtResource(path) => {
+                map.serialize_entry("type", "ext_resource")?;
+                map.serialize_entry("path", path)?;
+            }
+            Item::SubResource(id) => {
+                map.serialize_entry("type", "sub_resource")?;
+                map.serialize_entry("id", &id.to_string())?;
+            }
+            Item::Node(path) => {
+                map.serialize_entry("type", "node")?;
+                map.serialize_entry("path", path)?;
+            }
+            Item::Connection {
+                signal,
+                from,
+                to,
+                method,
+            } => {
+                map.serialize_entry("type", "connection")?;
+                map.serialize_entry("signal", signal)?;
+                map.serialize_entry("from", from)?;
+                map.serialize_entry("to", to)?;
+                map.serialize_entry("method", method)?;
+            }
+        }
+
+        map.end()
+    }
+}
+
+/// Values are serialized as GodotCfg source code.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Change<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+
+        let mut map = serializer.serialize_map(None)?;
+
+        match self {
+            Change::Added(item) => {
+                map.serialize_entry("change", "added")?;
+                map.serialize_entry("item", item)?;
+            }
+            Change::Removed(item) => {
+                map.serialize_entry("change", "removed")?;
+                map.serialize_entry("item", item)?;
+            }
+            Change::Moved { from, to } | Change::Renamed { from, to } => {
+                let change = match self {
+                    Change::Moved { .. } => "moved",
+                    _ => "renamed",
+                };
+
+                map.serialize_entry("change", change)?;
+                map.serialize_entry("item", &Item::Node(to.clone()))?;
+                map.serialize_entry("from", from)?;
+                map.serialize_entry("to", to)?;
+            }
+            Change::Modified {
+                item,
+                key,
+                old,
+                new,
+            } => {
+                map.serialize_entry("change", "modified")?;
+                map.serialize_entry("item", item)?;
+                map.serialize_entry("key", key)?;
+                map.serialize_entry("old", &old.as_ref().map(Value::to_string))?;
+                map.serialize_entry("new", &new.as_ref().map(Value::to_string))?;
+            }
+        }
+
+        map.end()
+    }
+}
+
+/// Find the differences between two versions of a scene.
+pub fn diff<'a>(old: &Scene<'a>, new: &Scene<'a>) -> Vec<Change<'a>> {
+    let (old_subs, new_subs) = match_sub_resources(old, new);
+    let old_canonical = canonicalize(old, &old_subs);
+    let new_canonical = canonicalize(new, &new_subs);
+
+    let mut differ = Differ::default();
+
+    differ.header([old, &old_canonical], [new, &new_canonical]);
+    differ.ext_resources(old, new);
+    differ.sub_resources([old, &old_canonical], [new, &new_canonical], &new_subs);
+    let moves = differ.nodes([old, &old_canonical], [new, &new_canonical]);
+    differ.connections([old, &old_canonical], [new, &new_canonical], &moves);
+
+    differ.changes
+}
+
+/// A pair of a value as written in the scene and it's canonical form used
+/// for comparisons.
+type Pair<'a> = (Value<'a>, Value<'a>);
+
+/// A scene and it's canonical form.
+type Version<'s, 'a> = [&'s Scene<'a>; 2];
+
+/// Diffing state.
+#[derive(Default)]
+struct Differ<'a> {
+    changes: Vec<Change<'a>>,
+}
+
+impl<'a> Differ<'a> {
+    fn compare(
+        &mut self,
+        item: &Item<'a>,
+        key: &'a str,
+        old: Option<Pair<'a>>,
+        new: Option<Pair<'a>>,
+    ) {
+        if old.as_ref().map(|p| &p.1) != new.as_ref().map(|p| &p.1) {
+            self.changes.push(Change::Modified {
+                item: item.clone(),
+                key,
+                old: old.map(|p| p.0),
+                new: new.map(|p| p.0),
+            });
+        }
+    }
+
+    fn compare_properties(
+        &mut self,
+        item: &Item<'a>,
+        old: [&Properties<'a>; 2],
+        new: [&Properties<'a>; 2],
+    ) {
+        let pair = |properties: [&Properties<'a>; 2], key| {
+            Some((
+                get_property(properties[0], key)?.clone(),
+                get_property(properties[1], key)?.clone(),
+            ))
+        };
+
+        let keys = old[0].iter().map(|(k, _)| *k).chain(
+            new[0]
+                .iter()
+                .map(|(k, _)| *k)
+                .filter(|k| get_property(old[0], k).is_none()),
+        );
+
+        for key in keys {
+            self.compare(item, key, pair(old, key), pair(new, key));
+        }
+    }
+
+    fn header(&mut self, old: Version<'_, 'a>, new: Version<'_, 'a>) {
+        let int = |value: Option<i64>| value.map(|v| (Value::Integer(v), Value::Integer(v)));
+        let string = |value: Option<&'a str>| value.map(|v| (Value::String(v), Value::String(v)));
+
+        self.compare(
+            &Item::Header,
+            "format",
+            int(old[0].header.format),
+            int(new[0].header.format),
+        );
+        self.compare(
+            &Item::Header,
+            "uid",
+            string(old[0].header.uid),
+            string(new[0].header.uid),
+        );
+        self.compare_properties(
+            &Item::Header,
+            [&old[0].header.parameters, &old[1].header.parameters],
+            [&new[0].header.parameters, &new[1].header.parameters],
+        );
+    }
+
+    fn ext_resources(&mut self, old: &Scene<'a>, new: &Scene<'a>) {
+        let key = |r: &ExtResource<'a>| r.uid.unwrap_or(r.path);
+        let string = |value: &'a str| Some((Value::String(value), Value::String(value)));
+
+        for resource in &new.ext_resources {
+            let item = Item::ExtResource(resource.path);
+
+            let Some(previous) = old.ext_resources.iter().find(|r| key(r) == key(resource)) else {
+                self.changes.push(Change::Added(item));
+                continue;
+            };
+
+            self.compare(&item, "type", string(previous.kind), string(resource.kind));
+            self.compare(&item, "path", string(previous.path), string(resource.path));
+            self.compare_properties(
+                &item,
+                [&previous.parameters, &previous.parameters],
+                [&resource.parameters, &resource.parameters],
+            );
+        }
+
+        for resource in &old.ext_resources {
+            if !new.ext_resources.iter().any(|r| key(r) == key(resource)) {
+                self.changes
+                    .push(Change::Removed(Item::ExtResource(resource.path)));
+            }
+        }
+    }
+
+    fn sub_resources(&mut self, old: Version<'_, 'a>, new: Version<'_, 'a>, new_subs: &[usize]) {
+        let string = |value: &'a str| Some((Value::String(value), Value::String(value)));
+
+        for (idx, resource) in new[0].sub_resources.iter().enumerate() {
+            let item = Item::SubResource(resource.id);
+
+            let Some(previous) = old[0].sub_resources.get(new_subs[idx]) else {
+                self.changes.push(Change::Added(item));
+                continue;
+            };
+
+            let [old_canonical, new_canonical] = [
+                &old[1].sub_resources[new_subs[idx]],
+                &new[1].sub_resources[idx],
+            ];
+
+            self.compare(&item, "type", string(previous.kind), string(resource.kind));
+            self.compare_properties(
+                &item,
+                [&previous.parameters, &old_canonical.parameters],
+                [&resource.parameters, &new_canonical.parameters],
+            );
+            self.compare_properties(
+                &item,
+                [&previous.properties, &old_canonical.properties],
+                [&resource.properties, &new_canonical.properties],
+            );
+        }
+
+        for (idx, resource) in old[0].sub_resources.iter().enumerate() {
+            if !new_subs.contains(&idx) {
+                self.changes
+                    .push(Change::Removed(Item::SubResource(resource.id)));
+            }
+        }
+    }
+
+    /// Diff nodes. Returns the list of moved or renamed nodes.
+    fn nodes(&mut self, old: Version<'_, 'a>, new: Version<'_, 'a>) -> Vec<(String, String)> {
+        let old_paths = old[0].nodes.iter().map(|n| n.path()).collect::<Vec<_>>();
+        let new_paths = new[0].nodes.iter().map(|n| n.path()).collect::<Vec<_>>();
+
+        let mut matches: Vec<Option<usize>> = vec![None; new_paths.len()];
+        let mut moves: Vec<(String, String)> = vec![];
+
+        // nodes are declared after their parents, so moves of ancestors are
+        // always known by the time we get to their descendants
+        for (idx, path) in old_paths.iter().enumerate() {
+            let path = translate(path, &moves);
+
+            if let Some(found) = new_paths.iter().position(|p| *p == path) {
+                if matches[found].is_none() {
+                    matches[found] = Some(idx);
+                    continue;
+                }
+            }
+
+            // a node is considered moved if it has the same name but a different
+            // parent, and renamed if it has the same parent but a different name,
+            // with moves taking priority
+            let node = &old[1].nodes[idx];
+            let parent = node.parent.map(|p| translate(normalize_path(p), &moves));
+            let is_candidate = |i: usize, moved: bool| {
+                let n = &new[1].nodes[i];
+                let same_name = n.name == node.name;
+                let same_parent = n.parent.map(normalize_path) == parent.as_deref();
+
+                matches[i].is_none()
+                    && !n.is_root()
+                    && !old_paths.contains(&new_paths[i])
+                    && (same_name, same_parent) == (moved, !moved)
+                    && n.kind == node.kind
+                    && n.instance == node.instance
+                    && n.properties == node.properties
+            };
+            let candidate = (0..new_paths.len())
+                .find(|&i| is_candidate(i, true))
+                .or_else(|| (0..new_paths.len()).find(|&i| is_candidate(i, false)));
+
+            if let Some(found) = candidate {
+                let (from, to) = (old_paths[idx].clone(), new_paths[found].clone());
+
+                matches[found] = Some(idx);
+                moves.push((from.clone(), to.clone()));
+
+                if new[0].nodes[found].name == node.name {
+                    self.changes.push(Change::Moved { from, to });
+                } else {
+                    self.changes.push(Change::Renamed { from, to });
+                }
+            }
+        }
+
+        let string = |value: Option<&'a str>| value.map(|v| (Value::String(v), Value::String(v)));
+        let groups = |groups: &[&'a str]| {
+            let value = Value::Array(groups.iter().map(|g| Value::String(g)).collect());
+
+            (!groups.is_empty()).then(|| (value.clone(), value))
+        };
+
+        for (idx, node) in new[0].nodes.iter().enumerate() {
+            let item = Item::Node(new_paths[idx].clone());
+
+            let Some(previous_idx) = matches[idx] else {
+                // only report the topmost added node
+                if !node
+                    .parent
+                    .is_some_and(|p| is_unmatched(&new_paths, &matches, normalize_path(p)))
+                {
+                    self.changes.push(Change::Added(item));
+                }
+
+                continue;
+            };
+
+            let previous = &old[0].nodes[previous_idx];
+            let [old_canonical, new_canonical] = [&old[1].nodes[previous_idx], &new[1].nodes[idx]];
+
+            self.compare(&item, "type", string(previous.kind), string(node.kind));
+            self.compare(
+                &item,
+                "instance",
+                previous
+                    .instance
+                    .zip(old_canonical.instance)
+                    .map(|(o, c)| (Value::ExtResource(o), Value::ExtResource(c))),
+                node.instance
+                    .zip(new_canonical.instance)
+                    .map(|(o, c)| (Value::ExtResource(o), Value::ExtResource(c))),
+            );
+            self.compare(
+                &item,
+                "groups",
+                groups(&previous.groups),
+                groups(&node.groups),
+            );
+            self.compare_properties(
+                &item,
+                [&previous.parameters, &old_canonical.parameters],
+                [&node.parameters, &new_canonical.parameters],
+            );
+            self.compare_properties(
+                &item,
+                [&previous.properties, &old_canonical.properties],
+                [&node.properties, &new_canonical.properties],
+            );
+        }
+
+        let old_matches = (0..old_paths.len())
+            .map(|idx| matches.iter().position(|m| *m == Some(idx)))
+            .collect::<Vec<_>>();
+
+        for (idx, node) in old[0].nodes.iter().enumerate() {
+            let removed = old_matches[idx].is_none();
+            let parent_removed = node
+                .parent
+                .is_some_and(|p| is_unmatched(&old_paths, &old_matches, normalize_path(p)));
+
+            if removed && !parent_removed {
+                self.changes
+                    .push(Change::Removed(Item::Node(old_paths[idx].clone())));
+            }
+        }
+
+        moves
+    }
+
+    fn connections(
+        &mut self,
+        old: Version<'_, 'a>,
+        new: Version<'_, 'a>,
+        moves: &[(String, String)],
+    ) {
+        let int = |value: Option<i64>| value.map(|v| (Value::Integer(v), Value::Integer(v)));
+
+        let mut matched = vec![false; old[0].connections.len()];
+
+        for (idx, connection) in new[0].connections.iter().enumerate() {
+            let item = Item::Connection {
+                signal: connection.signal,
+                from: connection.from,
+                to: connection.to,
+                method: connection.method,
+            };
+
+            let found = old[0].connections.iter().position(|c| {
+                c.signal == connection.signal
+                    && c.method == connection.method
+                    && translate(normalize_path(c.from), moves) == normalize_path(connection.from)
+                    && translate(normalize_path(c.to), moves) == normalize_path(connection.to)
+            });
+
+            let Some(previous_idx) = found else {
+                self.changes.push(Change::Added(item));
+                continue;
+            };
+
+            matched[previous_idx] = true;
+
+            let previous = &old[0].connections[previous_idx];
+
+            self.compare(&item, "flags", int(previous.flags), int(connection.flags));
+            self.compare_properties(
+                &item,
+                [
+                    &previous.parameters,
+                    &old[1].connections[previous_idx].parameters,
+                ],
+                [&connection.parameters, &new[1].connections[idx].parameters],
+            );
+        }
+
+        for (idx, connection) in old[0].connections.iter().enumerate() {
+            if !matched[idx] {
+                self.changes.push(Change::Removed(Item::Connection {
+                    signal: connection.signal,
+                    from: connection.from,
+                    to: connection.to,
+                    method: connection.method,
+                }));
+            }
+        }
+    }
+}
+
+/// Whether the node at `path` exists and has no counterpart in the other scene.
+fn is_unmatched<T>(paths: &[String], matches: &[Option<T>], path: &str) -> bool {
+    paths
+        .iter()
+        .position(|p| p == path)
+        .is_some_and(|idx| matches[idx].is_none())
+}
+
+/// Apply node moves to a path.
+fn translate(path: &str, moves: &[(String, String)]) -> String {
+    for (from, to) in moves.iter().rev() {
+        if path == from {
+            return to.clone();
+        }
+
+        if let Some(rest) = path.strip_prefix(from.as_str()) {
+            if rest.starts_with('/') {
+                return format!("{}{}", to, rest);
+            }
+        }
+    }
+
+    path.to_owned()
+}
+
+/// Assign every internal resource of both scenes a canonical index, so that
+/// the same resource gets the same index in both scenes regardless of it's id.
+fn match_sub_resources(old: &Scene<'_>, new: &Scene<'_>) -> (Vec<usize>, Vec<usize>) {
+    let old_subs = (0..old.sub_resources.len()).collect::<Vec<_>>();
+    let old_canonical = canonicalize(old, &old_subs);
+
+    let mut new_subs = vec![];
+    let mut taken = vec![false; old_subs.len()];
+    let mut next = old_subs.len();
+
+    for resource in &new.sub_resources {
+        // internal resources can only refer to resources declared before
+        // them, so the ones we've matched so far are enough
+        let mut canonical = resource.clone();
+
+        for (_, value) in canonical
+            .parameters
+            .iter_mut()
+            .chain(&mut canonical.properties)
+        {
+            for_each_value_reference(value, &mut |kind, id| {
+                canonicalize_id(kind, id, new, &new_subs)
+            });
+        }
+
+        let same_contents = |i: usize| {
+            let other = &old_canonical.sub_resources[i];
+
+            other.kind == canonical.kind
+                && other.parameters == canonical.parameters
+                && other.properties == canonical.properties
+        };
+        let same_id = |i: usize| {
+            let other = &old.sub_resources[i];
+
+            other.id == resource.id && other.kind == resource.kind
+        };
+
+        let found = (0..taken.len())
+            .find(|&i| !taken[i] && same_contents(i))
+            .or_else(|| (0..taken.len()).find(|&i| !taken[i] && same_id(i)));
+
+        match found {
+            Some(i) => {
+                taken[i] = true;
+                new_subs.push(i);
+            }
+            None => {
+                new_subs.push(next);
+                next += 1;
+            }
+        }
+    }
+
+    (old_subs, new_subs)
+}
+
+/// Replace resource ids in a scene with their canonical forms: paths for
+/// external resources and indices from `subs` for internal resources.
+fn canonicalize<'a>(scene: &Scene<'a>, subs: &[usize]) -> Scene<'a> {
+    let mut canonical = scene.clone();
+
+    for resource in &mut canonical.ext_resources {
+        resource.id = ResourceId::String(resource.path);
+    }
+
+    for (idx, resource) in canonical.sub_resources.iter_mut().enumerate() {
+        resource.id = ResourceId::Integer(subs[idx] as i64);
+    }
+
+    for_each_reference(&mut canonical, &mut |kind, id| {
+        canonicalize_id(kind, id, scene, subs)
+    });
+
+    canonical
+}
+
+fn canonicalize_id<'a>(kind: RefKind, id: &mut ResourceId<'a>, scene: &Scene<'a>, subs: &[usize]) {
+    match kind {
+        RefKind::External => {
+            if let Some(resource) = scene.ext_resource(*id) {
+                *id = ResourceId::String(resource.path);
+            }
+        }
+        RefKind::Internal => {
+            let idx = scene.sub_resources.iter().position(|r| r.id == *id);
+
+            if let Some(canonical) = idx.and_then(|idx| subs.get(idx)) {
+                *id = ResourceId::Integer(*canonical as i64);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scene::diff::*;
+
+    const OLD: &str = r#"[gd_scene load_steps=3 format=3]
+
+[ext_resource type="Script" path="res://player.gd" id="1_a"]
+[ext_resource type="Texture2D" path="res://icon.png" id="2_b"]
+
+[sub_resource type="CircleShape2D" id="CircleShape2D_x"]
+radius = 4.0
+
+[node name="Player" type="Node2D"]
+script = ExtResource("1_a")
+
+[node name="Body" type="Node2D" parent="."]
+
+[node name="Sprite" type="Sprite2D" parent="Body"]
+texture = ExtResource("2_b")
+
+[node name="Shape" type="CollisionShape2D" parent="Body"]
+shape = SubResource("CircleShape2D_x")
+
+[node name="Old" type="Node" parent="."]
+
+[connection signal="ready" from="Body/Sprite" to="." method="_on_ready"]
+"#;
+
+    #[test]
+    fn test_diff_ignores_reordering_and_ids() {
+        let new = r#"[gd_scene load_steps=3 format=3]
+
+[ext_resource type="Texture2D" path="res://icon.png" id="1_q"]
+[ext_resource type="Script" path="res://player.gd" id="2_w"]
+
+[sub_resource type="CircleShape2D" id="CircleShape2D_y"]
+radius = 4.0
+
+[node name="Player" type="Node2D"]
+script = ExtResource("2_w")
+
+[node name="Old" type="Node" parent="."]
+
+[node name="Body" type="Node2D" parent="."]
+
+[node name="Shape" type="CollisionShape2D" parent="Body"]
+shape = SubResource("CircleShape2D_y")
+
+[node name="Sprite" type="Sprite2D" parent="Body"]
+texture = ExtResource("1_q")
+
+[connection signal="ready" from="Body/Sprite" to="." method="_on_ready"]
+"#;
+
+        let old = Scene::parse(OLD).unwrap();
+        let new = Scene::parse(new).unwrap();
+
+        assert_eq!(diff(&old, &new), vec![]);
+    }
+
+    #[test]
+    fn test_diff() {
+        let new = r#"[gd_scene load_steps=3 format=3]
+
+[ext_resource type="Script" path="res://player.gd" id="1_a"]
+[ext_resource type="Texture2D" path="res://icon.png" id="2_b"]
+
+[sub_resource type="CircleShape2D" id="CircleShape2D_x"]
+radius = 8.0
+
+[node name="Player" type="Node2D"]
+script = ExtResource("1_a")
+
+[node name="Visuals" type="Node2D" parent="."]
+
+[node name="Body" type="Node2D" parent="Visuals"]
+
+[node name="Sprite" type="Sprite2D" parent="Visuals/Body"]
+texture = ExtResource("2_b")
+modulate = Color(1, 0, 0, 1)
+
+[node name="Shape" type="CollisionShape2D" parent="Visuals/Body"]
+shape = SubResource("CircleShape2D_x")
+
+[node name="New" type="Node" parent="."]
+
+[connection signal="ready" from="Visuals/Body/Sprite" to="." method="_on_ready"]
+"#;
+
+        let old = Scene::parse(OLD).unwrap();
+        let new = Scene::parse(new).unwrap();
+        let changes = diff(&old, &new)
+            .into_iter()
+            .map(|c| c.to_string())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            changes,
+            vec![
+                "~ sub_resource `CircleShape2D_x`: radius: 4.0 -> 8.0",
+                "> node `Body` moved to `Visuals/Body`",
+                "> node `Old` renamed to `New`",
+                "+ node `Visuals`",
+                "~ node `Visuals/Body/Sprite`: modulate: (none) -> Color(1, 0, 0, 1)",
+            ]
+        );
+    }
+}
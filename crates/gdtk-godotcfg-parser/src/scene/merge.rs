@@ -0,0 +1,693 @@
+//! Three-way merging of scenes.
+//!
+//! Nodes are matched by their paths, external resources by their uids (or
+//! paths, if they have no uid), internal resources by their ids and
+//! properties by their keys. A change made on only one side is always taken,
+//! and a change made on both sides in different ways is a [Conflict].
+//!
+//! Since both sides may add resources with the same id, ids are first made
+//! consistent across all three versions with [Renames::new] before they
+//! are passed to [merge].
+
+use std::fmt::{Display, Formatter};
+
+use crate::{
+    ast::{ResourceId, Value},
+    error::Error,
+    scene::{
+        for_each_reference, Connection, ExtResource, Node, Properties, RefKind, Scene, SubResource,
+    },
+};
+
+/// Merge three versions of a scene given as source code. Returns the merged
+/// scene's source code and the list of conflicts.
+pub fn merge_sources<'s>(
+    base: &'s str,
+    ours: &'s str,
+    theirs: &'s str,
+) -> Result<(String, Vec<Conflict>), Error<'s>> {
+    let base = Scene::parse(base)?;
+    let ours = Scene::parse(ours)?;
+    let theirs = Scene::parse(theirs)?;
+
+    let renames = Renames::new(&base, &ours, &theirs);
+    let merged = merge(&base, &ours, &theirs, &renames);
+
+    Ok((merged.scene.to_string(), merged.conflicts))
+}
+
+/// Merge `ours` and `theirs`, which are both derived from `base`. `renames`
+/// must be created from the same three scenes.
+pub fn merge<'a>(
+    base: &Scene<'a>,
+    ours: &Scene<'a>,
+    theirs: &Scene<'a>,
+    renames: &'a Renames,
+) -> Merge<'a> {
+    let base = remap(base, Side::Base, renames);
+    let ours = remap(ours, Side::Ours, renames);
+    let theirs = remap(theirs, Side::Theirs, renames);
+
+    let mut merger = Merger::default();
+    let mut scene = Scene::default();
+
+    scene.header.format = merger.scalar(
+        "gd_scene",
+        "format",
+        Some(&base.header.format),
+        &ours.header.format,
+        &theirs.header.format,
+    );
+    scene.header.uid = merger.scalar(
+        "gd_scene",
+        "uid",
+        Some(&base.header.uid),
+        &ours.header.uid,
+        &theirs.header.uid,
+    );
+    scene.header.parameters = merger.properties(
+        "gd_scene",
+        [
+            &base.header.parameters,
+            &ours.header.parameters,
+            &theirs.header.parameters,
+        ],
+    );
+
+    scene.ext_resources = merger.list(
+        [
+            &base.ext_resources,
+            &ours.ext_resources,
+            &theirs.ext_resources,
+        ],
+        |r| r.id,
+        |r: &ExtResource<'_>| format!("ext_resource `{}`", r.id),
+        |merger, _, ours, _| merger.keep_ours(format!("ext_resource `{}`", ours.id), ours),
+    );
+
+    scene.sub_resources = merger.list(
+        [
+            &base.sub_resources,
+            &ours.sub_resources,
+            &theirs.sub_resources,
+        ],
+        |r| r.id,
+        |r: &SubResource<'_>| format!("sub_resource `{}`", r.id),
+        |merger, base, ours, theirs| {
+            let item = format!("sub_resource `{}`", ours.id);
+            let empty = vec![];
+
+            SubResource {
+                kind: merger.scalar(
+                    &item,
+                    "type",
+                    base.map(|b| &b.kind),
+                    &ours.kind,
+                    &theirs.kind,
+                ),
+                id: ours.id,
+                parameters: merger.properties(
+                    &item,
+                    [
+                        base.map_or(&empty, |b| &b.parameters),
+                        &ours.parameters,
+                        &theirs.parameters,
+                    ],
+                ),
+                properties: merger.properties(
+                    &item,
+                    [
+                        base.map_or(&empty, |b| &b.properties),
+                        &ours.properties,
+                        &theirs.properties,
+                    ],
+                ),
+            }
+        },
+    );
+
+    scene.nodes = merger.list(
+        [&base.nodes, &ours.nodes, &theirs.nodes],
+        Node::path,
+        |n: &Node<'_>| format!("node `{}`", n.path()),
+        |merger, base, ours, theirs| {
+            let item = format!("node `{}`", ours.path());
+            let empty = vec![];
+
+            Node {
+                name: ours.name,
+                kind: merger.scalar(
+                    &item,
+                    "type",
+                    base.map(|b| &b.kind),
+                    &ours.kind,
+                    &theirs.kind,
+                ),
+                parent: ours.parent,
+                instance: merger.scalar(
+                    &item,
+                    "instance",
+                    base.map(|b| &b.instance),
+                    &ours.instance,
+                    &theirs.instance,
+                ),
+                groups: merger.scalar(
+                    &item,
+                    "groups",
+                    base.map(|b| &b.groups),
+                    &ours.groups,
+                    &theirs.groups,
+                ),
+                parameters: merger.properties(
+                    &item,
+                    [
+                        base.map_or(&empty, |b| &b.parameters),
+                        &ours.parameters,
+                        &theirs.parameters,
+                    ],
+                ),
+                properties: merger.properties(
+                    &item,
+                    [
+                        base.map_or(&empty, |b| &b.properties),
+                        &ours.properties,
+                        &theirs.properties,
+                    ],
+                ),
+            }
+        },
+    );
+
+    scene.connections = merger.list(
+        [&base.connections, &ours.connections, &theirs.connections],
+        |c| (c.signal, c.from, c.to, c.method),
+        describe_connection,
+        |merger, _, ours, _| merger.keep_ours(describe_connection(ours), ours),
+    );
+
+    scene.editables = merger.list(
+        [&base.editables, &ours.editables, &theirs.editables],
+        |path| *path,
+        |path| format!("editable `{}`", path),
+        |_, _, ours, _| ours,
+    );
+
+    // Godot 4.4+ doesn't write `load_steps` anymore, so only recompute it if it's there
+    scene.header.load_steps = ours
+        .header
+        .load_steps
+        .map(|_| (scene.ext_resources.len() + scene.sub_resources.len() + 1) as i64);
+
+    merger.check(&mut scene);
+
+    Merge {
+        scene,
+        conflicts: merger.conflicts,
+    }
+}
+
+/// The result of [merge].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Merge<'a> {
+    /// The merged scene. For every conflict, it contains our side of the
+    /// change (or the modified item, if the other side deleted it).
+    pub scene: Scene<'a>,
+    /// Changes that could not be merged automatically.
+    pub conflicts: Vec<Conflict>,
+}
+
+/// A change that could not be merged automatically.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conflict {
+    /// The conflicting item, e.g. ``node `Player/Sprite2D` ``.
+    pub item: String,
+    /// The conflicting property or parameter of the item, if any.
+    pub key: Option<String>,
+    /// The kind of the conflict.
+    pub kind: ConflictKind,
+}
+
+/// The kind of a [Conflict].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictKind {
+    /// Both sides changed the item in different ways.
+    BothModified,
+    /// One side changed the item, while the other one deleted it.
+    ModifiedDeleted,
+    /// The merged scene is not valid, e.g. one side deleted a node while
+    /// the other one added a child to it.
+    Invalid,
+}
+
+impl Display for Conflict {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match (self.kind, &self.key) {
+            (ConflictKind::BothModified, Some(key)) => {
+                write!(f, "`{}` of {} was changed on both sides.", key, self.item)
+            }
+            (ConflictKind::BothModified, None) => {
+                write!(f, "{} was changed on both sides.", self.item)
+            }
+            (ConflictKind::ModifiedDeleted, _) => {
+                write!(
+                    f,
+                    "{} was changed on one side, but deleted on the other.",
+                    self.item
+                )
+            }
+            (ConflictKind::Invalid, _) => write!(f, "Merged scene is invalid: {}", self.item),
+        }
+    }
+}
+
+/// One of the three versions of a scene being merged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Side {
+    Base,
+    Ours,
+    Theirs,
+}
+
+/// An owned [ResourceId].
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum OwnedId {
+    Integer(i64),
+    String(String),
+}
+
+impl OwnedId {
+    fn new(id: ResourceId<'_>) -> Self {
+        match id {
+            ResourceId::Integer(int) => Self::Integer(int),
+            ResourceId::String(string) => Self::String(string.to_owned()),
+        }
+    }
+
+    fn as_id(&self) -> ResourceId<'_> {
+        match self {
+            Self::Integer(int) => ResourceId::Integer(*int),
+            Self::String(string) => ResourceId::String(string),
+        }
+    }
+
+    /// Make an id based on this one that is not in `taken`.
+    fn fresh(&self, taken: &[OwnedId]) -> Self {
+        match self {
+            Self::Integer(_) => {
+                let max = taken.iter().filter_map(|id| match id {
+                    Self::Integer(int) => Some(*int),
+                    Self::String(_) => None,
+                });
+
+                Self::Integer(max.max().unwrap_or(0) + 1)
+            }
+            Self::String(string) => (2..)
+                .map(|n| Self::String(format!("{}_{}", string, n)))
+                .find(|id| !taken.contains(id))
+                .unwrap(),
+        }
+    }
+}
+
+/// Resource ids that have to change so that all three versions of a scene
+/// agree on them.
+#[derive(Debug, Default)]
+pub struct Renames {
+    renames: Vec<(Side, RefKind, OwnedId, OwnedId)>,
+}
+
+impl Renames {
+    /// Find resource ids that need to change.
+    pub fn new(base: &Scene<'_>, ours: &Scene<'_>, theirs: &Scene<'_>) -> Self {
+        let mut renames = Self::default();
+        let scenes = [
+            (Side::Base, base),
+            (Side::Ours, ours),
+            (Side::Theirs, theirs),
+        ];
+
+        // External resources: the same resource may have different ids on
+        // different sides, and different resources may have the same id.
+        let mut taken = scenes
+            .iter()
+            .flat_map(|(_, s)| s.ext_resources.iter().map(|r| OwnedId::new(r.id)))
+            .collect::<Vec<_>>();
+        let mut assigned: Vec<(&str, OwnedId)> = vec![];
+
+        for (_, scene) in [
+            (Side::Ours, ours),
+            (Side::Theirs, theirs),
+            (Side::Base, base),
+        ] {
+            for resource in &scene.ext_resources {
+                let key = resource.uid.unwrap_or(resource.path);
+
+                if assigned.iter().any(|(k, _)| *k == key) {
+                    continue;
+                }
+
+                let mut id = OwnedId::new(resource.id);
+
+                if assigned.iter().any(|(_, i)| *i == id) {
+                    id = id.fresh(&taken);
+                    taken.push(id.clone());
+                }
+
+                assigned.push((key, id));
+            }
+        }
+
+        for (side, scene) in scenes {
+            for resource in &scene.ext_resources {
+                let key = resource.uid.unwrap_or(resource.path);
+                let old = OwnedId::new(resource.id);
+                let (_, new) = assigned.iter().find(|(k, _)| *k == key).unwrap();
+
+                if old != *new {
+                    renames
+                        .renames
+                        .push((side, RefKind::External, old, new.clone()));
+                }
+            }
+        }
+
+        // Internal resources: ids only collide if both sides added a
+        // resource with the same id, in which case theirs is renamed.
+        let mut taken = scenes
+            .iter()
+            .flat_map(|(_, s)| s.sub_resources.iter().map(|r| OwnedId::new(r.id)))
+            .collect::<Vec<_>>();
+
+        for resource in &theirs.sub_resources {
+            let collides = base.sub_resource(resource.id).is_none()
+                && ours
+                    .sub_resource(resource.id)
+                    .is_some_and(|r| r != resource);
+
+            if collides {
+                let old = OwnedId::new(resource.id);
+                let new = old.fresh(&taken);
+
+                taken.push(new.clone());
+                renames
+                    .renames
+                    .push((Side::Theirs, RefKind::Internal, old, new));
+            }
+        }
+
+        renames
+    }
+
+    /// Get the new id of a resource.
+    fn get<'r>(&'r self, side: Side, kind: RefKind, id: ResourceId<'r>) -> ResourceId<'r> {
+        self.renames
+            .iter()
+            .find(|(s, k, old, _)| *s == side && *k == kind && old.as_id() == id)
+            .map_or(id, |(_, _, _, new)| new.as_id())
+    }
+}
+
+/// Apply `renames` to a copy of `scene`.
+fn remap<'a>(scene: &Scene<'a>, side: Side, renames: &'a Renames) -> Scene<'a> {
+    let mut scene = scene.clone();
+
+    for resource in &mut scene.ext_resources {
+        resource.id = renames.get(side, RefKind::External, resource.id);
+    }
+
+    for resource in &mut scene.sub_resources {
+        resource.id = renames.get(side, RefKind::Internal, resource.id);
+    }
+
+    for_each_reference(&mut scene, &mut |kind, id| {
+        *id = renames.get(side, kind, *id)
+    });
+
+    scene
+}
+
+fn describe_connection(connection: &Connection<'_>) -> String {
+    format!(
+        "connection `{}` from `{}` to `{}::{}`",
+        connection.signal, connection.from, connection.to, connection.method
+    )
+}
+
+/// Merging state.
+#[derive(Default)]
+struct Merger {
+    conflicts: Vec<Conflict>,
+}
+
+impl Merger {
+    fn conflict(&mut self, item: String, key: Option<String>, kind: ConflictKind) {
+        self.conflicts.push(Conflict { item, key, kind });
+    }
+
+    /// Report an item as changed on both sides and keep our version.
+    fn keep_ours<T: Clone>(&mut self, item: String, ours: &T) -> T {
+        self.conflict(item, None, ConflictKind::BothModified);
+
+        ours.clone()
+    }
+
+    /// Merge a single value.
+    fn scalar<T: Clone + PartialEq>(
+        &mut self,
+        item: &str,
+        key: &str,
+        base: Option<&T>,
+        ours: &T,
+        theirs: &T,
+    ) -> T {
+        if ours == theirs || base == Some(theirs) {
+            ours.clone()
+        } else if base == Some(ours) {
+            theirs.clone()
+        } else {
+            self.conflict(
+                item.to_owned(),
+                Some(key.to_owned()),
+                ConflictKind::BothModified,
+            );
+
+            ours.clone()
+        }
+    }
+
+    /// Merge a list of `key = value` pairs.
+    fn properties<'a>(&mut self, item: &str, lists: [&Properties<'a>; 3]) -> Properties<'a> {
+        self.list(
+            lists.map(Vec::as_slice),
+            |(key, _): &(&'a str, Value<'a>)| *key,
+            |_| item.to_owned(),
+            |merger, _, ours, _| {
+                merger.conflict(
+                    item.to_owned(),
+                    Some(ours.0.to_owned()),
+                    ConflictKind::BothModified,
+                );
+
+                ours.clone()
+            },
+        )
+    }
+
+    /// Merge a list of items identified by `key`. `merge` is called for items
+    /// that were changed on both sides in different ways.
+    fn list<T: Clone + PartialEq, K: PartialEq>(
+        &mut self,
+        [base, ours, theirs]: [&[T]; 3],
+        key: impl Fn(&T) -> K,
+        describe: impl Fn(&T) -> String,
+        mut merge: impl FnMut(&mut Self, Option<&T>, &T, &T) -> T,
+    ) -> Vec<T> {
+        let mut merged = vec![];
+
+        for k in order(ours, theirs, &key) {
+            let b = base.iter().find(|t| key(t) == k);
+            let o = ours.iter().find(|t| key(t) == k);
+            let t = theirs.iter().find(|t| key(t) == k);
+
+            let item = match (o, t) {
+                (Some(o), Some(t)) if o == t || b == Some(t) => o.clone(),
+                (Some(o), Some(t)) if b == Some(o) => t.clone(),
+                (Some(o), Some(t)) => merge(self, b, o, t),
+                (Some(item), None) | (None, Some(item)) => match b {
+                    None => item.clone(),
+                    Some(b) if b == item => continue,
+                    Some(_) => {
+                        self.conflict(describe(item), None, ConflictKind::ModifiedDeleted);
+
+                        item.clone()
+                    }
+                },
+                (None, None) => continue,
+            };
+
+            merged.push(item);
+        }
+
+        merged
+    }
+
+    /// Report problems with the merged scene.
+    fn check(&mut self, scene: &mut Scene<'_>) {
+        // the merged scene wasn't parsed, so there are no spans to point to
+        if let Err(error) = scene.validate(&[]) {
+            self.conflict(error.to_string(), None, ConflictKind::Invalid);
+        }
+
+        let mut references = vec![];
+
+        for_each_reference(scene, &mut |kind, id| references.push((kind, *id)));
+
+        for (kind, id) in references {
+            let exists = match kind {
+                RefKind::External => scene.ext_resource(id).is_some(),
+                RefKind::Internal => scene.sub_resource(id).is_some(),
+            };
+
+            if !exists {
+                self.conflict(
+                    format!("Reference to a deleted resource `{}`.", id),
+                    None,
+                    ConflictKind::Invalid,
+                );
+            }
+        }
+    }
+}
+
+/// The order of keys in the merged list: ours first, with items only
+/// theirs has inserted after the item that precedes them in theirs.
+fn order<T, K: PartialEq>(ours: &[T], theirs: &[T], key: &impl Fn(&T) -> K) -> Vec<K> {
+    let mut order = ours.iter().map(key).collect::<Vec<_>>();
+    let mut cursor = 0;
+
+    for item in theirs {
+        let k = key(item);
+
+        match order.iter().position(|o| *o == k) {
+            Some(idx) => cursor = idx + 1,
+            None => {
+                // skip items that only we have, so that our additions come first
+                while cursor < order.len() && !theirs.iter().any(|t| key(t) == order[cursor]) {
+                    cursor += 1;
+                }
+
+                order.insert(cursor, k);
+                cursor += 1;
+            }
+        }
+    }
+
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scene::merge::*;
+
+    const BASE: &str = r#"[gd_scene load_steps=2 format=3]
+
+[ext_resource type="Script" path="res://player.gd" id="1_a"]
+
+[node name="Player" type="Node2D"]
+script = ExtResource("1_a")
+
+[node name="Sprite" type="Sprite2D" parent="."]
+position = Vector2(0, 0)
+"#;
+
+    const OURS: &str = r#"[gd_scene load_steps=3 format=3]
+
+[ext_resource type="Script" path="res://player.gd" id="1_a"]
+[ext_resource type="Texture2D" path="res://icon.png" id="2_b"]
+
+[node name="Player" type="Node2D"]
+script = ExtResource("1_a")
+
+[node name="Sprite" type="Sprite2D" parent="."]
+position = Vector2(1, 0)
+texture = ExtResource("2_b")
+"#;
+
+    const THEIRS: &str = r#"[gd_scene load_steps=3 format=3]
+
+[ext_resource type="Script" path="res://player.gd" id="1_a"]
+[ext_resource type="PackedScene" path="res://gun.tscn" id="2_b"]
+
+[node name="Player" type="Node2D"]
+script = ExtResource("1_a")
+
+[node name="Sprite" type="Sprite2D" parent="."]
+position = Vector2(0, 0)
+
+[node name="Gun" parent="." instance=ExtResource("2_b")]
+"#;
+
+    #[test]
+    fn test_merge_clean() {
+        let (merged, conflicts) = merge_sources(BASE, OURS, THEIRS).unwrap();
+
+        assert_eq!(conflicts, vec![]);
+        assert_eq!(
+            merged,
+            r#"[gd_scene load_steps=4 format=3]
+
+[ext_resource type="Script" path="res://player.gd" id="1_a"]
+[ext_resource type="Texture2D" path="res://icon.png" id="2_b"]
+[ext_resource type="PackedScene" path="res://gun.tscn" id="2_b_2"]
+
+[node name="Player" type="Node2D"]
+script = ExtResource("1_a")
+
+[node name="Sprite" type="Sprite2D" parent="."]
+position = Vector2(1, 0)
+texture = ExtResource("2_b")
+
+[node name="Gun" parent="." instance=ExtResource("2_b_2")]
+"#
+        );
+    }
+
+    #[test]
+    fn test_merge_conflicts() {
+        let theirs = THEIRS.replace("Vector2(0, 0)", "Vector2(2, 0)");
+        let (merged, conflicts) = merge_sources(BASE, OURS, &theirs).unwrap();
+
+        assert!(merged.contains("position = Vector2(1, 0)"));
+        assert_eq!(
+            conflicts,
+            vec![Conflict {
+                item: "node `Sprite`".to_owned(),
+                key: Some("position".to_owned()),
+                kind: ConflictKind::BothModified,
+            }]
+        );
+
+        let ours = OURS.replace("[node name=\"Sprite\" type=\"Sprite2D\" parent=\".\"]\nposition = Vector2(1, 0)\ntexture = ExtResource(\"2_b\")\n", "");
+        let (_, conflicts) = merge_sources(BASE, ours.trim_end(), &theirs).unwrap();
+
+        assert_eq!(conflicts[0].kind, ConflictKind::ModifiedDeleted);
+    }
+
+    #[test]
+    fn test_merge_sub_resource_ids() {
+        let base = "[gd_scene load_steps=1 format=2]\n\n[node name=\"Root\" type=\"Node2D\"]\n";
+        let ours = "[gd_scene load_steps=2 format=2]\n\n[sub_resource type=\"CircleShape2D\" id=1]\n\n[node name=\"Root\" type=\"Node2D\"]\n\n[node name=\"A\" type=\"CollisionShape2D\" parent=\".\"]\nshape = SubResource( 1 )\n";
+        let theirs = "[gd_scene load_steps=2 format=2]\n\n[sub_resource type=\"RectangleShape2D\" id=1]\n\n[node name=\"Root\" type=\"Node2D\"]\n\n[node name=\"B\" type=\"CollisionShape2D\" parent=\".\"]\nshape = SubResource( 1 )\n";
+
+        let (merged, conflicts) = merge_sources(base, ours, theirs).unwrap();
+        let scene = Scene::parse(&merged).unwrap();
+
+        assert_eq!(conflicts, vec![]);
+        assert_eq!(scene.header.load_steps, Some(3));
+        assert_eq!(scene.sub_resources[1].id, ResourceId::Integer(2));
+        assert_eq!(
+            crate::scene::get_property(&scene.get_node("B").unwrap().properties, "shape"),
+            Some(&Value::SubResource(ResourceId::Integer(2)))
+        );
+    }
+}
@@ -0,0 +1,1081 @@
+//! A [serde] serializer for GodotCfg.
+//!
+//! The top-level value must be a struct or a map. Entries whose values are
+//! structs or maps are written as sections, and entries whose values are
+//! sequences of structs or maps are written as repeated sections (like
+//! `[ext_resource]`). Everything else is written as a parameter before the
+//! first section. Fields of a section that are wrapped in [Header] are
+//! written as its header parameters (like `type` in
+//! `[ext_resource type="Script"]`).
+//!
+//! Values are mapped from the serde data model as follows:
+//!
+//! - sequences and tuples are arrays;
+//! - maps and structs (that are not sections) are maps;
+//! - unit variants are strings, newtype and tuple variants are object
+//!   instances (e.g. `Vector2(1, 2)`), and struct variants are objects;
+//! - `None` and units are `null`.
+
+use std::fmt::Write;
+
+use serde::{
+    de::{Deserialize, Deserializer},
+    ser::{self, Impossible, Serialize},
+};
+
+use crate::{ast::Value, serializer::is_bare_key};
+
+/// An error that occured during serialization.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum Error {
+    /// The top-level value is not a struct or a map.
+    #[error("Only structs and maps can be serialized as a file.")]
+    UnsupportedTopLevel,
+
+    /// A map key is not a string, which is required for parameter keys.
+    #[error("Parameter keys must be strings.")]
+    NonStringKey,
+
+    /// An integer does not fit into 64 bits.
+    #[error("Integer {0} is out of range.")]
+    IntegerOutOfRange(u128),
+
+    /// An error reported by a [Serialize] implementation.
+    #[error("{0}")]
+    Custom(String),
+}
+
+impl ser::Error for Error {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        Self::Custom(msg.to_string())
+    }
+}
+
+/// The name [Header] is serialized with, which is how it's told apart from
+/// other values.
+const HEADER: &str = "$godotcfg::Header";
+
+/// A header parameter of a section, like `format` in
+/// `[gd_resource type="Resource" format=3]`. Deserializes like `T`, as the
+/// deserializer doesn't tell header parameters from properties.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Header<T>(pub T);
+
+impl<T: Serialize> Serialize for Header<T> {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_newtype_struct(HEADER, &self.0)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Header<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer).map(Header)
+    }
+}
+
+/// Serialize `value` as a GodotCfg file.
+pub fn to_string<T: Serialize + ?Sized>(value: &T) -> Result<String, Error> {
+    let mut document = DocumentSerializer::default();
+
+    value.serialize(&mut document)?;
+
+    let mut output = String::new();
+
+    for (key, value) in &document.parameters {
+        write_parameter(&mut output, key, value);
+    }
+
+    for (name, section) in &document.sections {
+        if !output.is_empty() {
+            output.push('\n');
+        }
+
+        // writing into a `String` never fails
+        let _ = write!(output, "[{}", name);
+
+        for (key, value) in &section.header {
+            let _ = write!(output, " {}={}", key, value);
+        }
+
+        output.push_str("]\n");
+
+        if !section.properties.is_empty() {
+            output.push('\n');
+        }
+
+        for (key, value) in &section.properties {
+            write_parameter(&mut output, key, value);
+        }
+    }
+
+    Ok(output)
+}
+
+/// Serialize `value` as a single GodotCfg value.
+pub fn to_value_string<T: Serialize + ?Sized>(value: &T) -> Result<String, Error> {
+    value.serialize(ValueSerializer)
+}
+
+fn write_parameter(output: &mut String, key: &str, value: &str) {
+    let _ = if is_bare_key(key) {
+        writeln!(output, "{}={}", key, value)
+    } else {
+        writeln!(output, "\"{}\"={}", escape(key), value)
+    };
+}
+
+/// A list of serialized `key=value` pairs.
+type Table = Vec<(String, String)>;
+
+/// A serialized section.
+#[derive(Clone, Default)]
+struct Section {
+    header: Table,
+    properties: Table,
+}
+
+impl Section {
+    fn field<T: Serialize + ?Sized>(&mut self, key: String, value: &T) -> Result<(), Error> {
+        match value.serialize(HeaderSerializer) {
+            Ok(header) => self.header.push((key, header)),
+            Err(Error::UnsupportedTopLevel) => self
+                .properties
+                .push((key, value.serialize(ValueSerializer)?)),
+            Err(other) => return Err(other),
+        }
+
+        Ok(())
+    }
+}
+
+/// Serializes the top-level struct or map.
+#[derive(Default)]
+struct DocumentSerializer {
+    parameters: Table,
+    sections: Vec<(String, Section)>,
+    key: Option<String>,
+}
+
+impl DocumentSerializer {
+    fn entry<T: Serialize + ?Sized>(&mut self, key: String, value: &T) -> Result<(), Error> {
+        match value.serialize(TablesSerializer) {
+            Ok(sections) => self
+                .sections
+                .extend(sections.into_iter().map(|section| (key.clone(), section))),
+            Err(Error::UnsupportedTopLevel) => self
+                .parameters
+                .push((key, value.serialize(ValueSerializer)?)),
+            Err(other) => return Err(other),
+        }
+
+        Ok(())
+    }
+}
+
+/// Implement serializer methods that always fail with `$err`.
+macro_rules! reject {
+    ($err:expr; $($fun:ident($($ty:ty),*) -> $ret:ty;)*) => {
+        $(fn $fun(self, $(_: $ty),*) -> Result<$ret, Error> {
+            Err($err)
+        })*
+    };
+}
+
+/// Implement serializer methods for values that can not be tables.
+macro_rules! not_a_table {
+    ($ok:ty) => {
+        reject! {
+            Error::UnsupportedTopLevel;
+            serialize_bool(bool) -> $ok;
+            serialize_i8(i8) -> $ok;
+            serialize_i16(i16) -> $ok;
+            serialize_i32(i32) -> $ok;
+            serialize_i64(i64) -> $ok;
+            serialize_u8(u8) -> $ok;
+            serialize_u16(u16) -> $ok;
+            serialize_u32(u32) -> $ok;
+            serialize_u64(u64) -> $ok;
+            serialize_f32(f32) -> $ok;
+            serialize_f64(f64) -> $ok;
+            serialize_char(char) -> $ok;
+            serialize_str(&str) -> $ok;
+            serialize_bytes(&[u8]) -> $ok;
+            serialize_none() -> $ok;
+            serialize_unit() -> $ok;
+            serialize_unit_struct(&'static str) -> $ok;
+            serialize_unit_variant(&'static str, u32, &'static str) -> $ok;
+            serialize_tuple(usize) -> Self::SerializeTuple;
+            serialize_tuple_struct(&'static str, usize) -> Self::SerializeTupleStruct;
+            serialize_tuple_variant(&'static str, u32, &'static str, usize) -> Self::SerializeTupleVariant;
+            serialize_struct_variant(&'static str, u32, &'static str, usize) -> Self::SerializeStructVariant;
+        }
+
+        fn serialize_newtype_variant<T: Serialize + ?Sized>(
+            self,
+            _name: &'static str,
+            _variant_index: u32,
+            _variant: &'static str,
+            _value: &T,
+        ) -> Result<$ok, Error> {
+            Err(Error::UnsupportedTopLevel)
+        }
+    };
+}
+
+impl ser::Serializer for &mut DocumentSerializer {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = Impossible<(), Error>;
+    type SerializeTuple = Impossible<(), Error>;
+    type SerializeTupleStruct = Impossible<(), Error>;
+    type SerializeTupleVariant = Impossible<(), Error>;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Impossible<(), Error>;
+
+    not_a_table!(());
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<(), Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+        Err(Error::UnsupportedTopLevel)
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Error> {
+        Ok(self)
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, Error> {
+        Ok(self)
+    }
+}
+
+impl ser::SerializeMap for &mut DocumentSerializer {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Error> {
+        self.key = Some(key.serialize(KeySerializer)?);
+
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        let key = self
+            .key
+            .take()
+            .expect("serialize_value called before serialize_key");
+
+        self.entry(key, value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeStruct for &mut DocumentSerializer {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.entry(key.to_owned(), value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// Serializes a struct or a map into a single section, or a sequence of them
+/// into many. Fails with [Error::UnsupportedTopLevel] on anything else.
+struct TablesSerializer;
+
+/// The state of [TablesSerializer].
+#[derive(Default)]
+struct TablesState {
+    sections: Vec<Section>,
+    key: Option<String>,
+}
+
+impl ser::Serializer for TablesSerializer {
+    type Ok = Vec<Section>;
+    type Error = Error;
+    type SerializeSeq = TablesState;
+    type SerializeTuple = Impossible<Vec<Section>, Error>;
+    type SerializeTupleStruct = Impossible<Vec<Section>, Error>;
+    type SerializeTupleVariant = Impossible<Vec<Section>, Error>;
+    type SerializeMap = TablesState;
+    type SerializeStruct = TablesState;
+    type SerializeStructVariant = Impossible<Vec<Section>, Error>;
+
+    not_a_table!(Vec<Section>);
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Vec<Section>, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Vec<Section>, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+        // an empty sequence is written as `key=[]`
+        if len == Some(0) {
+            return Err(Error::UnsupportedTopLevel);
+        }
+
+        Ok(TablesState::default())
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Error> {
+        Ok(TablesState {
+            sections: vec![Section::default()],
+            key: None,
+        })
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, Error> {
+        self.serialize_map(Some(len))
+    }
+}
+
+impl ser::SerializeSeq for TablesState {
+    type Ok = Vec<Section>;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        match value.serialize(TablesSerializer)?.as_slice() {
+            [section] => self.sections.push(section.clone()),
+            _ => return Err(Error::UnsupportedTopLevel),
+        }
+
+        Ok(())
+    }
+
+    fn end(self) -> Result<Vec<Section>, Error> {
+        if self.sections.is_empty() {
+            Err(Error::UnsupportedTopLevel)
+        } else {
+            Ok(self.sections)
+        }
+    }
+}
+
+impl ser::SerializeMap for TablesState {
+    type Ok = Vec<Section>;
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Error> {
+        self.key = Some(key.serialize(KeySerializer)?);
+
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        let key = self
+            .key
+            .take()
+            .expect("serialize_value called before serialize_key");
+
+        self.sections[0].field(key, value)
+    }
+
+    fn end(self) -> Result<Vec<Section>, Error> {
+        Ok(self.sections)
+    }
+}
+
+impl ser::SerializeStruct for TablesState {
+    type Ok = Vec<Section>;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.sections[0].field(key.to_owned(), value)
+    }
+
+    fn end(self) -> Result<Vec<Section>, Error> {
+        Ok(self.sections)
+    }
+}
+
+/// Serializes a [Header] into the text of its value. Fails with
+/// [Error::UnsupportedTopLevel] on anything else.
+struct HeaderSerializer;
+
+impl ser::Serializer for HeaderSerializer {
+    type Ok = String;
+    type Error = Error;
+    type SerializeSeq = Impossible<String, Error>;
+    type SerializeTuple = Impossible<String, Error>;
+    type SerializeTupleStruct = Impossible<String, Error>;
+    type SerializeTupleVariant = Impossible<String, Error>;
+    type SerializeMap = Impossible<String, Error>;
+    type SerializeStruct = Impossible<String, Error>;
+    type SerializeStructVariant = Impossible<String, Error>;
+
+    not_a_table!(String);
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<String, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<String, Error> {
+        match name {
+            HEADER => value.serialize(ValueSerializer),
+            _ => value.serialize(self),
+        }
+    }
+
+    reject! {
+        Error::UnsupportedTopLevel;
+        serialize_seq(Option<usize>) -> Self::SerializeSeq;
+        serialize_map(Option<usize>) -> Self::SerializeMap;
+        serialize_struct(&'static str, usize) -> Self::SerializeStruct;
+    }
+}
+
+/// Serializes a parameter key.
+struct KeySerializer;
+
+impl ser::Serializer for KeySerializer {
+    type Ok = String;
+    type Error = Error;
+    type SerializeSeq = Impossible<String, Error>;
+    type SerializeTuple = Impossible<String, Error>;
+    type SerializeTupleStruct = Impossible<String, Error>;
+    type SerializeTupleVariant = Impossible<String, Error>;
+    type SerializeMap = Impossible<String, Error>;
+    type SerializeStruct = Impossible<String, Error>;
+    type SerializeStructVariant = Impossible<String, Error>;
+
+    fn serialize_str(self, v: &str) -> Result<String, Error> {
+        Ok(v.to_owned())
+    }
+
+    fn serialize_char(self, v: char) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<String, Error> {
+        Ok(variant.to_owned())
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<String, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<String, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<String, Error> {
+        Err(Error::NonStringKey)
+    }
+
+    reject! {
+        Error::NonStringKey;
+        serialize_bool(bool) -> String;
+        serialize_i8(i8) -> String;
+        serialize_i16(i16) -> String;
+        serialize_i32(i32) -> String;
+        serialize_i64(i64) -> String;
+        serialize_u8(u8) -> String;
+        serialize_u16(u16) -> String;
+        serialize_u32(u32) -> String;
+        serialize_u64(u64) -> String;
+        serialize_f32(f32) -> String;
+        serialize_f64(f64) -> String;
+        serialize_bytes(&[u8]) -> String;
+        serialize_none() -> String;
+        serialize_unit() -> String;
+        serialize_unit_struct(&'static str) -> String;
+        serialize_seq(Option<usize>) -> Self::SerializeSeq;
+        serialize_tuple(usize) -> Self::SerializeTuple;
+        serialize_tuple_struct(&'static str, usize) -> Self::SerializeTupleStruct;
+        serialize_tuple_variant(&'static str, u32, &'static str, usize) -> Self::SerializeTupleVariant;
+        serialize_map(Option<usize>) -> Self::SerializeMap;
+        serialize_struct(&'static str, usize) -> Self::SerializeStruct;
+        serialize_struct_variant(&'static str, u32, &'static str, usize) -> Self::SerializeStructVariant;
+    }
+}
+
+/// Serializes a value into GodotCfg text.
+struct ValueSerializer;
+
+/// The state of a [ValueSerializer] while serializing a compound value.
+struct Compound {
+    output: String,
+    /// The string that closes the compound value.
+    close: &'static str,
+    /// Whether an element was already written.
+    started: bool,
+    /// Whether this is a map-like value (``{"key": value}``).
+    map: bool,
+}
+
+impl Compound {
+    fn new(open: String, close: &'static str, map: bool) -> Self {
+        Self {
+            output: open,
+            close,
+            started: false,
+            map,
+        }
+    }
+
+    fn separate(&mut self) {
+        if self.started {
+            self.output.push_str(if self.map { ",\n" } else { ", " });
+        } else if self.map {
+            self.output.push('\n');
+        }
+
+        self.started = true;
+    }
+
+    fn element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.separate();
+        self.output.push_str(&value.serialize(ValueSerializer)?);
+
+        Ok(())
+    }
+
+    fn key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Error> {
+        self.element(key)?;
+        self.output.push_str(": ");
+
+        Ok(())
+    }
+
+    fn field<T: Serialize + ?Sized>(&mut self, key: &str, value: &T) -> Result<(), Error> {
+        self.key(key)?;
+        self.output.push_str(&value.serialize(ValueSerializer)?);
+
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<String, Error> {
+        if self.map && self.started {
+            self.output.push('\n');
+        }
+
+        self.output.push_str(self.close);
+
+        Ok(self.output)
+    }
+}
+
+impl ser::Serializer for ValueSerializer {
+    type Ok = String;
+    type Error = Error;
+    type SerializeSeq = Compound;
+    type SerializeTuple = Compound;
+    type SerializeTupleStruct = Compound;
+    type SerializeTupleVariant = Compound;
+    type SerializeMap = Compound;
+    type SerializeStruct = Compound;
+    type SerializeStructVariant = ObjectSerializer;
+
+    fn serialize_bool(self, v: bool) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<String, Error> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<String, Error> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<String, Error> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<String, Error> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<String, Error> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<String, Error> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<String, Error> {
+        match i64::try_from(v) {
+            Ok(v) => self.serialize_i64(v),
+            Err(_) => Err(Error::IntegerOutOfRange(v.into())),
+        }
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<String, Error> {
+        self.serialize_f64(v.into())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<String, Error> {
+        Ok(Value::Float(v).to_string())
+    }
+
+    fn serialize_char(self, v: char) -> Result<String, Error> {
+        self.serialize_str(v.encode_utf8(&mut [0; 4]))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<String, Error> {
+        Ok(format!("\"{}\"", escape(v)))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<String, Error> {
+        let bytes = v.iter().map(u8::to_string).collect::<Vec<_>>();
+
+        Ok(format!("PackedByteArray({})", bytes.join(", ")))
+    }
+
+    fn serialize_none(self) -> Result<String, Error> {
+        Ok("null".to_owned())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<String, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<String, Error> {
+        self.serialize_none()
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<String, Error> {
+        self.serialize_none()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<String, Error> {
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<String, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<String, Error> {
+        Ok(format!("{}({})", variant, value.serialize(self)?))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+        Ok(Compound::new("[".to_owned(), "]", false))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Error> {
+        Ok(Compound::new(format!("{}(", variant), ")", false))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Error> {
+        Ok(Compound::new("{".to_owned(), "}", true))
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, Error> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Error> {
+        Ok(ObjectSerializer(format!("Object({}", variant)))
+    }
+}
+
+impl ser::SerializeSeq for Compound {
+    type Ok = String;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<String, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTuple for Compound {
+    type Ok = String;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<String, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleStruct for Compound {
+    type Ok = String;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<String, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleVariant for Compound {
+    type Ok = String;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<String, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeMap for Compound {
+    type Ok = String;
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Error> {
+        self.key(key)
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.output.push_str(&value.serialize(ValueSerializer)?);
+
+        Ok(())
+    }
+
+    fn end(self) -> Result<String, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStruct for Compound {
+    type Ok = String;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.field(key, value)
+    }
+
+    fn end(self) -> Result<String, Error> {
+        self.finish()
+    }
+}
+
+/// Serializes a struct variant as an ``Object(Variant,"key":value)``.
+struct ObjectSerializer(String);
+
+impl ser::SerializeStructVariant for ObjectSerializer {
+    type Ok = String;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        let _ = write!(
+            self.0,
+            ",\"{}\":{}",
+            escape(key),
+            value.serialize(ValueSerializer)?
+        );
+
+        Ok(())
+    }
+
+    fn end(mut self) -> Result<String, Error> {
+        // Godot puts a newline after every object
+        self.0.push_str(")\n");
+
+        Ok(self.0)
+    }
+}
+
+/// Escape a string for use in a string literal.
+fn escape(string: &str) -> String {
+    let mut output = String::with_capacity(string.len());
+
+    for char in string.chars() {
+        match char {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            other => output.push(other),
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use crate::ser::*;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Shape {
+        Circle,
+        Vector2(f32, f32),
+        Custom { sides: u8 },
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Preset {
+        name: String,
+        tags: Vec<String>,
+        shapes: Vec<Shape>,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Dependency {
+        path: String,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct File {
+        config_version: i32,
+        #[serde(rename = "preset.0")]
+        preset: Preset,
+        dependency: Vec<Dependency>,
+    }
+
+    fn file() -> File {
+        File {
+            config_version: 5,
+            preset: Preset {
+                name: "Say \"hi\"".to_owned(),
+                tags: vec![],
+                shapes: vec![
+                    Shape::Circle,
+                    Shape::Vector2(1.0, 0.5),
+                    Shape::Custom { sides: 3 },
+                ],
+            },
+            dependency: vec![
+                Dependency {
+                    path: "res://a.gd".to_owned(),
+                },
+                Dependency {
+                    path: "res://b.gd".to_owned(),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_to_string() {
+        assert_eq!(
+            to_string(&file()).unwrap(),
+            r#"config_version=5
+
+[preset.0]
+
+name="Say \"hi\""
+tags=[]
+shapes=["Circle", Vector2(1.0, 0.5), Object(Custom,"sides":3)
+]
+
+[dependency]
+
+path="res://a.gd"
+
+[dependency]
+
+path="res://b.gd"
+"#
+        );
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let serialized = to_string(&file()).unwrap();
+
+        assert_eq!(crate::de::from_str::<File>(&serialized), Ok(file()));
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct GdResource {
+        #[serde(rename = "type")]
+        type_: Header<String>,
+        script_class: Header<String>,
+        load_steps: Header<i32>,
+        format: Header<i32>,
+        uid: Header<String>,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct ExtResource {
+        #[serde(rename = "type")]
+        type_: Header<String>,
+        path: Header<String>,
+        id: Header<String>,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Stats {
+        script: String,
+        health: i32,
+        name: String,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Tres {
+        gd_resource: GdResource,
+        ext_resource: Vec<ExtResource>,
+        resource: Stats,
+    }
+
+    #[test]
+    fn test_headers() {
+        // as saved by Godot 4.3
+        let source = r#"[gd_resource type="Resource" script_class="Stats" load_steps=2 format=3 uid="uid://c2k8l1y0y2x3q"]
+
+[ext_resource type="Script" path="res://stats.gd" id="1_x4v2a"]
+
+[resource]
+script = ExtResource("1_x4v2a")
+health = 100
+name = "Hero"
+"#;
+
+        let tres = crate::de::from_str::<Tres>(source).unwrap();
+
+        assert_eq!(tres.gd_resource.format, Header(3));
+        assert_eq!(tres.ext_resource[0].path.0, "res://stats.gd");
+
+        let serialized = to_string(&tres).unwrap();
+
+        // `ExtResource(...)` deserializes into its id
+        assert_eq!(
+            serialized,
+            r#"[gd_resource type="Resource" script_class="Stats" load_steps=2 format=3 uid="uid://c2k8l1y0y2x3q"]
+
+[ext_resource type="Script" path="res://stats.gd" id="1_x4v2a"]
+
+[resource]
+
+script="1_x4v2a"
+health=100
+name="Hero"
+"#
+        );
+        assert_eq!(crate::de::from_str::<Tres>(&serialized), Ok(tres));
+    }
+
+    #[test]
+    fn test_values() {
+        assert_eq!(to_value_string(&None::<i32>).unwrap(), "null");
+        assert_eq!(to_value_string(&[1, 2]).unwrap(), "[1, 2]");
+        assert_eq!(
+            to_value_string(&std::collections::BTreeMap::from([("a", 1), ("b", 2)])).unwrap(),
+            "{\n\"a\": 1,\n\"b\": 2\n}"
+        );
+        assert_eq!(to_string(&1), Err(Error::UnsupportedTopLevel));
+    }
+}
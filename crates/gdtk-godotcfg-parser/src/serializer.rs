@@ -0,0 +1,264 @@
+use std::fmt::{Display, Formatter, Result, Write};
+
+use crate::ast::{ElementType, File, Line, ResourceId, Value};
+
+/// Serialize a [File] back into GodotCfg text.
+///
+/// The output follows the layout Godot uses for `ConfigFile`-style files
+/// (like `project.godot`), i.e. sections are surrounded by blank lines and
+/// parameters are written as `key=value`.
+pub fn serialize(file: &File<'_>) -> String {
+    let mut output = String::new();
+
+    for (idx, line) in file.iter().enumerate() {
+        let after_comments = idx != 0 && file[idx - 1].is_comment() && !line.is_comment();
+
+        if after_comments || (idx != 0 && line.is_section()) {
+            output.push('\n');
+        }
+
+        // writing into a `String` never fails
+        let _ = writeln!(output, "{}", line);
+
+        if line.is_section() {
+            output.push('\n');
+        }
+    }
+
+    output
+}
+
+impl Display for Line<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Line::Comment(comment) => write!(f, ";{}", comment.trim_end_matches(['\r', '\n'])),
+            Line::Section(identifier, parameters) => {
+                write!(f, "[{}", identifier)?;
+
+                for (key, value) in parameters {
+                    write!(f, " {}={}", key, value)?;
+                }
+
+                f.write_char(']')
+            }
+            Line::Parameter(path, value) => {
+                if is_bare_key(path) {
+                    write!(f, "{}={}", path, value)
+                } else {
+                    write!(f, "\"{}\"={}", path, value)
+                }
+            }
+        }
+    }
+}
+
+impl Display for Value<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Value::Null => f.write_str("null"),
+            Value::Boolean(boolean) => write!(f, "{}", boolean),
+            Value::Integer(int) => write!(f, "{}", int),
+            Value::Float(float) => write_float(f, *float),
+            Value::String(string) => write!(f, "\"{}\"", string),
+            Value::StringName(string) => write!(f, "&\"{}\"", string),
+            Value::NodePath(path) => write!(f, "NodePath(\"{}\")", path),
+            Value::Array(values) => write_array(f, values),
+            Value::TypedArray(element, values) => {
+                write!(f, "Array[{}](", element)?;
+                write_array(f, values)?;
+                f.write_char(')')
+            }
+            Value::PackedArray(kind, values) => {
+                write!(f, "{}(", kind.name())?;
+                write_separated(f, values)?;
+                f.write_char(')')
+            }
+            Value::Map(pairs) => write_map(f, pairs),
+            Value::TypedMap(key, value, pairs) => {
+                write!(f, "Dictionary[{}, {}](", key, value)?;
+                write_map(f, pairs)?;
+                f.write_char(')')
+            }
+            Value::ExtResource(id) => write!(f, "ExtResource({})", id.to_value()),
+            Value::SubResource(id) => write!(f, "SubResource({})", id.to_value()),
+            Value::Object(identifier, properties) => {
+                write!(f, "Object({}", identifier)?;
+
+                for (key, value) in properties {
+                    write!(f, ",\"{}\":{}", key, value)?;
+                }
+
+                // Godot puts a newline after every object
+                f.write_str(")\n")
+            }
+            Value::ObjectInstance(identifier, arguments) => {
+                write!(f, "{}(", identifier)?;
+                write_separated(f, arguments)?;
+                f.write_char(')')
+            }
+        }
+    }
+}
+
+impl Display for ResourceId<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            ResourceId::Integer(int) => write!(f, "{}", int),
+            ResourceId::String(string) => f.write_str(string),
+        }
+    }
+}
+
+impl Display for ElementType<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            ElementType::Named(name) => f.write_str(name),
+            ElementType::ExtResource(id) => write!(f, "ExtResource({})", id.to_value()),
+            ElementType::SubResource(id) => write!(f, "SubResource({})", id.to_value()),
+        }
+    }
+}
+
+/// Whether a parameter key can be written without quotes.
+pub(crate) fn is_bare_key(key: &str) -> bool {
+    !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '/' | '.'))
+}
+
+/// Write an array expression.
+fn write_array(f: &mut Formatter<'_>, values: &[Value<'_>]) -> Result {
+    f.write_char('[')?;
+    write_separated(f, values)?;
+    f.write_char(']')
+}
+
+/// Write a map expression.
+fn write_map(f: &mut Formatter<'_>, pairs: &[(Value<'_>, Value<'_>)]) -> Result {
+    if pairs.is_empty() {
+        return f.write_str("{}");
+    }
+
+    f.write_str("{\n")?;
+
+    for (idx, (key, value)) in pairs.iter().enumerate() {
+        if idx != 0 {
+            f.write_str(",\n")?;
+        }
+
+        write!(f, "{}: {}", key, value)?;
+    }
+
+    f.write_str("\n}")
+}
+
+/// Write ``values`` separated by ``", "``.
+fn write_separated(f: &mut Formatter<'_>, values: &[Value<'_>]) -> Result {
+    for (idx, value) in values.iter().enumerate() {
+        if idx != 0 {
+            f.write_str(", ")?;
+        }
+
+        write!(f, "{}", value)?;
+    }
+
+    Ok(())
+}
+
+/// Write a float the way Godot does, i.e. always with a fractional part.
+fn write_float(f: &mut Formatter<'_>, float: f64) -> Result {
+    if float.is_nan() {
+        f.write_str("nan")
+    } else if float == f64::INFINITY {
+        f.write_str("inf")
+    } else if float == f64::NEG_INFINITY {
+        f.write_str("inf_neg")
+    } else if float.fract() == 0.0 {
+        write!(f, "{:.1}", float)
+    } else {
+        write!(f, "{}", float)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::*;
+
+    #[test]
+    fn test_value_display() {
+        assert_eq!(Value::Null.to_string(), "null");
+        assert_eq!(Value::Integer(-3).to_string(), "-3");
+        assert_eq!(Value::Float(1.0).to_string(), "1.0");
+        assert_eq!(Value::Float(0.25).to_string(), "0.25");
+        assert_eq!(Value::String("ok").to_string(), "\"ok\"");
+        assert_eq!(
+            Value::ObjectInstance("Vector2", vec![Value::Integer(1), Value::Float(2.5)])
+                .to_string(),
+            "Vector2(1, 2.5)"
+        );
+        assert_eq!(
+            Value::Map(vec![(Value::String("a"), Value::Integer(1))]).to_string(),
+            "{\n\"a\": 1\n}"
+        );
+        assert_eq!(
+            Value::Object("InputEventKey", vec![("echo", Value::Boolean(false))]).to_string(),
+            "Object(InputEventKey,\"echo\":false)\n"
+        );
+        assert_eq!(Value::Float(f64::NEG_INFINITY).to_string(), "inf_neg");
+        assert_eq!(Value::StringName("a").to_string(), "&\"a\"");
+        assert_eq!(Value::NodePath("a/b").to_string(), "NodePath(\"a/b\")");
+        assert_eq!(
+            Value::ExtResource(ResourceId::String("1_a")).to_string(),
+            "ExtResource(\"1_a\")"
+        );
+        assert_eq!(
+            Value::TypedArray(
+                ElementType::ExtResource(ResourceId::String("1_a")),
+                vec![Value::Integer(1)]
+            )
+            .to_string(),
+            "Array[ExtResource(\"1_a\")]([1])"
+        );
+        assert_eq!(
+            Value::TypedMap(
+                ElementType::Named("String"),
+                ElementType::Named("int"),
+                vec![]
+            )
+            .to_string(),
+            "Dictionary[String, int]({})"
+        );
+        assert_eq!(
+            Value::PackedArray(PackedArrayKind::Vector2, vec![Value::Integer(0)]).to_string(),
+            "PackedVector2Array(0)"
+        );
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let source = r#"; Engine configuration file.
+
+config_version=5
+
+[application]
+
+config/name="Test"
+config/features=PackedStringArray("4.2", "Forward Plus")
+
+[input]
+
+"move left"={
+"deadzone": 0.5,
+"events": [Object(InputEventKey,"keycode":0,"physical_keycode":65)
+, Object(InputEventJoypadMotion,"axis":0,"axis_value":-1.0)
+]
+}
+"#;
+        let file = crate::parser(source)
+            .collect::<Result<File<'_>, _>>()
+            .unwrap();
+
+        assert_eq!(crate::serializer::serialize(&file), source);
+    }
+}
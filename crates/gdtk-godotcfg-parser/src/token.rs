@@ -1,3 +1,5 @@
+use std::fmt::{Display, Formatter};
+
 use crate::error::Error;
 
 /// A range in the source.
@@ -46,7 +48,12 @@ impl<'a> Token<'a> {
             _ => "an identifier or a path",
         }
 
-        pub fn expect_integer -> (i32) {
+        pub fn expect_key -> (&'a str) {
+            TokenKind::Identifier(key) | TokenKind::Path(key) | TokenKind::String(key) => key,
+            _ => "an identifier, a path or a string",
+        }
+
+        pub fn expect_integer -> (i64) {
             TokenKind::Integer(int) => int,
             _ => "an integer",
         }
@@ -61,6 +68,16 @@ impl<'a> Token<'a> {
             _ => "a string",
         }
 
+        pub fn expect_string_name -> (&'a str) {
+            TokenKind::StringName(string) => string,
+            _ => "a string name",
+        }
+
+        pub fn expect_node_path -> (&'a str) {
+            TokenKind::NodePath(path) => path,
+            _ => "a node path",
+        }
+
         pub fn expect_boolean -> (bool) {
             TokenKind::Boolean(boolean) => boolean,
             _ => "a boolean",
@@ -129,6 +146,8 @@ impl<'a> Token<'a> {
             pub fn is_integer(&self) -> bool;
             pub fn is_float(&self) -> bool;
             pub fn is_string(&self) -> bool;
+            pub fn is_string_name(&self) -> bool;
+            pub fn is_node_path(&self) -> bool;
             pub fn is_boolean(&self) -> bool;
             pub fn is_null(&self) -> bool;
             pub fn is_colon(&self) -> bool;
@@ -141,6 +160,7 @@ impl<'a> Token<'a> {
             pub fn is_opening_brace(&self) -> bool;
             pub fn is_closing_brace(&self) -> bool;
             pub fn is_comment(&self) -> bool;
+            pub fn is_unrecognised(&self) -> bool;
         }
     }
 }
@@ -150,7 +170,10 @@ impl<'a> Token<'a> {
 #[derive(Debug, Clone, PartialEq, logos::Logos, enum_as_inner::EnumAsInner)]
 #[logos(error = crate::error::Error<'s>)]
 #[logos(skip r"[ \t\n\r\f]+")]
-#[logos(subpattern segment = "[a-zA-Z_][a-zA-Z0-9_]*")]
+#[logos(subpattern segment = "[a-zA-Z_][a-zA-Z0-9_.]*")]
+#[logos(subpattern path_segment = "[a-zA-Z0-9_][a-zA-Z0-9_.]*")]
+#[logos(subpattern string = r#""([^"\\]|\\(.|\n))*""#)]
+#[logos(subpattern exponent = "[eE][+-]?[0-9]+")]
 pub enum TokenKind<'a> {
     /* Literals */
 
@@ -159,21 +182,32 @@ pub enum TokenKind<'a> {
     Identifier(&'a str),
 
     /// A path identifier.
-    #[regex("(?&segment)(/(?&segment))+")]
+    #[regex("(?&path_segment)(/(?&path_segment))+")]
     Path(&'a str),
 
     /// An integer literal.
-    #[regex("-*[0-9]+", |lex| lex.slice().parse::<i32>().ok())]
-    Integer(i32),
-
-    /// A float literal.
-    #[regex("-*[0-9]+\\.[0-9]+", |lex| lex.slice().parse::<f64>().ok())]
+    #[regex("-*[0-9]+", |lex| lex.slice().parse::<i64>().ok())]
+    Integer(i64),
+
+    /// A float literal. ``inf``, ``inf_neg`` and ``nan`` are lexed as
+    /// identifiers, but ``-inf`` can not be.
+    #[regex("-*[0-9]+\\.[0-9]+(?&exponent)?", |lex| lex.slice().parse::<f64>().ok())]
+    #[regex("-*[0-9]+(?&exponent)", |lex| lex.slice().parse::<f64>().ok())]
+    #[token("-inf", |_| f64::NEG_INFINITY)]
     Float(f64),
 
     /// A string literal.
-    #[regex("\"[^\"]*\"", |lex| lex.slice().trim_matches('"'))]
+    #[regex("(?&string)", |lex| strip(lex.slice(), 1))]
     String(&'a str),
 
+    /// A ``&"string_name"`` literal.
+    #[regex("&(?&string)", |lex| strip(lex.slice(), 2))]
+    StringName(&'a str),
+
+    /// A ``^"node/path"`` literal.
+    #[regex("\\^(?&string)", |lex| strip(lex.slice(), 2))]
+    NodePath(&'a str),
+
     /// A boolean literal.
     #[regex("true|false", |lex| lex.slice().parse::<bool>().ok())]
     Boolean(bool),
@@ -225,6 +259,42 @@ pub enum TokenKind<'a> {
     /// A comment.
     #[regex(";[^\r\n]*\n?", |lex| &lex.slice()[1..])]
     Comment(&'a str),
+
+    /// A character sequence that is not a valid token. Never produced
+    /// by logos directly, see [crate::lexer].
+    Unrecognised(&'a str),
+}
+
+impl Display for TokenKind<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Identifier(ident) => write!(f, "identifier `{}`", ident),
+            Self::Path(path) => write!(f, "path `{}`", path),
+            Self::Integer(int) => write!(f, "integer `{}`", int),
+            Self::Float(float) => write!(f, "float `{}`", float),
+            Self::String(string) => write!(f, "string `\"{}\"`", string),
+            Self::StringName(string) => write!(f, "string name `&\"{}\"`", string),
+            Self::NodePath(path) => write!(f, "node path `^\"{}\"`", path),
+            Self::Boolean(boolean) => write!(f, "`{}`", boolean),
+            Self::Null => f.write_str("`null`"),
+            Self::Assignment => f.write_str("`=`"),
+            Self::Colon => f.write_str("`:`"),
+            Self::Comma => f.write_str("`,`"),
+            Self::OpeningParenthesis => f.write_str("`(`"),
+            Self::ClosingParenthesis => f.write_str("`)`"),
+            Self::OpeningBracket => f.write_str("`[`"),
+            Self::ClosingBracket => f.write_str("`]`"),
+            Self::OpeningBrace => f.write_str("`{`"),
+            Self::ClosingBrace => f.write_str("`}`"),
+            Self::Comment(_) => f.write_str("a comment"),
+            Self::Unrecognised(slice) => write!(f, "unrecognised `{}`", slice),
+        }
+    }
+}
+
+/// Strip the ``prefix`` leading characters and the closing quote off a string literal.
+fn strip(slice: &str, prefix: usize) -> &str {
+    &slice[prefix..slice.len() - 1]
 }
 
 #[cfg(test)]
@@ -246,11 +316,29 @@ mod tests {
     fn test_literals() -> Test {
         assert_eq!(lex!("ident"), TokenKind::Identifier("ident"));
         assert_eq!(lex!("path/to/smth"), TokenKind::Path("path/to/smth"));
+        assert_eq!(
+            lex!("2d_physics/layer_1"),
+            TokenKind::Path("2d_physics/layer_1")
+        );
+        assert_eq!(
+            lex!("path/to/smth.mobile"),
+            TokenKind::Path("path/to/smth.mobile")
+        );
         assert_eq!(lex!("01234"), TokenKind::Integer(1234));
         assert_eq!(lex!("-0123"), TokenKind::Integer(-123));
         assert_eq!(lex!("1.0"), TokenKind::Float(1.0));
         assert_eq!(lex!("-1.0"), TokenKind::Float(-1.0));
+        assert_eq!(lex!("1e5"), TokenKind::Float(1e5));
+        assert_eq!(lex!("-1.5e-3"), TokenKind::Float(-1.5e-3));
+        assert_eq!(lex!("-inf"), TokenKind::Float(f64::NEG_INFINITY));
+        assert_eq!(
+            lex!("9007199254740993"),
+            TokenKind::Integer(9007199254740993)
+        );
         assert_eq!(lex!("\"ok\""), TokenKind::String("ok"));
+        assert_eq!(lex!(r#""a \"b\"""#), TokenKind::String(r#"a \"b\""#));
+        assert_eq!(lex!("&\"ok\""), TokenKind::StringName("ok"));
+        assert_eq!(lex!("^\"a/b\""), TokenKind::NodePath("a/b"));
         assert_eq!(lex!("true"), TokenKind::Boolean(true));
         assert_eq!(lex!("false"), TokenKind::Boolean(false));
         assert_eq!(lex!("null"), TokenKind::Null);
@@ -276,6 +364,7 @@ mod tests {
     #[test]
     fn test_specials() -> Test {
         assert_eq!(lex!("; ok"), TokenKind::Comment(" ok"));
+        assert_eq!(lex!("?"), TokenKind::Unrecognised("?"));
 
         Ok(())
     }
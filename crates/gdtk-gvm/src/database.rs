@@ -0,0 +1,253 @@
+//! The on-disk format of the `local_versions` file.
+//!
+//! The file starts with [MAGIC] and a little-endian `u32` schema version,
+//! followed by the rkyv-encoded [LocalVersions] of that schema. Files written
+//! before the format was versioned have no header and are treated as schema 0.
+//! Files using an older schema are migrated to the current one when loaded.
+
+use rkyv::util::AlignedVec;
+
+use crate::types::{LocalVersion, LocalVersions, VersionOrigin};
+
+/// The bytes every versioned `local_versions` file starts with.
+pub const MAGIC: &[u8; 4] = b"GDLV";
+
+/// The current schema version. Bump it (and add a migration) whenever
+/// [LocalVersions] changes.
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// Encode `data` using the current schema.
+pub fn encode(data: &LocalVersions) -> Result<Vec<u8>, crate::Error> {
+    let body = rkyv::to_bytes::<rkyv::rancor::Error>(data)?;
+    let mut output = Vec::with_capacity(MAGIC.len() + 4 + body.len());
+
+    output.extend_from_slice(MAGIC);
+    output.extend_from_slice(&SCHEMA_VERSION.to_le_bytes());
+    output.extend_from_slice(&body);
+
+    Ok(output)
+}
+
+/// Decode a `local_versions` file, migrating it to the current schema if
+/// needed. Returns the data and whether it was migrated.
+pub fn decode(bytes: &[u8]) -> Result<(LocalVersions, bool), crate::Error> {
+    let (schema, body) = match bytes.strip_prefix(MAGIC.as_slice()) {
+        Some(rest) if rest.len() >= 4 => {
+            let (schema, body) = rest.split_at(4);
+
+            (u32::from_le_bytes(schema.try_into().unwrap()), body)
+        }
+        _ => (0, bytes),
+    };
+
+    // rkyv needs the data to be aligned, which a slice of a file's contents isn't
+    let mut aligned = AlignedVec::<16>::with_capacity(body.len());
+    aligned.extend_from_slice(body);
+
+    match schema {
+        0 => {
+            let data = rkyv::from_bytes::<v0::LocalVersions, rkyv::rancor::Error>(&aligned)?;
+
+            Ok((v1::migrate(v0::migrate(data)), true))
+        }
+        1 => {
+            let data = rkyv::from_bytes::<v1::LocalVersions, rkyv::rancor::Error>(&aligned)?;
+
+            Ok((v1::migrate(data), true))
+        }
+        SCHEMA_VERSION => Ok((
+            rkyv::from_bytes::<LocalVersions, rkyv::rancor::Error>(&aligned)?,
+            false,
+        )),
+        newer => Err(crate::Error::UnsupportedSchema(newer)),
+    }
+}
+
+/// The unversioned format, before checksums and linked versions.
+mod v0 {
+    #[derive(rkyv::Archive, rkyv::Deserialize, rkyv::Serialize)]
+    pub struct LocalVersions(pub Vec<LocalVersion>);
+
+    #[derive(rkyv::Archive, rkyv::Deserialize, rkyv::Serialize)]
+    pub struct LocalVersion {
+        pub name: String,
+        pub path: String,
+        pub mono: bool,
+    }
+
+    pub fn migrate(data: LocalVersions) -> super::v1::LocalVersions {
+        super::v1::LocalVersions(
+            data.0
+                .into_iter()
+                .map(|v| super::v1::LocalVersion {
+                    name: v.name,
+                    path: v.path,
+                    mono: v.mono,
+                    sha512: None,
+                    origin: super::v1::VersionOrigin::Installed,
+                })
+                .collect(),
+        )
+    }
+}
+
+/// Before the default version was recorded.
+///
+/// The types are copies of the ones at the time rather than the current ones,
+/// so that changing the current ones doesn't change how old files are read.
+mod v1 {
+    #[derive(rkyv::Archive, rkyv::Deserialize, rkyv::Serialize)]
+    pub struct LocalVersions(pub Vec<LocalVersion>);
+
+    #[derive(rkyv::Archive, rkyv::Deserialize, rkyv::Serialize)]
+    pub struct LocalVersion {
+        pub name: String,
+        pub path: String,
+        pub mono: bool,
+        pub sha512: Option<String>,
+        pub origin: VersionOrigin,
+    }
+
+    #[derive(rkyv::Archive, rkyv::Deserialize, rkyv::Serialize)]
+    pub enum VersionOrigin {
+        Installed,
+        Linked {
+            source: Option<String>,
+            commit: Option<String>,
+        },
+    }
+
+    pub fn migrate(data: LocalVersions) -> super::LocalVersions {
+        let versions = data
+            .0
+            .into_iter()
+            .map(|v| super::LocalVersion {
+                name: v.name,
+                path: v.path,
+                mono: v.mono,
+                sha512: v.sha512,
+                origin: match v.origin {
+                    VersionOrigin::Installed => super::VersionOrigin::Installed,
+                    VersionOrigin::Linked { source, commit } => {
+                        super::VersionOrigin::Linked { source, commit }
+                    }
+                },
+            })
+            .collect::<Vec<_>>();
+
+        // the default version used to be a symlink to its executable
+        let default = gdtk_paths::default_godot_path()
+            .ok()
+            .and_then(|link| std::fs::read_link(link).ok())
+            .and_then(|target| {
+                versions
+                    .iter()
+                    .find(|v| v.executable().as_std_path() == target)
+            })
+            .map(|v| (v.name.clone(), v.mono));
+
+        super::LocalVersions { versions, default }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode, encode, MAGIC, SCHEMA_VERSION};
+    use crate::types::VersionOrigin;
+
+    /// An unversioned file with `4.2.2-stable` at `/godots/4.2.2-stable`.
+    const SCHEMA_0: &[u8] = &[
+        52, 46, 50, 46, 50, 45, 115, 116, 97, 98, 108, 101, 47, 103, 111, 100, 111, 116, 115, 47,
+        52, 46, 50, 46, 50, 45, 115, 116, 97, 98, 108, 101, 140, 0, 0, 0, 224, 255, 255, 255, 148,
+        0, 0, 0, 228, 255, 255, 255, 0, 0, 0, 0, 236, 255, 255, 255, 1, 0, 0, 0,
+    ];
+
+    /// The body of a schema 1 file with an installed mono `4.3-stable` and a
+    /// `custom` version linked from `/src/godot` at `deadbeef`.
+    const SCHEMA_1_BODY: &[u8] = &[
+        52, 46, 51, 45, 115, 116, 97, 98, 108, 101, 47, 103, 111, 100, 111, 116, 115, 47, 52, 46,
+        51, 45, 115, 116, 97, 98, 108, 101, 45, 109, 111, 110, 111, 47, 115, 114, 99, 47, 103, 111,
+        100, 111, 116, 47, 98, 105, 110, 47, 115, 114, 99, 47, 103, 111, 100, 111, 116, 0, 0, 0,
+        138, 0, 0, 0, 196, 255, 255, 255, 151, 0, 0, 0, 198, 255, 255, 255, 1, 0, 0, 0, 1, 0, 0, 0,
+        97, 98, 99, 255, 255, 255, 255, 255, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 99, 117, 115, 116, 111, 109, 255, 255, 142, 0, 0, 0, 161,
+        255, 255, 255, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 0, 138,
+        0, 0, 0, 143, 255, 255, 255, 1, 0, 0, 0, 100, 101, 97, 100, 98, 101, 101, 102, 136, 255,
+        255, 255, 2, 0, 0, 0,
+    ];
+
+    fn with_header(schema: u32, body: &[u8]) -> Vec<u8> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend_from_slice(&schema.to_le_bytes());
+        bytes.extend_from_slice(body);
+        bytes
+    }
+
+    #[test]
+    fn test_schema_0() {
+        let (data, migrated) = decode(SCHEMA_0).unwrap();
+
+        assert!(migrated);
+        assert_eq!(data.versions.len(), 1);
+
+        let version = &data.versions[0];
+        assert_eq!(version.name, "4.2.2-stable");
+        assert_eq!(version.path, "/godots/4.2.2-stable");
+        assert!(!version.mono);
+        assert_eq!(version.sha512, None);
+        assert!(matches!(version.origin, VersionOrigin::Installed));
+    }
+
+    #[test]
+    fn test_schema_1() {
+        let (data, migrated) = decode(&with_header(1, SCHEMA_1_BODY)).unwrap();
+
+        assert!(migrated);
+        assert_eq!(data.versions.len(), 2);
+
+        let installed = &data.versions[0];
+        assert_eq!(installed.name, "4.3-stable");
+        assert_eq!(installed.path, "/godots/4.3-stable-mono");
+        assert!(installed.mono);
+        assert_eq!(installed.sha512.as_deref(), Some("abc"));
+        assert!(matches!(installed.origin, VersionOrigin::Installed));
+
+        let linked = &data.versions[1];
+        assert_eq!(linked.name, "custom");
+        assert_eq!(linked.path, "/src/godot/bin");
+        assert_eq!(linked.sha512, None);
+        assert!(matches!(
+            &linked.origin,
+            VersionOrigin::Linked { source: Some(source), commit: Some(commit) }
+                if source == "/src/godot" && commit == "deadbeef"
+        ));
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let (mut data, _) = decode(&with_header(1, SCHEMA_1_BODY)).unwrap();
+        data.default = Some(("4.3-stable".to_owned(), true));
+
+        let bytes = encode(&data).unwrap();
+        assert_eq!(bytes[..4], *MAGIC);
+        assert_eq!(bytes[4..8], SCHEMA_VERSION.to_le_bytes());
+
+        let (decoded, migrated) = decode(&bytes).unwrap();
+        assert!(!migrated);
+        assert_eq!(decoded.default, data.default);
+        assert_eq!(
+            format!("{:?}", decoded.versions),
+            format!("{:?}", data.versions)
+        );
+    }
+
+    #[test]
+    fn test_newer_schema() {
+        let bytes = with_header(SCHEMA_VERSION + 1, SCHEMA_1_BODY);
+
+        assert!(matches!(
+            decode(&bytes),
+            Err(crate::Error::UnsupportedSchema(schema)) if schema == SCHEMA_VERSION + 1
+        ));
+    }
+}
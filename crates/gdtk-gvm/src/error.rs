@@ -18,9 +18,25 @@ pub enum Error {
     #[error("Surf error: {0:?}")]
     UreqError(#[from] UreqError),
 
+    #[error("GitHub GraphQL API error: {0}")]
+    GraphQLError(String),
+
     #[error("gdtk-paths error: {0:?}")]
     GdtkPathsError(#[from] GdtkPathsError),
 
-    #[error("Unable to retrieve GitHub authentication token. Is `gh` set up on your machine?")]
-    TokenRetrievalError,
+    #[error("The local versions database uses schema {0}, which this version of gdtk doesn't support. Please update gdtk.")]
+    UnsupportedSchema(u32),
+
+    #[error("The local versions database was loaded read-only and can't be saved.")]
+    ReadOnly,
+
+    #[error("No SHA-512 checksum found for {0}.")]
+    MissingChecksum(String),
+
+    #[error("SHA-512 checksum mismatch for {asset}: expected {expected}, got {actual}. The download may be corrupted or tampered with.")]
+    ChecksumMismatch {
+        asset: String,
+        expected: String,
+        actual: String,
+    },
 }
@@ -0,0 +1,296 @@
+//! A local copy of the list of Godot releases, so that versions can be listed
+//! and resolved without going through all releases on GitHub every time.
+//!
+//! The `release_index` file starts with [MAGIC] and a little-endian `u32`
+//! [INDEX_VERSION], followed by the rkyv-encoded [ReleaseIndex]. Files with
+//! another header are considered stale and fetched again.
+
+use std::time::{Duration, SystemTime};
+
+use rkyv::util::AlignedVec;
+
+use crate::{
+    online::GitHub,
+    queries::{release_assets::ReleaseAsset, releases::Release as GraphQLRelease},
+    version::OnlineVersion,
+};
+
+/// The bytes every `release_index` file starts with.
+pub const MAGIC: &[u8; 4] = b"GDRI";
+
+/// The current layout of the index. Bump it whenever [ReleaseIndex] changes.
+pub const INDEX_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, rkyv::Archive, rkyv::Deserialize, rkyv::Serialize)]
+pub struct CachedRelease {
+    pub tag_name: String,
+    pub is_prerelease: bool,
+    pub assets: Vec<ReleaseAsset>,
+}
+
+impl From<GraphQLRelease> for CachedRelease {
+    fn from(value: GraphQLRelease) -> Self {
+        Self {
+            tag_name: value.tag_name,
+            is_prerelease: value.is_prerelease,
+            assets: value
+                .release_assets
+                .nodes
+                .into_iter()
+                .flatten()
+                .flatten()
+                .collect(),
+        }
+    }
+}
+
+/// Represents the `release_index` file.
+#[derive(Debug, rkyv::Archive, rkyv::Deserialize, rkyv::Serialize)]
+pub struct ReleaseIndex {
+    /// When the index was last refreshed, in seconds since the Unix epoch.
+    fetched_at: u64,
+    releases: Vec<CachedRelease>,
+}
+
+impl ReleaseIndex {
+    /// Load the index, if it was fetched before.
+    pub fn load() -> Result<Option<Self>, crate::Error> {
+        let path = gdtk_paths::release_index_path()?;
+
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        Self::decode(&std::fs::read(&path)?)
+    }
+
+    /// Decode the contents of a `release_index` file. Returns `None` if it has
+    /// another header.
+    fn decode(content: &[u8]) -> Result<Option<Self>, crate::Error> {
+        // an index written by another version of gdtk is as good as none
+        let Some(body) = content
+            .strip_prefix(MAGIC.as_slice())
+            .and_then(|rest| rest.strip_prefix(INDEX_VERSION.to_le_bytes().as_slice()))
+        else {
+            return Ok(None);
+        };
+
+        // rkyv needs the data to be aligned, which a slice of a file's contents isn't
+        let mut aligned = AlignedVec::<16>::with_capacity(body.len());
+        aligned.extend_from_slice(body);
+
+        Ok(Some(rkyv::from_bytes::<_, rkyv::rancor::Error>(&aligned)?))
+    }
+
+    /// Fetch the whole index and save it.
+    pub fn fetch(github: &GitHub) -> Result<Self, crate::Error> {
+        let mut index = Self {
+            fetched_at: 0,
+            releases: vec![],
+        };
+
+        index.refresh(github)?;
+
+        Ok(index)
+    }
+
+    /// Fetch releases published since the index was last refreshed and save it.
+    /// Returns the number of new releases.
+    pub fn refresh(&mut self, github: &GitHub) -> Result<usize, crate::Error> {
+        let count = self.update(github)?;
+
+        self.save()?;
+
+        Ok(count)
+    }
+
+    /// Add releases published since the index was last refreshed, without
+    /// saving it. Returns the number of new releases.
+    fn update(&mut self, github: &GitHub) -> Result<usize, crate::Error> {
+        let new = github.fetch_releases(|tag| self.releases.iter().any(|r| r.tag_name == tag))?;
+        let count = new.len();
+
+        self.releases.extend(new);
+        self.fetched_at = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+
+        Ok(count)
+    }
+
+    /// Save the `release_index` file.
+    pub fn save(&self) -> Result<(), crate::Error> {
+        let contents = self.encode()?;
+        let path = gdtk_paths::release_index_path()?;
+
+        // write the whole file first, so that it's never left half-written. the
+        // index isn't locked, so each process needs a temporary file of its own
+        let temp = path.with_extension(format!("{}.tmp", std::process::id()));
+
+        std::fs::write(&temp, contents)?;
+        std::fs::rename(&temp, &path)?;
+
+        Ok(())
+    }
+
+    /// Encode the index, header included.
+    fn encode(&self) -> Result<Vec<u8>, crate::Error> {
+        let body = rkyv::to_bytes::<rkyv::rancor::Error>(self)?;
+        let mut contents = Vec::with_capacity(MAGIC.len() + 4 + body.len());
+
+        contents.extend_from_slice(MAGIC);
+        contents.extend_from_slice(&INDEX_VERSION.to_le_bytes());
+        contents.extend_from_slice(&body);
+
+        Ok(contents)
+    }
+
+    /// When the index was last refreshed.
+    pub fn fetched_at(&self) -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(self.fetched_at)
+    }
+
+    /// All indexed versions, newest first.
+    pub fn versions(&self) -> Vec<OnlineVersion> {
+        let mut output = self
+            .releases
+            .iter()
+            .cloned()
+            .map(OnlineVersion::from)
+            .collect::<Vec<_>>();
+
+        output.sort_unstable_by(|v1, v2| v2.as_ordered().cmp(v1.as_ordered()));
+
+        output
+    }
+
+    /// Assets of an indexed version.
+    pub fn assets(&self, tag_name: &str) -> Option<&[ReleaseAsset]> {
+        self.releases
+            .iter()
+            .find(|r| r.tag_name == tag_name)
+            .map(|r| r.assets.as_slice())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mockito::{Matcher, Server};
+
+    use super::{CachedRelease, ReleaseIndex, INDEX_VERSION, MAGIC};
+    use crate::{
+        online::GitHub,
+        queries::release_assets::{ReleaseAsset, Uri},
+    };
+
+    fn release(tag_name: &str) -> CachedRelease {
+        CachedRelease {
+            tag_name: tag_name.to_owned(),
+            is_prerelease: false,
+            assets: vec![],
+        }
+    }
+
+    fn tags(index: &ReleaseIndex) -> Vec<&str> {
+        index.releases.iter().map(|r| r.tag_name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let mut stable = release("4.3-stable");
+        stable.assets.push(ReleaseAsset {
+            name: "Godot_v4.3-stable_linux.x86_64.zip".to_owned(),
+            download_url: Uri("https://example.com/4.3.zip".to_owned()),
+        });
+
+        let index = ReleaseIndex {
+            fetched_at: 1_700_000_000,
+            releases: vec![
+                stable,
+                CachedRelease {
+                    is_prerelease: true,
+                    ..release("4.4-dev1")
+                },
+            ],
+        };
+
+        let bytes = index.encode().unwrap();
+        assert_eq!(bytes[..4], *MAGIC);
+        assert_eq!(bytes[4..8], INDEX_VERSION.to_le_bytes());
+
+        let decoded = ReleaseIndex::decode(&bytes).unwrap().unwrap();
+        assert_eq!(decoded.fetched_at, index.fetched_at);
+        assert_eq!(decoded.releases, index.releases);
+    }
+
+    #[test]
+    fn test_other_header() {
+        let index = ReleaseIndex {
+            fetched_at: 0,
+            releases: vec![release("4.3-stable")],
+        };
+        let body = index.encode().unwrap().split_off(8);
+
+        let with_header = |magic: &[u8], version: u32| {
+            let mut bytes = magic.to_vec();
+            bytes.extend_from_slice(&version.to_le_bytes());
+            bytes.extend_from_slice(&body);
+            bytes
+        };
+
+        assert!(ReleaseIndex::decode(&with_header(MAGIC, INDEX_VERSION))
+            .unwrap()
+            .is_some());
+        assert!(ReleaseIndex::decode(&with_header(b"GDLV", INDEX_VERSION))
+            .unwrap()
+            .is_none());
+        assert!(ReleaseIndex::decode(&with_header(MAGIC, INDEX_VERSION - 1))
+            .unwrap()
+            .is_none());
+        assert!(ReleaseIndex::decode(&with_header(MAGIC, INDEX_VERSION + 1))
+            .unwrap()
+            .is_none());
+        // files from before the index had a header
+        assert!(ReleaseIndex::decode(&body).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_update() {
+        let mut server = Server::new();
+        // the first page reaches a known release, so the second one isn't needed
+        let pages = [
+            (
+                "1",
+                r#"[{"tag_name": "4.3-stable", "prerelease": false, "assets": []},
+                    {"tag_name": "4.3-rc1", "prerelease": true, "assets": []},
+                    {"tag_name": "4.2-stable", "prerelease": false, "assets": []}]"#,
+                1,
+            ),
+            ("2", "[]", 0),
+        ]
+        .map(|(page, body, hits)| {
+            server
+                .mock("GET", "/repos/godotengine/godot-builds/releases")
+                .match_query(Matcher::UrlEncoded("page".into(), page.into()))
+                .with_header("content-type", "application/json")
+                .with_body(body)
+                .expect(hits)
+                .create()
+        });
+
+        let mut index = ReleaseIndex {
+            fetched_at: 0,
+            releases: vec![release("4.2-stable"), release("4.1-stable")],
+        };
+
+        let count = index.update(&GitHub::new(server.url(), None)).unwrap();
+
+        assert_eq!(count, 2);
+        assert_eq!(
+            tags(&index),
+            ["4.2-stable", "4.1-stable", "4.3-stable", "4.3-rc1"]
+        );
+        assert!(index.fetched_at > 0);
+        pages.iter().for_each(|mock| mock.assert());
+    }
+}
@@ -4,9 +4,12 @@ pub use versions;
 
 pub use crate::error::Error;
 pub use crate::manager::VersionManager;
+pub mod database;
 pub mod error;
+pub mod index;
 pub mod manager;
 pub mod online;
+pub mod projects;
 pub mod queries;
 pub mod types;
 pub mod utils;
@@ -1,39 +1,93 @@
-use std::io::Read;
+use std::fs::File;
 
 use crate::types::{LocalVersion, LocalVersions};
 
+/// Manages the `local_versions` file. Only one manager can exist at a time
+/// across all processes: [VersionManager::load] waits for the previous one
+/// to be dropped, so keep it around only as long as needed. Any number of
+/// read-only managers ([VersionManager::load_shared]) can exist at once,
+/// though.
 pub struct VersionManager {
     inner: LocalVersions,
+    /// Held for as long as the manager lives.
+    _lock: File,
+    /// Whether the lock is shared, in which case the manager can't be saved.
+    shared: bool,
 }
 
 impl VersionManager {
-    /// Load the `versions.toml` file.
+    /// Load the `local_versions` file, migrating it to the current schema
+    /// if needed.
     pub fn load() -> Result<Self, crate::Error> {
+        let lock = lock()?;
         let path = gdtk_paths::local_versions_path()?;
 
-        Ok(
-            match crate::utils::maybe_create_local_versions(path.as_std_path())? {
-                Some(inner) => Self { inner },
-                None => {
-                    let mut content = vec![];
-                    let mut file = std::fs::File::options().read(true).open(&path)?;
+        if let Some(inner) = crate::utils::maybe_create_local_versions(path.as_std_path())? {
+            return Ok(Self {
+                inner,
+                _lock: lock,
+                shared: false,
+            });
+        }
+
+        let content = std::fs::read(&path)?;
+        let (inner, migrated) = crate::database::decode(&content)?;
+        let manager = Self {
+            inner,
+            _lock: lock,
+            shared: false,
+        };
+
+        if migrated {
+            manager.save()?;
+        }
+
+        Ok(manager)
+    }
 
-                    file.read_to_end(&mut content)?;
+    /// Load the `local_versions` file for reading only. This only waits for a
+    /// [VersionManager::load]ed manager, not for other read-only ones.
+    /// Migrations happen in memory only, and [VersionManager::save] fails.
+    pub fn load_shared() -> Result<Self, crate::Error> {
+        let lock = crate::utils::lock_shared(gdtk_paths::local_versions_lock_path()?)?;
+        let path = gdtk_paths::local_versions_path()?;
 
-                    let inner = rkyv::from_bytes::<_, rkyv::rancor::Error>(&content)?;
+        let inner = match std::fs::read(&path) {
+            Ok(content) => crate::database::decode(&content)?.0,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => LocalVersions::default(),
+            Err(error) => return Err(error.into()),
+        };
+
+        Ok(Self {
+            inner,
+            _lock: lock,
+            shared: true,
+        })
+    }
 
-                    Self { inner }
-                }
-            },
-        )
+    /// A manager with no versions. Saving it overwrites the `local_versions`
+    /// file, e.g. if it can't be read anymore.
+    pub fn empty() -> Result<Self, crate::Error> {
+        Ok(Self {
+            inner: LocalVersions::default(),
+            _lock: lock()?,
+            shared: false,
+        })
     }
 
-    /// Save the `versions.toml` file.
+    /// Save the `local_versions` file.
     pub fn save(&self) -> Result<(), crate::Error> {
-        let contents = rkyv::to_bytes::<rkyv::rancor::Error>(&self.inner)?;
+        if self.shared {
+            return Err(crate::Error::ReadOnly);
+        }
+
+        let contents = crate::database::encode(&self.inner)?;
         let path = gdtk_paths::local_versions_path()?;
+        let temp = path.with_extension("tmp");
 
-        std::fs::write(&path, contents)?;
+        // write the whole file first, so that it's never left half-written
+        std::fs::write(&temp, contents)?;
+        std::fs::rename(&temp, &path)?;
 
         Ok(())
     }
@@ -41,7 +95,7 @@ impl VersionManager {
     /// Get all installed versions.
     #[inline]
     pub fn installed(&self) -> &[LocalVersion] {
-        &self.inner.0
+        &self.inner.versions
     }
 
     /// Try to find an installed version.
@@ -55,7 +109,7 @@ impl VersionManager {
     pub fn add_version(&mut self, data: LocalVersion) {
         debug_assert!(self.get_version(&data.name, data.mono).is_none());
 
-        self.inner.0.push(data);
+        self.inner.versions.push(data);
     }
 
     pub fn is_empty(&self) -> bool {
@@ -68,6 +122,32 @@ impl VersionManager {
             .iter()
             .position(|v| v.name == name && v.mono == mono)?;
 
-        Some(self.inner.0.swap_remove(idx))
+        if self
+            .inner
+            .default
+            .as_ref()
+            .is_some_and(|(n, m)| n == name && *m == mono)
+        {
+            self.inner.default = None;
+        }
+
+        Some(self.inner.versions.swap_remove(idx))
+    }
+
+    /// Get the default version, if there is one.
+    pub fn default_version(&self) -> Option<&LocalVersion> {
+        let (name, mono) = self.inner.default.as_ref()?;
+
+        self.get_version(name, *mono)
+    }
+
+    /// Change the default version.
+    pub fn set_default_version(&mut self, name: &str, mono: bool) {
+        self.inner.default = Some((name.to_owned(), mono));
     }
 }
+
+/// Wait for and take the `local_versions` lock.
+fn lock() -> Result<File, crate::Error> {
+    crate::utils::lock_exclusive(gdtk_paths::local_versions_lock_path()?)
+}
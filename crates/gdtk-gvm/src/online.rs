@@ -1,109 +1,496 @@
 use cynic::QueryBuilder;
 
 use crate::{
+    index::{CachedRelease, ReleaseIndex},
     queries::{
         release_assets::{ReleaseAsset, ReleaseAssetsQuery, ReleaseAssetsQueryVariables},
         releases::{ReleasesQuery, ReleasesQueryVariables},
-        GITHUB_GRAPHQL_API,
+        rest, GITHUB_API, GODOT_BUILDS_REPO,
     },
     version::OnlineVersion,
 };
 
+/// Get all Godot versions from the [ReleaseIndex], fetching it using
+/// [GitHub::from_env] if there's none yet.
 pub fn fetch_versions() -> Result<Vec<OnlineVersion>, crate::Error> {
-    let mut output = vec![];
-    let mut cursor_end = None;
-
-    loop {
-        let op = ReleasesQuery::build(ReleasesQueryVariables {
-            after: cursor_end.as_deref(),
-        });
-
-        let response = send_graphql_request(op)?.repository.unwrap().releases;
-
-        output.extend(
-            response
-                .nodes
-                .unwrap()
-                .into_iter()
-                .flatten()
-                .map(OnlineVersion::from),
-        );
+    let index = match ReleaseIndex::load()? {
+        Some(index) => index,
+        None => ReleaseIndex::fetch(&GitHub::from_env())?,
+    };
 
-        if response.page_info.has_next_page {
-            cursor_end = response.page_info.end_cursor;
-        } else {
-            break;
-        }
+    Ok(index.versions())
+}
+
+/// Get assets of a Godot version from the [ReleaseIndex], or using
+/// [GitHub::from_env] if the version isn't indexed.
+pub fn fetch_version_assets(tag_name: &str) -> Result<Vec<ReleaseAsset>, crate::Error> {
+    if let Some(assets) = ReleaseIndex::load()?
+        .as_ref()
+        .and_then(|index| index.assets(tag_name))
+    {
+        return Ok(assets.to_vec());
     }
 
-    output.sort_unstable_by(|v1, v2| v2.as_ordered().cmp(v1.as_ordered()));
+    GitHub::from_env().fetch_version_assets(tag_name)
+}
 
-    Ok(output)
+/// A client for the GitHub API or a compatible mirror.
+#[derive(Debug, Clone)]
+pub struct GitHub {
+    base_url: String,
+    token: Option<String>,
 }
 
-pub fn fetch_version_assets(tag_name: &str) -> Result<Vec<ReleaseAsset>, crate::Error> {
-    let mut output = vec![];
-    let mut cursor_end = None;
+impl GitHub {
+    /// Create a client for the API at `base_url` (e.g. `https://api.github.com`).
+    /// Without a `token`, the REST API is used, since the GraphQL API requires
+    /// authentication. With one, the GraphQL API is tried first, and the REST
+    /// API is used if that fails (e.g. because of rate limits).
+    pub fn new(base_url: impl Into<String>, token: Option<String>) -> Self {
+        let base_url = base_url.into().trim_end_matches('/').to_owned();
 
-    loop {
-        let op = ReleaseAssetsQuery::build(ReleaseAssetsQueryVariables {
-            after: cursor_end.as_deref(),
-            tag_name,
-        });
+        Self { base_url, token }
+    }
 
-        let response = send_graphql_request(op)?
-            .repository
-            .unwrap()
-            .release
-            .unwrap()
-            .release_assets;
+    /// Create a client configured from the environment. The base URL is taken
+    /// from `GDTK_GITHUB_API_URL`, and the token from `GDTK_GITHUB_TOKEN`,
+    /// `GITHUB_TOKEN`, `GH_TOKEN` or `gh auth token`, in that order.
+    pub fn from_env() -> Self {
+        let base_url =
+            std::env::var("GDTK_GITHUB_API_URL").unwrap_or_else(|_| GITHUB_API.to_owned());
+
+        Self::new(base_url, retrieve_token())
+    }
+
+    /// Fetch all Godot versions, newest first.
+    pub fn fetch_versions(&self) -> Result<Vec<OnlineVersion>, crate::Error> {
+        let mut output = self
+            .fetch_releases(|_| false)?
+            .into_iter()
+            .map(OnlineVersion::from)
+            .collect::<Vec<_>>();
+
+        output.sort_unstable_by(|v1, v2| v2.as_ordered().cmp(v1.as_ordered()));
+
+        Ok(output)
+    }
+
+    /// Fetch releases from newest to oldest, stopping after the first page
+    /// that contains a release `known` returns `true` for. Known releases
+    /// are left out.
+    pub fn fetch_releases(
+        &self,
+        known: impl Fn(&str) -> bool,
+    ) -> Result<Vec<CachedRelease>, crate::Error> {
+        if self.token.is_some() {
+            match self.fetch_releases_graphql(&known) {
+                Ok(releases) => return Ok(releases),
+                Err(error) => return self.fallback(&error).fetch_releases_rest(known),
+            }
+        }
+
+        self.fetch_releases_rest(known)
+    }
+
+    /// Fetch assets of a Godot version.
+    pub fn fetch_version_assets(&self, tag_name: &str) -> Result<Vec<ReleaseAsset>, crate::Error> {
+        if self.token.is_some() {
+            match self.fetch_version_assets_graphql(tag_name) {
+                Ok(assets) => return Ok(assets),
+                Err(error) => return self.fallback(&error).fetch_version_assets_rest(tag_name),
+            }
+        }
+
+        self.fetch_version_assets_rest(tag_name)
+    }
+
+    /// The client to retry a failed GraphQL request with using the REST API.
+    /// If the token was rejected, the REST API is used without it.
+    fn fallback(&self, error: &crate::Error) -> Self {
+        match error {
+            crate::Error::UreqError(ureq::Error::StatusCode(401)) => {
+                Self::new(self.base_url.clone(), None)
+            }
+            _ => self.clone(),
+        }
+    }
+
+    fn fetch_releases_graphql(
+        &self,
+        known: impl Fn(&str) -> bool,
+    ) -> Result<Vec<CachedRelease>, crate::Error> {
+        let mut output = vec![];
+        let mut cursor_end = None;
+
+        loop {
+            let op = ReleasesQuery::build(ReleasesQueryVariables {
+                after: cursor_end.as_deref(),
+            });
+
+            let response = self
+                .send_graphql_request(op)?
+                .repository
+                .ok_or_else(|| missing(GODOT_BUILDS_REPO))?
+                .releases;
+            let mut reached_known = false;
+
+            for release in response.nodes.into_iter().flatten().flatten() {
+                if known(&release.tag_name) {
+                    reached_known = true;
+                } else {
+                    output.push(CachedRelease::from(release));
+                }
+            }
+
+            if response.page_info.has_next_page && !reached_known {
+                cursor_end = response.page_info.end_cursor;
+            } else {
+                break;
+            }
+        }
+
+        Ok(output)
+    }
+
+    fn fetch_releases_rest(
+        &self,
+        known: impl Fn(&str) -> bool,
+    ) -> Result<Vec<CachedRelease>, crate::Error> {
+        let mut output = vec![];
+
+        for page in 1.. {
+            let releases: Vec<rest::Release> = self.send_rest_request(&format!(
+                "/repos/{}/releases?per_page=100&page={}",
+                GODOT_BUILDS_REPO, page
+            ))?;
+
+            if releases.is_empty() {
+                break;
+            }
+
+            let mut reached_known = false;
+
+            for release in releases {
+                if known(&release.tag_name) {
+                    reached_known = true;
+                } else {
+                    output.push(CachedRelease::from(release));
+                }
+            }
+
+            if reached_known {
+                break;
+            }
+        }
+
+        Ok(output)
+    }
+
+    fn fetch_version_assets_graphql(
+        &self,
+        tag_name: &str,
+    ) -> Result<Vec<ReleaseAsset>, crate::Error> {
+        let mut output = vec![];
+        let mut cursor_end = None;
+
+        loop {
+            let op = ReleaseAssetsQuery::build(ReleaseAssetsQueryVariables {
+                after: cursor_end.as_deref(),
+                tag_name,
+            });
+
+            let response = self
+                .send_graphql_request(op)?
+                .repository
+                .ok_or_else(|| missing(GODOT_BUILDS_REPO))?
+                .release
+                .ok_or_else(|| missing(tag_name))?
+                .release_assets;
+
+            output.extend(response.nodes.into_iter().flatten().flatten());
+
+            if response.page_info.has_next_page {
+                cursor_end = response.page_info.end_cursor;
+            } else {
+                break;
+            }
+        }
+
+        Ok(output)
+    }
+
+    fn fetch_version_assets_rest(&self, tag_name: &str) -> Result<Vec<ReleaseAsset>, crate::Error> {
+        let release: rest::Release = self.send_rest_request(&format!(
+            "/repos/{}/releases/tags/{}",
+            GODOT_BUILDS_REPO, tag_name
+        ))?;
+
+        Ok(release.assets.into_iter().map(ReleaseAsset::from).collect())
+    }
+
+    fn send_graphql_request<Q, V>(&self, op: cynic::Operation<Q, V>) -> Result<Q, crate::Error>
+    where
+        Q: serde::de::DeserializeOwned,
+        V: serde::Serialize,
+    {
+        let mut request = ureq::post(&format!("{}/graphql", self.base_url));
+
+        if let Some(token) = &self.token {
+            request = request.header(
+                ureq::http::header::AUTHORIZATION,
+                &format!("Bearer {}", token),
+            );
+        }
+
+        let json = request
+            .send_json(op)?
+            .into_body()
+            .read_json::<cynic::GraphQlResponse<Q>>()?;
 
-        output.extend(response.nodes.unwrap().into_iter().flatten());
+        match json.data {
+            Some(data) => Ok(data),
+            None => Err(crate::Error::GraphQLError(
+                json.errors
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|error| error.message)
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            )),
+        }
+    }
 
-        if response.page_info.has_next_page {
-            cursor_end = response.page_info.end_cursor;
-        } else {
-            break;
+    fn send_rest_request<T: serde::de::DeserializeOwned>(
+        &self,
+        path: &str,
+    ) -> Result<T, crate::Error> {
+        let mut request = ureq::get(&format!("{}{}", self.base_url, path))
+            .header(ureq::http::header::ACCEPT, "application/vnd.github+json");
+
+        if let Some(token) = &self.token {
+            request = request.header(
+                ureq::http::header::AUTHORIZATION,
+                &format!("Bearer {}", token),
+            );
         }
+
+        Ok(request.call()?.into_body().read_json::<T>()?)
     }
+}
+
+/// The name of the asset that contains SHA-512 checksums of all other assets
+/// of a release.
+pub const CHECKSUMS_ASSET: &str = "SHA512-SUMS.txt";
 
-    Ok(output)
+/// Download the `SHA512-SUMS.txt` of a release and find the checksum of `asset` in it.
+pub fn fetch_checksum(assets: &[ReleaseAsset], asset: &str) -> Result<String, crate::Error> {
+    let Some(sums) = assets.iter().find(|a| a.name == CHECKSUMS_ASSET) else {
+        return Err(crate::Error::MissingChecksum(asset.to_owned()));
+    };
+
+    let content = ureq::get(&sums.download_url.0)
+        .call()?
+        .into_body()
+        .read_to_string()?;
+
+    crate::utils::find_checksum(&content, asset)
+        .ok_or_else(|| crate::Error::MissingChecksum(asset.to_owned()))
+}
+
+/// An error for something the GraphQL API didn't find.
+fn missing(what: &str) -> crate::Error {
+    crate::Error::GraphQLError(format!("Could not resolve {}.", what))
 }
 
-fn retrieve_token() -> Result<String, crate::Error> {
-    let command = std::process::Command::new("gh")
+/// Find a GitHub token, if there is one.
+fn retrieve_token() -> Option<String> {
+    for var in ["GDTK_GITHUB_TOKEN", "GITHUB_TOKEN", "GH_TOKEN"] {
+        if let Some(token) = std::env::var(var).ok().filter(|t| !t.is_empty()) {
+            return Some(token);
+        }
+    }
+
+    // `gh` is optional
+    let output = std::process::Command::new("gh")
         .arg("auth")
         .arg("token")
-        .output()?;
-
-    let std::process::Output {
-        status,
-        stdout: output,
-        ..
-    } = command;
+        .output()
+        .ok()?;
 
-    if status.success() {
-        Ok(String::from_utf8(output.trim_ascii().to_owned())
-            .map_err(|_| crate::Error::TokenRetrievalError)?)
-    } else {
-        Err(crate::Error::TokenRetrievalError)
+    if !output.status.success() {
+        return None;
     }
+
+    String::from_utf8(output.stdout.trim_ascii().to_owned()).ok()
 }
 
-fn send_graphql_request<Q, V>(op: cynic::Operation<Q, V>) -> Result<Q, crate::Error>
-where
-    Q: serde::de::DeserializeOwned,
-    V: serde::Serialize,
-{
-    let token = format!("Bearer {}", retrieve_token()?);
+#[cfg(test)]
+mod tests {
+    use mockito::{Matcher, Mock, Server};
 
-    let response = ureq::post(GITHUB_GRAPHQL_API)
-        .header(ureq::http::header::AUTHORIZATION, &token)
-        .send_json(op)?;
+    use super::GitHub;
 
-    let json = response
-        .into_body()
-        .read_json::<cynic::GraphQlResponse<Q>>()?;
+    const RELEASES_PATH: &str = "/repos/godotengine/godot-builds/releases";
+
+    const GRAPHQL_RELEASES: &str = r#"{"data": {"repository": {"releases": {
+        "nodes": [{
+            "tagName": "4.3-stable",
+            "isPrerelease": false,
+            "releaseAssets": {
+                "nodes": [{"name": "Godot_v4.3-stable_linux.x86_64.zip", "downloadUrl": "https://example.com/4.3.zip"}],
+                "pageInfo": {"hasNextPage": false, "endCursor": null}
+            }
+        }],
+        "pageInfo": {"hasNextPage": false, "endCursor": null}
+    }}}}"#;
+
+    const REST_RELEASE: &str = r#"{
+        "tag_name": "4.2-stable",
+        "prerelease": false,
+        "assets": [{"name": "Godot_v4.2-stable_linux.x86_64.zip", "browser_download_url": "https://example.com/4.2.zip"}]
+    }"#;
+
+    fn json(mock: Mock, body: &str) -> Mock {
+        mock.with_header("content-type", "application/json")
+            .with_body(body)
+            .create()
+    }
+
+    /// Mock the REST API listing [REST_RELEASE], only accepting requests
+    /// with the `authorization` header matching `authorization`.
+    fn mock_rest(server: &mut Server, authorization: Matcher) -> Vec<Mock> {
+        [("1", format!("[{}]", REST_RELEASE)), ("2", "[]".to_owned())]
+            .into_iter()
+            .map(|(page, body)| {
+                let mock = server
+                    .mock("GET", RELEASES_PATH)
+                    .match_query(Matcher::AllOf(vec![
+                        Matcher::UrlEncoded("per_page".into(), "100".into()),
+                        Matcher::UrlEncoded("page".into(), page.into()),
+                    ]))
+                    .match_header("authorization", authorization.clone());
+
+                json(mock, &body)
+            })
+            .collect()
+    }
+
+    fn tags(github: &GitHub) -> Vec<String> {
+        github
+            .fetch_releases(|_| false)
+            .unwrap()
+            .into_iter()
+            .map(|release| release.tag_name)
+            .collect()
+    }
+
+    #[test]
+    fn test_graphql() {
+        let mut server = Server::new();
+        let graphql = json(
+            server
+                .mock("POST", "/graphql")
+                .match_header("authorization", "Bearer token"),
+            GRAPHQL_RELEASES,
+        );
+        let github = GitHub::new(server.url(), Some("token".to_owned()));
+
+        let releases = github.fetch_releases(|_| false).unwrap();
 
-    Ok(json.data.unwrap())
+        assert_eq!(releases.len(), 1);
+        assert_eq!(releases[0].tag_name, "4.3-stable");
+        assert_eq!(
+            releases[0].assets[0].download_url.0,
+            "https://example.com/4.3.zip"
+        );
+        graphql.assert();
+    }
+
+    #[test]
+    fn test_rest_without_token() {
+        let mut server = Server::new();
+        let graphql = server.mock("POST", "/graphql").expect(0).create();
+        let rest = mock_rest(&mut server, Matcher::Missing);
+
+        assert_eq!(tags(&GitHub::new(server.url(), None)), ["4.2-stable"]);
+        graphql.assert();
+        rest.iter().for_each(Mock::assert);
+    }
+
+    #[test]
+    fn test_graphql_errors_fall_back_to_rest() {
+        let mut server = Server::new();
+        let graphql = json(
+            server.mock("POST", "/graphql"),
+            r#"{"data": null, "errors": [{"message": "API rate limit exceeded"}]}"#,
+        );
+        let rest = mock_rest(&mut server, "Bearer token".into());
+
+        let github = GitHub::new(server.url(), Some("token".to_owned()));
+
+        assert_eq!(tags(&github), ["4.2-stable"]);
+        graphql.assert();
+        rest.iter().for_each(Mock::assert);
+    }
+
+    #[test]
+    fn test_rejected_token_falls_back_to_anonymous_rest() {
+        let mut server = Server::new();
+        let graphql = server.mock("POST", "/graphql").with_status(401).create();
+        let rest = mock_rest(&mut server, Matcher::Missing);
+
+        let github = GitHub::new(server.url(), Some("bad".to_owned()));
+
+        assert_eq!(tags(&github), ["4.2-stable"]);
+        graphql.assert();
+        rest.iter().for_each(Mock::assert);
+    }
+
+    #[test]
+    fn test_missing_release_falls_back_to_rest() {
+        let mut server = Server::new();
+        let graphql = json(
+            server.mock("POST", "/graphql"),
+            r#"{"data": {"repository": {"release": null}}}"#,
+        );
+        let rest = json(
+            server.mock("GET", format!("{}/tags/4.2-stable", RELEASES_PATH).as_str()),
+            REST_RELEASE,
+        );
+
+        let github = GitHub::new(server.url(), Some("token".to_owned()));
+        let assets = github.fetch_version_assets("4.2-stable").unwrap();
+
+        assert_eq!(assets.len(), 1);
+        assert_eq!(assets[0].name, "Godot_v4.2-stable_linux.x86_64.zip");
+        graphql.assert();
+        rest.assert();
+    }
+
+    // the only test that touches the environment, so that tests running in
+    // parallel don't see each other's variables
+    #[test]
+    fn test_token_from_env() {
+        let mut server = Server::new();
+
+        for var in ["GDTK_GITHUB_TOKEN", "GITHUB_TOKEN", "GH_TOKEN"] {
+            std::env::remove_var(var);
+        }
+
+        std::env::set_var("GDTK_GITHUB_API_URL", server.url());
+
+        for (var, token) in [("GH_TOKEN", "gh"), ("GITHUB_TOKEN", "github")] {
+            std::env::set_var(var, token);
+
+            let graphql = json(
+                server
+                    .mock("POST", "/graphql")
+                    .match_header("authorization", format!("Bearer {}", token).as_str()),
+                GRAPHQL_RELEASES,
+            );
+
+            assert_eq!(tags(&GitHub::from_env()), ["4.3-stable"]);
+            graphql.assert();
+        }
+    }
 }
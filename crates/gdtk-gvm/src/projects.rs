@@ -0,0 +1,67 @@
+//! A list of projects that pin a Godot version, kept so that versions
+//! still in use can be told apart from unused ones.
+
+use std::path::{Path, PathBuf};
+
+use gdtk_paths::camino::Utf8Path;
+
+/// Remember a project folder.
+pub fn register_project(path: &Path) -> Result<(), crate::Error> {
+    let Some(path) = path.to_str() else {
+        // the file is line-based, and such paths are rare enough to not bother
+        return Ok(());
+    };
+
+    let file = gdtk_paths::projects_path()?;
+
+    // usually the project is already known, which doesn't need the lock
+    if read_projects(&file)?.lines().any(|line| line == path) {
+        return Ok(());
+    }
+
+    // shims register projects whenever they run, possibly many at once
+    let _lock = crate::utils::lock_exclusive(file.with_extension("lock"))?;
+
+    // another process might have registered it in the meantime
+    let mut content = read_projects(&file)?;
+
+    if content.lines().any(|line| line == path) {
+        return Ok(());
+    }
+
+    content.push_str(path);
+    content.push('\n');
+
+    // write the whole file first, so that it's never left half-written
+    let temp = file.with_extension("tmp");
+
+    std::fs::write(&temp, content)?;
+    std::fs::rename(&temp, &file)?;
+
+    Ok(())
+}
+
+/// The contents of the projects file, which doesn't exist until a project
+/// is registered.
+fn read_projects(file: &Utf8Path) -> Result<String, crate::Error> {
+    match std::fs::read_to_string(file) {
+        Ok(content) => Ok(content),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(String::new()),
+        Err(error) => Err(error.into()),
+    }
+}
+
+/// All remembered project folders that still exist.
+pub fn registered_projects() -> Result<Vec<PathBuf>, crate::Error> {
+    let file = gdtk_paths::projects_path()?;
+
+    if !file.exists() {
+        return Ok(vec![]);
+    }
+
+    Ok(std::fs::read_to_string(&file)?
+        .lines()
+        .map(PathBuf::from)
+        .filter(|path| path.is_dir())
+        .collect())
+}
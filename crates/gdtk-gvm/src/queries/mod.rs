@@ -2,5 +2,10 @@
 mod schema {}
 pub mod release_assets;
 pub mod releases;
+pub mod rest;
 
-pub const GITHUB_GRAPHQL_API: &str = "https://api.github.com/graphql";
+/// The default base URL of the GitHub API.
+pub const GITHUB_API: &str = "https://api.github.com";
+
+/// The repository Godot builds are published to.
+pub const GODOT_BUILDS_REPO: &str = "godotengine/godot-builds";
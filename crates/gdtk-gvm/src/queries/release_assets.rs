@@ -33,7 +33,9 @@ pub struct ReleaseAssetConnection {
     pub page_info: PageInfo,
 }
 
-#[derive(cynic::QueryFragment, Debug)]
+#[derive(
+    cynic::QueryFragment, Debug, Clone, PartialEq, rkyv::Archive, rkyv::Deserialize, rkyv::Serialize,
+)]
 pub struct ReleaseAsset {
     pub name: String,
     pub download_url: Uri,
@@ -45,6 +47,8 @@ pub struct PageInfo {
     pub end_cursor: Option<String>,
 }
 
-#[derive(cynic::Scalar, Debug, Clone)]
+#[derive(
+    cynic::Scalar, Debug, Clone, PartialEq, rkyv::Archive, rkyv::Deserialize, rkyv::Serialize,
+)]
 #[cynic(graphql_type = "URI")]
 pub struct Uri(pub String);
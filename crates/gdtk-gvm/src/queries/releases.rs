@@ -1,4 +1,4 @@
-use super::schema;
+use super::{release_assets::ReleaseAssetConnection, schema};
 
 #[derive(cynic::QueryVariables, Debug)]
 pub struct ReleasesQueryVariables<'a> {
@@ -15,7 +15,7 @@ pub struct ReleasesQuery {
 #[derive(cynic::QueryFragment, Debug)]
 #[cynic(variables = "ReleasesQueryVariables")]
 pub struct Repository {
-    #[arguments(first: 100, after: $after)]
+    #[arguments(first: 100, after: $after, orderBy: { field: "CREATED_AT", direction: "DESC" })]
     pub releases: ReleaseConnection,
 }
 
@@ -25,10 +25,12 @@ pub struct ReleaseConnection {
     pub page_info: PageInfo,
 }
 
-#[derive(cynic::QueryFragment, Debug, PartialEq)]
+#[derive(cynic::QueryFragment, Debug)]
 pub struct Release {
     pub tag_name: String,
     pub is_prerelease: bool,
+    #[arguments(first: 100)]
+    pub release_assets: ReleaseAssetConnection,
 }
 
 #[derive(cynic::QueryFragment, Debug)]
@@ -0,0 +1,42 @@
+//! Types for the GitHub REST API, which (unlike the GraphQL API) can be used
+//! without authentication.
+
+use super::release_assets::{ReleaseAsset as GraphQLReleaseAsset, Uri};
+use crate::index::CachedRelease;
+
+#[derive(serde::Deserialize, Debug)]
+pub struct Release {
+    pub tag_name: String,
+    pub prerelease: bool,
+    #[serde(default)]
+    pub assets: Vec<ReleaseAsset>,
+}
+
+#[derive(serde::Deserialize, Debug)]
+pub struct ReleaseAsset {
+    pub name: String,
+    pub browser_download_url: String,
+}
+
+impl From<Release> for CachedRelease {
+    fn from(value: Release) -> Self {
+        Self {
+            tag_name: value.tag_name,
+            is_prerelease: value.prerelease,
+            assets: value
+                .assets
+                .into_iter()
+                .map(GraphQLReleaseAsset::from)
+                .collect(),
+        }
+    }
+}
+
+impl From<ReleaseAsset> for GraphQLReleaseAsset {
+    fn from(value: ReleaseAsset) -> Self {
+        Self {
+            name: value.name,
+            download_url: Uri(value.browser_download_url),
+        }
+    }
+}
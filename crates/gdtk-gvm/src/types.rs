@@ -1,10 +1,14 @@
-use std::fmt::Display;
+use std::{env::consts::EXE_SUFFIX, fmt::Display};
 
 use gdtk_paths::camino::Utf8PathBuf;
 
-/// Represents a `versions.toml` file.
-#[derive(Debug, Clone, rkyv::Archive, rkyv::Deserialize, rkyv::Serialize)]
-pub struct LocalVersions(pub Vec<LocalVersion>);
+/// Represents the `local_versions` file. See [crate::database] for its format.
+#[derive(Debug, Clone, Default, rkyv::Archive, rkyv::Deserialize, rkyv::Serialize)]
+pub struct LocalVersions {
+    pub versions: Vec<LocalVersion>,
+    /// The name of the default version and whether it's the mono variant.
+    pub default: Option<(String, bool)>,
+}
 
 #[derive(Debug, Clone, rkyv::Archive, rkyv::Deserialize, rkyv::Serialize, tabled::Tabled)]
 pub struct LocalVersion {
@@ -15,12 +19,102 @@ pub struct LocalVersion {
     pub path: String,
     #[tabled(skip)]
     pub mono: bool,
+    /// The SHA-512 checksum of the archive this version was installed from.
+    #[tabled(skip)]
+    pub sha512: Option<String>,
+    #[tabled(rename = "Source")]
+    pub origin: VersionOrigin,
+}
+
+/// How a version got onto this machine.
+#[derive(Debug, Clone, rkyv::Archive, rkyv::Deserialize, rkyv::Serialize)]
+pub enum VersionOrigin {
+    /// Installed by gdtk, which owns the installation folder.
+    Installed,
+    /// An existing binary or folder registered with `gdtk godot link`. Its
+    /// files are not managed by gdtk.
+    Linked {
+        /// Where the build came from, e.g. a repository URL.
+        source: Option<String>,
+        /// The engine commit the build was made from.
+        commit: Option<String>,
+    },
+}
+
+impl Display for VersionOrigin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VersionOrigin::Installed => f.write_str("installed"),
+            VersionOrigin::Linked { source, commit } => {
+                f.write_str("linked")?;
+
+                if let Some(source) = source {
+                    write!(f, " from {}", source)?;
+                }
+
+                if let Some(commit) = commit {
+                    write!(f, " at {}", commit)?;
+                }
+
+                Ok(())
+            }
+        }
+    }
 }
 
 impl LocalVersion {
     pub fn path(&self) -> Utf8PathBuf {
         Utf8PathBuf::from(&self.path)
     }
+
+    /// The folder this version lives in. Linked versions may point directly
+    /// at a binary, in which case this is the folder containing it.
+    pub fn folder(&self) -> Utf8PathBuf {
+        let path = self.path();
+
+        match path.parent() {
+            Some(parent) if path.is_file() => parent.to_owned(),
+            _ => path,
+        }
+    }
+
+    /// The path to the Godot executable.
+    pub fn executable(&self) -> Utf8PathBuf {
+        let path = self.path();
+
+        if path.is_file() {
+            path
+        } else {
+            path.join(format!("godot{}", EXE_SUFFIX))
+        }
+    }
+
+    /// The path to the console wrapper of the Godot executable, which only
+    /// exists on Windows. Elsewhere, this is the same as [LocalVersion::executable].
+    pub fn console_executable(&self) -> Utf8PathBuf {
+        let console = self.folder().join(format!("godot_console{}", EXE_SUFFIX));
+
+        if console.is_file() {
+            console
+        } else {
+            self.executable()
+        }
+    }
+
+    /// Whether gdtk owns the files of this version and may delete them.
+    pub fn is_owned(&self) -> bool {
+        matches!(self.origin, VersionOrigin::Installed)
+    }
+
+    /// The directory export templates are installed into. Godot is installed in
+    /// self-contained mode, so it's inside the installation folder.
+    pub fn templates_path(&self) -> Utf8PathBuf {
+        self.folder().join("editor_data").join("export_templates")
+    }
+
+    pub fn as_ordered(&self) -> impl Ord {
+        versions::Versioning::new(&self.name)
+    }
 }
 
 impl Display for LocalVersion {
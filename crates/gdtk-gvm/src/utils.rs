@@ -1,4 +1,6 @@
-use std::fmt::Display;
+use std::{fmt::Display, fs::File, path::Path};
+
+use fs4::fs_std::FileExt;
 
 use crate::{queries::release_assets::ReleaseAsset, types::LocalVersions};
 
@@ -12,6 +14,51 @@ pub fn coerce_version<V: Display>(input: &str, pool: Vec<V>) -> Result<Vec<V>, c
     Ok(matches_)
 }
 
+/// Find the checksum of `file` in a `sha512sum`-style list (`<hash>  <file>`
+/// on each line).
+pub fn find_checksum(sums: &str, file: &str) -> Option<String> {
+    sums.lines().find_map(|line| {
+        let (hash, name) = line.split_once(char::is_whitespace)?;
+
+        // binary mode entries are prefixed with `*`
+        (name.trim_start().trim_start_matches('*') == file).then(|| hash.to_ascii_lowercase())
+    })
+}
+
+/// Compute the hex-encoded SHA-512 checksum of everything `reader` yields.
+pub fn sha512(mut reader: impl std::io::Read) -> Result<String, crate::Error> {
+    use sha2::Digest;
+
+    let mut hasher = sha2::Sha512::new();
+
+    std::io::copy(&mut reader, &mut hasher)?;
+
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
+}
+
+/// Ensure that the `asset` read from `reader` has the `expected` SHA-512 checksum.
+pub fn verify_checksum(
+    reader: impl std::io::Read,
+    expected: &str,
+    asset: &str,
+) -> Result<(), crate::Error> {
+    let actual = sha512(reader)?;
+
+    if actual != expected {
+        return Err(crate::Error::ChecksumMismatch {
+            asset: asset.to_owned(),
+            expected: expected.to_owned(),
+            actual,
+        });
+    }
+
+    Ok(())
+}
+
 pub fn pick_asset(assets: &[ReleaseAsset], mono: bool) -> Option<&ReleaseAsset> {
     // something something consistency
     // see https://github.com/godotengine/godot-builds/issues/5
@@ -40,14 +87,21 @@ pub fn pick_asset(assets: &[ReleaseAsset], mono: bool) -> Option<&ReleaseAsset>
     assets.iter().find(|asset| asset.name.ends_with(suffix))
 }
 
+pub fn pick_templates_asset(assets: &[ReleaseAsset], mono: bool) -> Option<&ReleaseAsset> {
+    assets.iter().find(|asset| {
+        asset.name.ends_with("_export_templates.tpz")
+            && asset.name.ends_with("_mono_export_templates.tpz") == mono
+    })
+}
+
 /// If the given path does not exist, create an empty [crate::types::LocalVersions] instance,
 /// write it to that path, and return that instance.
 pub fn maybe_create_local_versions(
     path: &std::path::Path,
 ) -> Result<Option<LocalVersions>, crate::Error> {
     if !path.exists() {
-        let data = LocalVersions(vec![]);
-        let encoded = rkyv::to_bytes::<rkyv::rancor::Error>(&data)?;
+        let data = LocalVersions::default();
+        let encoded = crate::database::encode(&data)?;
 
         std::fs::write(path, encoded)?;
 
@@ -56,3 +110,30 @@ pub fn maybe_create_local_versions(
 
     Ok(None)
 }
+
+/// Wait for and take an exclusive advisory lock on the file at `path`,
+/// creating it if needed. The lock is released when the file is dropped.
+pub fn lock_exclusive(path: impl AsRef<Path>) -> Result<File, crate::Error> {
+    let file = open_lock_file(path)?;
+
+    file.lock_exclusive()?;
+
+    Ok(file)
+}
+
+/// Like [lock_exclusive], but other shared locks can be held at the same time.
+pub fn lock_shared(path: impl AsRef<Path>) -> Result<File, crate::Error> {
+    let file = open_lock_file(path)?;
+
+    file.lock_shared()?;
+
+    Ok(file)
+}
+
+fn open_lock_file(path: impl AsRef<Path>) -> Result<File, crate::Error> {
+    Ok(File::options()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(path)?)
+}
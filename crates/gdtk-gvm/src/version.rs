@@ -1,11 +1,11 @@
 use versions::Version as SemanticVersion;
 
-use crate::queries::releases::Release as GraphQLRelease;
+use crate::{index::CachedRelease, queries::release_assets::ReleaseAsset};
 
 #[derive(PartialEq, Debug)]
 pub struct OnlineVersion {
     semantic: SemanticVersion,
-    data: GraphQLRelease,
+    data: CachedRelease,
 }
 
 impl OnlineVersion {
@@ -17,6 +17,10 @@ impl OnlineVersion {
         self.data.is_prerelease
     }
 
+    pub fn assets(&self) -> &[ReleaseAsset] {
+        &self.data.assets
+    }
+
     pub fn as_ordered(&self) -> &impl Ord {
         &self.semantic
     }
@@ -28,8 +32,8 @@ impl std::fmt::Display for OnlineVersion {
     }
 }
 
-impl From<GraphQLRelease> for OnlineVersion {
-    fn from(value: GraphQLRelease) -> Self {
+impl From<CachedRelease> for OnlineVersion {
+    fn from(value: CachedRelease) -> Self {
         Self {
             semantic: SemanticVersion::new(&value.tag_name).expect("a valid version"),
             data: value,
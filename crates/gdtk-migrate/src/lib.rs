@@ -0,0 +1,246 @@
+//! Migration of GDScript code from Godot 3 to Godot 4.
+//!
+//! Godot 3 syntax isn't supported by the parser, so the migration works on
+//! tokens instead, rewriting spans of the source. Rewrites of a single pass
+//! never overlap: a rewrite that overlaps an earlier one, e.g. the inner
+//! `yield` of `yield(yield(a, "b"), "c")`, is left for the next pass, which
+//! runs on the output of the previous one.
+
+pub mod rules;
+#[cfg(test)]
+mod tests;
+pub mod utils;
+
+use gdtk_gdscript_parser::lexer::{Token, TokenKind};
+use gdtk_span::Span;
+use logos::Logos;
+
+/// The result of [migrate].
+pub struct Migration {
+    /// The migrated source.
+    pub output: String,
+    /// All changes made, in source order.
+    pub changes: Vec<Change>,
+}
+
+/// A change made by the migration.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    /// The span of the original source that was changed.
+    pub span: Span,
+    pub message: String,
+    pub kind: ChangeKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ChangeKind {
+    /// The code was rewritten to its Godot 4 equivalent.
+    Rewritten,
+    /// The code couldn't be translated, and a TODO comment was left above it.
+    Todo,
+}
+
+/// Migrate Godot 3 code to Godot 4.
+pub fn migrate(input: &str) -> Migration {
+    let mut output = input.to_owned();
+    let mut changes = vec![];
+    let mut passes: Vec<Vec<Applied>> = vec![];
+
+    loop {
+        let pass = run_pass(&output);
+
+        // report spans of the input, not of the intermediate output
+        for mut change in pass.changes {
+            for applied in passes.iter().rev() {
+                change.span = map_back(applied, change.span.start, false)
+                    ..map_back(applied, change.span.end, true);
+            }
+
+            changes.push(change);
+        }
+
+        output = pass.output;
+
+        if pass.complete {
+            break;
+        }
+
+        passes.push(pass.applied);
+    }
+
+    changes.sort_by_key(|change| (change.span.start, change.span.end));
+
+    Migration { output, changes }
+}
+
+fn run_pass(input: &str) -> Pass {
+    let mut migrator = Migrator::new(input);
+
+    for idx in 0..migrator.tokens.len() {
+        rules::annotations::tool(&mut migrator, idx);
+        rules::annotations::onready(&mut migrator, idx);
+        rules::annotations::export(&mut migrator, idx);
+        rules::annotations::rpc(&mut migrator, idx);
+        rules::setget::setget(&mut migrator, idx);
+        rules::yield_::yield_(&mut migrator, idx);
+        rules::callables::funcref(&mut migrator, idx);
+        rules::callables::connect(&mut migrator, idx);
+        rules::callables::connect_flags(&mut migrator, idx);
+    }
+
+    migrator.finish()
+}
+
+/// Map `offset` in the output of a pass back to its input. Offsets inside a
+/// replacement map to the start of the replaced span, or to its end if `end`
+/// is set.
+fn map_back(applied: &[Applied], offset: usize, end: bool) -> usize {
+    let (mut output_end, mut input_end) = (0, 0);
+
+    for edit in applied {
+        if offset < edit.output.start {
+            break;
+        }
+
+        if offset < edit.output.end {
+            return if end {
+                edit.input.end
+            } else {
+                edit.input.start
+            };
+        }
+
+        output_end = edit.output.end;
+        input_end = edit.input.end;
+    }
+
+    offset - output_end + input_end
+}
+
+/// The state of a migration.
+pub struct Migrator<'s> {
+    pub source: &'s str,
+    /// Tokens of the source, without blanks and comments.
+    pub tokens: Vec<Token<'s>>,
+    edits: Vec<Edit>,
+}
+
+/// The result of a single pass of [migrate].
+struct Pass {
+    output: String,
+    changes: Vec<Change>,
+    applied: Vec<Applied>,
+    /// Whether no edits had to be left for the next pass.
+    complete: bool,
+}
+
+/// An edit that was made by a pass.
+struct Applied {
+    /// The replaced span of the input.
+    input: Span,
+    /// The replacement's span of the output.
+    output: Span,
+}
+
+struct Edit {
+    span: Span,
+    replacement: String,
+    change: Change,
+}
+
+impl<'s> Migrator<'s> {
+    pub fn new(source: &'s str) -> Self {
+        let tokens = TokenKind::lexer(source)
+            .spanned()
+            .filter_map(|(result, span)| result.ok().map(|kind| Token { span, kind }))
+            .filter(|token| !matches!(token.kind, TokenKind::Blank(_) | TokenKind::Comment(_)))
+            .collect();
+
+        Self {
+            source,
+            tokens,
+            edits: vec![],
+        }
+    }
+
+    /// Replace `span` with `replacement`.
+    pub fn rewrite(&mut self, span: Span, replacement: String, message: impl Into<String>) {
+        self.edits.push(Edit {
+            change: Change {
+                span: span.clone(),
+                message: message.into(),
+                kind: ChangeKind::Rewritten,
+            },
+            span,
+            replacement,
+        });
+    }
+
+    /// Leave a TODO comment above the line of `span`, unless it's already
+    /// there.
+    pub fn todo(&mut self, span: Span, message: impl Into<String>) {
+        let message = message.into();
+        let line_start = utils::line_start(self.source, span.start);
+        let indent = utils::indent(self.source, span.start);
+        let comment = format!("{indent}# TODO(migrate): {message}\n");
+
+        // left by an earlier pass or run
+        if self.source[..line_start].ends_with(&comment) {
+            return;
+        }
+
+        self.edits.push(Edit {
+            span: line_start..line_start,
+            replacement: comment,
+            change: Change {
+                span,
+                message,
+                kind: ChangeKind::Todo,
+            },
+        });
+    }
+
+    /// The source text of `span`.
+    pub fn text(&self, span: &Span) -> &'s str {
+        &self.source[span.clone()]
+    }
+
+    fn finish(mut self) -> Pass {
+        self.edits
+            .sort_by_key(|edit| (edit.span.start, edit.span.end));
+
+        let mut output = String::with_capacity(self.source.len());
+        let mut changes = vec![];
+        let mut applied = vec![];
+        let mut complete = true;
+        let mut position = 0;
+
+        for edit in self.edits {
+            if edit.span.start < position {
+                complete = false;
+                continue;
+            }
+
+            output.push_str(&self.source[position..edit.span.start]);
+
+            let start = output.len();
+            output.push_str(&edit.replacement);
+            position = edit.span.end;
+
+            applied.push(Applied {
+                input: edit.span,
+                output: start..output.len(),
+            });
+            changes.push(edit.change);
+        }
+
+        output.push_str(&self.source[position..]);
+
+        Pass {
+            output,
+            changes,
+            applied,
+            complete,
+        }
+    }
+}
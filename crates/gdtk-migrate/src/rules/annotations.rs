@@ -0,0 +1,221 @@
+//! Keywords that became annotations.
+
+use gdtk_gdscript_parser::lexer::TokenKind;
+
+use crate::{
+    utils::{group, is_keyword, is_line_start},
+    Migrator,
+};
+
+/// `tool` -> `@tool`.
+pub fn tool(m: &mut Migrator<'_>, idx: usize) {
+    if !is_keyword(&m.tokens, idx, "tool") || !is_line_start(&m.tokens, idx) {
+        return;
+    }
+
+    if m.tokens.get(idx + 1).is_some_and(|t| !t.kind.is_newline()) {
+        return;
+    }
+
+    m.rewrite(
+        m.tokens[idx].span.clone(),
+        "@tool".to_owned(),
+        "`tool` is now the `@tool` annotation.",
+    );
+}
+
+/// `onready var` -> `@onready var`.
+pub fn onready(m: &mut Migrator<'_>, idx: usize) {
+    if !is_keyword(&m.tokens, idx, "onready") {
+        return;
+    }
+
+    let before_var = m.tokens.get(idx + 1).is_some_and(|t| t.kind.is_var())
+        || (idx + 1 < m.tokens.len() && is_keyword(&m.tokens, idx + 1, "export"));
+
+    if !before_var {
+        return;
+    }
+
+    m.rewrite(
+        m.tokens[idx].span.clone(),
+        "@onready".to_owned(),
+        "`onready` is now the `@onready` annotation.",
+    );
+}
+
+/// `export(Type, hints...) var` -> `@export_*(hints...) var: Type`.
+pub fn export(m: &mut Migrator<'_>, idx: usize) {
+    if !is_keyword(&m.tokens, idx, "export") {
+        return;
+    }
+
+    let keyword = m.tokens[idx].span.clone();
+
+    if m.tokens.get(idx + 1).is_some_and(|t| t.kind.is_var()) {
+        m.rewrite(
+            keyword,
+            "@export".to_owned(),
+            "`export` is now the `@export` annotation.",
+        );
+
+        return;
+    }
+
+    let Some(group) = group(&m.tokens, idx + 1) else {
+        return;
+    };
+
+    let span = keyword.start..group.span.end;
+    let original = m.text(&span);
+    let args = group.args.iter().map(|arg| m.text(arg)).collect::<Vec<_>>();
+
+    let Some((annotation, typehint)) = translate_export(&args) else {
+        m.todo(span, format!("Couldn't translate `{original}`."));
+        return;
+    };
+
+    m.rewrite(
+        span,
+        annotation.clone(),
+        format!("`{original}` is now `{annotation}`."),
+    );
+
+    // `export(...) onready var` is valid too
+    let var = (group.end + 1..m.tokens.len()).find(|&i| !is_keyword(&m.tokens, i, "onready"));
+
+    if let (Some(var), Some(typehint)) = (var, typehint) {
+        if m.tokens[var].kind.is_var() {
+            add_typehint(m, var + 1, &typehint);
+        }
+    }
+}
+
+/// Translate the arguments of a Godot 3 `export` into an annotation and
+/// a type hint.
+fn translate_export(args: &[&str]) -> Option<(String, Option<String>)> {
+    let Some((typ, hints)) = args.split_first() else {
+        return Some(("@export".to_owned(), None));
+    };
+
+    let annotation = match hints {
+        [] => "@export".to_owned(),
+        [element] if *typ == "Array" => {
+            return Some(("@export".to_owned(), Some(format!("Array[{element}]"))));
+        }
+        ["FILE", "GLOBAL", rest @ ..] => with_args("@export_global_file", rest),
+        ["FILE", rest @ ..] => with_args("@export_file", rest),
+        ["DIR", "GLOBAL"] => "@export_global_dir".to_owned(),
+        ["DIR"] => "@export_dir".to_owned(),
+        ["MULTILINE"] => "@export_multiline".to_owned(),
+        ["FLAGS", rest @ ..] => with_args("@export_flags", rest),
+        [layers] if layers.starts_with("LAYERS_") => format!(
+            "@export_flags_{}",
+            layers.trim_start_matches("LAYERS_").to_lowercase()
+        ),
+        ["EASE"] => "@export_exp_easing".to_owned(),
+        ["EXP", rest @ ..] if !rest.is_empty() && rest.iter().all(is_number) => {
+            format!("@export_range({}, \"exp\")", rest.join(", "))
+        }
+        ["RGB"] => "@export_color_no_alpha".to_owned(),
+        ["RGBA"] => "@export".to_owned(),
+        [max] if is_number(max) => format!("@export_range(0, {max})"),
+        [_, _] | [_, _, _] if hints.iter().all(is_number) => with_args("@export_range", hints),
+        hints if hints.iter().all(is_string) => with_args("@export_enum", hints),
+        _ => return None,
+    };
+
+    Some((annotation, Some((*typ).to_owned())))
+}
+
+fn with_args(annotation: &str, args: &[&str]) -> String {
+    match args {
+        [] => annotation.to_owned(),
+        args => format!("{}({})", annotation, args.join(", ")),
+    }
+}
+
+fn is_number(text: &&str) -> bool {
+    text.parse::<f64>().is_ok()
+}
+
+fn is_string(text: &&str) -> bool {
+    text.starts_with(['"', '\''])
+}
+
+/// Add a type hint to the variable whose name is at `idx`, unless it has one.
+fn add_typehint(m: &mut Migrator<'_>, idx: usize, typehint: &str) {
+    let Some(name) = m.tokens.get(idx).filter(|t| t.kind.is_identifier()) else {
+        return;
+    };
+    let name = name.span.clone();
+
+    match m.tokens.get(idx + 1).map(|t| (&t.kind, t.span.clone())) {
+        // `var x := value`
+        Some((TokenKind::Colon, colon)) => {
+            let Some(assignment) = m.tokens.get(idx + 2) else {
+                return;
+            };
+
+            if assignment.kind.is_assignment() && assignment.span.start == colon.end {
+                let span = name.end..assignment.span.end;
+
+                m.rewrite(
+                    span,
+                    format!(": {typehint} ="),
+                    format!("Added the exported type `{typehint}` as a type hint."),
+                );
+            }
+        }
+        _ => m.rewrite(
+            name.end..name.end,
+            format!(": {typehint}"),
+            format!("Added the exported type `{typehint}` as a type hint."),
+        ),
+    }
+}
+
+/// `remote func` -> `@rpc("any_peer") func`, and so on.
+pub fn rpc(m: &mut Migrator<'_>, idx: usize) {
+    let Some((keyword, annotation)) = RPC_KEYWORDS
+        .iter()
+        .find(|(keyword, _)| is_keyword(&m.tokens, idx, keyword))
+    else {
+        return;
+    };
+
+    let span = m.tokens[idx].span.clone();
+
+    match m.tokens.get(idx + 1).map(|t| &t.kind) {
+        Some(TokenKind::Func) => {
+            m.rewrite(
+                span.clone(),
+                (*annotation).to_owned(),
+                format!("`{keyword}` is now `{annotation}`."),
+            );
+
+            if keyword.starts_with("master") {
+                m.todo(
+                    span,
+                    "`master` functions could only run on the network master. Check `multiplayer.get_remote_sender_id()` if that matters.",
+                );
+            }
+        }
+        Some(TokenKind::Var) => m.todo(
+            span,
+            format!("`{keyword}` variables have no Godot 4 equivalent. Use an `@rpc` function to set them."),
+        ),
+        _ => (),
+    }
+}
+
+/// Godot 3 RPC keywords and their Godot 4 annotations.
+const RPC_KEYWORDS: &[(&str, &str)] = &[
+    ("remote", "@rpc(\"any_peer\")"),
+    ("remotesync", "@rpc(\"any_peer\", \"call_local\")"),
+    ("sync", "@rpc(\"any_peer\", \"call_local\")"),
+    ("master", "@rpc(\"any_peer\")"),
+    ("mastersync", "@rpc(\"any_peer\", \"call_local\")"),
+    ("puppet", "@rpc"),
+    ("puppetsync", "@rpc(\"call_local\")"),
+];
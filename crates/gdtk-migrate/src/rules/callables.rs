@@ -0,0 +1,115 @@
+//! `FuncRef`s and object-method pairs that became `Callable`s.
+
+use gdtk_gdscript_parser::lexer::TokenKind;
+
+use crate::{
+    utils::{as_string, group, is_declared_or_accessed},
+    Migrator,
+};
+
+/// `funcref(obj, "method")` -> `Callable(obj, "method")`, `FuncRef` ->
+/// `Callable` and `ref.call_func()` -> `ref.call()`.
+pub fn funcref(m: &mut Migrator<'_>, idx: usize) {
+    let before_call = m
+        .tokens
+        .get(idx + 1)
+        .is_some_and(|t| t.kind.is_opening_parenthesis());
+    let after_period = idx > 0 && m.tokens[idx - 1].kind.is_period();
+
+    let (replacement, message) = match m.tokens[idx].kind {
+        TokenKind::Identifier("funcref")
+            if before_call && !is_declared_or_accessed(&m.tokens, idx) =>
+        {
+            ("Callable", "`funcref` is now `Callable`.")
+        }
+        TokenKind::Identifier("FuncRef") if !is_declared_or_accessed(&m.tokens, idx) => {
+            ("Callable", "`FuncRef` is now `Callable`.")
+        }
+        TokenKind::Identifier("call_func") if before_call && after_period => {
+            ("call", "`FuncRef.call_func` is now `Callable.call`.")
+        }
+        _ => return,
+    };
+
+    m.rewrite(m.tokens[idx].span.clone(), replacement.to_owned(), message);
+}
+
+/// `connect("signal", obj, "method", [binds], flags)` ->
+/// `connect("signal", Callable(obj, "method").bind(binds), flags)`, and the same
+/// for `disconnect` and `is_connected`.
+pub fn connect(m: &mut Migrator<'_>, idx: usize) {
+    let TokenKind::Identifier(name @ ("connect" | "disconnect" | "is_connected")) =
+        m.tokens[idx].kind
+    else {
+        return;
+    };
+
+    if idx > 0 && m.tokens[idx - 1].kind.is_func() {
+        return;
+    }
+
+    let Some(group) = group(&m.tokens, idx + 1) else {
+        return;
+    };
+
+    // in Godot 4, the third argument is never a string
+    let [_, target, method, rest @ ..] = group.args.as_slice() else {
+        return;
+    };
+
+    if as_string(&m.tokens, method).is_none() {
+        return;
+    }
+
+    let callable = format!("Callable({}, {})", m.text(target), m.text(method));
+
+    let replacement = match (name, rest) {
+        (_, []) => callable,
+        ("connect", [binds]) => bind(callable, m.text(binds)),
+        ("connect", [binds, flags]) => {
+            let flags = m.text(flags).replace(ONESHOT, ONE_SHOT);
+
+            format!("{}, {flags}", bind(callable, m.text(binds)))
+        }
+        _ => {
+            m.todo(
+                group.span,
+                format!("Couldn't translate this `{name}` call."),
+            );
+            return;
+        }
+    };
+
+    let span = target.start..group.args.last().unwrap().end;
+
+    m.rewrite(
+        span,
+        replacement,
+        format!("`{name}` now takes a `Callable` instead of an object and a method name."),
+    );
+}
+
+const ONESHOT: &str = "CONNECT_ONESHOT";
+const ONE_SHOT: &str = "CONNECT_ONE_SHOT";
+
+/// `CONNECT_ONESHOT` -> `CONNECT_ONE_SHOT`.
+pub fn connect_flags(m: &mut Migrator<'_>, idx: usize) {
+    if m.tokens[idx].kind != TokenKind::Identifier(ONESHOT) {
+        return;
+    }
+
+    m.rewrite(
+        m.tokens[idx].span.clone(),
+        ONE_SHOT.to_owned(),
+        "`CONNECT_ONESHOT` is now `CONNECT_ONE_SHOT`.",
+    );
+}
+
+/// Bind the `binds` array of a Godot 3 `connect` to `callable`.
+fn bind(callable: String, binds: &str) -> String {
+    match binds.strip_prefix('[').and_then(|b| b.strip_suffix(']')) {
+        Some(inner) if inner.trim().is_empty() => callable,
+        Some(inner) => format!("{}.bind({})", callable, inner.trim()),
+        None => format!("{callable}.bindv({binds})"),
+    }
+}
@@ -0,0 +1,4 @@
+pub mod annotations;
+pub mod callables;
+pub mod setget;
+pub mod yield_;
@@ -0,0 +1,48 @@
+use crate::{
+    utils::{indent, indent_unit, is_keyword},
+    Migrator,
+};
+
+/// `var x = 1 setget set_x, get_x` -> `var x = 1:` followed by a
+/// `set = set_x, get = get_x` line.
+pub fn setget(m: &mut Migrator<'_>, idx: usize) {
+    if idx == 0 || !is_keyword(&m.tokens, idx, "setget") {
+        return;
+    }
+
+    let keyword = m.tokens[idx].span.clone();
+    let mut end = idx;
+    let mut accessors = vec![];
+
+    if let Some(setter) = m.tokens.get(end + 1).filter(|t| t.kind.is_identifier()) {
+        accessors.push(format!("set = {}", m.text(&setter.span)));
+        end += 1;
+    }
+
+    if m.tokens.get(end + 1).is_some_and(|t| t.kind.is_comma()) {
+        if let Some(getter) = m.tokens.get(end + 2).filter(|t| t.kind.is_identifier()) {
+            accessors.push(format!("get = {}", m.text(&getter.span)));
+            end += 2;
+        }
+    }
+
+    if accessors.is_empty() {
+        m.todo(keyword, "Couldn't translate this `setget`.");
+        return;
+    }
+
+    // include the whitespace before `setget`
+    let span = m.tokens[idx - 1].span.end..m.tokens[end].span.end;
+    let replacement = format!(
+        ":\n{}{}{}",
+        indent(m.source, keyword.start),
+        indent_unit(m.source),
+        accessors.join(", "),
+    );
+
+    m.rewrite(
+        span,
+        replacement,
+        "`setget` is now a `set`/`get` block. Note that setters and getters are now called inside the class too.",
+    );
+}
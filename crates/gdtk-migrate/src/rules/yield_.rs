@@ -0,0 +1,53 @@
+use crate::{
+    utils::{as_string, group},
+    Migrator,
+};
+
+/// `yield(object, "signal")` -> `await object.signal`, and
+/// `yield(coroutine(), "completed")` -> `await coroutine()`.
+pub fn yield_(m: &mut Migrator<'_>, idx: usize) {
+    if !m.tokens[idx].kind.is_yield() {
+        return;
+    }
+
+    let keyword = m.tokens[idx].span.clone();
+
+    let Some(group) = group(&m.tokens, idx + 1) else {
+        m.todo(keyword, "Couldn't translate this `yield`.");
+        return;
+    };
+
+    let span = keyword.start..group.span.end;
+
+    if let [object, signal] = group.args.as_slice() {
+        if let Some(signal) = as_string(&m.tokens, signal) {
+            let nested = m
+                .tokens
+                .iter()
+                .any(|token| token.span.start == object.start && token.kind.is_yield());
+            let object = m.text(object);
+            let replacement = match signal {
+                "completed" => format!("await {object}"),
+                // a nested `yield` becomes an `await`, which binds looser than `.`
+                signal if nested => format!("await ({object}).{}", renamed_signal(signal)),
+                signal => format!("await {object}.{}", renamed_signal(signal)),
+            };
+
+            m.rewrite(span, replacement, "`yield` is now `await`.");
+            return;
+        }
+    }
+
+    m.todo(
+        span,
+        "`yield` without a signal has no Godot 4 equivalent. Use `await` on a signal instead.",
+    );
+}
+
+/// The Godot 4 name of a built-in signal.
+fn renamed_signal(signal: &str) -> &str {
+    match signal {
+        "idle_frame" => "process_frame",
+        other => other,
+    }
+}
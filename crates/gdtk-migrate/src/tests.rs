@@ -0,0 +1,112 @@
+use crate::{migrate, ChangeKind};
+
+macro_rules! test_eq {
+    ($input: expr, $expected: expr) => {
+        assert_eq!(migrate($input).output, $expected);
+    };
+}
+
+#[test]
+fn test_annotations() {
+    test_eq!("tool\nextends Node\n", "@tool\nextends Node\n");
+    test_eq!("onready var x = $X\n", "@onready var x = $X\n");
+    test_eq!("export var x = 1\n", "@export var x = 1\n");
+    test_eq!("export(int) var x = 1\n", "@export var x: int = 1\n");
+    test_eq!("export(int) var x := 1\n", "@export var x: int = 1\n");
+    test_eq!("export(float) var x: float\n", "@export var x: float\n");
+    test_eq!(
+        "export(int, 0, 10) onready var x\n",
+        "@export_range(0, 10) @onready var x: int\n"
+    );
+    test_eq!(
+        "export(String, FILE, \"*.txt\") var x\n",
+        "@export_file(\"*.txt\") var x: String\n"
+    );
+    test_eq!(
+        "export(String, \"A\", \"B\") var x\n",
+        "@export_enum(\"A\", \"B\") var x: String\n"
+    );
+    test_eq!("export(Array, int) var x\n", "@export var x: Array[int]\n");
+    test_eq!(
+        "remotesync func f():\n\tpass\n",
+        "@rpc(\"any_peer\", \"call_local\") func f():\n\tpass\n"
+    );
+}
+
+#[test]
+fn test_setget() {
+    test_eq!(
+        "var x = 1 setget set_x, get_x\n",
+        "var x = 1:\n\tset = set_x, get = get_x\n"
+    );
+    test_eq!(
+        "class A:\n    var x setget , get_x\n",
+        "class A:\n    var x:\n        get = get_x\n"
+    );
+}
+
+#[test]
+fn test_yield() {
+    test_eq!(
+        "\tyield(get_tree(), \"idle_frame\")\n",
+        "\tawait get_tree().process_frame\n"
+    );
+    test_eq!("var r = yield(f(), \"completed\")\n", "var r = await f()\n");
+}
+
+#[test]
+fn test_overlapping() {
+    let input = "var r = yield(yield(a, \"b\"), \"c\")\n";
+    let migration = migrate(input);
+
+    assert_eq!(migration.output, "var r = await (await a.b).c\n");
+    assert_eq!(migration.changes.len(), 2);
+    assert_eq!(
+        &input[migration.changes[0].span.clone()],
+        "yield(yield(a, \"b\"), \"c\")"
+    );
+    assert_eq!(
+        &input[migration.changes[1].span.clone()],
+        "yield(yield(a, \"b\"), \"c\")"
+    );
+}
+
+#[test]
+fn test_callables() {
+    test_eq!(
+        "connect(\"pressed\", self, \"_on_pressed\")\n",
+        "connect(\"pressed\", Callable(self, \"_on_pressed\"))\n"
+    );
+    test_eq!(
+        "b.connect(\"pressed\", self, \"f\", [1, 2], CONNECT_ONESHOT)\n",
+        "b.connect(\"pressed\", Callable(self, \"f\").bind(1, 2), CONNECT_ONE_SHOT)\n"
+    );
+    test_eq!(
+        "b.connect(\"pressed\", f, CONNECT_DEFERRED | Object.CONNECT_ONESHOT)\n",
+        "b.connect(\"pressed\", f, CONNECT_DEFERRED | Object.CONNECT_ONE_SHOT)\n"
+    );
+    test_eq!(
+        "var r: FuncRef = funcref(self, \"f\")\nr.call_func()\n",
+        "var r: Callable = Callable(self, \"f\")\nr.call()\n"
+    );
+    // already migrated
+    test_eq!(
+        "b.connect(\"pressed\", Callable(self, \"f\"), 0)\n",
+        "b.connect(\"pressed\", Callable(self, \"f\"), 0)\n"
+    );
+}
+
+#[test]
+fn test_todos() {
+    let migration = migrate("func f():\n\tyield()\n");
+
+    assert_eq!(
+        migration.output,
+        "func f():\n\t# TODO(migrate): `yield` without a signal has no Godot 4 equivalent. Use `await` on a signal instead.\n\tyield()\n"
+    );
+    assert_eq!(migration.changes.len(), 1);
+    assert_eq!(migration.changes[0].kind, ChangeKind::Todo);
+
+    // TODOs aren't repeated by later runs
+    test_eq!(&migration.output, migration.output);
+}
@@ -0,0 +1,126 @@
+use gdtk_gdscript_parser::lexer::{Token, TokenKind};
+use gdtk_span::Span;
+
+/// The offset of the start of the line that contains `offset`.
+pub fn line_start(source: &str, offset: usize) -> usize {
+    source[..offset].rfind('\n').map_or(0, |idx| idx + 1)
+}
+
+/// The indentation of the line that contains `offset`.
+pub fn indent(source: &str, offset: usize) -> &str {
+    let line = &source[line_start(source, offset)..];
+    let len = line.len() - line.trim_start_matches([' ', '\t']).len();
+
+    &line[..len]
+}
+
+/// One level of indentation, as used in `source`. Defaults to a tab.
+pub fn indent_unit(source: &str) -> &'static str {
+    let spaces = source
+        .lines()
+        .find(|line| line.starts_with([' ', '\t']))
+        .is_some_and(|line| line.starts_with(' '));
+
+    if spaces {
+        "    "
+    } else {
+        "\t"
+    }
+}
+
+/// Whether the token at `idx` is the first one on its line.
+pub fn is_line_start(tokens: &[Token<'_>], idx: usize) -> bool {
+    idx == 0 || tokens[idx - 1].kind.is_newline()
+}
+
+/// Whether the token at `idx` is the Godot 3 keyword `keyword`. These
+/// keywords don't exist anymore, so they lex as identifiers.
+pub fn is_keyword(tokens: &[Token<'_>], idx: usize, keyword: &str) -> bool {
+    tokens[idx].kind == TokenKind::Identifier(keyword) && !is_declared_or_accessed(tokens, idx)
+}
+
+/// Whether the identifier at `idx` is declared or accessed as a property, e.g.
+/// `var tool` or `obj.tool`, rather than used on its own.
+pub fn is_declared_or_accessed(tokens: &[Token<'_>], idx: usize) -> bool {
+    idx > 0
+        && matches!(
+            tokens[idx - 1].kind,
+            TokenKind::Annotation
+                | TokenKind::Period
+                | TokenKind::Var
+                | TokenKind::Const
+                | TokenKind::Func
+                | TokenKind::Signal
+                | TokenKind::Class
+                | TokenKind::ClassName
+                | TokenKind::Enum
+        )
+}
+
+/// A parenthesized group of comma-separated arguments.
+pub struct Group {
+    /// The span from the opening to the closing parenthesis, inclusive.
+    pub span: Span,
+    /// The index of the closing parenthesis.
+    pub end: usize,
+    /// The spans of the arguments.
+    pub args: Vec<Span>,
+}
+
+/// Parse the group starting with an opening parenthesis at `idx`. Returns
+/// `None` if there's no parenthesis at `idx` or it's unclosed.
+pub fn group(tokens: &[Token<'_>], idx: usize) -> Option<Group> {
+    if !tokens.get(idx)?.kind.is_opening_parenthesis() {
+        return None;
+    }
+
+    let mut depth = 0usize;
+    let mut args = vec![];
+    let mut arg_start: Option<usize> = None;
+    let mut arg_end = 0;
+
+    for (current, token) in tokens.iter().enumerate().skip(idx + 1) {
+        match &token.kind {
+            TokenKind::OpeningParenthesis | TokenKind::OpeningBracket | TokenKind::OpeningBrace => {
+                depth += 1
+            }
+            TokenKind::ClosingParenthesis if depth == 0 => {
+                if let Some(start) = arg_start {
+                    args.push(start..arg_end);
+                }
+
+                return Some(Group {
+                    span: tokens[idx].span.start..token.span.end,
+                    end: current,
+                    args,
+                });
+            }
+            TokenKind::ClosingParenthesis | TokenKind::ClosingBracket | TokenKind::ClosingBrace => {
+                depth = depth.saturating_sub(1)
+            }
+            TokenKind::Comma if depth == 0 => {
+                if let Some(start) = arg_start.take() {
+                    args.push(start..arg_end);
+                }
+
+                continue;
+            }
+            TokenKind::Newline => continue,
+            _ => (),
+        }
+
+        arg_start.get_or_insert(token.span.start);
+        arg_end = token.span.end;
+    }
+
+    None
+}
+
+/// Whether `span` holds exactly one token, which is a string literal.
+/// Returns the string's contents.
+pub fn as_string<'s>(tokens: &[Token<'s>], span: &Span) -> Option<&'s str> {
+    tokens.iter().find_map(|token| match token.kind {
+        TokenKind::String(string) if token.span == *span => Some(string),
+        _ => None,
+    })
+}
@@ -129,6 +129,7 @@ pub macro dir {
     },
 }
 
+dirs_wrapper!(pub cache_dir);
 dirs_wrapper!(pub config_local_dir);
 dirs_wrapper!(pub data_local_dir);
 dirs_wrapper!(pub home_dir);
@@ -145,6 +146,20 @@ dir! {
     / #[dir: true] "gdtk"
 }
 
+dir! {
+    /// The gdtk cache root, which holds [downloads_path]. Everything in it can
+    /// be deleted safely.
+    pub base_cache_dir: cache_dir
+    / #[dir: true] "gdtk"
+}
+
+dir! {
+    /// Where downloaded archives are kept, so that they don't have to be
+    /// downloaded again.
+    pub downloads_path: base_cache_dir
+    / #[dir: true] "downloads"
+}
+
 dir! {
     /// TODO
     pub godots_path: base_data_dir
@@ -157,6 +172,12 @@ dir! {
     / #[dir: true] "logs"
 }
 
+dir! {
+    /// Where `gdtk repl` keeps its history.
+    pub repl_history_path: base_data_dir
+    / #[dir: false] "repl_history"
+}
+
 pub fn executable_path() -> Result<Utf8PathBuf, Error> {
     dir! {
         gdtk_bin_dir: {
@@ -190,7 +211,8 @@ pub fn executable_path() -> Result<Utf8PathBuf, Error> {
         .or(local_bin_path())
 }
 
-/// Returns the path to the default godot executable.
+/// Returns the path the default Godot version used to be linked to, before
+/// it was replaced by shims.
 pub fn default_godot_path() -> Result<Utf8PathBuf, Error> {
     let mut base = executable_path()?;
 
@@ -200,7 +222,25 @@ pub fn default_godot_path() -> Result<Utf8PathBuf, Error> {
     Ok(base)
 }
 
+dir! {
+    /// Where the cached list of Godot releases is kept.
+    pub release_index_path: base_data_dir
+    / #[dir: false] "release_index"
+}
+
+dir! {
+    /// A list of projects that pin a Godot version.
+    pub projects_path: base_data_dir
+    / #[dir: false] "projects"
+}
+
 dir! {
     pub local_versions_path: base_data_dir
     / #[dir: false] "local_versions"
 }
+
+dir! {
+    /// Locked by whoever is using the `local_versions` file.
+    pub local_versions_lock_path: base_data_dir
+    / #[dir: false] "local_versions.lock"
+}
@@ -0,0 +1,132 @@
+//! The assertions of [TEST_CASE], implemented as native methods so that
+//! failures point at the assertion in the test.
+
+use gdtk_gdscript_interpreter::{operators, Error, ErrorKind, Interpreter, Value};
+
+/// The engine class test scripts extend.
+pub const TEST_CASE: &str = "TestCase";
+
+/// Checks an assertion's arguments, returning why it failed, if it did.
+type Check = for<'v> fn(&[Value<'v>]) -> Result<Option<String>, Error>;
+
+/// Define the assertions of [TEST_CASE] in `interpreter`.
+pub fn define(interpreter: &Interpreter<'_>) {
+    define_check(interpreter, "assert_eq", 2, |args| {
+        Ok((!args[0].equals(&args[1]))
+            .then(|| format!("expected {} to equal {}", args[0].repr(), args[1].repr())))
+    });
+
+    define_check(interpreter, "assert_ne", 2, |args| {
+        Ok(args[0].equals(&args[1]).then(|| {
+            format!(
+                "expected {} to not equal {}",
+                args[0].repr(),
+                args[1].repr()
+            )
+        }))
+    });
+
+    define_check(interpreter, "assert_true", 1, |args| {
+        Ok((!matches!(args[0], Value::Bool(true)))
+            .then(|| format!("expected {} to be true", args[0].repr())))
+    });
+
+    define_check(interpreter, "assert_false", 1, |args| {
+        Ok((!matches!(args[0], Value::Bool(false)))
+            .then(|| format!("expected {} to be false", args[0].repr())))
+    });
+
+    define_check(interpreter, "assert_null", 1, |args| {
+        Ok((!matches!(args[0], Value::Null))
+            .then(|| format!("expected {} to be null", args[0].repr())))
+    });
+
+    define_check(interpreter, "assert_not_null", 1, |args| {
+        Ok(matches!(args[0], Value::Null).then(|| "expected a value, got null".to_owned()))
+    });
+
+    define_check(interpreter, "assert_almost_eq", 3, |args| {
+        let numbers = (args[0].as_float(), args[1].as_float(), args[2].as_float());
+
+        let (Some(got), Some(expected), Some(tolerance)) = numbers else {
+            return Err(invalid("assert_almost_eq", "expected numbers"));
+        };
+
+        Ok(((got - expected).abs() > tolerance)
+            .then(|| format!("expected {got} to be within {tolerance} of {expected}")))
+    });
+
+    define_check(interpreter, "assert_has", 2, |args| {
+        Ok((!operators::contains(&args[1], &args[0])?)
+            .then(|| format!("expected {} to contain {}", args[0].repr(), args[1].repr())))
+    });
+
+    interpreter.define_native_method(TEST_CASE, "fail", |_, _, args| {
+        Box::pin(async move {
+            check_count("fail", &args, 0)?;
+
+            Err(ErrorKind::AssertionFailed(args.first().map(Value::to_string)).into())
+        })
+    });
+
+    interpreter.define_native_method(TEST_CASE, "assert_raises", |interpreter, _, args| {
+        Box::pin(async move {
+            check_count("assert_raises", &args, 1)?;
+
+            let Value::Callable(callable) = &args[0] else {
+                return Err(invalid("assert_raises", "expected a Callable"));
+            };
+
+            match interpreter.invoke(callable.clone(), vec![]).await {
+                Ok(_) => Err(failure(&args, 1, "expected an error".to_owned())),
+                Err(_) => Ok(Value::Null),
+            }
+        })
+    });
+}
+
+/// Define an assertion that takes `count` arguments and an optional message.
+fn define_check(interpreter: &Interpreter<'_>, name: &'static str, count: usize, check: Check) {
+    interpreter.define_native_method(TEST_CASE, name, move |_, _, args| {
+        Box::pin(async move {
+            check_count(name, &args, count)?;
+
+            match check(&args[..count])? {
+                Some(reason) => Err(failure(&args, count, reason)),
+                None => Ok(Value::Null),
+            }
+        })
+    });
+}
+
+fn check_count(name: &str, args: &[Value<'_>], count: usize) -> Result<(), Error> {
+    if args.len() == count || args.len() == count + 1 {
+        return Ok(());
+    }
+
+    Err(ErrorKind::ArgumentCount {
+        function: name.to_owned(),
+        expected: format!("{count} to {}", count + 1),
+        received: args.len(),
+    }
+    .into())
+}
+
+/// A failed assertion. The message passed to the assertion, if any, goes
+/// first.
+fn failure(args: &[Value<'_>], message_idx: usize, reason: String) -> Error {
+    let message = match args.get(message_idx) {
+        Some(message) => format!("{message}: {reason}"),
+        None => reason,
+    };
+
+    ErrorKind::AssertionFailed(Some(message)).into()
+}
+
+fn invalid(function: &str, message: &str) -> Error {
+    ErrorKind::InvalidArgument {
+        function: function.to_owned(),
+        message: message.to_owned(),
+    }
+    .into()
+}
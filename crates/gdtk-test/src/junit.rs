@@ -0,0 +1,102 @@
+//! JUnit XML reports, as understood by most CI services.
+
+use std::fmt::Write;
+
+use crate::{Outcome, Suite};
+
+/// Render the results of `suites` as a JUnit XML report.
+pub fn junit(suites: &[Suite]) -> String {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+
+    let tests: usize = suites.iter().map(|suite| suite.cases.len()).sum();
+    let failures: usize = suites
+        .iter()
+        .map(|suite| suite.count(Outcome::is_failed))
+        .sum();
+    let errors: usize = suites
+        .iter()
+        .map(|suite| suite.count(Outcome::is_errored))
+        .sum();
+    let time: f64 = suites.iter().map(|suite| suite.time().as_secs_f64()).sum();
+
+    let _ = writeln!(
+        xml,
+        "<testsuites tests=\"{tests}\" failures=\"{failures}\" errors=\"{errors}\" time=\"{time:.3}\">"
+    );
+
+    for suite in suites {
+        let _ = writeln!(
+            xml,
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{:.3}\">",
+            escape(&suite.name),
+            suite.cases.len(),
+            suite.count(Outcome::is_failed),
+            suite.count(Outcome::is_errored),
+            suite.time().as_secs_f64(),
+        );
+
+        for case in &suite.cases {
+            let _ = write!(
+                xml,
+                "    <testcase name=\"{}\" classname=\"{}\" time=\"{:.3}\"",
+                escape(&case.name),
+                escape(&suite.name),
+                case.time.as_secs_f64(),
+            );
+
+            let element = match &case.outcome {
+                Outcome::Passed => None,
+                Outcome::Failed(error) => Some(("failure", error)),
+                Outcome::Errored(error) => Some(("error", error)),
+            };
+
+            if element.is_none() && case.output.is_empty() {
+                xml.push_str("/>\n");
+                continue;
+            }
+
+            xml.push_str(">\n");
+
+            if let Some((tag, error)) = element {
+                let _ = writeln!(
+                    xml,
+                    "      <{tag} message=\"{}\"/>",
+                    escape(&error.to_string())
+                );
+            }
+
+            if !case.output.is_empty() {
+                let _ = writeln!(
+                    xml,
+                    "      <system-out>{}</system-out>",
+                    escape(&case.output)
+                );
+            }
+
+            xml.push_str("    </testcase>\n");
+        }
+
+        xml.push_str("  </testsuite>\n");
+    }
+
+    xml.push_str("</testsuites>\n");
+
+    xml
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for char in text.chars() {
+        match char {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(char),
+        }
+    }
+
+    escaped
+}
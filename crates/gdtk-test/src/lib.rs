@@ -0,0 +1,250 @@
+//! A unit testing framework for GDScript, running tests with
+//! [gdtk_gdscript_interpreter].
+//!
+//! Test scripts extend [TestCase](assertions::TEST_CASE) and define test
+//! functions, named `test_*`. A test function with parameters runs once for
+//! every element of the array returned by `params_*` (`params_foo` for
+//! `test_foo`): arrays are spread over the parameters, other values are
+//! passed as the only argument. Every script gets a single instance, on which
+//! `before_each` and `after_each` are called around each test.
+
+pub mod assertions;
+pub mod junit;
+#[cfg(test)]
+mod tests;
+
+use std::time::{Duration, Instant};
+
+use gdtk_gdscript_ast::{ASTExprKind, ASTFile, ASTFunction, ASTStatement};
+use gdtk_gdscript_interpreter::{Capture, Error, ErrorKind, Interpreter, Value};
+
+use crate::assertions::TEST_CASE;
+
+/// The results of a test script.
+#[derive(Debug)]
+pub struct Suite {
+    pub name: String,
+    pub cases: Vec<Case>,
+}
+
+impl Suite {
+    pub fn count(&self, predicate: impl Fn(&Outcome) -> bool) -> usize {
+        self.cases
+            .iter()
+            .filter(|case| predicate(&case.outcome))
+            .count()
+    }
+
+    pub fn time(&self) -> Duration {
+        self.cases.iter().map(|case| case.time).sum()
+    }
+
+    /// A suite with a single errored case named after the script, for scripts
+    /// that fail to parse or load.
+    pub fn broken(name: &str, error: Error) -> Self {
+        Self {
+            name: name.to_owned(),
+            cases: vec![Case {
+                name: name.to_owned(),
+                outcome: Outcome::Errored(error),
+                output: String::new(),
+                time: Duration::ZERO,
+            }],
+        }
+    }
+}
+
+/// The result of a single test, or of a single set of parameters of a
+/// parameterized test.
+#[derive(Debug)]
+pub struct Case {
+    /// The name of the test function. Parameterized tests also have the
+    /// index of the parameters, e.g. `test_foo[1]`.
+    pub name: String,
+    pub outcome: Outcome,
+    /// What the test printed.
+    pub output: String,
+    pub time: Duration,
+}
+
+#[derive(Debug)]
+pub enum Outcome {
+    Passed,
+    /// An assertion failed.
+    Failed(Error),
+    /// Any other error.
+    Errored(Error),
+}
+
+impl Outcome {
+    pub fn is_passed(&self) -> bool {
+        matches!(self, Outcome::Passed)
+    }
+
+    pub fn is_failed(&self) -> bool {
+        matches!(self, Outcome::Failed(_))
+    }
+
+    pub fn is_errored(&self) -> bool {
+        matches!(self, Outcome::Errored(_))
+    }
+
+    fn from_result(result: Result<(), Error>) -> Self {
+        match result {
+            Ok(()) => Outcome::Passed,
+            Err(error) if matches!(error.kind, ErrorKind::AssertionFailed(_)) => {
+                Outcome::Failed(error)
+            }
+            Err(error) => Outcome::Errored(error),
+        }
+    }
+}
+
+/// Run the tests of a script. Returns `None` if the script doesn't extend
+/// [TestCase](TEST_CASE). If the script fails to load, that's reported as a
+/// single errored case named after the script.
+pub fn run_file<'a>(file: &'a ASTFile<'a>, name: &str) -> Option<Suite> {
+    let output = Capture::default();
+    // errors pushed by a test are part of its output, as they are in the engine's log
+    let interpreter = Interpreter::with_outputs(output.clone(), output.clone());
+
+    assertions::define(&interpreter);
+
+    let started = Instant::now();
+    let broken = |error| {
+        let mut suite = Suite::broken(name, error);
+        suite.cases[0].output = output.take();
+        suite.cases[0].time = started.elapsed();
+
+        Some(suite)
+    };
+
+    // check the class before instantiating it, so that `_init` only runs for
+    // test scripts
+    let class = match interpreter.load(file, name) {
+        Ok(class) if class.native() != TEST_CASE => return None,
+        Ok(class) => class,
+        Err(error) => return broken(error),
+    };
+
+    let object = match interpreter.instantiate(&class, vec![]) {
+        Ok(object) => object,
+        Err(error) => return broken(error),
+    };
+
+    let object = Value::Object(object);
+    let has = |function: &str| class.function(function).is_some();
+    let call = |function: &str, args: Vec<Value<'a>>| interpreter.call(&object, function, args);
+
+    let mut cases = vec![];
+
+    for function in test_functions(file) {
+        let started = Instant::now();
+        let parameterized = function
+            .parameters
+            .as_ref()
+            .is_some_and(|parameters| !parameters.is_empty());
+
+        let params = if parameterized {
+            let source = format!("params_{}", &name_of(function)["test_".len()..]);
+
+            match call(&source, vec![]) {
+                Ok(Value::Array(params)) => Some(params.to_vec()),
+                Ok(other) => {
+                    let error = ErrorKind::InvalidArgument {
+                        function: source,
+                        message: format!("expected an Array, got {}", other.type_name()),
+                    };
+
+                    cases.push(Case {
+                        name: name_of(function).to_owned(),
+                        outcome: Outcome::Errored(error.into()),
+                        output: output.take(),
+                        time: started.elapsed(),
+                    });
+
+                    continue;
+                }
+                Err(error) => {
+                    cases.push(Case {
+                        name: name_of(function).to_owned(),
+                        outcome: Outcome::Errored(error),
+                        output: output.take(),
+                        time: started.elapsed(),
+                    });
+
+                    continue;
+                }
+            }
+        } else {
+            None
+        };
+
+        let runs = match params {
+            Some(params) => params
+                .into_iter()
+                .enumerate()
+                .map(|(idx, params)| {
+                    let args = match params {
+                        Value::Array(args) => args.to_vec(),
+                        other => vec![other],
+                    };
+
+                    (format!("{}[{idx}]", name_of(function)), args)
+                })
+                .collect(),
+            None => vec![(name_of(function).to_owned(), vec![])],
+        };
+
+        for (case, args) in runs {
+            let started = Instant::now();
+
+            let mut result = Ok(());
+
+            if has("before_each") {
+                result = call("before_each", vec![]).map(drop);
+            }
+
+            if result.is_ok() {
+                result = call(name_of(function), args).map(drop);
+            }
+
+            // Clean up even after failures, but report the first error.
+            if has("after_each") {
+                let cleanup = call("after_each", vec![]).map(drop);
+                result = result.and(cleanup);
+            }
+
+            cases.push(Case {
+                name: case,
+                outcome: Outcome::from_result(result),
+                output: output.take(),
+                time: started.elapsed(),
+            });
+        }
+    }
+
+    Some(Suite {
+        name: name.to_owned(),
+        cases,
+    })
+}
+
+/// Test functions of a script, in declaration order.
+fn test_functions<'a>(file: &'a ASTFile<'a>) -> impl Iterator<Item = &'a ASTFunction<'a>> {
+    file.body.iter().filter_map(|stmt| match stmt {
+        ASTStatement::Func(function) if name_of(function).starts_with("test_") => Some(function),
+        _ => None,
+    })
+}
+
+fn name_of<'a>(function: &ASTFunction<'a>) -> &'a str {
+    match function
+        .identifier
+        .as_deref()
+        .map(|identifier| &identifier.kind)
+    {
+        Some(ASTExprKind::Identifier(name)) => name,
+        _ => "",
+    }
+}
@@ -0,0 +1,171 @@
+use gdtk_gdscript_interpreter::ErrorKind;
+use gdtk_gdscript_parser::{lexer::lex, parse_file};
+
+use crate::{junit::junit, run_file, Outcome, Suite};
+
+fn run(source: &str) -> Option<Suite> {
+    let source = Box::leak(source.to_owned().into_boxed_str());
+    let file = Box::leak(Box::new(parse_file(lex(source))));
+
+    run_file(file, "test_example.gd")
+}
+
+fn outcomes(suite: &Suite) -> Vec<(&str, &str)> {
+    suite
+        .cases
+        .iter()
+        .map(|case| {
+            let outcome = match case.outcome {
+                Outcome::Passed => "passed",
+                Outcome::Failed(_) => "failed",
+                Outcome::Errored(_) => "errored",
+            };
+
+            (case.name.as_str(), outcome)
+        })
+        .collect()
+}
+
+#[test]
+fn test_not_a_test_case() {
+    assert!(run("extends Node\nfunc test_a():\n\tpass\n").is_none());
+    assert!(run("func test_a():\n\tpass\n").is_none());
+    // `_init` of other scripts doesn't run
+    assert!(run("func _init():\n\treturn undefined\nfunc test_a():\n\tpass\n").is_none());
+}
+
+#[test]
+fn test_broken_init() {
+    let suite = run("extends TestCase\nfunc _init():\n\treturn undefined\n").unwrap();
+
+    assert_eq!(outcomes(&suite), [("test_example.gd", "errored")]);
+}
+
+#[test]
+fn test_assertions() {
+    let suite = run(concat!(
+        "extends TestCase\n",
+        "func test_pass():\n",
+        "\tassert_eq(1 + 1, 2)\n",
+        "\tassert_ne(1, 2)\n",
+        "\tassert_true(1 < 2)\n",
+        "\tassert_false(1 > 2)\n",
+        "\tassert_null(null)\n",
+        "\tassert_not_null(1)\n",
+        "\tassert_almost_eq(0.1 + 0.2, 0.3, 0.0001)\n",
+        "\tassert_has([1, 2], 2)\n",
+        "\tassert_raises(func(): return 1 / 0)\n",
+        "func test_fail():\n",
+        "\tassert_eq(1, 2, \"math\")\n",
+        "func test_error():\n",
+        "\treturn undefined\n",
+    ))
+    .unwrap();
+
+    assert_eq!(
+        outcomes(&suite),
+        [
+            ("test_pass", "passed"),
+            ("test_fail", "failed"),
+            ("test_error", "errored")
+        ]
+    );
+
+    let Outcome::Failed(error) = &suite.cases[1].outcome else {
+        unreachable!();
+    };
+
+    assert!(matches!(
+        &error.kind,
+        ErrorKind::AssertionFailed(Some(message)) if message == "math: expected 1 to equal 2"
+    ));
+    assert!(error.span.is_some());
+}
+
+#[test]
+fn test_hooks() {
+    let suite = run(concat!(
+        "extends TestCase\n",
+        "var count = 0\n",
+        "func before_each():\n",
+        "\tcount += 1\n",
+        "\tprint(\"before \", count)\n",
+        "func after_each():\n",
+        "\tprint(\"after \", count)\n",
+        "func test_a():\n",
+        "\tpush_warning(\"in test_a\")\n",
+        "\tassert_eq(count, 1)\n",
+        "func test_b():\n",
+        "\tassert_eq(count, 1)\n",
+    ))
+    .unwrap();
+
+    assert_eq!(
+        outcomes(&suite),
+        [("test_a", "passed"), ("test_b", "failed")]
+    );
+    assert_eq!(
+        suite.cases[0].output,
+        "before 1\nWARNING: in test_a\nafter 1\n"
+    );
+    assert_eq!(suite.cases[1].output, "before 2\nafter 2\n");
+}
+
+#[test]
+fn test_parameterized() {
+    let suite = run(concat!(
+        "extends TestCase\n",
+        "func params_add():\n",
+        "\treturn [[1, 2, 3], [2, 2, 5]]\n",
+        "func test_add(a, b, sum):\n",
+        "\tassert_eq(a + b, sum)\n",
+        "func params_positive():\n",
+        "\treturn [1, 2]\n",
+        "func test_positive(n):\n",
+        "\tassert_true(n > 0)\n",
+        "func test_missing(n):\n",
+        "\tpass\n",
+    ))
+    .unwrap();
+
+    assert_eq!(
+        outcomes(&suite),
+        [
+            ("test_add[0]", "passed"),
+            ("test_add[1]", "failed"),
+            ("test_positive[0]", "passed"),
+            ("test_positive[1]", "passed"),
+            ("test_missing", "errored")
+        ]
+    );
+}
+
+#[test]
+fn test_junit() {
+    let suite = run(concat!(
+        "extends TestCase\n",
+        "func test_pass():\n",
+        "\tpass\n",
+        "func test_fail():\n",
+        "\tprint(\"<hi>\")\n",
+        "\tassert_true(false)\n",
+    ))
+    .unwrap();
+
+    let xml = junit(&[suite]);
+
+    assert!(xml.contains("<testsuites tests=\"2\" failures=\"1\" errors=\"0\""));
+    assert!(xml.contains("<testcase name=\"test_pass\" classname=\"test_example.gd\""));
+    assert!(xml.contains("<failure message=\"Assertion failed: expected false to be true\"/>"));
+    assert!(xml.contains("<system-out>&lt;hi&gt;\n</system-out>"));
+}
+
+#[test]
+fn test_junit_broken() {
+    let error = ErrorKind::ParseError("unexpected token".to_owned());
+    let xml = junit(&[Suite::broken("test_broken.gd", error.into())]);
+
+    assert!(xml.contains("<testsuites tests=\"1\" failures=\"0\" errors=\"1\""));
+    assert!(xml.contains("<testcase name=\"test_broken.gd\" classname=\"test_broken.gd\""));
+    assert!(xml.contains("<error message=\"Parse Error: unexpected token\"/>"));
+}
@@ -2,12 +2,21 @@ use tracing::level_filters::LevelFilter;
 
 #[cfg(any(debug_assertions, feature = "dev"))]
 use crate::cli::dev::DevCommand;
-use crate::cli::{godot::GodotCommand, lint::LintCommand};
+use crate::cli::{
+    check::CheckCommand, doc::DocCommand, godot::GodotCommand, lint::LintCommand,
+    migrate::MigrateCommand, repl::ReplCommand, scene::SceneCommand, test::TestCommand,
+};
 
+pub mod check;
 #[cfg(any(debug_assertions, feature = "dev"))]
 pub mod dev;
+pub mod doc;
 pub mod godot;
 pub mod lint;
+pub mod migrate;
+pub mod repl;
+pub mod scene;
+pub mod test;
 pub mod utils;
 
 pub struct Cli {
@@ -42,7 +51,10 @@ impl tapcli::Command for Cli {
                     parser.next();
                 }
                 tapcli::ArgRef::Long("help") => todo!(),
-                tapcli::ArgRef::Value("dev" | "godot" | "lint") => {
+                tapcli::ArgRef::Value(
+                    "check" | "dev" | "doc" | "godot" | "lint" | "migrate" | "repl" | "scene"
+                    | "test",
+                ) => {
                     return Ok(Self {
                         verbosity: verbosity.unwrap_or(0),
                         command: Command::parse(parser)?,
@@ -61,13 +73,25 @@ impl tapcli::Command for Cli {
 }
 
 pub enum Command {
+    /// Check GodotCfg files (`.tscn`, `.tres`, `project.godot`, etc.) for errors.
+    Check(CheckCommand),
     /// Namespace for arbitrary commands useful when working on gdtk.
     #[cfg(any(debug_assertions, feature = "dev"))]
     Dev(DevCommand),
+    /// Generate API documentation from `##` doc comments.
+    Doc(DocCommand),
     /// Manage your Godot installations.
     Godot(GodotCommand),
     /// Lint GDScript code.
     Lint(LintCommand),
+    /// Migrate GDScript code from Godot 3 to Godot 4.
+    Migrate(MigrateCommand),
+    /// Run GDScript interactively.
+    Repl(ReplCommand),
+    /// Work with scenes (`.tscn` files).
+    Scene(SceneCommand),
+    /// Run GDScript unit tests.
+    Test(TestCommand),
 }
 
 impl tapcli::Command for Command {
@@ -75,10 +99,16 @@ impl tapcli::Command for Command {
 
     fn parse(parser: &mut tapcli::Parser) -> Result<Self, Self::Error> {
         let command = match parser.next().unwrap().as_ref() {
+            tapcli::ArgRef::Value("check") => Self::Check(CheckCommand::parse(parser)?),
             #[cfg(any(debug_assertions, feature = "dev"))]
             tapcli::ArgRef::Value("dev") => Self::Dev(DevCommand::parse(parser)?),
+            tapcli::ArgRef::Value("doc") => Self::Doc(DocCommand::parse(parser)?),
             tapcli::ArgRef::Value("godot") => Self::Godot(GodotCommand::parse(parser)?),
             tapcli::ArgRef::Value("lint") => Self::Lint(LintCommand::parse(parser)?),
+            tapcli::ArgRef::Value("migrate") => Self::Migrate(MigrateCommand::parse(parser)?),
+            tapcli::ArgRef::Value("repl") => Self::Repl(ReplCommand::parse(parser)?),
+            tapcli::ArgRef::Value("scene") => Self::Scene(SceneCommand::parse(parser)?),
+            tapcli::ArgRef::Value("test") => Self::Test(TestCommand::parse(parser)?),
             _ => unreachable!(),
         };
 
@@ -91,10 +121,16 @@ impl tapcli::Command for Command {
 
     fn run(self) -> Result<Self::Output, Self::Error> {
         match self {
+            Self::Check(cmd) => cmd.run(),
             #[cfg(any(debug_assertions, feature = "dev"))]
             Self::Dev(cmd) => cmd.run(),
+            Self::Doc(cmd) => cmd.run(),
             Self::Godot(cmd) => cmd.run(),
             Self::Lint(cmd) => cmd.run(),
+            Self::Migrate(cmd) => cmd.run(),
+            Self::Repl(cmd) => cmd.run(),
+            Self::Scene(cmd) => cmd.run(),
+            Self::Test(cmd) => cmd.run(),
         }
     }
 }
@@ -0,0 +1,86 @@
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use diagnosis::protocol::Visualizer;
+
+use super::unknown;
+use crate::utils::{get_content, resolve_files_by_exts};
+
+/// Extensions of files that use the GodotCfg format.
+const EXTENSIONS: &[&str] = &["godot", "tscn", "tres", "cfg", "import"];
+
+pub struct CheckCommand {
+    pub files: Vec<PathBuf>,
+}
+
+impl tapcli::Command for CheckCommand {
+    type Error = anyhow::Error;
+
+    fn parse(parser: &mut tapcli::Parser) -> Result<Self, Self::Error> {
+        let mut files = Vec::new();
+
+        for arg in parser {
+            match arg {
+                tapcli::Arg::Value(path) => files.push(path.into()),
+                other => unknown!(other),
+            }
+        }
+
+        Ok(Self { files })
+    }
+
+    fn run(self) -> Result<Self::Output, Self::Error> {
+        let files = resolve_files_by_exts(self.files, EXTENSIONS)?;
+        let mut errors = 0;
+
+        for file in &files {
+            match run_on_file(file) {
+                Ok(count) => errors += count,
+                // a file that can't be read counts as one error
+                Err(error) => {
+                    eprintln!("{}: {error}\n", file.display());
+                    errors += 1;
+                }
+            }
+        }
+
+        if errors > 0 {
+            anyhow::bail!("Checked {} file(s), {} errors.", files.len(), errors);
+        }
+
+        eprintln!("Checked {} file(s), all good. ✨️", files.len());
+
+        Ok(())
+    }
+}
+
+fn run_on_file(file: &Path) -> anyhow::Result<usize> {
+    let content = get_content(file)?;
+    let (lines, mut errors) = gdtk_godotcfg_parser::parse_spanned(&content);
+
+    // semantic checks only make sense for files that parsed successfully
+    if errors.is_empty() && file.extension().is_some_and(|e| e == "tscn") {
+        if let Err(error) = gdtk_godotcfg_parser::scene::Scene::from_lines(lines) {
+            errors.push(error);
+        }
+    }
+
+    let source_name = match file.to_str().unwrap() {
+        "-" => "<stdin>",
+        other => other,
+    };
+
+    let vis = diagnosis::visualizers::codespan::CodespanVisualizer::new(source_name, &content);
+    let mut stderr = diagnosis::visualizers::codespan::codespan_reporting::term::termcolor::StandardStream::stderr(diagnosis::visualizers::codespan::codespan_reporting::term::termcolor::ColorChoice::Always);
+
+    for error in &errors {
+        let message = error.to_string();
+
+        vis.visualize(error.as_diagnostic(&message), &mut stderr)?;
+        write!(stderr, "\n\n")?;
+    }
+
+    Ok(errors.len())
+}
@@ -0,0 +1,231 @@
+//! Conversion of Godot's documentation BBCode to Markdown and HTML.
+
+use std::collections::HashSet;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Target {
+    Markdown,
+    Html,
+}
+
+/// Resolves references like `[MyClass]` or `[method foo]` to links.
+pub struct Links<'a> {
+    /// Names of all documented classes.
+    pub classes: &'a HashSet<String>,
+    /// The extension of class pages, e.g. `md`.
+    pub extension: &'static str,
+}
+
+impl Links<'_> {
+    /// The page of a class, if it is documented.
+    pub fn class(&self, class: &str) -> Option<String> {
+        self.classes
+            .contains(class)
+            .then(|| format!("{}.{}", class, self.extension))
+    }
+
+    /// The anchor of a member, which is either in the current class (`foo`)
+    /// or in another one (`MyClass.foo`).
+    pub fn member(&self, member: &str) -> Option<String> {
+        match member.rsplit_once('.') {
+            Some((class, member)) => self.class(class).map(|page| format!("{page}#{member}")),
+            None => Some(format!("#{member}")),
+        }
+    }
+}
+
+/// Convert BBCode to the given target. Unknown tags are kept as is.
+pub fn convert(input: &str, target: Target, links: &Links<'_>) -> String {
+    let mut out = String::new();
+    let mut rest = input;
+
+    while let Some(start) = rest.find('[') {
+        out.push_str(&text(&rest[..start], target));
+        rest = &rest[start..];
+
+        let Some(end) = rest.find(']') else {
+            break;
+        };
+
+        let tag = &rest[1..end];
+        rest = &rest[end + 1..];
+
+        let (name, arg) = tag.split_once([' ', '=']).unwrap_or((tag, ""));
+
+        match (name, target) {
+            ("b", Target::Markdown) | ("/b", Target::Markdown) => out.push_str("**"),
+            ("i", Target::Markdown) | ("/i", Target::Markdown) => out.push('*'),
+            ("s", Target::Markdown) | ("/s", Target::Markdown) => out.push_str("~~"),
+            ("br", Target::Markdown) => out.push_str("  \n"),
+            ("b" | "/b" | "i" | "/i" | "s" | "/s" | "br", Target::Html)
+            | ("u" | "/u" | "kbd" | "/kbd", _) => {
+                out.push('<');
+                out.push_str(name);
+                out.push('>');
+            }
+            ("lb", _) => out.push('['),
+            ("rb", _) => out.push(']'),
+            ("code", _) => out.push_str(&code(take_until(&mut rest, "[/code]"), target)),
+            ("codeblock", _) => {
+                let content = take_until(&mut rest, "[/codeblock]").trim_matches('\n');
+                let lang = arg.strip_prefix("lang=").unwrap_or("gdscript");
+
+                out.push_str(&codeblock(content, lang, target));
+            }
+            ("url", _) => {
+                let content = take_until(&mut rest, "[/url]");
+                let url = if arg.is_empty() { content } else { arg };
+
+                out.push_str(&link(&text(content, target), url, target));
+            }
+            ("color" | "/color" | "center" | "/center" | "font" | "/font", _)
+            | ("font_size" | "/font_size" | "indent" | "/indent", _)
+            | ("codeblocks" | "/codeblocks" | "gdscript" | "/gdscript", _)
+            | ("csharp" | "/csharp", _) => (),
+            ("param", _) => out.push_str(&code(arg, target)),
+            ("method" | "member" | "signal" | "constant" | "enum", _)
+            | ("annotation" | "theme_item" | "constructor" | "operator", _) => {
+                let label = code(arg, target);
+
+                out.push_str(&match links.member(arg) {
+                    Some(url) => link(&label, &url, target),
+                    None => label,
+                });
+            }
+            _ if is_class_name(tag) => {
+                let label = code(tag, target);
+
+                out.push_str(&match links.class(tag) {
+                    Some(url) => link(&label, &url, target),
+                    None => label,
+                });
+            }
+            _ => {
+                out.push_str(&text("[", target));
+                out.push_str(&text(tag, target));
+                out.push_str(&text("]", target));
+            }
+        }
+    }
+
+    out.push_str(&text(rest, target));
+
+    out
+}
+
+/// Split `rest` at `closing`, returning the part before it. Unclosed tags
+/// take the rest of the input.
+fn take_until<'a>(rest: &mut &'a str, closing: &str) -> &'a str {
+    let (content, after) = rest.split_once(closing).unwrap_or((rest, ""));
+    *rest = after;
+
+    content
+}
+
+fn is_class_name(tag: &str) -> bool {
+    tag.starts_with(|c: char| c.is_ascii_uppercase())
+        && tag
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '.')
+}
+
+/// Plain text, where single newlines are line breaks.
+fn text(input: &str, target: Target) -> String {
+    match target {
+        Target::Markdown => input.replace('\n', "  \n"),
+        Target::Html => escape_html(input).replace('\n', "<br>\n"),
+    }
+}
+
+fn code(input: &str, target: Target) -> String {
+    match target {
+        Target::Markdown => format!("`{input}`"),
+        Target::Html => format!("<code>{}</code>", escape_html(input)),
+    }
+}
+
+fn codeblock(input: &str, lang: &str, target: Target) -> String {
+    match target {
+        Target::Markdown => format!("\n```{lang}\n{input}\n```\n"),
+        Target::Html => format!(
+            "<pre><code class=\"language-{lang}\">{}</code></pre>",
+            escape_html(input)
+        ),
+    }
+}
+
+fn link(label: &str, url: &str, target: Target) -> String {
+    match target {
+        Target::Markdown => format!("[{label}]({url})"),
+        Target::Html => format!("<a href=\"{}\">{label}</a>", escape_html(url)),
+    }
+}
+
+pub fn escape_html(input: &str) -> String {
+    input
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn convert_with(input: &str, target: Target) -> String {
+        let classes = HashSet::from(["Player".to_owned()]);
+        let links = Links {
+            classes: &classes,
+            extension: "md",
+        };
+
+        convert(input, target, &links)
+    }
+
+    #[test]
+    fn test_markdown() {
+        let md = |input| convert_with(input, Target::Markdown);
+
+        assert_eq!(md("[b]bold[/b] and [i]italic[/i]"), "**bold** and *italic*");
+        assert_eq!(md("[code]a < b[/code]"), "`a < b`");
+        assert_eq!(md("[lb]b[rb]"), "[b]");
+        assert_eq!(
+            md("[url=https://example.com]here[/url]"),
+            "[here](https://example.com)"
+        );
+        assert_eq!(md("[param speed]"), "`speed`");
+        assert_eq!(md("line\nbreak"), "line  \nbreak");
+        assert_eq!(
+            md("[codeblock]\nvar x = 1\n[/codeblock]"),
+            "\n```gdscript\nvar x = 1\n```\n"
+        );
+    }
+
+    #[test]
+    fn test_links() {
+        let md = |input| convert_with(input, Target::Markdown);
+
+        assert_eq!(md("[Player]"), "[`Player`](Player.md)");
+        assert_eq!(md("[Node]"), "`Node`");
+        assert_eq!(md("[method jump]"), "[`jump`](#jump)");
+        assert_eq!(
+            md("[member Player.speed]"),
+            "[`Player.speed`](Player.md#speed)"
+        );
+        assert_eq!(md("[signal Node.ready]"), "`Node.ready`");
+    }
+
+    #[test]
+    fn test_html() {
+        let html = |input| convert_with(input, Target::Html);
+
+        assert_eq!(html("[b]a & b[/b]"), "<b>a &amp; b</b>");
+        assert_eq!(html("[code]<T>[/code]"), "<code>&lt;T&gt;</code>");
+        assert_eq!(
+            html("[Player]"),
+            "<a href=\"Player.md\"><code>Player</code></a>"
+        );
+        assert_eq!(html("[unknown tag]"), "[unknown tag]");
+    }
+}
@@ -0,0 +1,733 @@
+use std::collections::{HashMap, HashSet};
+
+use gdtk_gdscript_ast::{
+    ASTExpr, ASTExprKind, ASTFunctionKind, ASTPrefixOpKind, ASTStatement, ASTVariable,
+    ASTVariableKind,
+};
+use gdtk_gdscript_parser::lexer::DocComment;
+
+/// Documentation of a class, i.e. a script or an inner class.
+pub struct ClassDoc {
+    /// The class' name, with inner classes prefixed by their outer class' name
+    /// (e.g. `Outer.Inner`).
+    pub name: String,
+    pub extends: Option<String>,
+    pub docs: Docs,
+    pub signals: Vec<SignalDoc>,
+    pub enums: Vec<EnumDoc>,
+    pub constants: Vec<ConstantDoc>,
+    pub properties: Vec<PropertyDoc>,
+    pub methods: Vec<MethodDoc>,
+}
+
+/// The contents of a doc comment. Texts are in Godot's BBCode.
+#[derive(Default)]
+pub struct Docs {
+    /// The first paragraph of a class' docs. Always empty for members.
+    pub brief: String,
+    pub description: String,
+    /// `@tutorial` links, as `(title, url)` pairs.
+    pub tutorials: Vec<(String, String)>,
+    /// The `@deprecated` message, which may be empty.
+    pub deprecated: Option<String>,
+    /// The `@experimental` message, which may be empty.
+    pub experimental: Option<String>,
+}
+
+impl Docs {
+    fn parse(lines: &[&str], with_brief: bool) -> Self {
+        let mut docs = Docs::default();
+        let mut text = vec![];
+
+        for line in lines {
+            let trimmed = line.trim();
+
+            if let Some(rest) = trimmed.strip_prefix("@tutorial") {
+                let (title, url) = match rest.split_once(':') {
+                    Some((title, url)) => (title, url),
+                    None => ("", rest),
+                };
+                let title = title.trim().trim_start_matches('(').trim_end_matches(')');
+
+                docs.tutorials
+                    .push((title.trim().to_owned(), url.trim().to_owned()));
+            } else if let Some(rest) = trimmed.strip_prefix("@deprecated") {
+                docs.deprecated = Some(tag_message(rest));
+            } else if let Some(rest) = trimmed.strip_prefix("@experimental") {
+                docs.experimental = Some(tag_message(rest));
+            } else {
+                text.push(*line);
+            }
+        }
+
+        let text = text.join("\n");
+        let text = text.trim();
+
+        if with_brief {
+            let (brief, description) = text.split_once("\n\n").unwrap_or((text, ""));
+
+            docs.brief = brief.trim().to_owned();
+            docs.description = description.trim().to_owned();
+        } else {
+            docs.description = text.to_owned();
+        }
+
+        docs
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.brief.is_empty()
+            && self.description.is_empty()
+            && self.tutorials.is_empty()
+            && self.deprecated.is_none()
+            && self.experimental.is_none()
+    }
+}
+
+fn tag_message(rest: &str) -> String {
+    rest.trim().trim_start_matches(':').trim().to_owned()
+}
+
+pub struct SignalDoc {
+    pub name: String,
+    pub parameters: Vec<ParameterDoc>,
+    pub docs: Docs,
+}
+
+pub struct EnumDoc {
+    /// The enum's name, or `None` for unnamed enums.
+    pub name: Option<String>,
+    pub variants: Vec<ConstantDoc>,
+    pub docs: Docs,
+}
+
+/// A constant or an enum variant.
+pub struct ConstantDoc {
+    pub name: String,
+    pub value: String,
+    pub docs: Docs,
+}
+
+pub struct PropertyDoc {
+    pub name: String,
+    pub typehint: Option<String>,
+    pub default: Option<String>,
+    pub is_static: bool,
+    pub docs: Docs,
+}
+
+pub struct MethodDoc {
+    pub name: String,
+    pub parameters: Vec<ParameterDoc>,
+    pub return_type: Option<String>,
+    pub is_static: bool,
+    pub docs: Docs,
+}
+
+pub struct ParameterDoc {
+    pub name: String,
+    pub typehint: Option<String>,
+    pub default: Option<String>,
+}
+
+/// Extract the documentation of a script and its inner classes. `name` is used
+/// when the script has no `class_name`.
+pub fn extract(
+    source: &str,
+    file: &[ASTStatement<'_>],
+    comments: &[DocComment<'_>],
+    name: &str,
+) -> Vec<ClassDoc> {
+    let extractor = Extractor::new(source, file, comments);
+    let mut classes = vec![];
+
+    let name = file
+        .iter()
+        .find_map(|stmt| stmt.as_class_name())
+        .map_or(name, |stmt| extractor.text(&stmt.identifier));
+    let extends = file
+        .iter()
+        .find_map(|stmt| stmt.as_extends())
+        .map(|stmt| extractor.text(&stmt.identifier).to_owned());
+
+    let docs = Docs::parse(&extractor.script_docs(file), true);
+
+    extractor.class(name.to_owned(), extends, docs, file, &mut classes);
+
+    classes
+}
+
+struct Extractor<'s> {
+    source: &'s str,
+    /// Standalone doc comments by line.
+    standalone: HashMap<usize, &'s str>,
+    /// Trailing doc comments by line.
+    trailing: HashMap<usize, &'s str>,
+    /// Lines that hold annotations, which may sit between doc comments and
+    /// the declaration they document.
+    annotations: HashSet<usize>,
+}
+
+impl<'s> Extractor<'s> {
+    fn new(source: &'s str, file: &[ASTStatement<'_>], comments: &[DocComment<'s>]) -> Self {
+        let mut extractor = Self {
+            source,
+            standalone: HashMap::new(),
+            trailing: HashMap::new(),
+            annotations: HashSet::new(),
+        };
+
+        for comment in comments {
+            match comment.trailing {
+                true => extractor.trailing.insert(comment.line, comment.text),
+                false => extractor.standalone.insert(comment.line, comment.text),
+            };
+        }
+
+        extractor.collect_annotations(file);
+
+        extractor
+    }
+
+    fn collect_annotations(&mut self, block: &[ASTStatement<'_>]) {
+        for stmt in block {
+            match stmt {
+                ASTStatement::Annotation(annotation) => {
+                    let line = self.line(&annotation.identifier);
+                    self.annotations.insert(line);
+                }
+                ASTStatement::Class(class) => self.collect_annotations(&class.body),
+                _ => (),
+            }
+        }
+    }
+
+    fn class(
+        &self,
+        name: String,
+        extends: Option<String>,
+        docs: Docs,
+        body: &[ASTStatement<'_>],
+        classes: &mut Vec<ClassDoc>,
+    ) {
+        let mut class = ClassDoc {
+            name,
+            extends,
+            docs,
+            signals: vec![],
+            enums: vec![],
+            constants: vec![],
+            properties: vec![],
+            methods: vec![],
+        };
+        let mut inner = vec![];
+
+        for stmt in body {
+            match stmt {
+                ASTStatement::Signal(signal) => {
+                    let Some(docs) = self.member_docs(&signal.identifier) else {
+                        continue;
+                    };
+
+                    class.signals.push(SignalDoc {
+                        name: self.text(&signal.identifier).to_owned(),
+                        parameters: self.parameters(signal.parameters.as_deref()),
+                        docs,
+                    });
+                }
+                ASTStatement::Enum(enum_) => {
+                    // a trailing comment on the first line belongs to the first variant
+                    let lines = self.docs_before(self.line_of(enum_.span.start));
+                    let private = enum_
+                        .identifier
+                        .as_ref()
+                        .is_some_and(|i| self.text(i).starts_with('_'));
+
+                    if lines.is_empty() && private {
+                        continue;
+                    }
+
+                    let docs = Docs::parse(&lines, false);
+
+                    // `None` once a value can't be computed, e.g. after `A = SOME_CONSTANT`
+                    let mut next = Some(0i64);
+                    let mut variants = vec![];
+
+                    for variant in &enum_.variants {
+                        let value = match &variant.value {
+                            Some(expr) => match enum_value(expr) {
+                                Some(value) => {
+                                    next = value.checked_add(1);
+                                    value.to_string()
+                                }
+                                None => {
+                                    next = None;
+                                    self.text(expr).to_owned()
+                                }
+                            },
+                            None => match next {
+                                Some(value) => {
+                                    next = value.checked_add(1);
+                                    value.to_string()
+                                }
+                                None => "?".to_owned(),
+                            },
+                        };
+
+                        let Some(docs) = self.member_docs(&variant.identifier) else {
+                            continue;
+                        };
+
+                        variants.push(ConstantDoc {
+                            name: self.text(&variant.identifier).to_owned(),
+                            value,
+                            docs,
+                        });
+                    }
+
+                    class.enums.push(EnumDoc {
+                        name: enum_.identifier.as_ref().map(|i| self.text(i).to_owned()),
+                        variants,
+                        docs,
+                    });
+                }
+                ASTStatement::Variable(variable) => {
+                    let Some(docs) = self.member_docs(&variable.identifier) else {
+                        continue;
+                    };
+
+                    let name = self.text(&variable.identifier).to_owned();
+                    let typehint = variable.typehint.as_ref().map(|t| self.text(t).to_owned());
+                    let default = variable.value.as_ref().map(|v| self.text(v).to_owned());
+
+                    match variable.kind {
+                        ASTVariableKind::Constant => class.constants.push(ConstantDoc {
+                            name,
+                            value: default.unwrap_or_default(),
+                            docs,
+                        }),
+                        ASTVariableKind::Regular | ASTVariableKind::Static => {
+                            class.properties.push(PropertyDoc {
+                                name,
+                                typehint,
+                                default,
+                                is_static: variable.kind.is_static(),
+                                docs,
+                            })
+                        }
+                        ASTVariableKind::Binding => (),
+                    }
+                }
+                ASTStatement::Func(func) => {
+                    let Some(identifier) = &func.identifier else {
+                        continue;
+                    };
+                    let Some(docs) = self.member_docs(identifier) else {
+                        continue;
+                    };
+
+                    class.methods.push(MethodDoc {
+                        name: self.text(identifier).to_owned(),
+                        parameters: self.parameters(func.parameters.as_deref()),
+                        return_type: func.return_type.as_ref().map(|t| self.text(t).to_owned()),
+                        is_static: func.kind == ASTFunctionKind::Static,
+                        docs,
+                    });
+                }
+                ASTStatement::Class(inner_class) => inner.push(inner_class),
+                _ => (),
+            }
+        }
+
+        let outer = class.name.clone();
+        classes.push(class);
+
+        for inner_class in inner {
+            let Some(lines) = self.member_lines(&inner_class.identifier) else {
+                continue;
+            };
+
+            self.class(
+                format!("{}.{}", outer, self.text(&inner_class.identifier)),
+                inner_class
+                    .extends
+                    .as_ref()
+                    .map(|e| self.text(e).to_owned()),
+                Docs::parse(&lines, true),
+                &inner_class.body,
+                classes,
+            );
+        }
+    }
+
+    fn member_docs(&self, identifier: &ASTExpr<'_>) -> Option<Docs> {
+        self.member_lines(identifier)
+            .map(|lines| Docs::parse(&lines, false))
+    }
+
+    /// Doc comment lines of a member. Returns `None` for private (`_`-prefixed)
+    /// members without doc comments, which are left out.
+    fn member_lines(&self, identifier: &ASTExpr<'_>) -> Option<Vec<&'s str>> {
+        let line = self.line(identifier);
+
+        let lines = match self.trailing.get(&line) {
+            Some(text) => vec![*text],
+            None => self.docs_before(line),
+        };
+
+        if lines.is_empty() && self.text(identifier).starts_with('_') {
+            return None;
+        }
+
+        Some(lines)
+    }
+
+    /// The block of standalone doc comments right above `line`, skipping
+    /// annotations.
+    fn docs_before(&self, line: usize) -> Vec<&'s str> {
+        let mut lines = vec![];
+        let mut current = line;
+
+        while current > 0 {
+            current -= 1;
+
+            if let Some(text) = self.standalone.get(&current) {
+                lines.push(*text);
+            } else if !self.annotations.contains(&current) {
+                break;
+            }
+        }
+
+        lines.reverse();
+        lines
+    }
+
+    /// Class docs of a script, i.e. the doc comments at the top of the file
+    /// that aren't attached to its first member.
+    fn script_docs(&self, file: &[ASTStatement<'_>]) -> Vec<&'s str> {
+        let first_member = file.iter().find_map(|stmt| match stmt {
+            ASTStatement::Signal(signal) => Some(self.line(&signal.identifier)),
+            ASTStatement::Enum(enum_) => Some(self.line_of(enum_.span.start)),
+            ASTStatement::Variable(variable) => Some(self.line(&variable.identifier)),
+            ASTStatement::Func(func) => Some(self.line_of(func.span.start)),
+            ASTStatement::Class(class) => Some(self.line(&class.identifier)),
+            _ => None,
+        });
+
+        let end = first_member.unwrap_or(usize::MAX);
+        let member_docs = first_member.map_or(0, |line| self.docs_before(line).len());
+
+        let mut lines = self
+            .standalone
+            .iter()
+            .filter(|(line, _)| **line < end)
+            .collect::<Vec<_>>();
+
+        lines.sort_unstable_by_key(|(line, _)| **line);
+        lines.truncate(lines.len() - member_docs.min(lines.len()));
+
+        lines.into_iter().map(|(_, text)| *text).collect()
+    }
+
+    fn parameters(&self, parameters: Option<&[ASTVariable<'_>]>) -> Vec<ParameterDoc> {
+        parameters
+            .unwrap_or_default()
+            .iter()
+            .map(|param| ParameterDoc {
+                name: self.text(&param.identifier).to_owned(),
+                typehint: param.typehint.as_ref().map(|t| self.text(t).to_owned()),
+                default: param.value.as_ref().map(|v| self.text(v).to_owned()),
+            })
+            .collect()
+    }
+
+    /// The source text of an expression.
+    fn text(&self, expr: &ASTExpr<'_>) -> &'s str {
+        &self.source[expr.span.clone()]
+    }
+
+    fn line(&self, expr: &ASTExpr<'_>) -> usize {
+        self.line_of(expr.span.start)
+    }
+
+    /// The (zero-indexed) line of a byte offset.
+    fn line_of(&self, offset: usize) -> usize {
+        self.source[..offset].matches('\n').count()
+    }
+}
+
+/// The value of an enum variant given as an integer literal, possibly
+/// negated.
+fn enum_value(expr: &ASTExpr<'_>) -> Option<i64> {
+    match &expr.kind {
+        ASTExprKind::Number(n) => i64::try_from(*n).ok(),
+        ASTExprKind::PrefixExpr(op, operand) => match (op.kind, &operand.kind) {
+            (ASTPrefixOpKind::Negation, ASTExprKind::Number(n)) => 0i64.checked_sub_unsigned(*n),
+            (ASTPrefixOpKind::Identity, _) => enum_value(operand),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+impl SignalDoc {
+    pub fn signature(&self) -> String {
+        format!("signal {}({})", self.name, parameters(&self.parameters))
+    }
+}
+
+impl EnumDoc {
+    pub fn signature(&self) -> String {
+        match &self.name {
+            Some(name) => format!("enum {name}"),
+            None => "enum".to_owned(),
+        }
+    }
+}
+
+impl ConstantDoc {
+    pub fn signature(&self) -> String {
+        format!("const {} = {}", self.name, self.value)
+    }
+}
+
+impl PropertyDoc {
+    pub fn signature(&self) -> String {
+        let mut signature = match self.is_static {
+            true => format!("static var {}", self.name),
+            false => format!("var {}", self.name),
+        };
+
+        if let Some(typehint) = &self.typehint {
+            signature.push_str(": ");
+            signature.push_str(typehint);
+        }
+
+        if let Some(default) = &self.default {
+            signature.push_str(" = ");
+            signature.push_str(default);
+        }
+
+        signature
+    }
+}
+
+impl MethodDoc {
+    pub fn signature(&self) -> String {
+        let mut signature = match self.is_static {
+            true => format!("static func {}", self.name),
+            false => format!("func {}", self.name),
+        };
+
+        signature.push('(');
+        signature.push_str(&parameters(&self.parameters));
+        signature.push(')');
+
+        if let Some(return_type) = &self.return_type {
+            signature.push_str(" -> ");
+            signature.push_str(return_type);
+        }
+
+        signature
+    }
+}
+
+fn parameters(parameters: &[ParameterDoc]) -> String {
+    parameters
+        .iter()
+        .map(|param| {
+            let mut out = param.name.clone();
+
+            if let Some(typehint) = &param.typehint {
+                out.push_str(": ");
+                out.push_str(typehint);
+            }
+
+            if let Some(default) = &param.default {
+                out.push_str(" = ");
+                out.push_str(default);
+            }
+
+            out
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use gdtk_gdscript_parser::{
+        lexer::{doc_comments, lex},
+        parse_file,
+    };
+
+    use super::*;
+
+    fn extract_source(source: &str) -> Vec<ClassDoc> {
+        let comments = doc_comments(source);
+        let parsed = parse_file(lex(source));
+
+        extract(source, &parsed.body, &comments, "Script")
+    }
+
+    fn names<T>(members: &[T], name: impl Fn(&T) -> &str) -> Vec<&str> {
+        members.iter().map(name).collect()
+    }
+
+    #[test]
+    fn test_attachment() {
+        let classes = extract_source(concat!(
+            "## A script.\n",
+            "##\n",
+            "## More about it.\n",
+            "## @tutorial(Intro): https://example.com\n",
+            "extends Node\n",
+            "\n",
+            "## The speed.\n",
+            "@export\n",
+            "var speed = 1\n",
+            "\n",
+            "## Says hi.\n",
+            "## @deprecated: Use [method greet].\n",
+            "func hi(name: String = \"you\") -> void:\n",
+            "\tpass\n",
+        ));
+
+        let [script] = classes.as_slice() else {
+            panic!("expected a single class");
+        };
+
+        assert_eq!(script.name, "Script");
+        assert_eq!(script.extends.as_deref(), Some("Node"));
+        assert_eq!(script.docs.brief, "A script.");
+        assert_eq!(script.docs.description, "More about it.");
+        assert_eq!(
+            script.docs.tutorials,
+            [("Intro".to_owned(), "https://example.com".to_owned())]
+        );
+
+        assert_eq!(script.properties[0].docs.description, "The speed.");
+        assert_eq!(script.properties[0].signature(), "var speed = 1");
+
+        let method = &script.methods[0];
+
+        assert_eq!(method.docs.description, "Says hi.");
+        assert_eq!(
+            method.docs.deprecated.as_deref(),
+            Some("Use [method greet].")
+        );
+        assert_eq!(
+            method.signature(),
+            "func hi(name: String = \"you\") -> void"
+        );
+    }
+
+    #[test]
+    fn test_trailing() {
+        let classes = extract_source(concat!(
+            "## Ignored, as the trailing comment wins.\n",
+            "var health = 10 ## Current health.\n",
+            "const MAX = 100 ## The maximum health.\n",
+        ));
+
+        let script = &classes[0];
+
+        assert!(script.docs.is_empty());
+        assert_eq!(script.properties[0].docs.description, "Current health.");
+        assert_eq!(script.constants[0].docs.description, "The maximum health.");
+        assert_eq!(script.constants[0].signature(), "const MAX = 100");
+    }
+
+    #[test]
+    fn test_private() {
+        let classes = extract_source(concat!(
+            "signal _internal\n",
+            "var _secret = 1\n",
+            "## Private, but documented.\n",
+            "var _documented = 2\n",
+            "var public = 3\n",
+            "func _ready():\n",
+            "\tpass\n",
+            "class _Helper:\n",
+            "\tvar x\n",
+        ));
+
+        let script = &classes[0];
+
+        assert!(script.signals.is_empty());
+        assert_eq!(
+            names(&script.properties, |p| &p.name),
+            ["_documented", "public"]
+        );
+        assert!(script.methods.is_empty());
+        assert_eq!(classes.len(), 1);
+    }
+
+    #[test]
+    fn test_enums() {
+        let classes = extract_source(concat!(
+            "enum State { IDLE, ## Doing nothing.\n",
+            "\tRUNNING = 5, ## Moving.\n",
+            "\tJUMPING,\n",
+            "\t_HIDDEN,\n",
+            "}\n",
+            "## Directions.\n",
+            "enum { LEFT, RIGHT }\n",
+            "enum _Private { A }\n",
+        ));
+
+        let enums = &classes[0].enums;
+
+        assert_eq!(enums.len(), 2);
+
+        // the first variant's trailing comment isn't the enum's
+        assert_eq!(enums[0].signature(), "enum State");
+        assert!(enums[0].docs.is_empty());
+        assert_eq!(
+            names(&enums[0].variants, |v| &v.name),
+            ["IDLE", "RUNNING", "JUMPING"]
+        );
+        assert_eq!(enums[0].variants[0].docs.description, "Doing nothing.");
+        assert_eq!(enums[0].variants[1].docs.description, "Moving.");
+        assert_eq!(enums[0].variants[2].value, "6");
+
+        assert_eq!(enums[1].signature(), "enum");
+        assert_eq!(enums[1].docs.description, "Directions.");
+
+        let classes = extract_source(concat!(
+            "enum { A = -1, B, C = -9223372036854775808 }\n",
+            "enum { D = 9223372036854775807, E }\n",
+            "enum { F = A | B, G }\n",
+        ));
+
+        let values = |idx: usize| names(&classes[0].enums[idx].variants, |v| &v.value);
+
+        assert_eq!(values(0), ["-1", "0", "-9223372036854775808"]);
+        // values that can't be computed
+        assert_eq!(values(1), ["9223372036854775807", "?"]);
+        assert_eq!(values(2), ["A | B", "?"]);
+    }
+
+    #[test]
+    fn test_inner_classes() {
+        let classes = extract_source(concat!(
+            "class_name Outer\n",
+            "## An inner class.\n",
+            "##\n",
+            "## With details.\n",
+            "class Inner extends RefCounted:\n",
+            "\t## A static method.\n",
+            "\tstatic func make() -> Inner:\n",
+            "\t\treturn Inner.new()\n",
+        ));
+
+        assert_eq!(names(&classes, |c| &c.name), ["Outer", "Outer.Inner"]);
+
+        let inner = &classes[1];
+
+        assert_eq!(inner.extends.as_deref(), Some("RefCounted"));
+        assert_eq!(inner.docs.brief, "An inner class.");
+        assert_eq!(inner.docs.description, "With details.");
+        assert_eq!(inner.methods[0].signature(), "static func make() -> Inner");
+    }
+}
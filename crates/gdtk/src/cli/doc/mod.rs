@@ -0,0 +1,173 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    cli::{
+        doc::{
+            bbcode::{Links, Target},
+            extract::ClassDoc,
+        },
+        unknown,
+        utils::ParserExt,
+    },
+    utils::{get_content, resolve_files_by_ext, try_parse_file},
+};
+
+pub mod bbcode;
+pub mod extract;
+pub mod site;
+pub mod xml;
+
+pub struct DocCommand {
+    /// Scripts, or directories with scripts, to document. Defaults to the
+    /// current directory.
+    pub files: Vec<PathBuf>,
+    pub format: Format,
+    /// The directory to write the documentation to.
+    pub output: PathBuf,
+}
+
+pub enum Format {
+    Markdown,
+    Html,
+    /// Godot's XML class reference format.
+    Xml,
+}
+
+impl tapcli::Command for DocCommand {
+    type Error = anyhow::Error;
+
+    fn parse(parser: &mut tapcli::Parser) -> Result<Self, Self::Error> {
+        let mut files = Vec::new();
+        let mut format = Format::Markdown;
+        let mut output = PathBuf::from("docs");
+
+        while let Some(arg) = parser.next() {
+            match arg.as_ref() {
+                tapcli::ArgRef::Long("format") => {
+                    format = match parser.next_value()?.as_str() {
+                        "markdown" | "md" => Format::Markdown,
+                        "html" => Format::Html,
+                        "xml" => Format::Xml,
+                        other => anyhow::bail!(
+                            "Unknown format: {other:?}. Expected one of: markdown, html, xml."
+                        ),
+                    }
+                }
+                tapcli::ArgRef::Long("output") => output = parser.next_value()?.into(),
+                tapcli::ArgRef::Value(path) => files.push(path.into()),
+                _ => unknown!(arg),
+            }
+        }
+
+        if files.is_empty() {
+            files.push(PathBuf::from("."));
+        }
+
+        Ok(Self {
+            files,
+            format,
+            output,
+        })
+    }
+
+    fn run(self) -> Result<Self::Output, Self::Error> {
+        let files = resolve_files_by_ext(self.files, "gd")?;
+        let mut classes = vec![];
+        // the file each class comes from, as every class gets a page named after it
+        let mut sources = HashMap::new();
+        let mut broken = 0;
+
+        for file in &files {
+            let documented = match document_file(file) {
+                Ok(documented) => documented,
+                Err(error) => {
+                    eprintln!("{}: {error}\n", file.display());
+                    broken += 1;
+                    continue;
+                }
+            };
+
+            for class in documented {
+                if let Some(other) = sources.insert(class.name.clone(), file) {
+                    anyhow::bail!(
+                        "Both `{}` and `{}` define a class named `{}`. Give one of them a different `class_name`.",
+                        other.display(),
+                        file.display(),
+                        class.name
+                    );
+                }
+
+                classes.push(class);
+            }
+        }
+
+        if broken > 0 {
+            anyhow::bail!("Couldn't document {} file(s).", broken);
+        }
+
+        classes.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+
+        std::fs::create_dir_all(&self.output)?;
+
+        let names = classes
+            .iter()
+            .map(|class| class.name.clone())
+            .collect::<HashSet<_>>();
+
+        let (target, extension) = match self.format {
+            Format::Markdown => (Target::Markdown, "md"),
+            Format::Html => (Target::Html, "html"),
+            Format::Xml => {
+                for class in &classes {
+                    let path = self.output.join(format!("{}.xml", class.name));
+                    std::fs::write(path, xml::class_xml(class))?;
+                }
+
+                eprintln!(
+                    "Documented {} class(es) in {}.",
+                    classes.len(),
+                    self.output.display()
+                );
+
+                return Ok(());
+            }
+        };
+
+        let links = Links {
+            classes: &names,
+            extension,
+        };
+
+        for class in &classes {
+            let path = self.output.join(format!("{}.{}", class.name, extension));
+            std::fs::write(path, site::class_page(class, target, &links))?;
+        }
+
+        let index = self.output.join(format!("index.{extension}"));
+        std::fs::write(index, site::index_page(&classes, target, &links))?;
+
+        eprintln!(
+            "Documented {} class(es) in {}.",
+            classes.len(),
+            self.output.display()
+        );
+
+        Ok(())
+    }
+}
+
+fn document_file(file: &Path) -> anyhow::Result<Vec<ClassDoc>> {
+    let content = get_content(file)?;
+    let comments = gdtk_gdscript_parser::lexer::doc_comments(&content);
+    let parsed = try_parse_file(&content).map_err(|message| anyhow::anyhow!(message))?;
+
+    let name = file
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("<stdin>");
+
+    Ok(extract::extract(&content, &parsed.body, &comments, name))
+}
@@ -0,0 +1,292 @@
+//! Markdown and HTML documentation sites.
+
+use std::fmt::Write;
+
+use crate::cli::doc::{
+    bbcode::{convert, escape_html, Links, Target},
+    extract::{ClassDoc, Docs},
+};
+
+/// Render the page of a class.
+pub fn class_page(class: &ClassDoc, target: Target, links: &Links<'_>) -> String {
+    let mut page = Page {
+        out: String::new(),
+        target,
+        links,
+    };
+
+    page.heading(1, &class.name, None);
+
+    if let Some(extends) = &class.extends {
+        let extends = match links.class(extends) {
+            Some(url) => page.link(&page.code(extends), &url),
+            None => page.code(extends),
+        };
+
+        let inherits = format!("{} {}", page.strong("Inherits:"), extends);
+        page.raw(&inherits);
+    }
+
+    page.notes(&class.docs);
+
+    if !class.docs.brief.is_empty() {
+        page.bbcode(&class.docs.brief);
+    }
+
+    if !class.docs.description.is_empty() {
+        page.heading(2, "Description", None);
+        page.bbcode(&class.docs.description);
+    }
+
+    if !class.docs.tutorials.is_empty() {
+        page.heading(2, "Tutorials", None);
+
+        let items = class
+            .docs
+            .tutorials
+            .iter()
+            .map(|(title, url)| {
+                let title = if title.is_empty() { url } else { title };
+                page.link(&page.escape(title), url)
+            })
+            .collect::<Vec<_>>();
+
+        page.list(&items);
+    }
+
+    if !class.signals.is_empty() {
+        page.heading(2, "Signals", None);
+
+        for signal in &class.signals {
+            page.member(&signal.name, &signal.signature(), &signal.docs);
+        }
+    }
+
+    if !class.enums.is_empty() {
+        page.heading(2, "Enumerations", None);
+
+        for enum_ in &class.enums {
+            let anchor = enum_.name.as_deref().unwrap_or_default();
+            page.member(anchor, &enum_.signature(), &enum_.docs);
+
+            let items = enum_
+                .variants
+                .iter()
+                .map(|variant| {
+                    let mut item = page.code(&format!("{} = {}", variant.name, variant.value));
+
+                    if !variant.docs.description.is_empty() {
+                        item.push_str(" — ");
+                        item.push_str(&convert(&variant.docs.description, target, links));
+                    }
+
+                    item
+                })
+                .collect::<Vec<_>>();
+
+            page.list(&items);
+        }
+    }
+
+    if !class.constants.is_empty() {
+        page.heading(2, "Constants", None);
+
+        for constant in &class.constants {
+            page.member(&constant.name, &constant.signature(), &constant.docs);
+        }
+    }
+
+    if !class.properties.is_empty() {
+        page.heading(2, "Properties", None);
+
+        for property in &class.properties {
+            page.member(&property.name, &property.signature(), &property.docs);
+        }
+    }
+
+    if !class.methods.is_empty() {
+        page.heading(2, "Methods", None);
+
+        for method in &class.methods {
+            page.member(&method.name, &method.signature(), &method.docs);
+        }
+    }
+
+    page.finish(&class.name)
+}
+
+/// Render the index page, which lists all classes.
+pub fn index_page(classes: &[ClassDoc], target: Target, links: &Links<'_>) -> String {
+    let mut page = Page {
+        out: String::new(),
+        target,
+        links,
+    };
+
+    page.heading(1, "API reference", None);
+
+    let items = classes
+        .iter()
+        .map(|class| {
+            let mut item = page.link(
+                &page.escape(&class.name),
+                &links.class(&class.name).unwrap(),
+            );
+
+            if !class.docs.brief.is_empty() {
+                item.push_str(" — ");
+                item.push_str(&convert(&class.docs.brief, target, links));
+            }
+
+            item
+        })
+        .collect::<Vec<_>>();
+
+    page.list(&items);
+
+    page.finish("API reference")
+}
+
+struct Page<'a> {
+    out: String,
+    target: Target,
+    links: &'a Links<'a>,
+}
+
+impl Page<'_> {
+    fn member(&mut self, anchor: &str, signature: &str, docs: &Docs) {
+        let code = self.code(signature);
+        let anchor = (!anchor.is_empty()).then_some(anchor);
+
+        self.heading(3, &code, anchor);
+        self.notes(docs);
+
+        if !docs.description.is_empty() {
+            self.bbcode(&docs.description);
+        }
+    }
+
+    /// `@deprecated` and `@experimental` notes.
+    fn notes(&mut self, docs: &Docs) {
+        for (label, message) in [
+            ("Deprecated", &docs.deprecated),
+            ("Experimental", &docs.experimental),
+        ] {
+            let Some(message) = message else {
+                continue;
+            };
+
+            let mut note = self.strong(&format!("{label}."));
+
+            if !message.is_empty() {
+                note.push(' ');
+                note.push_str(&convert(message, self.target, self.links));
+            }
+
+            match self.target {
+                Target::Markdown => writeln!(self.out, "> {note}\n").unwrap(),
+                Target::Html => writeln!(self.out, "<blockquote>{note}</blockquote>").unwrap(),
+            }
+        }
+    }
+
+    /// A heading whose `text` is already rendered.
+    fn heading(&mut self, level: usize, text: &str, anchor: Option<&str>) {
+        match self.target {
+            Target::Markdown => {
+                if let Some(anchor) = anchor {
+                    writeln!(self.out, "<a id=\"{anchor}\"></a>\n").unwrap();
+                }
+
+                writeln!(self.out, "{} {}\n", "#".repeat(level), text).unwrap();
+            }
+            Target::Html => {
+                let id = anchor
+                    .map(|anchor| format!(" id=\"{}\"", escape_html(anchor)))
+                    .unwrap_or_default();
+
+                writeln!(self.out, "<h{level}{id}>{text}</h{level}>").unwrap();
+            }
+        }
+    }
+
+    fn bbcode(&mut self, input: &str) {
+        let text = convert(input, self.target, self.links);
+        self.raw(&text);
+    }
+
+    /// A paragraph whose `text` is already rendered.
+    fn raw(&mut self, text: &str) {
+        match self.target {
+            Target::Markdown => writeln!(self.out, "{text}\n").unwrap(),
+            Target::Html => writeln!(self.out, "<div>{text}</div>").unwrap(),
+        }
+    }
+
+    /// A list whose `items` are already rendered.
+    fn list(&mut self, items: &[String]) {
+        match self.target {
+            Target::Markdown => {
+                for item in items {
+                    writeln!(self.out, "- {item}").unwrap();
+                }
+
+                self.out.push('\n');
+            }
+            Target::Html => {
+                self.out.push_str("<ul>\n");
+
+                for item in items {
+                    writeln!(self.out, "<li>{item}</li>").unwrap();
+                }
+
+                self.out.push_str("</ul>\n");
+            }
+        }
+    }
+
+    fn code(&self, input: &str) -> String {
+        match self.target {
+            Target::Markdown => format!("`{input}`"),
+            Target::Html => format!("<code>{}</code>", escape_html(input)),
+        }
+    }
+
+    fn strong(&self, input: &str) -> String {
+        match self.target {
+            Target::Markdown => format!("**{input}**"),
+            Target::Html => format!("<strong>{}</strong>", escape_html(input)),
+        }
+    }
+
+    fn link(&self, label: &str, url: &str) -> String {
+        match self.target {
+            Target::Markdown => format!("[{label}]({url})"),
+            Target::Html => format!("<a href=\"{}\">{label}</a>", escape_html(url)),
+        }
+    }
+
+    fn escape(&self, input: &str) -> String {
+        match self.target {
+            Target::Markdown => input.to_owned(),
+            Target::Html => escape_html(input),
+        }
+    }
+
+    fn finish(self, title: &str) -> String {
+        match self.target {
+            Target::Markdown => self.out,
+            Target::Html => format!(
+                "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+                escape_html(title),
+                STYLE,
+                self.out,
+            ),
+        }
+    }
+}
+
+const STYLE: &str = "body { max-width: 60rem; margin: 2rem auto; padding: 0 1rem; font-family: sans-serif; line-height: 1.5; } \
+code, pre { font-family: monospace; background: #f2f2f2; border-radius: 4px; } \
+pre { padding: 0.75rem; overflow-x: auto; } \
+blockquote { margin: 0; padding: 0.25rem 1rem; border-left: 4px solid #e0a000; }";
@@ -0,0 +1,213 @@
+//! Godot's XML class reference format, as used by `doc/classes` in the engine
+//! and by `--doctool`. Texts are kept as BBCode.
+
+use std::fmt::Write;
+
+use crate::cli::doc::{
+    bbcode::escape_html as escape,
+    extract::{ClassDoc, Docs, ParameterDoc},
+};
+
+/// The type GDScript gives to untyped values.
+const VARIANT: &str = "Variant";
+
+/// The base class of scripts without `extends`.
+const REF_COUNTED: &str = "RefCounted";
+
+pub fn class_xml(class: &ClassDoc) -> String {
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\" ?>\n");
+
+    writeln!(
+        out,
+        "<class name=\"{}\" inherits=\"{}\"{} xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\" xsi:noNamespaceSchemaLocation=\"https://raw.githubusercontent.com/godotengine/godot/master/doc/class.xsd\">",
+        escape(&class.name),
+        escape(class.extends.as_deref().unwrap_or(REF_COUNTED)),
+        notes(&class.docs),
+    )
+    .unwrap();
+
+    text_element(&mut out, 1, "brief_description", &class.docs.brief);
+    text_element(&mut out, 1, "description", &class.docs.description);
+
+    if class.docs.tutorials.is_empty() {
+        out.push_str("\t<tutorials>\n\t</tutorials>\n");
+    } else {
+        out.push_str("\t<tutorials>\n");
+
+        for (title, url) in &class.docs.tutorials {
+            writeln!(
+                out,
+                "\t\t<link title=\"{}\">{}</link>",
+                escape(title),
+                escape(url)
+            )
+            .unwrap();
+        }
+
+        out.push_str("\t</tutorials>\n");
+    }
+
+    if !class.methods.is_empty() {
+        out.push_str("\t<methods>\n");
+
+        for method in &class.methods {
+            let qualifiers = match method.is_static {
+                true => " qualifiers=\"static\"",
+                false => "",
+            };
+
+            writeln!(
+                out,
+                "\t\t<method name=\"{}\"{}{}>",
+                escape(&method.name),
+                qualifiers,
+                notes(&method.docs),
+            )
+            .unwrap();
+            writeln!(
+                out,
+                "\t\t\t<return type=\"{}\" />",
+                escape(method.return_type.as_deref().unwrap_or(VARIANT))
+            )
+            .unwrap();
+            parameters(&mut out, &method.parameters);
+            text_element(&mut out, 3, "description", &method.docs.description);
+            out.push_str("\t\t</method>\n");
+        }
+
+        out.push_str("\t</methods>\n");
+    }
+
+    if !class.properties.is_empty() {
+        out.push_str("\t<members>\n");
+
+        for property in &class.properties {
+            write!(
+                out,
+                "\t\t<member name=\"{}\" type=\"{}\" setter=\"\" getter=\"\"",
+                escape(&property.name),
+                escape(property.typehint.as_deref().unwrap_or(VARIANT)),
+            )
+            .unwrap();
+
+            if let Some(default) = &property.default {
+                write!(out, " default=\"{}\"", escape(default)).unwrap();
+            }
+
+            writeln!(out, "{}>", notes(&property.docs)).unwrap();
+            text(&mut out, 3, &property.docs.description);
+            out.push_str("\t\t</member>\n");
+        }
+
+        out.push_str("\t</members>\n");
+    }
+
+    if !class.signals.is_empty() {
+        out.push_str("\t<signals>\n");
+
+        for signal in &class.signals {
+            writeln!(
+                out,
+                "\t\t<signal name=\"{}\"{}>",
+                escape(&signal.name),
+                notes(&signal.docs),
+            )
+            .unwrap();
+            parameters(&mut out, &signal.parameters);
+            text_element(&mut out, 3, "description", &signal.docs.description);
+            out.push_str("\t\t</signal>\n");
+        }
+
+        out.push_str("\t</signals>\n");
+    }
+
+    let enum_variants = class.enums.iter().flat_map(|enum_| {
+        enum_
+            .variants
+            .iter()
+            .map(move |variant| (variant, enum_.name.as_deref()))
+    });
+    let constants = enum_variants
+        .chain(class.constants.iter().map(|constant| (constant, None)))
+        .collect::<Vec<_>>();
+
+    if !constants.is_empty() {
+        out.push_str("\t<constants>\n");
+
+        for (constant, enum_) in constants {
+            write!(
+                out,
+                "\t\t<constant name=\"{}\" value=\"{}\"",
+                escape(&constant.name),
+                escape(&constant.value),
+            )
+            .unwrap();
+
+            if let Some(enum_) = enum_ {
+                write!(out, " enum=\"{}\"", escape(enum_)).unwrap();
+            }
+
+            writeln!(out, "{}>", notes(&constant.docs)).unwrap();
+            text(&mut out, 3, &constant.docs.description);
+            out.push_str("\t\t</constant>\n");
+        }
+
+        out.push_str("\t</constants>\n");
+    }
+
+    out.push_str("</class>\n");
+
+    out
+}
+
+/// `deprecated` and `experimental` attributes.
+fn notes(docs: &Docs) -> String {
+    let mut out = String::new();
+
+    if let Some(message) = &docs.deprecated {
+        write!(out, " deprecated=\"{}\"", escape(message)).unwrap();
+    }
+
+    if let Some(message) = &docs.experimental {
+        write!(out, " experimental=\"{}\"", escape(message)).unwrap();
+    }
+
+    out
+}
+
+fn parameters(out: &mut String, parameters: &[ParameterDoc]) {
+    for (index, param) in parameters.iter().enumerate() {
+        write!(
+            out,
+            "\t\t\t<param index=\"{}\" name=\"{}\" type=\"{}\"",
+            index,
+            escape(&param.name),
+            escape(param.typehint.as_deref().unwrap_or(VARIANT)),
+        )
+        .unwrap();
+
+        if let Some(default) = &param.default {
+            write!(out, " default=\"{}\"", escape(default)).unwrap();
+        }
+
+        out.push_str(" />\n");
+    }
+}
+
+fn text_element(out: &mut String, indent: usize, tag: &str, content: &str) {
+    writeln!(out, "{}<{}>", "\t".repeat(indent), tag).unwrap();
+    text(out, indent + 1, content);
+    writeln!(out, "{}</{}>", "\t".repeat(indent), tag).unwrap();
+}
+
+/// Text lines, indented with tabs like in the engine's class reference.
+fn text(out: &mut String, indent: usize, content: &str) {
+    for line in content.lines() {
+        if !line.is_empty() {
+            out.push_str(&"\t".repeat(indent));
+            out.push_str(&escape(line));
+        }
+
+        out.push('\n');
+    }
+}
@@ -1,27 +1,42 @@
 use gdtk_gvm::VersionManager;
 
-use crate::cli::{godot::symlink_default_version, utils::prompt_local_version};
-
-pub struct GodotDefaultCommand;
+use crate::cli::{
+    godot::set_default_version,
+    unknown,
+    utils::{select_local_version, VersionQuery},
+};
+
+pub struct GodotDefaultCommand {
+    query: VersionQuery,
+}
 
 impl tapcli::Command for GodotDefaultCommand {
     type Error = anyhow::Error;
 
-    fn parse(_: &mut tapcli::Parser) -> Result<Self, Self::Error> {
-        Ok(Self)
+    fn parse(parser: &mut tapcli::Parser) -> Result<Self, Self::Error> {
+        let mut query = VersionQuery::default();
+
+        for arg in parser {
+            if !query.accept(arg.as_ref()) {
+                unknown!(arg);
+            }
+        }
+
+        Ok(Self { query })
     }
 
     fn run(self) -> Result<Self::Output, Self::Error> {
-        let manager = VersionManager::load()?;
+        let mut manager = VersionManager::load()?;
 
         if manager.is_empty() {
             eprintln!("No versions installed. Install one by running `gdtk godot install`.");
             return Ok(());
         }
 
-        let version = prompt_local_version(&manager)?;
+        let version = select_local_version(&manager, &self.query)?.clone();
 
-        symlink_default_version(&version.path())?;
+        set_default_version(&mut manager, &version)?;
+        manager.save()?;
 
         eprintln!("Version {} set as default!", version);
 
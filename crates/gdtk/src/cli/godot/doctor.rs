@@ -0,0 +1,239 @@
+use std::{env::consts::EXE_SUFFIX, fmt::Display};
+
+use gdtk_gvm::{
+    types::{LocalVersion, VersionOrigin},
+    VersionManager,
+};
+use gdtk_paths::camino::Utf8PathBuf;
+
+use crate::cli::{
+    godot::{
+        set_default_version,
+        shim::{install_shims, missing_shims},
+    },
+    unknown,
+    utils::confirm,
+};
+
+pub struct GodotDoctorCommand {
+    yes: bool,
+}
+
+enum Issue {
+    /// The `local_versions` file can't be read.
+    Unreadable(gdtk_gvm::Error),
+    /// A Godot installation in `godots_path` that no version refers to.
+    Orphaned {
+        path: Utf8PathBuf,
+        name: String,
+        mono: bool,
+    },
+    /// A folder in `godots_path` that no version refers to and that doesn't
+    /// look like a Godot installation.
+    Stray(Utf8PathBuf),
+    /// A version whose files are gone.
+    Missing(LocalVersion),
+    /// There are versions, but none of them is the default one.
+    NoDefault,
+    /// Some of the shims are missing.
+    MissingShims(Vec<Utf8PathBuf>),
+}
+
+impl Display for Issue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Issue::Unreadable(error) => {
+                write!(f, "The local versions database is unreadable: {}", error)
+            }
+            Issue::Orphaned { path, .. } => write!(
+                f,
+                "`{}` doesn't belong to any version, and will be registered again.",
+                path
+            ),
+            Issue::Stray(path) => write!(
+                f,
+                "`{}` doesn't belong to any version or contain Godot, and will be deleted.",
+                path
+            ),
+            Issue::Missing(version) => {
+                write!(f, "Godot {} is missing from `{}`.", version, version.path)
+            }
+            Issue::NoDefault => f.write_str("No default version is set."),
+            Issue::MissingShims(shims) => {
+                let shims = shims.iter().map(|s| s.as_str()).collect::<Vec<_>>();
+
+                write!(f, "Missing shims: {}.", shims.join(", "))
+            }
+        }
+    }
+}
+
+impl tapcli::Command for GodotDoctorCommand {
+    type Error = anyhow::Error;
+
+    fn parse(parser: &mut tapcli::Parser) -> Result<Self, Self::Error> {
+        let mut yes = false;
+
+        for arg in parser {
+            match arg.as_ref() {
+                tapcli::ArgRef::Long("yes") | tapcli::ArgRef::Short('y') => yes = true,
+                _ => unknown!(arg),
+            }
+        }
+
+        Ok(Self { yes })
+    }
+
+    fn run(self) -> Result<Self::Output, Self::Error> {
+        let (mut manager, mut issues) = match VersionManager::load() {
+            Ok(manager) => (manager, vec![]),
+            // only a corrupted file is reset, everything in `godots_path` is then reported as
+            // orphaned and registered again. Other errors, like a database written by a newer
+            // version of gdtk, would lose data that is still fine.
+            Err(error @ gdtk_gvm::Error::RkyvError(_)) => {
+                (VersionManager::empty()?, vec![Issue::Unreadable(error)])
+            }
+            Err(error) => return Err(error.into()),
+        };
+
+        issues.extend(find_issues(&manager)?);
+
+        if issues.is_empty() {
+            eprintln!("No problems found.");
+            return Ok(());
+        }
+
+        for issue in &issues {
+            eprintln!("- {}", issue);
+        }
+
+        if !self.yes && !confirm("Repair?")? {
+            anyhow::bail!("Command cancelled.");
+        }
+
+        for issue in issues {
+            match issue {
+                Issue::Unreadable(_) => eprintln!("Resetting the local versions database."),
+                Issue::Orphaned { path, name, mono } => {
+                    repair_orphaned(&mut manager, path, name, mono)
+                }
+                Issue::Stray(path) => {
+                    std::fs::remove_dir_all(&path)?;
+                    eprintln!("Removed `{}`.", path);
+                }
+                Issue::Missing(version) => {
+                    manager.remove_version(&version.name, version.mono);
+                    eprintln!("Unregistered Godot {}.", version);
+                }
+                // handled below, once all versions are registered
+                Issue::NoDefault => (),
+                Issue::MissingShims(_) => {
+                    install_shims()?;
+                    eprintln!("Installed the shims.");
+                }
+            }
+        }
+
+        // missing versions might have included the default one
+        if manager.default_version().is_none() {
+            let newest = manager
+                .installed()
+                .iter()
+                .max_by_key(|v| v.as_ordered())
+                .cloned();
+
+            if let Some(newest) = newest {
+                set_default_version(&mut manager, &newest)?;
+                eprintln!("Godot {} set as default.", newest);
+            }
+        }
+
+        manager.save()?;
+
+        eprintln!("Done!");
+
+        Ok(())
+    }
+}
+
+fn find_issues(manager: &VersionManager) -> anyhow::Result<Vec<Issue>> {
+    let mut issues = vec![];
+
+    for version in manager.installed() {
+        if !version.path().exists() {
+            issues.push(Issue::Missing(version.clone()));
+        }
+    }
+
+    for entry in gdtk_paths::godots_path()?.read_dir_utf8()? {
+        let entry = entry?;
+
+        // hidden folders are installs in progress, see `GodotInstallCommand::run`
+        if !entry.file_type()?.is_dir() || entry.file_name().starts_with('.') {
+            continue;
+        }
+
+        let path = entry.path().to_owned();
+        let owned = manager
+            .installed()
+            .iter()
+            .any(|v| v.is_owned() && v.path() == path);
+
+        if owned {
+            continue;
+        }
+
+        if !path.join(format!("godot{}", EXE_SUFFIX)).is_file() {
+            issues.push(Issue::Stray(path));
+            continue;
+        }
+
+        // see the naming in `GodotInstallCommand::run`
+        let (name, mono) = match entry.file_name().strip_suffix("-mono") {
+            Some(name) => (name.to_owned(), true),
+            None => (entry.file_name().to_owned(), false),
+        };
+
+        issues.push(Issue::Orphaned { path, name, mono });
+    }
+
+    // without versions, there's nothing to be the default or for shims to run
+    if manager.is_empty() {
+        return Ok(issues);
+    }
+
+    if manager.default_version().is_none() {
+        issues.push(Issue::NoDefault);
+    }
+
+    let missing = missing_shims()?;
+
+    if !missing.is_empty() {
+        issues.push(Issue::MissingShims(missing));
+    }
+
+    Ok(issues)
+}
+
+/// Register an orphaned Godot installation again.
+fn repair_orphaned(manager: &mut VersionManager, path: Utf8PathBuf, name: String, mono: bool) {
+    if manager.get_version(&name, mono).is_some() {
+        eprintln!(
+            "Skipped `{}`, a version with the same name is already registered.",
+            path
+        );
+        return;
+    }
+
+    let version = LocalVersion {
+        name,
+        path: path.into_string(),
+        mono,
+        sha512: None,
+        origin: VersionOrigin::Installed,
+    };
+
+    eprintln!("Registered Godot {}.", version);
+
+    manager.add_version(version);
+}
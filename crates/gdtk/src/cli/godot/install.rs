@@ -1,92 +1,196 @@
 use std::{
     env::consts::EXE_SUFFIX,
     fs::File,
-    io::{Read, Seek},
+    io::{Read, Seek, Write},
 };
 
-use cliui::{Action, Prompt, StateDisplay};
+use cliui::{Action, Prompt};
 use gdtk_gvm::{
-    online::{fetch_version_assets, fetch_versions},
-    types::LocalVersion,
-    utils::pick_asset,
+    online::{fetch_checksum, fetch_version_assets, fetch_versions},
+    types::{LocalVersion, VersionOrigin},
+    utils::{coerce_version, lock_exclusive, pick_asset, sha512, verify_checksum},
     version::OnlineVersion,
+    VersionManager,
 };
 use gdtk_paths::camino::{Utf8Path, Utf8PathBuf};
 
-use super::symlink_default_version;
+use super::set_default_version;
+use crate::cli::{
+    unknown,
+    utils::{ParserExt, VersionQuery},
+};
 
 pub struct GodotInstallCommand {
-    version: OnlineVersion,
+    name: String,
     mono: bool,
+    /// Install from this archive instead of downloading one.
+    archive: Option<Utf8PathBuf>,
+}
+
+impl GodotInstallCommand {
+    /// Select a version to install. See [select_online_version].
+    pub fn from_query(
+        query: &VersionQuery,
+        latest: bool,
+        prerelease: bool,
+    ) -> anyhow::Result<Self> {
+        let (version, mono) = select_online_version(query, latest, prerelease)?;
+
+        Ok(Self {
+            name: version.name().to_owned(),
+            mono,
+            archive: None,
+        })
+    }
+
+    /// Install from a local archive. The version is taken from `query` or,
+    /// if not specified, from the archive's name (e.g. `Godot_v4.3-stable_linux.x86_64.zip`).
+    pub fn from_archive(archive: Utf8PathBuf, query: &VersionQuery) -> anyhow::Result<Self> {
+        let file_name = archive.file_name().unwrap_or_default();
+
+        let name = match &query.version {
+            Some(version) => version.clone(),
+            None => match file_name
+                .strip_prefix("Godot_v")
+                .and_then(|rest| rest.split('_').next())
+            {
+                Some(name) => name.to_owned(),
+                None => anyhow::bail!(
+                    "Couldn't tell the version of `{}`, please specify it.",
+                    file_name
+                ),
+            },
+        };
+
+        Ok(Self {
+            name,
+            mono: query.mono || file_name.contains("_mono_"),
+            archive: Some(archive),
+        })
+    }
 }
 
 impl tapcli::Command for GodotInstallCommand {
     type Error = anyhow::Error;
 
-    fn parse(_: &mut tapcli::Parser) -> Result<Self, Self::Error> {
-        let (version, mono) = prompt_for_version()?;
+    fn parse(parser: &mut tapcli::Parser) -> Result<Self, Self::Error> {
+        let mut query = VersionQuery::default();
+        let mut latest = false;
+        let mut prerelease = false;
+        let mut archive = None;
+
+        while let Some(arg) = parser.next() {
+            match arg.as_ref() {
+                tapcli::ArgRef::Long("latest") => latest = true,
+                tapcli::ArgRef::Long("prerelease") => prerelease = true,
+                tapcli::ArgRef::Long("from-archive") => {
+                    archive = Some(Utf8PathBuf::from(parser.next_value()?))
+                }
+                _ if query.accept(arg.as_ref()) => (),
+                _ => unknown!(arg),
+            }
+        }
 
-        Ok(Self { version, mono })
+        match archive {
+            Some(archive) => Self::from_archive(archive, &query),
+            None => Self::from_query(&query, latest, prerelease),
+        }
     }
 
     fn run(self) -> Result<Self::Output, Self::Error> {
-        let mut manager = gdtk_gvm::VersionManager::load()?;
-
-        let target_dir = format!(
-            "{}{}",
-            self.version.name(),
-            if self.mono { "-mono" } else { "" }
-        );
-        let target_dir = gdtk_paths::godots_path()?.join(&target_dir);
-
-        if manager
-            .get_version(self.version.name(), self.mono)
+        let display = format!("{}{}", self.name, if self.mono { " (mono)" } else { "" });
+        let dir_name = format!("{}{}", self.name, if self.mono { "-mono" } else { "" });
+        let godots = gdtk_paths::godots_path()?;
+        let target_dir = godots.join(&dir_name);
+
+        // the manager is locked while it's alive, so don't hold it during the download
+        if VersionManager::load()?
+            .get_version(&self.name, self.mono)
             .is_some()
         {
-            anyhow::bail!("Godot {} is already installed.", self.version.name());
+            anyhow::bail!("Godot {} is already installed.", display);
         }
 
-        let assets = fetch_version_assets(self.version.name())?;
-        let asset = pick_asset(&assets, self.mono)
-            .expect("Couldn't find a Godot build for current OS/arch pair.");
-
         let mut status = spinoff::Spinner::new(
             spinoff::spinners::Dots2,
             "Preparing..",
             spinoff::Color::Cyan,
         );
 
-        let resp = ureq::get(&asset.download_url.0).call()?;
+        let (archive, checksum) = match self.archive {
+            // there's nothing to verify against offline, but the hash is still worth recording
+            Some(archive) => {
+                let checksum = sha512(File::open(&archive)?)?;
 
-        let content = download(resp, &mut status)?;
+                (archive, checksum)
+            }
+            None => {
+                let assets = fetch_version_assets(&self.name)?;
+                let Some(asset) = pick_asset(&assets, self.mono) else {
+                    anyhow::bail!("Couldn't find a Godot build for current OS/arch pair.");
+                };
 
-        let mut source = std::io::Cursor::new(content);
+                let checksum = fetch_checksum(&assets, &asset.name)?;
+                let archive = download(&asset.download_url.0, &asset.name, &mut status)?;
+
+                status.update_text("Verifying..");
+
+                verify_cached_download(&archive, &checksum, &asset.name)?;
+
+                (archive, checksum)
+            }
+        };
 
         status.update_text("Extracting..");
 
-        extract_godot(&mut source, &target_dir, self.mono)?;
+        // extract next to the target and move it into place when done, so that
+        // a failed or concurrent install never leaves a half-extracted version behind
+        let staging = godots.join(format!(".{}.{}.partial", dir_name, std::process::id()));
+
+        rollback(&staging, || {
+            extract_godot(File::open(&archive)?, &staging, self.mono)?;
+
+            // Enable self-contained mode.
+            File::create(staging.join("._sc_"))?;
+
+            Ok(())
+        })?;
 
         status.update_text("Setting up..");
 
-        // Enable self-contained mode.
-        std::fs::File::create(target_dir.join("._sc_"))?;
+        let mut manager = VersionManager::load()?;
 
-        if manager.is_empty() {
-            symlink_default_version(&target_dir)?;
+        // another install might have finished in the meantime
+        if manager.get_version(&self.name, self.mono).is_some() || target_dir.exists() {
+            std::fs::remove_dir_all(&staging)?;
+            anyhow::bail!(
+                "Godot {} was installed by someone else in the meantime. If it doesn't work, run `gdtk godot doctor`.",
+                display
+            );
         }
 
-        manager.add_version(LocalVersion {
-            name: self.version.name().to_owned(),
-            path: target_dir.into_string(),
+        rollback(&staging, || Ok(std::fs::rename(&staging, &target_dir)?))?;
+
+        let version = LocalVersion {
+            name: self.name,
+            path: target_dir.clone().into_string(),
             mono: self.mono,
-        });
+            sha512: Some(checksum),
+            origin: VersionOrigin::Installed,
+        };
+
+        rollback(&target_dir, || {
+            if manager.is_empty() {
+                set_default_version(&mut manager, &version)?;
+            }
+
+            manager.add_version(version);
+            manager.save()?;
 
-        manager.save()?;
+            Ok(())
+        })?;
 
-        status.success(&format!(
-            "Installed Godot {}!",
-            self.version.display(&self.mono)
-        ));
+        status.success(&format!("Installed Godot {}!", display));
 
         Ok(())
     }
@@ -94,44 +198,142 @@ impl tapcli::Command for GodotInstallCommand {
 
 // 16KB at a time
 const BYTES_AT_A_TIME: usize = 1024 * 16;
-// most godot version don't exceed 100MB, so
-// this should help avoid spontaneous allocations
-const PREALLOCATION_SIZE: usize = 1024 * 1024 * 100;
 
-fn download(
-    resp: ureq::http::Response<ureq::Body>,
+/// Download `url` into the download cache as `file_name`, resuming a previous
+/// partial download if there is one. Returns the path to the downloaded file.
+pub fn download(
+    url: &str,
+    file_name: &str,
     status: &mut spinoff::Spinner,
-) -> anyhow::Result<Vec<u8>> {
+) -> anyhow::Result<Utf8PathBuf> {
+    let downloads = gdtk_paths::downloads_path()?;
+    let target = downloads.join(file_name);
+
+    // concurrent installs of the same version would otherwise append to and
+    // resume from each other's partial file. whoever comes second waits and
+    // then finds the download in the cache
+    let _lock = lock_exclusive(downloads.join(format!("{}.lock", file_name)))?;
+
+    if target.exists() {
+        status.update_text("Using a cached download..");
+        return Ok(target);
+    }
+
+    let partial = downloads.join(format!("{}.part", file_name));
+    let offset = std::fs::metadata(&partial).map_or(0, |m| m.len());
+
+    let mut request = ureq::get(url);
+
+    if offset > 0 {
+        request = request.header(ureq::http::header::RANGE, format!("bytes={}-", offset));
+    }
+
+    let resp = match request.call() {
+        // the partial download is actually complete
+        Err(ureq::Error::StatusCode(416)) if offset > 0 => {
+            std::fs::rename(&partial, &target)?;
+            return Ok(target);
+        }
+        other => other?,
+    };
+
+    // the server is free to ignore the range and send the whole file
+    let resumed = resp.status() == ureq::http::StatusCode::PARTIAL_CONTENT;
+    let offset = if resumed { offset } else { 0 };
+
     let total = resp
         .headers()
         .get(ureq::http::header::CONTENT_LENGTH)
-        .map(|v| v.to_str())
-        .unwrap()?
-        .parse::<usize>()? as f64;
-
-    let mut percentage = 0;
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<u64>().ok())
+        .map(|length| length + offset);
+
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(resumed)
+        .truncate(!resumed)
+        .open(&partial)?;
+
+    let mut downloaded = offset;
+    let mut progress = String::new();
     let mut reader = resp.into_body().into_reader();
     let mut chunk = [0u8; BYTES_AT_A_TIME];
-    let mut output = Vec::with_capacity(PREALLOCATION_SIZE);
 
-    while let Ok(bytes_read) = reader.read(&mut chunk) {
-        let new_percentage = ((output.len() as f64 / total) * 100.) as usize;
-
-        if new_percentage != percentage {
-            percentage = new_percentage;
-            status.update_text(format!("Downloading.. {}%", percentage));
-        }
+    loop {
+        let bytes_read = reader.read(&mut chunk)?;
 
         if bytes_read == 0 {
             break;
         }
 
-        output.extend_from_slice(&chunk[..bytes_read]);
+        file.write_all(&chunk[..bytes_read])?;
+        downloaded += bytes_read as u64;
+
+        let new_progress = match total {
+            Some(total) => format!("{}%", downloaded * 100 / total),
+            None => format!("{} MB", downloaded / 1024 / 1024),
+        };
+
+        if new_progress != progress {
+            progress = new_progress;
+            status.update_text(format!("Downloading.. {}", progress));
+        }
     }
 
+    drop(file);
+    std::fs::rename(&partial, &target)?;
+
     status.update_text("Download complete!");
 
-    Ok(output)
+    Ok(target)
+}
+
+/// Verify a downloaded archive, removing it from the cache if it's broken
+/// so that it's downloaded again next time.
+pub fn verify_cached_download(
+    archive: &Utf8Path,
+    checksum: &str,
+    asset: &str,
+) -> anyhow::Result<()> {
+    if let Err(error) = verify_checksum(File::open(archive)?, checksum, asset) {
+        std::fs::remove_file(archive)?;
+
+        return Err(error.into());
+    }
+
+    Ok(())
+}
+
+/// Run `f`, removing `path` if it fails.
+pub fn rollback<T>(path: &Utf8Path, f: impl FnOnce() -> anyhow::Result<T>) -> anyhow::Result<T> {
+    let result = f();
+
+    if result.is_err() && path.exists() {
+        // the original error is more useful than one from cleaning up
+        let _ = std::fs::remove_dir_all(path);
+    }
+
+    result
+}
+
+/// Write a file entry of an archive to `path`, keeping its permissions on Unix
+/// (e.g. so that Godot stays executable).
+pub fn extract_file(entry: &mut zip::read::ZipFile<'_>, path: &Utf8Path) -> anyhow::Result<()> {
+    let mut target = File::create(path)?;
+
+    std::io::copy(entry, &mut target)?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        if let Some(mode) = entry.unix_mode() {
+            target.set_permissions(std::fs::Permissions::from_mode(mode))?;
+        }
+    }
+
+    Ok(())
 }
 
 fn extract_godot(
@@ -160,9 +362,7 @@ fn extract_godot(
             continue;
         }
 
-        let mut entry_target = File::create(&full)?;
-
-        std::io::copy(&mut entry, &mut entry_target)?;
+        extract_file(&mut entry, &full)?;
     }
 
     Ok(())
@@ -197,12 +397,56 @@ fn normalize_entry_path(path: Utf8PathBuf, unwrap_top_dir: bool) -> Utf8PathBuf
 const TOGGLE_MONO_KEY: cliui::Key = cliui::Key::Char('m');
 const TOGGLE_MONO_DESC: &str = "Install the mono variant?";
 
-fn prompt_for_version() -> anyhow::Result<(OnlineVersion, bool)> {
-    let available_versions = fetch_versions()?;
+/// Find an online version matching `query`. Prereleases are only considered
+/// if `prerelease` is set or if they are requested by their full name.
+fn select_online_version(
+    query: &VersionQuery,
+    latest: bool,
+    prerelease: bool,
+) -> anyhow::Result<(OnlineVersion, bool)> {
+    let input = query.version.as_deref();
+    let available_versions = fetch_versions()?
+        .into_iter()
+        .filter(|v| prerelease || !v.is_dev() || Some(v.name()) == input)
+        .collect::<Vec<_>>();
+
+    let Some(input) = input else {
+        if latest || query.yes {
+            let Some(newest) = available_versions.into_iter().next() else {
+                anyhow::bail!("No versions available.");
+            };
+
+            return Ok((newest, query.mono));
+        }
+
+        return prompt_for_version(available_versions, query.mono);
+    };
+
+    // versions are sorted from newest to oldest
+    let mut matches = coerce_version(input, available_versions)?;
+
+    if let Some(idx) = matches.iter().position(|v| v.name() == input) {
+        return Ok((matches.swap_remove(idx), query.mono));
+    }
 
+    match matches.len() {
+        0 => anyhow::bail!(
+            "No Godot version matches `{}`. Run `gdtk godot list --online --refresh` to update the list of versions.",
+            input
+        ),
+        1 => Ok((matches.remove(0), query.mono)),
+        _ if latest || query.yes => Ok((matches.remove(0), query.mono)),
+        _ => prompt_for_version(matches, query.mono),
+    }
+}
+
+fn prompt_for_version(
+    available_versions: Vec<OnlineVersion>,
+    mono: bool,
+) -> anyhow::Result<(OnlineVersion, bool)> {
     let (version, mono) = Prompt::builder()
         .with_question("Select version")
-        .with_state(false)
+        .with_state(mono)
         .with_items(available_versions)
         .with_action(
             TOGGLE_MONO_KEY,
@@ -0,0 +1,106 @@
+use std::env::consts::EXE_SUFFIX;
+
+use gdtk_gvm::{
+    types::{LocalVersion, VersionOrigin},
+    VersionManager,
+};
+use gdtk_paths::camino::Utf8PathBuf;
+
+use crate::cli::{godot::set_default_version, missing, unknown, utils::ParserExt};
+
+/// Register an existing Godot binary, or a folder containing a `godot`
+/// binary, e.g. a custom engine build.
+pub struct GodotLinkCommand {
+    name: String,
+    path: Utf8PathBuf,
+    mono: bool,
+    /// Where the build came from, e.g. a repository URL.
+    source: Option<String>,
+    /// The engine commit the build was made from.
+    commit: Option<String>,
+}
+
+impl tapcli::Command for GodotLinkCommand {
+    type Error = anyhow::Error;
+
+    fn parse(parser: &mut tapcli::Parser) -> Result<Self, Self::Error> {
+        let mut name = None;
+        let mut path = None;
+        let mut mono = false;
+        let mut source = None;
+        let mut commit = None;
+
+        while let Some(arg) = parser.next() {
+            match arg.as_ref() {
+                tapcli::ArgRef::Long("mono") => mono = true,
+                tapcli::ArgRef::Long("source") => source = Some(parser.next_value()?),
+                tapcli::ArgRef::Long("commit") => commit = Some(parser.next_value()?),
+                tapcli::ArgRef::Value(value) if name.is_none() => name = Some(value.to_owned()),
+                tapcli::ArgRef::Value(value) if path.is_none() => {
+                    path = Some(Utf8PathBuf::from(value))
+                }
+                _ => unknown!(arg),
+            }
+        }
+
+        let Some(name) = name else {
+            missing!("a version name");
+        };
+
+        let Some(path) = path else {
+            missing!("a path to a Godot binary or folder");
+        };
+
+        Ok(Self {
+            name,
+            path,
+            mono,
+            source,
+            commit,
+        })
+    }
+
+    fn run(self) -> Result<Self::Output, Self::Error> {
+        let mut manager = VersionManager::load()?;
+
+        if manager.get_version(&self.name, self.mono).is_some() {
+            anyhow::bail!(
+                "Godot {}{} already exists.",
+                self.name,
+                if self.mono { " (mono)" } else { "" }
+            );
+        }
+
+        let path = Utf8PathBuf::try_from(std::fs::canonicalize(&self.path)?)?;
+
+        if path.is_dir() && !path.join(format!("godot{}", EXE_SUFFIX)).is_file() {
+            anyhow::bail!(
+                "`{}` doesn't contain a `godot{}` binary, link the binary itself instead.",
+                path,
+                EXE_SUFFIX
+            );
+        }
+
+        let version = LocalVersion {
+            name: self.name,
+            path: path.into_string(),
+            mono: self.mono,
+            sha512: None,
+            origin: VersionOrigin::Linked {
+                source: self.source,
+                commit: self.commit,
+            },
+        };
+
+        if manager.is_empty() {
+            set_default_version(&mut manager, &version)?;
+        }
+
+        eprintln!("Linked Godot {} to `{}`!", version, version.path);
+
+        manager.add_version(version);
+        manager.save()?;
+
+        Ok(())
+    }
+}
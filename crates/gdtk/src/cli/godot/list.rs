@@ -1,14 +1,40 @@
-pub struct GodotListCommand;
+use gdtk_gvm::{index::ReleaseIndex, online::GitHub};
+
+use crate::cli::unknown;
+
+pub struct GodotListCommand {
+    online: bool,
+    /// Fetch versions released since the last time.
+    refresh: bool,
+}
 
 impl tapcli::Command for GodotListCommand {
     type Error = anyhow::Error;
 
-    fn parse(_: &mut tapcli::Parser) -> Result<Self, Self::Error> {
-        Ok(Self)
+    fn parse(parser: &mut tapcli::Parser) -> Result<Self, Self::Error> {
+        let mut online = false;
+        let mut refresh = false;
+
+        for arg in parser {
+            match arg.as_ref() {
+                tapcli::ArgRef::Long("online") => online = true,
+                tapcli::ArgRef::Long("refresh") => refresh = true,
+                _ => unknown!(arg),
+            }
+        }
+
+        Ok(Self {
+            online: online || refresh,
+            refresh,
+        })
     }
 
     fn run(self) -> Result<Self::Output, Self::Error> {
-        let manager = gdtk_gvm::VersionManager::load()?;
+        if self.online {
+            return list_online(self.refresh);
+        }
+
+        let manager = gdtk_gvm::VersionManager::load_shared()?;
 
         if manager.is_empty() {
             eprintln!("No versions installed.");
@@ -22,3 +48,42 @@ impl tapcli::Command for GodotListCommand {
         Ok(())
     }
 }
+
+const DAY: u64 = 60 * 60 * 24;
+
+fn list_online(refresh: bool) -> anyhow::Result<()> {
+    let index = match ReleaseIndex::load()? {
+        Some(mut index) if refresh => {
+            let new = index.refresh(&GitHub::from_env())?;
+
+            eprintln!("Found {} new version(s).", new);
+
+            index
+        }
+        Some(index) => {
+            let age = index.fetched_at().elapsed().unwrap_or_default();
+
+            if age.as_secs() >= DAY {
+                eprintln!(
+                    "The list of versions is {} day(s) old, use `--refresh` to update it.",
+                    age.as_secs() / DAY
+                );
+            }
+
+            index
+        }
+        None => ReleaseIndex::fetch(&GitHub::from_env())?,
+    };
+
+    for version in index.versions() {
+        let suffix = if version.is_dev() {
+            " (prerelease)"
+        } else {
+            ""
+        };
+
+        println!("{}{}", version, suffix);
+    }
+
+    Ok(())
+}
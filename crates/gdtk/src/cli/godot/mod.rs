@@ -1,18 +1,30 @@
-use std::env::consts::EXE_SUFFIX;
+use std::path::{Path, PathBuf};
+
+use gdtk_gvm::{types::LocalVersion, VersionManager};
 
 use crate::cli::{
     godot::{
-        default::GodotDefaultCommand, install::GodotInstallCommand, list::GodotListCommand,
-        run::GodotRunCommand, uninstall::GodotUninstallCommand,
+        default::GodotDefaultCommand, doctor::GodotDoctorCommand, install::GodotInstallCommand,
+        link::GodotLinkCommand, list::GodotListCommand, prune::GodotPruneCommand,
+        run::GodotRunCommand, templates::GodotTemplatesCommand, uninstall::GodotUninstallCommand,
+        upgrade::GodotUpgradeCommand, which::GodotWhichCommand,
     },
     unknown,
+    utils::{confirm, select_local_version, VersionQuery},
 };
 
 pub mod default;
+pub mod doctor;
 pub mod install;
+pub mod link;
 pub mod list;
+pub mod prune;
 pub mod run;
+pub mod shim;
+pub mod templates;
 pub mod uninstall;
+pub mod upgrade;
+pub mod which;
 
 pub enum GodotCommand {
     /// List locally-installed or online Godot versions.
@@ -27,8 +39,26 @@ pub enum GodotCommand {
     /// Uninstall the specified Godot version.
     Uninstall(GodotUninstallCommand),
 
+    /// Register an existing Godot binary or folder as a version.
+    Link(GodotLinkCommand),
+
+    /// Install the newest patch release of each installed minor version.
+    Upgrade(GodotUpgradeCommand),
+
+    /// Uninstall versions that no project uses.
+    Prune(GodotPruneCommand),
+
     /// Change the default Godot version.
     Default(GodotDefaultCommand),
+
+    /// Manage export templates of installed Godot versions.
+    Templates(GodotTemplatesCommand),
+
+    /// Print the path to the Godot binary the current project uses.
+    Which(GodotWhichCommand),
+
+    /// Find and repair problems with installed Godot versions.
+    Doctor(GodotDoctorCommand),
 }
 
 impl tapcli::Command for GodotCommand {
@@ -41,7 +71,13 @@ impl tapcli::Command for GodotCommand {
             tapcli::ArgRef::Value("run") => Ok(Self::Run(GodotRunCommand::parse(parser)?)),
             tapcli::ArgRef::Value("install") => Ok(Self::Install(GodotInstallCommand::parse(parser)?)),
             tapcli::ArgRef::Value("uninstall") => Ok(Self::Uninstall(GodotUninstallCommand::parse(parser)?)),
+            tapcli::ArgRef::Value("link") => Ok(Self::Link(GodotLinkCommand::parse(parser)?)),
+            tapcli::ArgRef::Value("upgrade") => Ok(Self::Upgrade(GodotUpgradeCommand::parse(parser)?)),
+            tapcli::ArgRef::Value("prune") => Ok(Self::Prune(GodotPruneCommand::parse(parser)?)),
             tapcli::ArgRef::Value("default") => Ok(Self::Default(GodotDefaultCommand::parse(parser)?)),
+            tapcli::ArgRef::Value("templates") => Ok(Self::Templates(GodotTemplatesCommand::parse(parser)?)),
+            tapcli::ArgRef::Value("which") => Ok(Self::Which(GodotWhichCommand::parse(parser)?)),
+            tapcli::ArgRef::Value("doctor") => Ok(Self::Doctor(GodotDoctorCommand::parse(parser)?)),
             other => unknown!(other),
         }
     }
@@ -52,26 +88,164 @@ impl tapcli::Command for GodotCommand {
             GodotCommand::Run(c) => c.run(),
             GodotCommand::Install(c) => c.run(),
             GodotCommand::Uninstall(c) => c.run(),
+            GodotCommand::Link(c) => c.run(),
+            GodotCommand::Upgrade(c) => c.run(),
+            GodotCommand::Prune(c) => c.run(),
             GodotCommand::Default(c) => c.run(),
+            GodotCommand::Templates(c) => c.run(),
+            GodotCommand::Which(c) => c.run(),
+            GodotCommand::Doctor(c) => c.run(),
         }
     }
 }
 
-fn symlink_default_version(
-    installation_folder: &gdtk_paths::camino::Utf8Path,
-) -> anyhow::Result<()> {
-    let original = installation_folder.join(format!("godot{}", EXE_SUFFIX));
-    let link = gdtk_paths::default_godot_path()?;
+/// Make `version` the default one, which the shims run outside of projects
+/// that pin a version. The manager has to be saved afterwards.
+fn set_default_version(manager: &mut VersionManager, version: &LocalVersion) -> anyhow::Result<()> {
+    manager.set_default_version(&version.name, version.mono);
+
+    shim::install_shims()
+}
+
+/// A Godot version requested by a project.
+struct PinnedVersion {
+    version: String,
+    mono: bool,
+    /// The file the version was read from.
+    source: PathBuf,
+}
+
+impl PinnedVersion {
+    /// Look for a pin in the current directory and it's ancestors, see
+    /// [PinnedVersion::find_in]. The project is remembered, so that
+    /// `gdtk godot prune` keeps the version it uses.
+    fn find() -> anyhow::Result<Option<Self>> {
+        let pin = Self::find_in(&std::env::current_dir()?)?;
 
-    if link.exists() {
-        std::fs::remove_file(&link)?;
+        // remembering the project is best-effort, it shouldn't keep Godot from running
+        if let Some(project) = pin.as_ref().and_then(|pin| pin.source.parent()) {
+            if let Err(error) = gdtk_gvm::projects::register_project(project) {
+                eprintln!("Couldn't remember `{}`: {}", project.display(), error);
+            }
+        }
+
+        Ok(pin)
     }
 
-    #[cfg(windows)]
-    mslnk::ShellLink::new(original)?.create_lnk(link)?;
+    /// Look for a `.godot-version` or a `project.godot` file in `start`
+    /// and it's ancestors.
+    ///
+    /// `.godot-version` contains a version name (or a prefix of it), optionally
+    /// followed by `mono`, e.g. `4.3-stable mono`. Otherwise, the version is
+    /// taken from `config/features` in `project.godot`, and C# projects use
+    /// the mono variant.
+    fn find_in(start: &Path) -> anyhow::Result<Option<Self>> {
+        for dir in start.ancestors() {
+            let source = dir.join(".godot-version");
+
+            if source.is_file() {
+                let content = std::fs::read_to_string(&source)?;
+                let mut parts = content.split_whitespace();
+
+                let Some(version) = parts.next() else {
+                    anyhow::bail!("`{}` is empty.", source.display());
+                };
+
+                let mono = match parts.next() {
+                    None => false,
+                    Some("mono") => true,
+                    Some(other) => anyhow::bail!(
+                        "Unexpected `{}` in `{}`, expected `mono`.",
+                        other,
+                        source.display()
+                    ),
+                };
+
+                return Ok(Some(Self {
+                    version: version.to_owned(),
+                    mono,
+                    source,
+                }));
+            }
+
+            let source = dir.join("project.godot");
+
+            if source.is_file() {
+                let content = std::fs::read_to_string(&source)?;
+                let settings = gdtk_godotcfg_parser::project::ProjectSettings::parse(&content)
+                    .map_err(|e| anyhow::anyhow!("Couldn't parse `{}`: {e}", source.display()))?;
+
+                // the project doesn't care, and neither do we
+                let Some(version) = settings.godot_version() else {
+                    return Ok(None);
+                };
+
+                return Ok(Some(Self {
+                    version: version.to_owned(),
+                    mono: settings.features().contains(&"C#"),
+                    source,
+                }));
+            }
+        }
 
-    #[cfg(unix)]
-    std::os::unix::fs::symlink(original, link)?;
+        Ok(None)
+    }
+
+    fn query(&self) -> VersionQuery {
+        VersionQuery {
+            version: Some(self.version.clone()),
+            mono: self.mono,
+            yes: true,
+        }
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "Godot {}{} (required by `{}`)",
+            self.version,
+            if self.mono { " (mono)" } else { "" },
+            self.source.display()
+        )
+    }
+
+    /// Find an installed version matching this one.
+    fn select<'m>(&self, manager: &'m VersionManager) -> anyhow::Result<&'m LocalVersion> {
+        select_local_version(manager, &self.query())
+            .map_err(|_| anyhow::anyhow!("{} isn't installed.", self.describe()))
+    }
+
+    /// Find an installed version matching this one, offering to install it
+    /// if there is none.
+    fn select_or_install(&self, yes: bool) -> anyhow::Result<LocalVersion> {
+        if let Ok(version) = self.select(&VersionManager::load()?) {
+            return Ok(version.clone());
+        }
+
+        eprintln!("{} isn't installed.", self.describe());
+
+        if !yes && !confirm("Install it?")? {
+            anyhow::bail!("Command cancelled.");
+        }
+
+        let install = GodotInstallCommand::from_query(&self.query(), true, false)?;
+
+        tapcli::Command::run(install)?;
+
+        Ok(self.select(&VersionManager::load()?)?.clone())
+    }
+}
+
+/// Select an installed version for a command: the one from the command line
+/// if specified, otherwise the one the current project requires, otherwise
+/// one selected interactively.
+fn select_version(query: &VersionQuery) -> anyhow::Result<LocalVersion> {
+    if query.version.is_none() {
+        if let Some(mut pin) = PinnedVersion::find()? {
+            pin.mono |= query.mono;
+
+            return pin.select_or_install(query.yes);
+        }
+    }
 
-    Ok(())
+    Ok(select_local_version(&VersionManager::load()?, query)?.clone())
 }
@@ -0,0 +1,130 @@
+use gdtk_gvm::{projects::registered_projects, types::LocalVersion, VersionManager};
+use gdtk_paths::camino::Utf8Path;
+
+use crate::cli::{godot::PinnedVersion, unknown, utils::confirm};
+
+/// Uninstall versions that aren't the default one and aren't pinned by any
+/// project gdtk has seen. Linked versions are left alone.
+pub struct GodotPruneCommand {
+    yes: bool,
+}
+
+impl tapcli::Command for GodotPruneCommand {
+    type Error = anyhow::Error;
+
+    fn parse(parser: &mut tapcli::Parser) -> Result<Self, Self::Error> {
+        let mut yes = false;
+
+        for arg in parser {
+            match arg.as_ref() {
+                tapcli::ArgRef::Long("yes") | tapcli::ArgRef::Short('y') => yes = true,
+                _ => unknown!(arg),
+            }
+        }
+
+        Ok(Self { yes })
+    }
+
+    fn run(self) -> Result<Self::Output, Self::Error> {
+        let mut manager = VersionManager::load()?;
+
+        let mut used = vec![];
+
+        if let Some(default) = manager.default_version() {
+            used.push((default.name.clone(), default.mono));
+        }
+
+        for project in registered_projects()? {
+            // a project that doesn't pin anything anymore, or pins a version
+            // that isn't installed, doesn't keep anything
+            let Ok(Some(pin)) = PinnedVersion::find_in(&project) else {
+                continue;
+            };
+
+            if let Ok(version) = pin.select(&manager) {
+                used.push((version.name.clone(), version.mono));
+            }
+        }
+
+        let unused = manager
+            .installed()
+            .iter()
+            .filter(|v| v.is_owned() && !used.contains(&(v.name.clone(), v.mono)))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        let godots = gdtk_paths::godots_path()?;
+
+        eprintln!(
+            "Godot versions take up {} in `{}`.",
+            format_size(dir_size(&godots)?),
+            godots
+        );
+
+        if unused.is_empty() {
+            eprintln!("Every version is in use, nothing to prune.");
+            return Ok(());
+        }
+
+        let mut freed = 0;
+
+        for version in &unused {
+            let size = dir_size(&version.path())?;
+
+            freed += size;
+            eprintln!("- Godot {} ({})", version, format_size(size));
+        }
+
+        eprintln!("Pruning would free {}.", format_size(freed));
+
+        if !self.yes && !confirm("Uninstall these versions?")? {
+            anyhow::bail!("Command cancelled.");
+        }
+
+        for version in unused {
+            uninstall(&mut manager, &version)?;
+        }
+
+        manager.save()?;
+
+        eprintln!("Freed {}!", format_size(freed));
+
+        Ok(())
+    }
+}
+
+fn uninstall(manager: &mut VersionManager, version: &LocalVersion) -> anyhow::Result<()> {
+    manager.remove_version(&version.name, version.mono);
+
+    if version.path().exists() {
+        std::fs::remove_dir_all(version.path())?;
+    }
+
+    Ok(())
+}
+
+/// The total size of all files in `path`, in bytes.
+fn dir_size(path: &Utf8Path) -> anyhow::Result<u64> {
+    let mut size = 0;
+
+    if !path.exists() {
+        return Ok(size);
+    }
+
+    for entry in path.read_dir_utf8()? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+
+        if file_type.is_dir() {
+            size += dir_size(entry.path())?;
+        } else if file_type.is_file() {
+            size += entry.metadata()?.len();
+        }
+    }
+
+    Ok(size)
+}
+
+fn format_size(bytes: u64) -> String {
+    format!("{:.1} MB", bytes as f64 / 1024.0 / 1024.0)
+}
@@ -1,51 +1,102 @@
-use std::process::{Command, Stdio};
+use std::process::Command;
 
-use gdtk_gvm::VersionManager;
+use gdtk_paths::camino::Utf8PathBuf;
 
-use crate::cli::{unknown, utils::prompt_local_version};
+use crate::cli::{
+    godot::select_version,
+    unknown,
+    utils::{ParserExt, VersionQuery},
+};
 
 pub struct GodotRunCommand {
-    extra_args: Vec<String>,
+    query: VersionQuery,
+    /// Use the console wrapper on Windows.
+    console: bool,
+    /// Run Godot with `--headless`.
+    headless: bool,
+    /// The project to run Godot in, passed as `--path`.
+    project: Option<Utf8PathBuf>,
+    /// Arguments after `--`, passed to Godot as is.
+    engine_args: Vec<String>,
 }
 
 impl tapcli::Command for GodotRunCommand {
     type Error = anyhow::Error;
 
     fn parse(parser: &mut tapcli::Parser) -> Result<Self, Self::Error> {
-        let mut extra_args = Vec::new();
+        let mut query = VersionQuery::default();
+        let mut console = false;
+        let mut headless = false;
+        let mut project = None;
+        let mut engine_args = Vec::new();
 
-        for arg in parser {
-            match arg {
-                tapcli::Arg::Value(value) => extra_args.push(value),
+        while let Some(arg) = parser.next() {
+            if parser.after_separator() {
+                if let tapcli::Arg::Value(value) = arg {
+                    engine_args.push(value);
+                }
+
+                continue;
+            }
+
+            match arg.as_ref() {
+                tapcli::ArgRef::Long("version") => query.version = Some(parser.next_value()?),
+                tapcli::ArgRef::Long("console") => console = true,
+                tapcli::ArgRef::Long("headless") => headless = true,
+                tapcli::ArgRef::Long("project") => {
+                    project = Some(Utf8PathBuf::from(parser.next_value()?))
+                }
+                _ if query.accept(arg.as_ref()) => (),
                 _ => unknown!(arg),
             }
         }
 
-        Ok(Self { extra_args })
+        Ok(Self {
+            query,
+            console,
+            headless,
+            project,
+            engine_args,
+        })
     }
 
     fn run(self) -> Result<Self::Output, Self::Error> {
-        let manager = VersionManager::load()?;
-        let version = prompt_local_version(&manager)?;
+        let version = select_version(&self.query)?;
+
+        let program = if self.console {
+            version.console_executable()
+        } else {
+            version.executable()
+        };
+
+        let mut command = Command::new(&program);
 
-        let program = version.path().join("godot");
+        if self.headless {
+            command.arg("--headless");
+        }
+
+        if let Some(project) = &self.project {
+            command.arg("--path").arg(project);
+        }
+
+        command.args(&self.engine_args);
 
         eprintln!(
             "Running `{}{}`",
             program,
-            self.extra_args.iter().fold(String::new(), |mut acc, arg| {
+            command.get_args().fold(String::new(), |mut acc, arg| {
                 acc.push(' ');
-                acc.push_str(arg.as_str());
+                acc.push_str(&arg.to_string_lossy());
                 acc
             })
         );
 
-        let mut child = Command::new(program)
-            .args(self.extra_args)
-            .stdout(Stdio::null())
-            .spawn()?;
+        let status = command.status()?;
 
-        child.wait()?;
+        if !status.success() {
+            // a signal is the only reason for there to be no code
+            std::process::exit(status.code().unwrap_or(1));
+        }
 
         Ok(())
     }
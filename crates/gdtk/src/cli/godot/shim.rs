@@ -0,0 +1,156 @@
+//! Shims are copies of (or hard links to) the gdtk executable named like
+//! Godot's executables. When invoked, they run the Godot version the current
+//! project uses, much like `rustup`'s proxies.
+
+use std::{env::consts::EXE_SUFFIX, process::Command};
+
+use gdtk_gvm::{types::LocalVersion, VersionManager};
+use gdtk_paths::camino::{Utf8Path, Utf8PathBuf};
+
+use crate::cli::{
+    godot::PinnedVersion,
+    utils::{select_local_version, VersionQuery},
+};
+
+/// Names of the shims.
+pub const SHIMS: [&str; 3] = ["godot", "godot-mono", "godot_console"];
+
+/// Overrides the version shims run, e.g. `4.3` or `4.3-stable mono`.
+pub const VERSION_VAR: &str = "GDTK_GODOT_VERSION";
+
+fn shim_path(bin: &Utf8Path, shim: &str) -> Utf8PathBuf {
+    bin.join(format!("{}{}", shim, EXE_SUFFIX))
+}
+
+/// Install (or update) the shims in [gdtk_paths::executable_path].
+pub fn install_shims() -> anyhow::Result<()> {
+    let gdtk = std::env::current_exe()?;
+    let bin = gdtk_paths::executable_path()?;
+
+    // the default version used to be a link to its executable
+    let legacy = gdtk_paths::default_godot_path()?;
+
+    if legacy.symlink_metadata().is_ok() {
+        std::fs::remove_file(&legacy)?;
+    }
+
+    for shim in SHIMS {
+        let path = shim_path(&bin, shim);
+
+        if path.symlink_metadata().is_ok() {
+            std::fs::remove_file(&path)?;
+        }
+
+        // hard links don't work across file systems
+        if std::fs::hard_link(&gdtk, &path).is_err() {
+            std::fs::copy(&gdtk, &path)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Paths of shims that aren't installed. Symlinks are old default version
+/// links rather than shims, so they count as missing too.
+pub fn missing_shims() -> anyhow::Result<Vec<Utf8PathBuf>> {
+    let bin = gdtk_paths::executable_path()?;
+
+    Ok(SHIMS
+        .iter()
+        .map(|shim| shim_path(&bin, shim))
+        .filter(|path| match path.symlink_metadata() {
+            Ok(metadata) => metadata.file_type().is_symlink(),
+            Err(_) => true,
+        })
+        .collect())
+}
+
+/// If gdtk was invoked through a shim, run Godot with the same arguments
+/// and exit with its exit code. Otherwise, do nothing.
+pub fn run_if_shim() -> anyhow::Result<()> {
+    let Some(shim) = std::env::args_os()
+        .next()
+        .and_then(|arg0| {
+            std::path::Path::new(&arg0)
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+        })
+        .filter(|stem| SHIMS.contains(&stem.as_str()))
+    else {
+        return Ok(());
+    };
+
+    let version = resolve(shim == "godot-mono")?;
+
+    let program = if shim == "godot_console" {
+        version.console_executable()
+    } else {
+        version.executable()
+    };
+
+    let mut command = Command::new(program);
+
+    command.args(std::env::args_os().skip(1));
+
+    exec(command)
+}
+
+/// Replace the current process with `command`. Only returns if that fails.
+#[cfg(unix)]
+fn exec(mut command: Command) -> anyhow::Result<()> {
+    use std::os::unix::process::CommandExt;
+
+    Err(command.exec().into())
+}
+
+/// Run `command` and exit with its exit code.
+#[cfg(not(unix))]
+fn exec(mut command: Command) -> anyhow::Result<()> {
+    let status = command.status()?;
+
+    std::process::exit(status.code().unwrap_or(1));
+}
+
+/// Find the version a shim should run: the one from [VERSION_VAR], the one
+/// the current project pins, or the default one, in that order.
+fn resolve(mono: bool) -> anyhow::Result<LocalVersion> {
+    // shims only read the database, so they don't need to wait for each other
+    let manager = VersionManager::load_shared()?;
+
+    if let Some(value) = std::env::var(VERSION_VAR)
+        .ok()
+        .filter(|v| !v.trim().is_empty())
+    {
+        let mut parts = value.split_whitespace();
+        let query = VersionQuery {
+            version: parts.next().map(str::to_owned),
+            mono: mono || parts.next() == Some("mono"),
+            yes: true,
+        };
+
+        return Ok(select_local_version(&manager, &query)?.clone());
+    }
+
+    if let Some(mut pin) = PinnedVersion::find()? {
+        pin.mono |= mono;
+
+        return Ok(pin.select(&manager)?.clone());
+    }
+
+    let Some(default) = manager.default_version() else {
+        anyhow::bail!("No default Godot version. Set one with `gdtk godot default`.");
+    };
+
+    if default.mono == mono {
+        return Ok(default.clone());
+    }
+
+    // `godot-mono` runs the mono variant of the default version
+    let query = VersionQuery {
+        version: Some(default.name.clone()),
+        mono,
+        yes: true,
+    };
+
+    Ok(select_local_version(&manager, &query)?.clone())
+}
@@ -0,0 +1,142 @@
+use std::io::{Read, Seek};
+
+use gdtk_gvm::{
+    online::{fetch_checksum, fetch_version_assets},
+    utils::pick_templates_asset,
+};
+use gdtk_paths::camino::{Utf8Component, Utf8Path, Utf8PathBuf};
+
+use crate::cli::{
+    godot::{
+        install::{download, extract_file, rollback, verify_cached_download},
+        select_version,
+    },
+    unknown,
+    utils::VersionQuery,
+};
+
+pub struct GodotTemplatesInstallCommand {
+    query: VersionQuery,
+}
+
+impl tapcli::Command for GodotTemplatesInstallCommand {
+    type Error = anyhow::Error;
+
+    fn parse(parser: &mut tapcli::Parser) -> Result<Self, Self::Error> {
+        let mut query = VersionQuery::default();
+
+        for arg in parser {
+            if !query.accept(arg.as_ref()) {
+                unknown!(arg);
+            }
+        }
+
+        Ok(Self { query })
+    }
+
+    fn run(self) -> Result<Self::Output, Self::Error> {
+        let version = select_version(&self.query)?;
+
+        let assets = fetch_version_assets(&version.name)?;
+        let Some(asset) = pick_templates_asset(&assets, version.mono) else {
+            anyhow::bail!("Couldn't find export templates for Godot {}.", version);
+        };
+
+        let mut status = spinoff::Spinner::new(
+            spinoff::spinners::Dots2,
+            "Preparing..",
+            spinoff::Color::Cyan,
+        );
+
+        let checksum = fetch_checksum(&assets, &asset.name)?;
+
+        let archive = download(&asset.download_url.0, &asset.name, &mut status)?;
+
+        status.update_text("Verifying..");
+
+        verify_cached_download(&archive, &checksum, &asset.name)?;
+
+        status.update_text("Extracting..");
+
+        let name = extract_templates(std::fs::File::open(&archive)?, &version.templates_path())?;
+
+        status.success(&format!(
+            "Installed export templates {} for Godot {}!",
+            name, version
+        ));
+
+        Ok(())
+    }
+}
+
+/// Extract a `.tpz` archive into `<templates_path>/<version>`, where
+/// `<version>` is read from the archive's `templates/version.txt`.
+/// Returns the `<version>`.
+fn extract_templates(
+    source: impl Read + Seek,
+    templates_path: &Utf8Path,
+) -> anyhow::Result<String> {
+    let mut archive = zip::ZipArchive::new(source)?;
+
+    let mut name = String::new();
+    archive
+        .by_name("templates/version.txt")?
+        .read_to_string(&mut name)?;
+    let name = name.trim().to_owned();
+
+    // the name becomes a folder in `templates_path`, and mustn't point anywhere else
+    let mut components = Utf8Path::new(&name).components();
+    let valid = matches!(
+        (components.next(), components.next()),
+        (Some(Utf8Component::Normal(_)), None)
+    );
+
+    if !valid || name.starts_with('.') {
+        anyhow::bail!("Invalid export templates version: {:?}.", name);
+    }
+
+    let target_dir = templates_path.join(&name);
+
+    if target_dir.exists() {
+        anyhow::bail!("Export templates {} are already installed.", name);
+    }
+
+    // see `GodotInstallCommand::run`
+    let staging = templates_path.join(format!(".{}.{}.partial", name, std::process::id()));
+
+    rollback(&staging, || extract_templates_into(&mut archive, &staging))?;
+    rollback(&staging, || Ok(std::fs::rename(&staging, &target_dir)?))?;
+
+    Ok(name)
+}
+
+fn extract_templates_into(
+    archive: &mut zip::ZipArchive<impl Read + Seek>,
+    target_dir: &Utf8Path,
+) -> anyhow::Result<()> {
+    std::fs::create_dir_all(target_dir)?;
+
+    for n in 0..archive.len() {
+        let mut entry = archive.by_index(n)?;
+
+        let entry_path = entry.enclosed_name().map(Utf8PathBuf::try_from).unwrap()?;
+
+        // everything is inside of a top-level `templates` directory
+        let Ok(entry_path) = entry_path.strip_prefix("templates") else {
+            continue;
+        };
+
+        let mut full = target_dir.to_owned();
+
+        full.extend(entry_path.components());
+
+        if entry.is_dir() {
+            std::fs::create_dir_all(&full)?;
+            continue;
+        }
+
+        extract_file(&mut entry, &full)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,54 @@
+use gdtk_gvm::VersionManager;
+
+use crate::cli::{
+    godot::templates::installed_templates,
+    unknown,
+    utils::{select_local_version, VersionQuery},
+};
+
+pub struct GodotTemplatesListCommand {
+    query: VersionQuery,
+}
+
+impl tapcli::Command for GodotTemplatesListCommand {
+    type Error = anyhow::Error;
+
+    fn parse(parser: &mut tapcli::Parser) -> Result<Self, Self::Error> {
+        let mut query = VersionQuery::default();
+
+        for arg in parser {
+            if !query.accept(arg.as_ref()) {
+                unknown!(arg);
+            }
+        }
+
+        Ok(Self { query })
+    }
+
+    fn run(self) -> Result<Self::Output, Self::Error> {
+        let manager = VersionManager::load_shared()?;
+
+        if manager.is_empty() {
+            eprintln!("No versions installed.");
+            return Ok(());
+        }
+
+        // without a version, list templates of every installed version
+        let versions = match self.query.version {
+            Some(_) => vec![select_local_version(&manager, &self.query)?],
+            None => manager.installed().iter().collect(),
+        };
+
+        for version in versions {
+            let templates = installed_templates(version)?;
+
+            if templates.is_empty() {
+                eprintln!("Godot {}: no export templates installed.", version);
+            } else {
+                eprintln!("Godot {}: {}", version, templates.join(", "));
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,70 @@
+use crate::cli::{
+    godot::templates::{
+        install::GodotTemplatesInstallCommand, list::GodotTemplatesListCommand,
+        remove::GodotTemplatesRemoveCommand,
+    },
+    missing, unknown,
+};
+
+pub mod install;
+pub mod list;
+pub mod remove;
+
+pub enum GodotTemplatesCommand {
+    /// Install export templates for an installed Godot version.
+    Install(GodotTemplatesInstallCommand),
+
+    /// List installed export templates.
+    List(GodotTemplatesListCommand),
+
+    /// Remove export templates of an installed Godot version.
+    Remove(GodotTemplatesRemoveCommand),
+}
+
+impl tapcli::Command for GodotTemplatesCommand {
+    type Error = anyhow::Error;
+
+    #[rustfmt::skip]
+    fn parse(parser: &mut tapcli::Parser) -> Result<Self, Self::Error> {
+        let Some(arg) = parser.next() else {
+            missing!("a subcommand");
+        };
+
+        match arg.as_ref() {
+            tapcli::ArgRef::Value("install") => Ok(Self::Install(GodotTemplatesInstallCommand::parse(parser)?)),
+            tapcli::ArgRef::Value("list") => Ok(Self::List(GodotTemplatesListCommand::parse(parser)?)),
+            tapcli::ArgRef::Value("remove") => Ok(Self::Remove(GodotTemplatesRemoveCommand::parse(parser)?)),
+            other => unknown!(other),
+        }
+    }
+
+    fn run(self) -> Result<Self::Output, Self::Error> {
+        match self {
+            GodotTemplatesCommand::Install(c) => c.run(),
+            GodotTemplatesCommand::List(c) => c.run(),
+            GodotTemplatesCommand::Remove(c) => c.run(),
+        }
+    }
+}
+
+/// Names of export templates installed for a version (e.g. `4.3.stable.mono`).
+fn installed_templates(version: &gdtk_gvm::types::LocalVersion) -> anyhow::Result<Vec<String>> {
+    let path = version.templates_path();
+
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+
+    let mut names = vec![];
+
+    for entry in path.read_dir_utf8()? {
+        let entry = entry?;
+
+        // hidden folders are installs in progress, see `extract_templates`
+        if entry.file_type()?.is_dir() && !entry.file_name().starts_with('.') {
+            names.push(entry.file_name().to_owned());
+        }
+    }
+
+    Ok(names)
+}
@@ -0,0 +1,50 @@
+use gdtk_gvm::VersionManager;
+
+use crate::cli::{
+    godot::templates::installed_templates,
+    unknown,
+    utils::{select_local_version, VersionQuery},
+};
+
+pub struct GodotTemplatesRemoveCommand {
+    query: VersionQuery,
+}
+
+impl tapcli::Command for GodotTemplatesRemoveCommand {
+    type Error = anyhow::Error;
+
+    fn parse(parser: &mut tapcli::Parser) -> Result<Self, Self::Error> {
+        let mut query = VersionQuery::default();
+
+        for arg in parser {
+            if !query.accept(arg.as_ref()) {
+                unknown!(arg);
+            }
+        }
+
+        Ok(Self { query })
+    }
+
+    fn run(self) -> Result<Self::Output, Self::Error> {
+        let manager = VersionManager::load()?;
+        let version = select_local_version(&manager, &self.query)?;
+
+        let templates = installed_templates(version)?;
+
+        if templates.is_empty() {
+            anyhow::bail!("Godot {} has no export templates installed.", version);
+        }
+
+        for name in &templates {
+            std::fs::remove_dir_all(version.templates_path().join(name))?;
+        }
+
+        eprintln!(
+            "Removed export templates {} of Godot {}!",
+            templates.join(", "),
+            version
+        );
+
+        Ok(())
+    }
+}
@@ -1,14 +1,27 @@
 use gdtk_gvm::VersionManager;
 
-use crate::cli::utils::prompt_local_version;
+use crate::cli::{
+    unknown,
+    utils::{select_local_version, VersionQuery},
+};
 
-pub struct GodotUninstallCommand;
+pub struct GodotUninstallCommand {
+    query: VersionQuery,
+}
 
 impl tapcli::Command for GodotUninstallCommand {
     type Error = anyhow::Error;
 
-    fn parse(_: &mut tapcli::Parser) -> Result<Self, Self::Error> {
-        Ok(Self)
+    fn parse(parser: &mut tapcli::Parser) -> Result<Self, Self::Error> {
+        let mut query = VersionQuery::default();
+
+        for arg in parser {
+            if !query.accept(arg.as_ref()) {
+                unknown!(arg);
+            }
+        }
+
+        Ok(Self { query })
     }
 
     fn run(self) -> Result<Self::Output, Self::Error> {
@@ -19,17 +32,27 @@ impl tapcli::Command for GodotUninstallCommand {
             return Ok(());
         }
 
-        let version = prompt_local_version(&manager)?.clone();
+        let version = select_local_version(&manager, &self.query)?.clone();
 
         let Some(previous) = manager.remove_version(&version.name, version.mono) else {
             anyhow::bail!("Godot {} isn't installed.", &version)
         };
 
-        std::fs::remove_dir_all(previous.path)?;
+        // linked versions are only unregistered, their files aren't ours to delete
+        if previous.is_owned() {
+            std::fs::remove_dir_all(previous.path)?;
+        }
 
         manager.save()?;
 
-        println!("Godot {} uninstalled!", &version);
+        if previous.is_owned() {
+            println!("Godot {} uninstalled!", &version);
+        } else {
+            println!(
+                "Godot {} unlinked, `{}` was left intact.",
+                &version, previous.path
+            );
+        }
 
         Ok(())
     }
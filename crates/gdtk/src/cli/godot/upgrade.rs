@@ -0,0 +1,287 @@
+use gdtk_gvm::{
+    index::ReleaseIndex, online::GitHub, types::LocalVersion, version::OnlineVersion,
+    versions::Versioning, VersionManager,
+};
+
+use crate::cli::{
+    godot::{install::GodotInstallCommand, set_default_version},
+    unknown,
+    utils::{confirm, VersionQuery},
+};
+
+/// Install the newest stable patch release of each installed minor version,
+/// e.g. 4.2.2 if 4.2.1 is installed. Old versions are kept, see
+/// `gdtk godot prune`.
+pub struct GodotUpgradeCommand {
+    /// Make the upgrade of the default version the new default.
+    default: bool,
+    yes: bool,
+}
+
+impl tapcli::Command for GodotUpgradeCommand {
+    type Error = anyhow::Error;
+
+    fn parse(parser: &mut tapcli::Parser) -> Result<Self, Self::Error> {
+        let mut default = false;
+        let mut yes = false;
+
+        for arg in parser {
+            match arg.as_ref() {
+                tapcli::ArgRef::Long("default") => default = true,
+                tapcli::ArgRef::Long("yes") | tapcli::ArgRef::Short('y') => yes = true,
+                _ => unknown!(arg),
+            }
+        }
+
+        Ok(Self { default, yes })
+    }
+
+    fn run(self) -> Result<Self::Output, Self::Error> {
+        // the manager is locked while it's alive, and installing needs it
+        let (installed, default) = {
+            let manager = VersionManager::load()?;
+
+            (
+                manager.installed().to_vec(),
+                manager.default_version().cloned(),
+            )
+        };
+
+        let online = match ReleaseIndex::load()? {
+            Some(mut index) => {
+                index.refresh(&GitHub::from_env())?;
+                index
+            }
+            None => ReleaseIndex::fetch(&GitHub::from_env())?,
+        }
+        .versions();
+
+        let upgrades = find_upgrades(&installed, &online);
+
+        if upgrades.is_empty() {
+            eprintln!("Everything is up to date.");
+            return Ok(());
+        }
+
+        for (old, new) in &upgrades {
+            eprintln!("- Godot {} -> {}", old, new);
+        }
+
+        if !self.yes && !confirm("Install these versions?")? {
+            anyhow::bail!("Command cancelled.");
+        }
+
+        for (old, new) in &upgrades {
+            let query = VersionQuery {
+                version: Some(new.name().to_owned()),
+                mono: old.mono,
+                yes: true,
+            };
+
+            tapcli::Command::run(GodotInstallCommand::from_query(&query, true, false)?)?;
+        }
+
+        let Some(default) = default.filter(|_| self.default) else {
+            return Ok(());
+        };
+
+        let Some((_, new)) = upgrades
+            .iter()
+            .find(|(old, _)| old.name == default.name && old.mono == default.mono)
+        else {
+            return Ok(());
+        };
+
+        let mut manager = VersionManager::load()?;
+        let Some(version) = manager.get_version(new.name(), default.mono).cloned() else {
+            return Ok(());
+        };
+
+        set_default_version(&mut manager, &version)?;
+        manager.save()?;
+
+        eprintln!("Godot {} set as default!", version);
+
+        Ok(())
+    }
+}
+
+/// Pair the newest installed version of each minor version with a newer
+/// stable release of the same minor version, if there is one.
+fn find_upgrades<'a>(
+    installed: &'a [LocalVersion],
+    online: &'a [OnlineVersion],
+) -> Vec<(&'a LocalVersion, &'a OnlineVersion)> {
+    let mut upgrades: Vec<(&LocalVersion, &OnlineVersion)> = vec![];
+
+    for version in installed.iter().filter(|v| v.is_owned()) {
+        let series = minor_series(&version.name);
+
+        // only consider the newest installed version of each series. Linked
+        // versions are managed elsewhere, and don't count
+        let newest_installed = installed
+            .iter()
+            .filter(|v| v.is_owned() && v.mono == version.mono && minor_series(&v.name) == series)
+            .max_by_key(|v| v.as_ordered());
+
+        if newest_installed.is_some_and(|v| v.name != version.name) {
+            continue;
+        }
+
+        // versions are sorted from newest to oldest
+        let Some(newest) = online
+            .iter()
+            .find(|v| !v.is_dev() && minor_series(v.name()) == series)
+        else {
+            continue;
+        };
+
+        if Versioning::new(newest.name()) > Versioning::new(&version.name) {
+            upgrades.push((version, newest));
+        }
+    }
+
+    upgrades
+}
+
+/// The major and minor components of a version name, e.g. `4.2` for `4.2.1-stable`.
+fn minor_series(name: &str) -> String {
+    let number = name.split('-').next().unwrap_or(name);
+
+    number.split('.').take(2).collect::<Vec<_>>().join(".")
+}
+
+#[cfg(test)]
+mod tests {
+    use gdtk_gvm::{
+        index::CachedRelease,
+        types::{LocalVersion, VersionOrigin},
+        version::OnlineVersion,
+    };
+
+    use super::{find_upgrades, minor_series};
+
+    fn installed(name: &str, mono: bool) -> LocalVersion {
+        LocalVersion {
+            name: name.to_owned(),
+            path: format!("/godots/{name}"),
+            mono,
+            sha512: None,
+            origin: VersionOrigin::Installed,
+        }
+    }
+
+    fn linked(name: &str) -> LocalVersion {
+        LocalVersion {
+            origin: VersionOrigin::Linked {
+                source: None,
+                commit: None,
+            },
+            ..installed(name, false)
+        }
+    }
+
+    /// Online versions, newest first. Names ending with `*` are prereleases.
+    fn online(names: &[&str]) -> Vec<OnlineVersion> {
+        names
+            .iter()
+            .map(|name| {
+                OnlineVersion::from(CachedRelease {
+                    tag_name: name.trim_end_matches('*').to_owned(),
+                    is_prerelease: name.ends_with('*'),
+                    assets: vec![],
+                })
+            })
+            .collect()
+    }
+
+    fn upgrades(
+        installed: &[LocalVersion],
+        online: &[OnlineVersion],
+    ) -> Vec<(String, bool, String)> {
+        find_upgrades(installed, online)
+            .into_iter()
+            .map(|(old, new)| (old.name.clone(), old.mono, new.name().to_owned()))
+            .collect()
+    }
+
+    fn upgrade(old: &str, mono: bool, new: &str) -> (String, bool, String) {
+        (old.to_owned(), mono, new.to_owned())
+    }
+
+    #[test]
+    fn test_minor_series() {
+        assert_eq!(minor_series("4.2.1-stable"), "4.2");
+        assert_eq!(minor_series("4.3-stable"), "4.3");
+        assert_eq!(minor_series("4.10.1-rc1"), "4.10");
+    }
+
+    #[test]
+    fn test_newest_of_series() {
+        let online = online(&[
+            "4.3.1-stable",
+            "4.2.3-stable",
+            "4.2.2-stable",
+            "4.1.4-stable",
+        ]);
+
+        assert_eq!(
+            upgrades(
+                &[
+                    installed("4.2.1-stable", false),
+                    installed("4.2.2-stable", false),
+                    installed("4.1.4-stable", false),
+                ],
+                &online
+            ),
+            [upgrade("4.2.2-stable", false, "4.2.3-stable")]
+        );
+    }
+
+    #[test]
+    fn test_mono() {
+        let online = online(&["4.2.3-stable", "4.2.2-stable"]);
+
+        // the non-mono 4.2.2 doesn't hide the mono 4.2.1
+        assert_eq!(
+            upgrades(
+                &[
+                    installed("4.2.1-stable", true),
+                    installed("4.2.2-stable", false),
+                ],
+                &online
+            ),
+            [
+                upgrade("4.2.1-stable", true, "4.2.3-stable"),
+                upgrade("4.2.2-stable", false, "4.2.3-stable"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_prereleases() {
+        let installed = [installed("4.2.1-stable", false)];
+
+        assert_eq!(
+            upgrades(&installed, &online(&["4.2.3-rc1*", "4.2.2-stable"])),
+            [upgrade("4.2.1-stable", false, "4.2.2-stable")]
+        );
+        assert!(upgrades(&installed, &online(&["4.2.2-rc1*", "4.2.1-stable"])).is_empty());
+    }
+
+    #[test]
+    fn test_linked() {
+        let online = online(&["4.2.3-stable", "4.2.2-stable"]);
+
+        assert!(upgrades(&[linked("4.2.1-stable")], &online).is_empty());
+
+        // a newer linked version doesn't hide an installed one
+        assert_eq!(
+            upgrades(
+                &[installed("4.2.1-stable", false), linked("4.2.2-stable")],
+                &online
+            ),
+            [upgrade("4.2.1-stable", false, "4.2.3-stable")]
+        );
+    }
+}
@@ -0,0 +1,48 @@
+use gdtk_gvm::VersionManager;
+
+use crate::cli::{
+    godot::PinnedVersion,
+    unknown,
+    utils::{select_local_version, VersionQuery},
+};
+
+pub struct GodotWhichCommand {
+    query: VersionQuery,
+}
+
+impl tapcli::Command for GodotWhichCommand {
+    type Error = anyhow::Error;
+
+    fn parse(parser: &mut tapcli::Parser) -> Result<Self, Self::Error> {
+        let mut query = VersionQuery::default();
+
+        for arg in parser {
+            if !query.accept(arg.as_ref()) {
+                unknown!(arg);
+            }
+        }
+
+        Ok(Self { query })
+    }
+
+    fn run(self) -> Result<Self::Output, Self::Error> {
+        let manager = VersionManager::load_shared()?;
+
+        let pin = match self.query.version {
+            Some(_) => None,
+            None => PinnedVersion::find()?,
+        };
+
+        let version = match pin {
+            Some(mut pin) => {
+                pin.mono |= self.query.mono;
+                pin.select(&manager)?
+            }
+            None => select_local_version(&manager, &self.query)?,
+        };
+
+        println!("{}", version.executable());
+
+        Ok(())
+    }
+}
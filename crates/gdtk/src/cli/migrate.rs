@@ -0,0 +1,114 @@
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use diagnosis::protocol::Visualizer;
+use gdtk_migrate::ChangeKind;
+
+use super::unknown;
+use crate::utils::{get_content, resolve_files_by_ext};
+
+pub struct MigrateCommand {
+    /// Scripts, or directories with them. Defaults to the current directory.
+    pub files: Vec<PathBuf>,
+    /// Only report changes, without writing them.
+    pub dry_run: bool,
+}
+
+impl tapcli::Command for MigrateCommand {
+    type Error = anyhow::Error;
+
+    fn parse(parser: &mut tapcli::Parser) -> Result<Self, Self::Error> {
+        let mut files = Vec::new();
+        let mut dry_run = false;
+
+        for arg in parser {
+            match arg.as_ref() {
+                tapcli::ArgRef::Long("dry-run") => dry_run = true,
+                tapcli::ArgRef::Value(path) => files.push(path.into()),
+                _ => unknown!(arg),
+            }
+        }
+
+        if files.is_empty() {
+            files.push(PathBuf::from("."));
+        }
+
+        Ok(Self { files, dry_run })
+    }
+
+    fn run(self) -> Result<Self::Output, Self::Error> {
+        let files = resolve_files_by_ext(self.files, "gd")?;
+        let mut counts = Counter::default();
+
+        for file in &files {
+            let file_counts = run_on_file(file, self.dry_run)?;
+
+            counts.rewrites += file_counts.rewrites;
+            counts.todos += file_counts.todos;
+        }
+
+        eprintln!(
+            "{} {} file(s), {} rewrites, {} TODOs left.",
+            if self.dry_run { "Checked" } else { "Migrated" },
+            files.len(),
+            counts.rewrites,
+            counts.todos,
+        );
+
+        Ok(())
+    }
+}
+
+fn run_on_file(file: &Path, dry_run: bool) -> anyhow::Result<Counter> {
+    let content = get_content(file)?;
+    let migration = gdtk_migrate::migrate(&content);
+
+    let source_name = match file.to_str().unwrap() {
+        "-" => "<stdin>",
+        other => other,
+    };
+
+    let vis = diagnosis::visualizers::codespan::CodespanVisualizer::new(source_name, &content);
+    let mut counter = Counter::default();
+    let mut stderr = diagnosis::visualizers::codespan::codespan_reporting::term::termcolor::StandardStream::stderr(diagnosis::visualizers::codespan::codespan_reporting::term::termcolor::ColorChoice::Always);
+
+    for change in &migration.changes {
+        let severity = match change.kind {
+            ChangeKind::Rewritten => {
+                counter.rewrites += 1;
+                diagnosis::Severity::Custom("migrated")
+            }
+            ChangeKind::Todo => {
+                counter.todos += 1;
+                diagnosis::Severity::Warning
+            }
+        };
+
+        let diagnostic = diagnosis::Diagnostic::new(&change.message, severity)
+            .with_span(&change.span)
+            .add_highlight(diagnosis::Highlight::new(&change.span));
+
+        vis.visualize(diagnostic, &mut stderr)?;
+        write!(stderr, "\n\n")?;
+    }
+
+    if dry_run {
+        return Ok(counter);
+    }
+
+    if source_name == "<stdin>" {
+        print!("{}", migration.output);
+    } else if !migration.changes.is_empty() {
+        std::fs::write(file, migration.output)?;
+    }
+
+    Ok(counter)
+}
+
+#[derive(Default)]
+struct Counter {
+    rewrites: usize,
+    todos: usize,
+}
@@ -0,0 +1,174 @@
+use std::io::Write;
+
+use diagnosis::protocol::Visualizer;
+use gdtk_gdscript_ast::ASTFile;
+use gdtk_gdscript_interpreter::{Interpreter, Session, Value};
+use gdtk_gdscript_parser::lexer::{lex, TokenKind};
+use rustyline::error::ReadlineError;
+
+use super::unknown;
+use crate::utils::try_parse_file;
+
+const SOURCE_NAME: &str = "<repl>";
+
+const HELP: &str = "\
+Enter GDScript statements or declarations. Blocks end with an empty line.
+
+:type <expr>  Show the type of an expression.
+:ast <code>   Show the syntax tree of some code.
+:help         Show this message.
+:quit         Exit (or press Ctrl+D).";
+
+pub struct ReplCommand;
+
+impl tapcli::Command for ReplCommand {
+    type Error = anyhow::Error;
+
+    fn parse(parser: &mut tapcli::Parser) -> Result<Self, Self::Error> {
+        if let Some(arg) = parser.next() {
+            unknown!(arg);
+        }
+
+        Ok(Self)
+    }
+
+    fn run(self) -> Result<Self::Output, Self::Error> {
+        let history = gdtk_paths::repl_history_path()?;
+        let mut editor = rustyline::DefaultEditor::new()?;
+        let mut session = Session::new(Interpreter::new(), SOURCE_NAME);
+
+        // There's no history on the first run.
+        let _ = editor.load_history(&history);
+
+        eprintln!("Type :help for help.");
+
+        while let Some(input) = read_input(&mut editor)? {
+            let input = input.trim();
+
+            match input.split_once(char::is_whitespace).unwrap_or((input, "")) {
+                ("", _) => (),
+                (":help", _) => eprintln!("{HELP}"),
+                (":quit" | ":exit", _) => break,
+                (":ast", code) => {
+                    if let Some((_, file)) = parse(code) {
+                        println!("{file:#?}");
+                    }
+                }
+                (":type", code) => run(&mut session, code, true)?,
+                (command, _) if command.starts_with(':') => {
+                    eprintln!("Unknown command {command}. Type :help for help.");
+                }
+                _ => run(&mut session, input, false)?,
+            }
+        }
+
+        editor.save_history(&history)?;
+
+        Ok(())
+    }
+}
+
+/// Read lines until they form a complete input. Returns `None` on end of
+/// input.
+fn read_input(editor: &mut rustyline::DefaultEditor) -> anyhow::Result<Option<String>> {
+    let mut input = String::new();
+
+    loop {
+        let prompt = if input.is_empty() { ">>> " } else { "... " };
+
+        match editor.readline(prompt) {
+            Ok(line) => {
+                if !line.trim().is_empty() {
+                    editor.add_history_entry(&line)?;
+                }
+
+                input.push_str(&line);
+                input.push('\n');
+
+                if !is_incomplete(&input) {
+                    return Ok(Some(input));
+                }
+            }
+            // Ctrl+C discards the current input.
+            Err(ReadlineError::Interrupted) => input.clear(),
+            Err(ReadlineError::Eof) => return Ok(None),
+            Err(error) => return Err(error.into()),
+        }
+    }
+}
+
+/// Whether more lines are needed: brackets are still open, or a block was
+/// started and not yet ended with an empty line.
+fn is_incomplete(input: &str) -> bool {
+    let tokens = lex(input).collect::<Vec<_>>();
+
+    let depth = tokens.iter().fold(0i32, |depth, token| match token.kind {
+        TokenKind::OpeningParenthesis | TokenKind::OpeningBracket | TokenKind::OpeningBrace => {
+            depth + 1
+        }
+        TokenKind::ClosingParenthesis | TokenKind::ClosingBracket | TokenKind::ClosingBrace => {
+            depth - 1
+        }
+        _ => depth,
+    });
+
+    let opens_block = tokens
+        .iter()
+        .rev()
+        .find(|token| !matches!(token.kind, TokenKind::Newline | TokenKind::Dedent))
+        .is_some_and(|token| matches!(token.kind, TokenKind::Colon));
+
+    let has_block = tokens
+        .iter()
+        .any(|token| matches!(token.kind, TokenKind::Indent));
+
+    depth > 0 || opens_block || (has_block && !input.ends_with("\n\n"))
+}
+
+/// Parse `code`, reporting syntax errors. The source and the syntax tree are
+/// leaked, as values created by the code may refer to them for the rest of
+/// the session.
+fn parse(code: &str) -> Option<(&'static str, &'static ASTFile<'static>)> {
+    let code: &'static str = Box::leak(format!("{code}\n").into_boxed_str());
+
+    // The parser panics on syntax errors, which mustn't end the session.
+    match try_parse_file(code) {
+        Ok(file) => Some((code, Box::leak(Box::new(file)))),
+        Err(message) => {
+            eprintln!("Syntax error: {message}");
+            None
+        }
+    }
+}
+
+/// Run `code` and print its value, or just the value's type.
+fn run(session: &mut Session<'static>, code: &str, type_only: bool) -> anyhow::Result<()> {
+    let Some((source, file)) = parse(code) else {
+        return Ok(());
+    };
+
+    match session.run(file) {
+        Ok(value) if type_only => println!("{}", value.type_name()),
+        Ok(Value::Null) => (),
+        Ok(value) => println!("{}", value.repr()),
+        Err(error) => {
+            let message = error.to_string();
+            let mut diagnostic = diagnosis::Diagnostic::new(&message, diagnosis::Severity::Error);
+
+            if let Some(span) = &error.span {
+                diagnostic = diagnostic
+                    .with_span(span)
+                    .add_highlight(diagnosis::Highlight::new(span));
+            }
+
+            let vis =
+                diagnosis::visualizers::codespan::CodespanVisualizer::new(SOURCE_NAME, source);
+            let mut stderr = diagnosis::visualizers::codespan::codespan_reporting::term::termcolor::StandardStream::stderr(diagnosis::visualizers::codespan::codespan_reporting::term::termcolor::ColorChoice::Always);
+
+            vis.visualize(diagnostic, &mut stderr)?;
+            writeln!(stderr)?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,65 @@
+use std::path::PathBuf;
+
+use crate::cli::{missing, unknown};
+
+/// `gdtk scene diff <old> <new> [--json]`
+///
+/// Print structural differences between two versions of a scene, ignoring
+/// reorderings and resource id changes.
+pub struct SceneDiffCommand {
+    pub old: PathBuf,
+    pub new: PathBuf,
+    pub json: bool,
+}
+
+impl tapcli::Command for SceneDiffCommand {
+    type Error = anyhow::Error;
+
+    fn parse(parser: &mut tapcli::Parser) -> Result<Self, Self::Error> {
+        let mut files = Vec::new();
+        let mut json = false;
+
+        for arg in parser {
+            match arg.as_ref() {
+                tapcli::ArgRef::Long("json") => json = true,
+                tapcli::ArgRef::Value(path) => files.push(PathBuf::from(path)),
+                _ => unknown!(arg),
+            }
+        }
+
+        let mut files = files.into_iter();
+
+        let (Some(old), Some(new)) = (files.next(), files.next()) else {
+            missing!("<old> <new>");
+        };
+
+        if let Some(extra) = files.next() {
+            unknown!(extra);
+        }
+
+        Ok(Self { old, new, json })
+    }
+
+    fn run(self) -> Result<Self::Output, Self::Error> {
+        let old = std::fs::read_to_string(&self.old)?;
+        let new = std::fs::read_to_string(&self.new)?;
+
+        let parse = |source| {
+            gdtk_godotcfg_parser::scene::Scene::parse(source).map_err(|e| anyhow::anyhow!("{e}"))
+        };
+
+        let changes = gdtk_godotcfg_parser::scene::diff::diff(&parse(&old)?, &parse(&new)?);
+
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&changes)?);
+        } else if changes.is_empty() {
+            eprintln!("No changes.");
+        } else {
+            for change in &changes {
+                println!("{change}");
+            }
+        }
+
+        Ok(())
+    }
+}
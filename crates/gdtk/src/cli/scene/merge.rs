@@ -0,0 +1,113 @@
+use std::path::PathBuf;
+
+use crate::cli::{missing, unknown};
+
+/// `gdtk scene merge <base> <ours> <theirs> [--stdout]`
+///
+/// Like `git merge-file`, the result is written to `<ours>` unless `--stdout`
+/// is passed, and the command fails if there are conflicts. On conflicts,
+/// `<ours>` is left untouched and `<ours>.conflicts` gets the list of
+/// conflicts (as comments), followed by the merged scene with our side of
+/// every conflict. To use it as a git merge driver, add this to your git
+/// config:
+///
+/// ```text
+/// [merge "gdtk"]
+///     name = gdtk scene merge driver
+///     driver = gdtk scene merge %O %A %B
+/// ```
+///
+/// And this to your `.gitattributes`:
+///
+/// ```text
+/// *.tscn merge=gdtk
+/// ```
+pub struct SceneMergeCommand {
+    pub base: PathBuf,
+    pub ours: PathBuf,
+    pub theirs: PathBuf,
+    pub stdout: bool,
+}
+
+impl tapcli::Command for SceneMergeCommand {
+    type Error = anyhow::Error;
+
+    fn parse(parser: &mut tapcli::Parser) -> Result<Self, Self::Error> {
+        let mut files = Vec::new();
+        let mut stdout = false;
+
+        for arg in parser {
+            match arg.as_ref() {
+                tapcli::ArgRef::Long("stdout") => stdout = true,
+                tapcli::ArgRef::Value(path) => files.push(PathBuf::from(path)),
+                _ => unknown!(arg),
+            }
+        }
+
+        let mut files = files.into_iter();
+
+        let (Some(base), Some(ours), Some(theirs)) = (files.next(), files.next(), files.next())
+        else {
+            missing!("<base> <ours> <theirs>");
+        };
+
+        if let Some(extra) = files.next() {
+            unknown!(extra);
+        }
+
+        Ok(Self {
+            base,
+            ours,
+            theirs,
+            stdout,
+        })
+    }
+
+    fn run(self) -> Result<Self::Output, Self::Error> {
+        let base = std::fs::read_to_string(&self.base)?;
+        let ours = std::fs::read_to_string(&self.ours)?;
+        let theirs = std::fs::read_to_string(&self.theirs)?;
+
+        let (merged, conflicts) =
+            gdtk_godotcfg_parser::scene::merge::merge_sources(&base, &ours, &theirs)
+                .map_err(|e| anyhow::anyhow!("{e}"))?;
+
+        for conflict in &conflicts {
+            eprintln!("{conflict}");
+        }
+
+        if self.stdout {
+            print!("{merged}");
+        } else if conflicts.is_empty() {
+            std::fs::write(&self.ours, merged)?;
+        } else {
+            // the merged scene looks resolved, so it mustn't take the place of `<ours>`
+            let mut path = self.ours.clone().into_os_string();
+            path.push(".conflicts");
+            let path = PathBuf::from(path);
+
+            let mut content = String::new();
+
+            for conflict in &conflicts {
+                content.push_str(&format!("; {conflict}\n"));
+            }
+
+            content.push('\n');
+            content.push_str(&merged);
+
+            std::fs::write(&path, content)?;
+
+            eprintln!(
+                "Left `{}` untouched, see `{}` for the conflicts.",
+                self.ours.display(),
+                path.display()
+            );
+        }
+
+        if !conflicts.is_empty() {
+            anyhow::bail!("Merged with {} conflict(s).", conflicts.len());
+        }
+
+        Ok(())
+    }
+}
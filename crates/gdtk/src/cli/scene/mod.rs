@@ -0,0 +1,35 @@
+use crate::cli::{
+    scene::{diff::SceneDiffCommand, merge::SceneMergeCommand},
+    unknown,
+};
+
+pub mod diff;
+pub mod merge;
+
+pub enum SceneCommand {
+    /// Show structural differences between two versions of a scene.
+    Diff(SceneDiffCommand),
+
+    /// Merge two versions of a scene. Can be used as a git merge driver.
+    Merge(SceneMergeCommand),
+}
+
+impl tapcli::Command for SceneCommand {
+    type Error = anyhow::Error;
+
+    #[rustfmt::skip]
+    fn parse(parser: &mut tapcli::Parser) -> Result<Self, Self::Error> {
+        match parser.next().unwrap().as_ref() {
+            tapcli::ArgRef::Value("diff") => Ok(Self::Diff(SceneDiffCommand::parse(parser)?)),
+            tapcli::ArgRef::Value("merge") => Ok(Self::Merge(SceneMergeCommand::parse(parser)?)),
+            other => unknown!(other),
+        }
+    }
+
+    fn run(self) -> Result<Self::Output, Self::Error> {
+        match self {
+            SceneCommand::Diff(c) => c.run(),
+            SceneCommand::Merge(c) => c.run(),
+        }
+    }
+}
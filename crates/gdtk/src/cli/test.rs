@@ -0,0 +1,161 @@
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use diagnosis::protocol::Visualizer;
+use gdtk_gdscript_interpreter::ErrorKind;
+use gdtk_test::{Outcome, Suite};
+
+use crate::{
+    cli::{unknown, utils::ParserExt},
+    utils::{get_content, resolve_files_by_ext, try_parse_file},
+};
+
+pub struct TestCommand {
+    /// Test scripts (`test_*.gd`), or directories with them. Defaults to the
+    /// current directory.
+    pub files: Vec<PathBuf>,
+    /// Where to write a JUnit XML report, if anywhere.
+    pub junit: Option<PathBuf>,
+}
+
+impl tapcli::Command for TestCommand {
+    type Error = anyhow::Error;
+
+    fn parse(parser: &mut tapcli::Parser) -> Result<Self, Self::Error> {
+        let mut files = Vec::new();
+        let mut junit = None;
+
+        while let Some(arg) = parser.next() {
+            match arg.as_ref() {
+                tapcli::ArgRef::Long("junit") => junit = Some(parser.next_value()?.into()),
+                tapcli::ArgRef::Value(path) => files.push(path.into()),
+                _ => unknown!(arg),
+            }
+        }
+
+        if files.is_empty() {
+            files.push(PathBuf::from("."));
+        }
+
+        Ok(Self { files, junit })
+    }
+
+    fn run(self) -> Result<Self::Output, Self::Error> {
+        let files = resolve_files_by_ext(self.files, "gd")?
+            .into_iter()
+            .filter(|file| {
+                file.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with("test_"))
+            })
+            .collect::<Vec<_>>();
+
+        let mut suites = vec![];
+
+        for file in &files {
+            match run_file(file) {
+                Ok(Some(suite)) => suites.push(suite),
+                Ok(None) => (),
+                // reported like a script that fails to parse, see `run_file`
+                Err(error) => {
+                    eprintln!("{}: {error}\n", file.display());
+
+                    let name = file.to_string_lossy();
+                    let error = ErrorKind::ReadError(error.to_string()).into();
+                    suites.push(Suite::broken(&name, error));
+                }
+            }
+        }
+
+        if let Some(path) = &self.junit {
+            std::fs::write(path, gdtk_test::junit::junit(&suites))?;
+        }
+
+        let count = |predicate: fn(&Outcome) -> bool| -> usize {
+            suites.iter().map(|suite| suite.count(predicate)).sum()
+        };
+
+        let passed = count(Outcome::is_passed);
+        let failed = count(Outcome::is_failed) + count(Outcome::is_errored);
+
+        eprintln!(
+            "Ran {} test(s) from {} file(s): {passed} passed, {failed} failed.",
+            passed + failed,
+            suites.len(),
+        );
+
+        if failed > 0 {
+            anyhow::bail!("Some tests failed.");
+        }
+
+        Ok(())
+    }
+}
+
+/// Run the tests of a script and report the results. Returns `None` if the
+/// script isn't a test script.
+fn run_file(file: &Path) -> anyhow::Result<Option<Suite>> {
+    let content = get_content(file)?;
+    let source_name = file.to_str().unwrap();
+
+    // broken test scripts are reported like failing ones, so that they show
+    // up in the JUnit report too
+    let suite = match try_parse_file(&content) {
+        Ok(parsed) => match gdtk_test::run_file(&parsed, source_name) {
+            Some(suite) => suite,
+            None => return Ok(None),
+        },
+        Err(message) => Suite::broken(source_name, ErrorKind::ParseError(message).into()),
+    };
+
+    eprintln!("{source_name}");
+
+    for case in &suite.cases {
+        let status = match case.outcome {
+            Outcome::Passed => "ok",
+            Outcome::Failed(_) => "FAILED",
+            Outcome::Errored(_) => "ERROR",
+        };
+
+        eprintln!(
+            "  {status:<6} {} ({:.3}s)",
+            case.name,
+            case.time.as_secs_f64()
+        );
+    }
+
+    let vis = diagnosis::visualizers::codespan::CodespanVisualizer::new(source_name, &content);
+    let mut stderr = diagnosis::visualizers::codespan::codespan_reporting::term::termcolor::StandardStream::stderr(diagnosis::visualizers::codespan::codespan_reporting::term::termcolor::ColorChoice::Always);
+
+    eprintln!();
+
+    for case in &suite.cases {
+        let (Outcome::Failed(error) | Outcome::Errored(error)) = &case.outcome else {
+            continue;
+        };
+
+        let message = format!("{}: {error}", case.name);
+        let mut diagnostic = diagnosis::Diagnostic::new(&message, diagnosis::Severity::Error);
+
+        if let Some(span) = &error.span {
+            diagnostic = diagnostic
+                .with_span(span)
+                .add_highlight(diagnosis::Highlight::new(span));
+        }
+
+        vis.visualize(diagnostic, &mut stderr)?;
+
+        if !case.output.is_empty() {
+            writeln!(
+                stderr,
+                "Output of {}:\n{}\n",
+                case.name,
+                case.output.trim_end()
+            )?;
+        }
+    }
+
+    Ok(Some(suite))
+}
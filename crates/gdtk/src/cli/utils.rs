@@ -1,5 +1,5 @@
 use cliui::Prompt;
-use gdtk_gvm::{types::LocalVersion, VersionManager};
+use gdtk_gvm::{types::LocalVersion, utils::coerce_version, VersionManager};
 
 use crate::cli::{missing, unknown};
 
@@ -21,16 +21,82 @@ pub impl tapcli::Parser {
     }
 }
 
-pub fn prompt_local_version(manager: &VersionManager) -> anyhow::Result<&LocalVersion> {
-    let available_versions = manager.installed();
+/// A version selected on the command line, e.g. `4.3 --mono`.
+#[derive(Default)]
+pub struct VersionQuery {
+    /// A (prefix of a) version name, e.g. `4.3` or `4.2.2-stable`.
+    pub version: Option<String>,
+    /// Whether to select the mono variant.
+    pub mono: bool,
+    /// Never prompt, pick the newest matching version instead.
+    pub yes: bool,
+}
+
+impl VersionQuery {
+    /// Try to consume an argument. Returns `false` if the argument is not
+    /// a `<version>`, `--mono` or `--yes`.
+    pub fn accept(&mut self, arg: tapcli::ArgRef<'_>) -> bool {
+        match arg {
+            tapcli::ArgRef::Long("mono") => self.mono = true,
+            tapcli::ArgRef::Long("yes") | tapcli::ArgRef::Short('y') => self.yes = true,
+            tapcli::ArgRef::Value(version) if self.version.is_none() => {
+                self.version = Some(version.to_owned())
+            }
+            _ => return false,
+        }
+
+        true
+    }
+}
 
-    if available_versions.is_empty() {
+/// Find an installed version matching `query`, prompting if there is more
+/// than one match (or no version specified at all).
+pub fn select_local_version<'m>(
+    manager: &'m VersionManager,
+    query: &VersionQuery,
+) -> anyhow::Result<&'m LocalVersion> {
+    let installed = manager.installed();
+
+    if installed.is_empty() {
         anyhow::bail!("No versions installed. Install one with `gdtk godot install`!");
     }
 
+    let Some(input) = &query.version else {
+        if query.yes {
+            anyhow::bail!("No version specified.");
+        }
+
+        return prompt_local_version(installed.iter().collect());
+    };
+
+    if let Some(exact) = installed
+        .iter()
+        .find(|v| v.name == *input && v.mono == query.mono)
+    {
+        return Ok(exact);
+    }
+
+    let pool = installed.iter().filter(|v| v.mono == query.mono).collect();
+    let mut matches = coerce_version(input, pool)?;
+
+    matches.sort_unstable_by(|v1, v2| v2.as_ordered().cmp(&v1.as_ordered()));
+
+    match matches.len() {
+        0 => anyhow::bail!(
+            "No installed version matches `{}`{}.",
+            input,
+            if query.mono { " (mono)" } else { "" }
+        ),
+        1 => Ok(matches[0]),
+        _ if query.yes => Ok(matches[0]),
+        _ => prompt_local_version(matches),
+    }
+}
+
+pub fn prompt_local_version(versions: Vec<&LocalVersion>) -> anyhow::Result<&LocalVersion> {
     let (Some(version), _) = Prompt::builder()
         .with_question("Select version")
-        .with_items(available_versions.iter().collect::<Vec<_>>()) // FIXME: remove vec requirement, somehow?
+        .with_items(versions)
         .build()
         .interact()?
     else {
@@ -39,3 +105,13 @@ pub fn prompt_local_version(manager: &VersionManager) -> anyhow::Result<&LocalVe
 
     Ok(version)
 }
+
+pub fn confirm(question: &'static str) -> anyhow::Result<bool> {
+    let (answer, _) = Prompt::builder()
+        .with_question(question)
+        .with_items(["Yes", "No"])
+        .build()
+        .interact()?;
+
+    Ok(answer == Some("Yes"))
+}
@@ -10,6 +10,8 @@ pub mod cli;
 pub mod utils;
 
 fn main() -> anyhow::Result<()> {
+    crate::cli::godot::shim::run_if_shim()?;
+
     let cli = crate::cli::Cli::from_env()?;
 
     setup_tracing(&cli)?;
@@ -1,8 +1,10 @@
 use std::{
     io::Read,
+    panic::AssertUnwindSafe,
     path::{Path, PathBuf},
 };
 
+use gdtk_gdscript_ast::ASTFile;
 use itertools::Itertools;
 
 use crate::cli::Cli;
@@ -24,6 +26,10 @@ pub fn setup_tracing(cli: &Cli) -> anyhow::Result<()> {
 }
 
 pub fn resolve_files_by_ext(files: Vec<PathBuf>, ext: &str) -> anyhow::Result<Vec<PathBuf>> {
+    resolve_files_by_exts(files, &[ext])
+}
+
+pub fn resolve_files_by_exts(files: Vec<PathBuf>, exts: &[&str]) -> anyhow::Result<Vec<PathBuf>> {
     if let [file] = files.as_slice() {
         if file.to_str().is_some_and(|p| p == "-") {
             return Ok(files);
@@ -55,7 +61,11 @@ pub fn resolve_files_by_ext(files: Vec<PathBuf>, ext: &str) -> anyhow::Result<Ve
     Ok(walker
         .filter_map(Result::ok)
         .map(|e| e.into_path())
-        .filter(|p| p.extension().is_some_and(|e| e == ext))
+        .filter(|p| {
+            p.extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| exts.contains(&e))
+        })
         .unique()
         .collect())
 }
@@ -70,3 +80,26 @@ pub fn get_content(file: &Path) -> anyhow::Result<String> {
         std::fs::read_to_string(file)?
     })
 }
+
+/// Parse GDScript code, returning the message of a syntax error instead of
+/// panicking, as the parser does.
+pub fn try_parse_file(code: &str) -> Result<ASTFile<'_>, String> {
+    let hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(|_| ()));
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        gdtk_gdscript_parser::parse_file(gdtk_gdscript_parser::lexer::lex(code))
+    }));
+    std::panic::set_hook(hook);
+
+    result.map_err(|payload| {
+        payload
+            .downcast_ref::<String>()
+            .cloned()
+            .or_else(|| {
+                payload
+                    .downcast_ref::<&str>()
+                    .map(|message| message.to_string())
+            })
+            .unwrap_or_else(|| "invalid syntax".to_owned())
+    })
+}
@@ -19,25 +19,50 @@ enum State {
 /// A command-line argument parser.
 #[derive(Debug)]
 pub struct Parser {
-    inner: std::iter::Peekable<std::iter::Skip<InnerParser>>,
+    inner: InnerParser,
+    /// The argument returned by the last [Parser::peek], if any, and whether
+    /// it came after a `--`.
+    peeked: Option<Option<(Arg, bool)>>,
+    /// Whether the last argument returned by [Parser::next] came after a `--`.
+    after_separator: bool,
 }
 
 impl Parser {
     /// Create a new `Parser` from the environment.
     pub fn from_env() -> Self {
+        let mut inner = InnerParser::from_env();
+
+        // skip the executable
+        inner.args.next();
+
         Self {
-            inner: InnerParser::from_env().skip(1).peekable(),
+            inner,
+            peeked: None,
+            after_separator: false,
         }
     }
 
     /// See [std::iter::Peekable::peek]
     pub fn peek(&mut self) -> Option<&Arg> {
-        self.inner.peek()
+        self.peeked
+            .get_or_insert_with(|| self.inner.next_marked())
+            .as_ref()
+            .map(|(arg, _)| arg)
     }
 
     /// See [std::iter::Peekable::peek_mut]
     pub fn peek_mut(&mut self) -> Option<&mut Arg> {
-        self.inner.peek_mut()
+        self.peeked
+            .get_or_insert_with(|| self.inner.next_marked())
+            .as_mut()
+            .map(|(arg, _)| arg)
+    }
+
+    /// Whether the last argument returned by [Parser::next] came after a `--`,
+    /// which makes it a value even if it looks like an option. Peeking doesn't
+    /// change it.
+    pub fn after_separator(&self) -> bool {
+        self.after_separator
     }
 }
 
@@ -45,7 +70,14 @@ impl Iterator for Parser {
     type Item = Arg;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.inner.next()
+        let (arg, after_separator) = match self.peeked.take() {
+            Some(peeked) => peeked,
+            None => self.inner.next_marked(),
+        }?;
+
+        self.after_separator = after_separator;
+
+        Some(arg)
     }
 }
 
@@ -64,6 +96,13 @@ impl InnerParser {
         }
     }
 
+    /// Parse the next argument, along with whether it came after a `--`.
+    fn next_marked(&mut self) -> Option<(Arg, bool)> {
+        let arg = self.next()?;
+
+        Some((arg, matches!(self.state, State::TreatAsValues)))
+    }
+
     /// Reset state to [State::None] and parse the next argument.
     fn switch(&mut self) -> Option<<Self as Iterator>::Item> {
         self.state = State::None;